@@ -7,9 +7,30 @@ complete -c eza -s 1 -l oneline -d "Display one entry per line"
 complete -c eza -s l -l long -d "Display extended file metadata as a table"
 complete -c eza -s G -l grid -d "Display entries in a grid"
 complete -c eza -s x -l across -d "Sort the grid across, rather than downwards"
+complete -c eza -l down -d "Sort the grid downwards (default)"
+complete -c eza -l grid-gutter -d "Set the number of spaces between grid columns" -x
+complete -c eza -l grid-min-columns -d "Lay the grid out in at least this many columns" -x
+complete -c eza -l grid-max-columns -d "Lay the grid out in at most this many columns" -x
+complete -c eza -l grid-spread -d "Spread leftover width across the grid's gutters"
+complete -c eza -l grid-shrink -d "Columns to drop from a long grid so more fit across" -x
+complete -c eza -l grid-details-threshold -d "Only use a long grid if it has at least this many rows" -x
 complete -c eza -s R -l recurse -d "Recurse into directories"
 complete -c eza -s T -l tree -d "Recurse into directories as a tree"
+complete -c eza -l flat -d "List everything beneath a directory together when recursing"
+complete -c eza -l tree-style -d "Which characters to draw trees with" -x -a "
+  unicode\t'Box drawing characters (default)'
+  rounded\t'Box drawing characters with rounded corners'
+  ascii\t'Plain ASCII characters'
+"
+complete -c eza -l verbose-errors -d "List every unreadable directory when recursing"
+complete -c eza -l inline-errors -d "Show entries that can't be read with an error note"
+complete -c eza -l pager -d "Page long listings, keeping the header row in view"
+complete -c eza -l deterministic -d "Give the same output on every computer and terminal"
+complete -c eza -l changed-since -d "List only what's changed since a saved snapshot" -r -F
+complete -c eza -l save-snapshot -d "Save a snapshot of the files to compare against later" -r -F
+complete -c eza -l from-manifest -d "List the entries in a JSON or CSV manifest instead" -r -F
 complete -c eza -s X -l dereference -d "Dereference symbolic links when displaying file information"
+complete -c eza -l dereference-command-line -d "Dereference only symbolic links given as arguments"
 complete -c eza -s F -l classify -d "Display type indicator by file names"
 complete -c eza -l color \
     -l colour -d "When to use terminal colours" -x -a "
@@ -30,14 +51,44 @@ complete -c eza -l color-scale-mode \
     fixed\t'Highlight based on fixed colors'
     gradient\t'Highlight based \'field\' in relation to other files'
 "
+complete -c eza -l export-ls-colors -d "Print the colour theme as an LS_COLORS string"
+complete -c eza -l dump-theme -d "Print every style in the theme and where it came from"
+complete -c eza -l theme -d "Pick colours for the terminal's background" -x -a "
+  light\t'Colours for a light background'
+  dark\t'Colours for a dark background'
+  auto\t'Ask the terminal what its background is'
+"
 complete -c eza -l icons -d "When to display icons" -x -a "
   always\t'Always display icons'
   auto\t'Display icons if standard output is a terminal'
   automatic\t'Display icons if standard output is a terminal'
   never\t'Never display icons'
 "
+complete -c eza -l icon-set -d "Which icons to use" -x -a "
+  nerd3\t'Nerd Fonts 3 icons'
+  nerd2\t'Nerd Fonts 2 icons'
+  unicode\t'Unicode symbols'
+  ascii\t'ASCII characters'
+"
+complete -c eza -l html -d "Display entries as a self-contained HTML table"
+complete -c eza -l sections -d "Split listings of more than COUNT entries by letter" -x
+complete -c eza -l total -d "Print the number and size of entries after each directory"
+complete -c eza -l max-results -d "List at most COUNT entries from each directory" -x
+complete -c eza -l format -d "Display each entry using a template" -x
 complete -c eza -l no-quotes -d "Don't quote file names with spaces"
-complete -c eza -l hyperlink -d "Display entries as hyperlinks"
+complete -c eza -l hyperlink -d "Display entries as hyperlinks" -x -a "
+  always\t'Always display hyperlinks'
+  auto\t'Display hyperlinks if standard output is a terminal'
+  automatic\t'Display hyperlinks if standard output is a terminal'
+  never\t'Never display hyperlinks'
+"
+complete -c eza -l shortcuts -d "Show the targets of desktop entries and shortcuts"
+complete -c eza -l socket-info -d "Show what has sockets and named pipes open"
+complete -c eza -l find-hardlinks -d "Show which earlier file each hard link is the same as"
+complete -c eza -l normalized -d "Show how names would look normalized" -x -a "
+  nfc\t'Compose characters with Unicode Normalization Form C'
+  fold\t'Compose characters and fold their case'
+"
 complete -c eza -l follow-symlinks -d "Drill down into symbolic links that point to directories"
 complete -c eza -l absolute -d "Display entries with their absolute path" -x -a "
   on\t'Show absolute path for listed entries'
@@ -50,10 +101,17 @@ complete -c eza -l smart-group -d "Only show group if it has a different name fr
 complete -c eza -l group-directories-first -d "Sort directories before other files"
 complete -c eza -l group-directories-last -d "Sort directories after other files"
 complete -c eza -l git-ignore -d "Ignore files mentioned in '.gitignore'"
+complete -c eza -l git-only -d "List only files Git tracks, or only changed ones" -x -a "
+  tracked\t'Files in the index'
+  dirty\t'Files with uncommitted changes'
+"
+complete -c eza -l ignore-files -d "Ignore files mentioned in '.ignore' and '.fdignore'"
 complete -c eza -s a -l all -d "Show hidden and 'dot' files. Use this twice to also show the '.' and '..' directories"
 complete -c eza -s A -l almost-all -d "Equivalent to --all; included for compatibility with `ls -A`"
+complete -c eza -l no-windows-hidden -d "Show files with the Windows hidden attribute without --all"
 complete -c eza -s d -l list-dirs -d "List directories like regular files"
 complete -c eza -s L -l level -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
+complete -c eza -l min-depth -d "Only list files at least this deep when recursing" -x -a "1 2 3 4 5 6 7 8 9"
 complete -c eza -s w -l width -d "Limits column output of grid, 0 implies auto-width"
 complete -c eza -s r -l reverse -d "Reverse the sort order"
 complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
@@ -75,30 +133,69 @@ complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
     newest\t'Sort by file modified time (newest first)'
     none\t'Do not sort files at all'
     oldest\t'Sort by file modified time'
+    random\t'Shuffle files'
     size\t'Sort by file size'
     time\t'Sort by file modified time'
     type\t'Sort by file type'
 "
+complete -c eza -l seed -d "Seed to shuffle files with for --sort=random" -x
 
 complete -c eza -s I -l ignore-glob -d "Ignore files that match these glob patterns" -r
+complete -c eza -l exclude-from -d "Ignore files that match the glob patterns in a file" -r -F
+complete -c eza -l prune-glob -d "Don't recurse into directories that match these glob patterns" -r
+complete -c eza -l regex -d "List only files whose names match this regex" -x
+complete -c eza -l iregex -d "List only files whose names match this regex, ignoring case" -x
+complete -c eza -l regex-full-path -d "Match --regex against paths rather than names"
+complete -c eza -l newer-than -d "List only files newer than a date or age" -x
+complete -c eza -l older-than -d "List only files older than a date or age" -x
+complete -c eza -l type -d "List only these kinds of file" -x -a "
+    f\t'Regular files'
+    d\t'Directories'
+    l\t'Symlinks'
+    p\t'Named pipes'
+    s\t'Sockets'
+    b\t'Block devices'
+    c\t'Character devices'
+"
+complete -c eza -l ext -d "List only files with these extensions" -x
+complete -c eza -l perm -d "List only files with these permissions" -x
 complete -c eza -s D -l only-dirs -d "List only directories"
 complete -c eza -s f -l only-files -d "List only files"
 complete -c eza -l show-symlinks -d "Explicitly show symbolic links (For use with --only-dirs | --only-files)"
 complete -c eza -l no-symlinks -d "Do not show symbolic links"
+complete -c eza -l only-broken-links -d "List only symbolic links whose targets don't exist"
+complete -c eza -l only-empty -d "List only empty files and directories"
+complete -c eza -l no-empty -d "List only files and directories that aren't empty"
+complete -c eza -l dedupe -d "List what several arguments lead to only once"
 
 # Long view options
 complete -c eza -s b -l binary -d "List file sizes with binary prefixes"
 complete -c eza -s B -l bytes -d "List file sizes in bytes, without any prefixes"
+complete -c eza -l size -d "Which sizes to list" -x -a "
+    apparent\t'How many bytes each file holds'
+    allocated\t'How much space each file takes up on disk'
+    both\t'Both sizes, side by side'
+"
+complete -c eza -l size-unit -d "List file sizes in units" -x -a "
+    bytes\t'Bytes'
+    sectors\t'512-byte sectors'
+    pages\t'4096-byte pages'
+    blocks:\t'Blocks of a given number of bytes'
+"
 complete -c eza -s g -l group -d "List each file's group"
 complete -c eza -s h -l header -d "Add a header row to each column"
+complete -c eza -l header-names -d "Rename header columns" -x
 complete -c eza -s H -l links -d "List each file's number of hard links"
 complete -c eza -s i -l inode -d "List each file's inode number"
 complete -c eza -s S -l blocksize -d "List each file's size of allocated file system blocks"
+complete -c eza -l link-target-details -d "List the permissions and size of symlinks' targets"
+complete -c eza -l vcs-status -d "List each file's Git, Mercurial, or Jujutsu status"
 complete -c eza -s t -l time -d "Which timestamp field to list" -x -a "
     modified\t'Display modified time'
     changed\t'Display changed time'
     accessed\t'Display accessed time'
     created\t'Display created time'
+    git\t'Display time of last commit'
 "
 complete -c eza -s m -l modified -d "Use the modified timestamp field"
 complete -c eza -s n -l numeric -d "List numeric user and group IDs."
@@ -113,19 +210,45 @@ complete -c eza -l time-style -d "How to format timestamps" -x -a "
     relative\t'Display relative timestamps'
     +FORMAT\t'Use custom time style'
 "
-complete -c eza -l total-size -d "Show recursive directory size (unix only)"
+complete -c eza -l total-size -d "Show recursive directory size (unix only)" -x -a "
+  apparent\t'Add up the lengths of the files inside'
+  allocated\t'Add up the space allocated to the files inside'
+"
+complete -c eza -l total-size-max-depth -d "Only count what's this many levels down with --total-size" -x
+complete -c eza -l lfs -d "List Git LFS pointer files with the size of the file they point to"
 complete -c eza -l no-permissions -d "Suppress the permissions field"
 complete -c eza -s o -l octal-permissions -d "List each file's permission in octal format"
+complete -c eza -l file-version -d "List the version of executables and libraries"
+complete -c eza -l access-rights -d "List what you may do with each file, from its DACL"
+complete -c eza -l cow -d "List btrfs subvolumes and compression, and ZFS datasets"
+complete -c eza -l project-id -d "List the project ID used for project quotas"
+complete -c eza -l storage-class -d "List the storage class of objects in object stores"
+complete -c eza -l mime -d "List each file's MIME type, sniffed from its contents"
+complete -c eza -l hash -d "List the SHA-256 digest of each file's contents"
+complete -c eza -l image-info -d "List the width and height of images"
+complete -c eza -l binary-info -d "List the architecture and linking of executables and libraries"
+complete -c eza -l preview -d "List the first N characters of text files"
 complete -c eza -l no-filesize -d "Suppress the filesize field"
 complete -c eza -l no-user -d "Suppress the user field"
 complete -c eza -l no-time -d "Suppress the time field"
+complete -c eza -l fields -d "Choose which columns to list, and in what order" -x
 complete -c eza -s M -l mounts -d "Show mount details"
 complete -c eza -l stdin -d "When piping to eza. Read file names from stdin"
 
 # Optional extras
 complete -c eza -l git -d "List each file's Git status, if tracked"
+complete -c eza -l git-log -d "List the hash and subject of each file's last commit"
+complete -c eza -l git-author -d "List the author of each file's last commit"
+complete -c eza -l git-owner -d "List who wrote the most lines of each file"
+complete -c eza -l git-since-tag -d "List the first tag to include each file's last change"
 complete -c eza -l no-git -d "Suppress Git status"
 complete -c eza -l git-repos -d "List each git-repos status and branch name"
 complete -c eza -l git-repos-no-status -d "List each git-repos branch name (much faster)"
 complete -c eza -s '@' -l extended -d "List each file's extended attributes and sizes"
+complete -c eza -l xattrs -d "List each file's extended attributes' names or values" -x -a "
+    names\t'Just their names and sizes'
+    values\t'Their values, cut short if long'
+"
 complete -c eza -s Z -l context -d "List each file's security context"
+complete -c eza -l acl -d "List each file's access control list entries"
+complete -c eza -l streams -d "List each file's alternate data streams"
@@ -0,0 +1,440 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Reading what executables and shared libraries were built for, for
+//! `--binary-info`.
+//!
+//! Both ELF files, as used on Linux and the BSDs, and PE files, as used on
+//! Windows, start with a header that says what they are. Whether one is
+//! dynamically linked and whether it’s been stripped take a look at the
+//! tables it points to, which can be anywhere in the file, so these get
+//! seeked to rather than the whole file being read.
+
+use std::fs;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::fs::fields::{BinaryFormat, BinaryInfo};
+
+/// The most section or program headers that get looked through, so a
+/// broken or malicious file can’t make this take forever.
+const MAX_HEADERS: u64 = 4096;
+
+/// Reads what the executable or shared library at the given path was built
+/// for, or nothing if it isn’t one.
+pub fn info(path: &Path) -> io::Result<Option<BinaryInfo>> {
+    from_reader(BufReader::new(fs::File::open(path)?))
+}
+
+fn from_reader(mut reader: impl Read + Seek) -> io::Result<Option<BinaryInfo>> {
+    let mut magic = [0; 4];
+    let result = reader.read_exact(&mut magic).and_then(|()| {
+        if magic == *b"\x7fELF" {
+            elf(reader)
+        } else if magic.starts_with(b"MZ") {
+            pe(reader)
+        } else {
+            Ok(None)
+        }
+    });
+
+    // A file that ends before everything’s been read is just a file that
+    // isn’t a binary.
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        result => result,
+    }
+}
+
+/// Reads numbers in an ELF file’s byte order, at its word size.
+struct ElfReader<R> {
+    reader: R,
+    big_endian: bool,
+    wide: bool,
+}
+
+impl<R: Read + Seek> ElfReader<R> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut buf = [0; N];
+        self.reader.read_exact(&mut buf)?;
+        if self.big_endian {
+            buf.reverse();
+        }
+        Ok(buf)
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        self.bytes().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.bytes().map(u32::from_le_bytes)
+    }
+
+    /// Reads an address or offset, which is as wide as the file’s words.
+    fn word(&mut self) -> io::Result<u64> {
+        if self.wide {
+            self.bytes().map(u64::from_le_bytes)
+        } else {
+            self.u32().map(u64::from)
+        }
+    }
+
+    fn seek(&mut self, offset: u64) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(offset)).map(|_| ())
+    }
+
+    /// Whether any of a table’s entries has the given 32-bit type, which
+    /// comes first in both program and section headers. A table that goes
+    /// past the largest offset there can be isn’t one to look through, so
+    /// it gives nothing.
+    fn any_entry_of_type(
+        &mut self,
+        offset: u64,
+        size: u16,
+        count: u16,
+        kind: u32,
+    ) -> io::Result<Option<bool>> {
+        for index in 0..u64::from(count).min(MAX_HEADERS) {
+            let entry = index
+                .checked_mul(u64::from(size))
+                .and_then(|start| start.checked_add(offset));
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+
+            self.seek(entry)?;
+            if self.u32()? == kind {
+                return Ok(Some(true));
+            }
+        }
+        Ok(Some(false))
+    }
+}
+
+fn elf(mut reader: impl Read + Seek) -> io::Result<Option<BinaryInfo>> {
+    const ET_EXEC: u16 = 2;
+    const ET_DYN: u16 = 3;
+    const PT_DYNAMIC: u32 = 2;
+    const SHT_SYMTAB: u32 = 2;
+
+    // The rest of `e_ident`: the word size and byte order, then padding.
+    let mut ident = [0; 12];
+    reader.read_exact(&mut ident)?;
+    let (wide, bits) = match ident[0] {
+        1 => (false, 32),
+        2 => (true, 64),
+        _ => return Ok(None),
+    };
+    let big_endian = match ident[1] {
+        1 => false,
+        2 => true,
+        _ => return Ok(None),
+    };
+
+    let mut elf = ElfReader {
+        reader,
+        big_endian,
+        wide,
+    };
+    let file_type = elf.u16()?;
+    if file_type != ET_EXEC && file_type != ET_DYN {
+        return Ok(None);
+    }
+    let machine = elf.u16()?;
+
+    // Skip `e_version` and `e_entry` to get to the tables.
+    elf.u32()?;
+    elf.word()?;
+    let program_headers = elf.word()?;
+    let section_headers = elf.word()?;
+    // Skip `e_flags` and `e_ehsize`.
+    elf.u32()?;
+    elf.u16()?;
+    let program_header_size = elf.u16()?;
+    let program_header_count = elf.u16()?;
+    let section_header_size = elf.u16()?;
+    let section_header_count = elf.u16()?;
+
+    let dynamic = elf.any_entry_of_type(
+        program_headers,
+        program_header_size,
+        program_header_count,
+        PT_DYNAMIC,
+    )?;
+
+    // Section headers start with their name, so their type comes after.
+    let symbols = match section_headers.checked_add(4) {
+        Some(section_types) => elf.any_entry_of_type(
+            section_types,
+            section_header_size,
+            section_header_count,
+            SHT_SYMTAB,
+        )?,
+        None => None,
+    };
+
+    let (Some(dynamic), Some(symbols)) = (dynamic, symbols) else {
+        return Ok(None);
+    };
+
+    Ok(Some(BinaryInfo {
+        format: BinaryFormat::Elf,
+        architecture: elf_architecture(machine, bits),
+        bits,
+        dynamic,
+        stripped: !symbols,
+    }))
+}
+
+fn elf_architecture(machine: u16, bits: u8) -> &'static str {
+    match (machine, bits) {
+        (0x03, _) => "x86",
+        (0x3E, _) => "x86-64",
+        (0x28, _) => "ARM",
+        (0xB7, _) => "AArch64",
+        (0xF3, 32) => "RISC-V 32",
+        (0xF3, _) => "RISC-V 64",
+        (0x08, _) => "MIPS",
+        (0x14, _) => "PowerPC",
+        (0x15, _) => "PowerPC 64",
+        (0x16, _) => "S/390",
+        (0x02 | 0x2B, _) => "SPARC",
+        (0x102, _) => "LoongArch",
+        _ => "unknown",
+    }
+}
+
+fn pe(mut reader: impl Read + Seek) -> io::Result<Option<BinaryInfo>> {
+    fn u16(bytes: &[u8]) -> u16 {
+        u16::from_le_bytes([bytes[0], bytes[1]])
+    }
+
+    fn u32(bytes: &[u8]) -> u32 {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    // Where the PE header is, past the DOS stub.
+    let mut offset = [0; 4];
+    reader.seek(SeekFrom::Start(0x3C))?;
+    reader.read_exact(&mut offset)?;
+
+    // The signature, the COFF header, and the start of the optional header
+    // up to the import table’s entry in its data directories.
+    let mut header = [0; 4 + 20 + 128];
+    reader.seek(SeekFrom::Start(u64::from(u32(&offset))))?;
+    reader.read_exact(&mut header)?;
+    if !header.starts_with(b"PE\0\0") {
+        return Ok(None);
+    }
+
+    let coff = &header[4..24];
+    let optional = &header[24..];
+    let (bits, directories) = match u16(&optional[0..2]) {
+        0x10B => (32, &optional[96..]),
+        0x20B => (64, &optional[112..]),
+        _ => return Ok(None),
+    };
+
+    let architecture = match u16(&coff[0..2]) {
+        0x014C => "x86",
+        0x8664 => "x86-64",
+        0x01C0 | 0x01C4 => "ARM",
+        0xAA64 => "AArch64",
+        _ => "unknown",
+    };
+
+    // The second data directory is the import table, which only files that
+    // load libraries have.
+    let dynamic = u32(&directories[12..16]) != 0;
+
+    // Symbols are either in the COFF symbol table or nowhere in the file.
+    let stripped = u32(&coff[8..12]) == 0 || u32(&coff[12..16]) == 0;
+
+    Ok(Some(BinaryInfo {
+        format: BinaryFormat::Pe,
+        architecture,
+        bits,
+        dynamic,
+        stripped,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Builds an ELF file with program and section headers of the given
+    /// types, and nothing else.
+    fn elf(
+        wide: bool,
+        big_endian: bool,
+        file_type: u16,
+        machine: u16,
+        program_types: &[u32],
+        section_types: &[u32],
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let push = |bytes: &mut Vec<u8>, value: u64, size: usize| {
+            let le = value.to_le_bytes();
+            let mut field = le[..size].to_vec();
+            if big_endian {
+                field.reverse();
+            }
+            bytes.extend_from_slice(&field);
+        };
+        let word = if wide { 8 } else { 4 };
+        let (header_size, program_size, section_size) =
+            if wide { (64, 56, 64) } else { (52, 32, 40) };
+        let program_headers = header_size;
+        let section_headers = program_headers + program_size * program_types.len();
+
+        bytes.extend_from_slice(b"\x7fELF");
+        bytes.extend_from_slice(&[if wide { 2 } else { 1 }, if big_endian { 2 } else { 1 }, 1]);
+        bytes.resize(16, 0);
+        push(&mut bytes, file_type.into(), 2);
+        push(&mut bytes, machine.into(), 2);
+        push(&mut bytes, 1, 4);
+        push(&mut bytes, 0, word);
+        push(&mut bytes, program_headers as u64, word);
+        push(&mut bytes, section_headers as u64, word);
+        push(&mut bytes, 0, 4);
+        push(&mut bytes, header_size as u64, 2);
+        push(&mut bytes, program_size as u64, 2);
+        push(&mut bytes, program_types.len() as u64, 2);
+        push(&mut bytes, section_size as u64, 2);
+        push(&mut bytes, section_types.len() as u64, 2);
+        push(&mut bytes, 0, 2);
+        assert_eq!(bytes.len(), header_size);
+
+        for kind in program_types {
+            let start = bytes.len();
+            push(&mut bytes, (*kind).into(), 4);
+            bytes.resize(start + program_size, 0);
+        }
+        for kind in section_types {
+            let start = bytes.len();
+            push(&mut bytes, 0, 4);
+            push(&mut bytes, (*kind).into(), 4);
+            bytes.resize(start + section_size, 0);
+        }
+        bytes
+    }
+
+    fn info_of(bytes: Vec<u8>) -> Option<BinaryInfo> {
+        from_reader(Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn dynamic_executable() {
+        // PT_PHDR, PT_INTERP, PT_LOAD, PT_DYNAMIC; SHT_PROGBITS, SHT_SYMTAB.
+        let bytes = elf(true, false, 3, 0x3E, &[6, 3, 1, 2], &[1, 2]);
+        assert_eq!(
+            info_of(bytes),
+            Some(BinaryInfo {
+                format: BinaryFormat::Elf,
+                architecture: "x86-64",
+                bits: 64,
+                dynamic: true,
+                stripped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn static_stripped_executable() {
+        let bytes = elf(true, false, 2, 0xB7, &[1], &[1, 3]);
+        assert_eq!(
+            info_of(bytes),
+            Some(BinaryInfo {
+                format: BinaryFormat::Elf,
+                architecture: "AArch64",
+                bits: 64,
+                dynamic: false,
+                stripped: true,
+            })
+        );
+    }
+
+    #[test]
+    fn big_endian() {
+        let bytes = elf(false, true, 2, 0x08, &[1, 2], &[2]);
+        assert_eq!(
+            info_of(bytes),
+            Some(BinaryInfo {
+                format: BinaryFormat::Elf,
+                architecture: "MIPS",
+                bits: 32,
+                dynamic: true,
+                stripped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn object_file() {
+        // Relocatable files, such as `.o` files, aren’t executables.
+        assert_eq!(info_of(elf(true, false, 1, 0x3E, &[], &[2])), None);
+    }
+
+    #[test]
+    fn truncated_elf() {
+        let mut bytes = elf(true, false, 3, 0x3E, &[2], &[2]);
+        bytes.truncate(40);
+        assert_eq!(info_of(bytes), None);
+    }
+
+    #[test]
+    fn overflowing_table_offsets() {
+        // `e_shoff` is the eight bytes at 40.
+        let mut bytes = elf(true, false, 3, 0x3E, &[], &[2]);
+        bytes[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(info_of(bytes), None);
+    }
+
+    #[test]
+    fn pe() {
+        let mut bytes = b"MZ".to_vec();
+        bytes.resize(0x3C, 0);
+        bytes.extend_from_slice(&0x40_u32.to_le_bytes());
+        bytes.extend_from_slice(b"PE\0\0");
+
+        // A COFF header for x86-64, without a symbol table...
+        bytes.extend_from_slice(&0x8664_u16.to_le_bytes());
+        bytes.resize(bytes.len() + 18, 0);
+
+        // ...and a PE32+ optional header with an import table.
+        let optional = bytes.len();
+        bytes.extend_from_slice(&0x20B_u16.to_le_bytes());
+        bytes.resize(optional + 112 + 12, 0);
+        bytes.extend_from_slice(&40_u32.to_le_bytes());
+
+        assert_eq!(
+            info_of(bytes),
+            Some(BinaryInfo {
+                format: BinaryFormat::Pe,
+                architecture: "x86-64",
+                bits: 64,
+                dynamic: true,
+                stripped: true,
+            })
+        );
+    }
+
+    #[test]
+    fn dos_executable() {
+        // Plain DOS programs start with `MZ` too, but have no PE header.
+        let mut bytes = b"MZ".to_vec();
+        bytes.resize(0x200, 0);
+        assert_eq!(info_of(bytes), None);
+    }
+
+    #[test]
+    fn not_a_binary() {
+        assert_eq!(info_of(b"#!/bin/sh\necho hello\n".to_vec()), None);
+    }
+}
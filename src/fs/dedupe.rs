@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Spotting the same file or directory when `--dedupe` finds it through
+//! more than one path, whether that’s several of the arguments, or an
+//! argument and a directory that gets recursed into.
+//!
+//! Anything one of the arguments leads to gets listed with a note of the
+//! other paths to it, and each directory’s contents only get listed once.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What identifies a file, so that the same file can be spotted when it’s
+/// reached through different paths: its device and inode numbers.
+#[cfg(unix)]
+pub type Identity = (u64, u64);
+
+/// What identifies a file. Windows has no inode numbers to go on, so this
+/// is the file’s canonical path instead.
+#[cfg(windows)]
+pub type Identity = PathBuf;
+
+/// Finds what identifies the file at the end of a path, following any
+/// symlinks.
+#[cfg(unix)]
+pub fn identity(path: &Path) -> Option<Identity> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Finds what identifies the file at the end of a path, following any
+/// symlinks.
+#[cfg(windows)]
+pub fn identity(path: &Path) -> Option<Identity> {
+    std::fs::canonicalize(path).ok()
+}
+
+/// The paths given as arguments, and the directories listed so far.
+#[derive(Debug, Default)]
+pub struct Dedupe {
+    /// Every path given as an argument, by what it leads to, in the order
+    /// they were given.
+    arguments: HashMap<Identity, Vec<PathBuf>>,
+
+    /// The directories whose contents have been listed.
+    listed: Mutex<HashSet<Identity>>,
+}
+
+impl Dedupe {
+    /// Notes a path given as an argument, returning whether it’s the first
+    /// one to lead to what it does. Paths that lead nowhere always are.
+    pub fn add_argument(&mut self, path: &Path) -> bool {
+        identity(path).map_or(true, |identity| self.add(identity, path))
+    }
+
+    /// The paths given as arguments that lead to the same thing as this
+    /// one, apart from the path itself.
+    pub fn aliases(&self, path: &Path) -> Vec<PathBuf> {
+        // With only one argument, there’s no other one to lead anywhere, so
+        // there’s no need to look anything up.
+        if self.arguments.values().map(Vec::len).sum::<usize>() < 2 {
+            return Vec::new();
+        }
+
+        identity(path)
+            .map(|identity| self.aliases_of(&identity, path))
+            .unwrap_or_default()
+    }
+
+    /// Notes that a directory’s contents are getting listed, returning
+    /// whether they haven’t been already.
+    pub fn first_listing(&self, path: &Path) -> bool {
+        identity(path).map_or(true, |identity| self.list(identity))
+    }
+
+    fn add(&mut self, identity: Identity, path: &Path) -> bool {
+        let paths = self.arguments.entry(identity).or_default();
+        paths.push(path.to_path_buf());
+        paths.len() == 1
+    }
+
+    fn aliases_of(&self, identity: &Identity, path: &Path) -> Vec<PathBuf> {
+        self.arguments
+            .get(identity)
+            .map(|paths| paths.iter().filter(|p| *p != path).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn list(&self, identity: Identity) -> bool {
+        self.listed.lock().unwrap().insert(identity)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arguments() {
+        let mut dedupe = Dedupe::default();
+        assert!(dedupe.add((1, 2), Path::new("a")));
+        assert!(dedupe.add((1, 3), Path::new("b")));
+        assert!(!dedupe.add((1, 2), Path::new("c")));
+
+        assert_eq!(
+            dedupe.aliases_of(&(1, 2), Path::new("a")),
+            vec![PathBuf::from("c")]
+        );
+        assert_eq!(
+            dedupe.aliases_of(&(1, 2), Path::new("a/sub/..")),
+            vec![PathBuf::from("a"), PathBuf::from("c")]
+        );
+        assert_eq!(
+            dedupe.aliases_of(&(1, 3), Path::new("b")),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn listings() {
+        let dedupe = Dedupe::default();
+        assert!(dedupe.list((1, 2)));
+        assert!(dedupe.list((1, 3)));
+        assert!(!dedupe.list((1, 2)));
+    }
+}
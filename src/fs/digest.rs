@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out the SHA-256 digests of files’ contents for `--hash`.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// How many characters a digest takes up when written in hexadecimal.
+pub const HEX_LENGTH: usize = 64;
+
+/// Hashes the contents of the file at the given path, returning the digest
+/// in lowercase hexadecimal.
+pub fn of_file(path: &Path) -> io::Result<String> {
+    of_reader(fs::File::open(path)?)
+}
+
+fn of_reader(mut reader: impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::with_capacity(HEX_LENGTH), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            of_reader(&b""[..]).unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn abc() {
+        let digest = of_reader(&b"abc"[..]).unwrap();
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(digest.len(), HEX_LENGTH);
+    }
+}
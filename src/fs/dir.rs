@@ -7,13 +7,14 @@
 use crate::fs::feature::git::GitCache;
 use crate::fs::fields::GitStatus;
 use std::fs;
-use std::fs::DirEntry;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
 
 use log::*;
 
+use crate::fs::manifest;
+use crate::fs::recursive_size::TotalSize;
 use crate::fs::File;
 
 /// A **Dir** provides a cached list of the file paths in a directory that’s
@@ -24,10 +25,27 @@ use crate::fs::File;
 /// accordingly. (See `File#get_source_files`)
 pub struct Dir {
     /// A vector of the files that have been read from this directory.
-    contents: Vec<DirEntry>,
+    contents: Vec<Entry>,
 
     /// The path that was read.
     pub path: PathBuf,
+
+    /// Whether this directory’s contents came from a manifest rather than
+    /// being read from the filesystem.
+    is_virtual: bool,
+}
+
+/// A file that was read from a directory. This holds on to what the
+/// `DirEntry` said rather than the `DirEntry` itself, because each one keeps
+/// the directory’s handle open for as long as it’s around, and recursing
+/// through a wide tree could otherwise run out of file descriptors.
+struct Entry {
+    path: PathBuf,
+    file_type: Option<fs::FileType>,
+
+    /// What a manifest says about the file, for one that isn’t on the
+    /// filesystem.
+    manifest: Option<manifest::Entry>,
 }
 
 impl Dir {
@@ -42,10 +60,42 @@ impl Dir {
     pub fn read_dir(path: PathBuf) -> io::Result<Self> {
         info!("Reading directory {:?}", &path);
 
-        let contents = fs::read_dir(&path)?.collect::<Result<Vec<_>, _>>()?;
+        let contents = fs::read_dir(&path)?
+            .map(|entry| {
+                entry.map(|entry| Entry {
+                    path: entry.path(),
+                    file_type: entry.file_type().ok(),
+                    manifest: None,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         info!("Read directory success {:?}", &path);
-        Ok(Self { contents, path })
+        Ok(Self {
+            contents,
+            path,
+            is_virtual: false,
+        })
+    }
+
+    /// Create a Dir object holding entries from a manifest, which get
+    /// listed as though they’d been read from a directory with the given
+    /// path.
+    pub fn from_manifest(path: PathBuf, entries: Vec<manifest::Entry>) -> Self {
+        let contents = entries
+            .into_iter()
+            .map(|entry| Entry {
+                path: PathBuf::from(&entry.name),
+                file_type: None,
+                manifest: Some(entry),
+            })
+            .collect();
+
+        Self {
+            contents,
+            path,
+            is_virtual: true,
+        }
     }
 
     /// Produce an iterator of IO results of trying to read all the files in
@@ -56,13 +106,26 @@ impl Dir {
         git: Option<&'ig GitCache>,
         git_ignoring: bool,
         deref_links: bool,
-        total_size: bool,
+        total_size: Option<TotalSize>,
     ) -> Files<'dir, 'ig> {
+        if let Some(git) = git.filter(|_| !self.is_virtual) {
+            git.discover_nested(&self.path);
+        }
+
+        // There’s nothing on the filesystem for `.` and `..` to be when the
+        // entries came from a manifest.
+        let next = if self.is_virtual {
+            DotsNext::Files
+        } else {
+            dots.dots()
+        };
+
         Files {
             inner: self.contents.iter(),
             dir: self,
             dotfiles: dots.shows_dotfiles(),
-            dots: dots.dots(),
+            windows_hidden: dots.shows_windows_hidden(),
+            dots: next,
             git,
             git_ignoring,
             deref_links,
@@ -72,7 +135,7 @@ impl Dir {
 
     /// Whether this directory contains a file with the given path.
     pub fn contains(&self, path: &Path) -> bool {
-        self.contents.iter().any(|p| p.path().as_path() == path)
+        self.contents.iter().any(|entry| entry.path == path)
     }
 
     /// Append a path onto the path specified by this directory.
@@ -85,7 +148,7 @@ impl Dir {
 #[allow(clippy::struct_excessive_bools)]
 pub struct Files<'dir, 'ig> {
     /// The internal iterator over the paths that have been read already.
-    inner: SliceIter<'dir, DirEntry>,
+    inner: SliceIter<'dir, Entry>,
 
     /// The directory that begat those paths.
     dir: &'dir Dir,
@@ -93,6 +156,11 @@ pub struct Files<'dir, 'ig> {
     /// Whether to include dotfiles in the list.
     dotfiles: bool,
 
+    /// Whether to include files with the Windows hidden attribute in the
+    /// list. This only has an effect on Windows.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    windows_hidden: bool,
+
     /// Whether the `.` or `..` directories should be produced first, before
     /// any files have been listed.
     dots: DotsNext,
@@ -104,8 +172,8 @@ pub struct Files<'dir, 'ig> {
     /// Whether symbolic links should be dereferenced when querying information.
     deref_links: bool,
 
-    /// Whether to calculate the directory size recursively
-    total_size: bool,
+    /// How to calculate the directory size recursively, if it should be.
+    total_size: Option<TotalSize>,
 }
 
 impl<'dir, 'ig> Files<'dir, 'ig> {
@@ -123,7 +191,7 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
     fn next_visible_file(&mut self) -> Option<File<'dir>> {
         loop {
             if let Some(entry) = self.inner.next() {
-                let path = entry.path();
+                let path = entry.path.clone();
                 let filename = File::filename(&path);
                 if !self.dotfiles && filename.starts_with('.') {
                     continue;
@@ -136,6 +204,12 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
                     continue;
                 }
 
+                if let Some(entry) = &entry.manifest {
+                    let mut file: File<'dir> = File::from_manifest(entry.clone());
+                    file.parent_dir = Some(self.dir);
+                    return Some(file);
+                }
+
                 if self.git_ignoring {
                     let git_status = self.git.map(|g| g.get(&path, false)).unwrap_or_default();
                     if git_status.unstaged == GitStatus::Ignored {
@@ -149,13 +223,14 @@ impl<'dir, 'ig> Files<'dir, 'ig> {
                     filename,
                     self.deref_links,
                     self.total_size,
-                    entry.file_type().ok(),
+                    entry.file_type,
                 );
 
                 // Windows has its own concept of hidden files, when dotfiles are
-                // hidden Windows hidden files should also be filtered out
+                // hidden Windows hidden files should also be filtered out,
+                // unless they’ve been asked for with --no-windows-hidden.
                 #[cfg(windows)]
-                if !self.dotfiles && file.attributes().map_or(false, |a| a.hidden) {
+                if !self.windows_hidden && file.attributes().map_or(false, |a| a.hidden) {
                     continue;
                 }
 
@@ -215,25 +290,38 @@ pub enum DotFilter {
     /// Show files and dotfiles, but hide `.` and `..`.
     Dotfiles,
 
-    /// Just show files, hiding anything beginning with a dot.
+    /// Just show files, hiding anything beginning with a dot, and on
+    /// Windows anything with the hidden attribute.
     #[default]
     JustFiles,
+
+    /// Hide anything beginning with a dot, but show files with the Windows
+    /// hidden attribute.
+    JustFilesAndWindowsHidden,
 }
 
 impl DotFilter {
     /// Whether this filter should show dotfiles in a listing.
-    fn shows_dotfiles(self) -> bool {
+    pub fn shows_dotfiles(self) -> bool {
         match self {
             Self::JustFiles => false,
+            Self::JustFilesAndWindowsHidden => false,
             Self::Dotfiles => true,
             Self::DotfilesAndDots => true,
         }
     }
 
+    /// Whether this filter should show files with the Windows hidden
+    /// attribute in a listing.
+    fn shows_windows_hidden(self) -> bool {
+        self != Self::JustFiles
+    }
+
     /// Whether this filter should add dot directories to a listing.
     fn dots(self) -> DotsNext {
         match self {
             Self::JustFiles => DotsNext::Files,
+            Self::JustFilesAndWindowsHidden => DotsNext::Files,
             Self::Dotfiles => DotsNext::Files,
             Self::DotfilesAndDots => DotsNext::Dot,
         }
@@ -70,6 +70,15 @@ pub struct RecurseOptions {
     /// The maximum number of times that recursion should descend to, if one
     /// is specified.
     pub max_depth: Option<usize>,
+
+    /// Whether everything beneath each directory should be listed together,
+    /// with the paths of its files relative to it, rather than in a listing
+    /// for each directory.
+    pub flat: bool,
+
+    /// How many levels down files have to be to get listed, with the files
+    /// in the directories given as arguments being one level down.
+    pub min_depth: usize,
 }
 
 impl RecurseOptions {
@@ -80,4 +89,9 @@ impl RecurseOptions {
             Some(d) => d <= depth,
         }
     }
+
+    /// Returns whether files of the given depth are too shallow to list.
+    pub fn is_too_shallow(self, depth: usize) -> bool {
+        depth < self.min_depth
+    }
 }
@@ -0,0 +1,299 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! POSIX access control lists, which Linux keeps in the
+//! `system.posix_acl_access` and `system.posix_acl_default` extended
+//! attributes, so they come along with the rest of a file’s attributes.
+//!
+//! Each attribute holds a version number followed by a list of entries,
+//! each of which is a tag saying who it applies to, the read, write, and
+//! execute bits, and a user or group ID for the entries that name one.
+
+use std::fmt::{self, Display, Formatter};
+
+use super::xattr::Attribute;
+
+/// Whether ACLs can be read on this platform.
+pub const ENABLED: bool = cfg!(target_os = "linux");
+
+/// The attribute holding the ACL that’s checked when accessing a file.
+pub const ACCESS: &str = "system.posix_acl_access";
+
+/// The attribute holding the ACL that new files in a directory inherit.
+pub const DEFAULT: &str = "system.posix_acl_default";
+
+const VERSION: u32 = 2;
+
+/// Whether the attribute with this name holds an ACL, rather than being
+/// an attribute in its own right.
+pub fn is_acl_attribute(name: &str) -> bool {
+    name == ACCESS || name == DEFAULT
+}
+
+/// A file’s access ACL and, for a directory, its default ACL.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Acl {
+    pub access: Vec<Entry>,
+    pub default: Vec<Entry>,
+}
+
+/// Who an entry applies to.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Tag {
+    /// The file’s owner, as with the user permission bits.
+    Owner,
+
+    /// A user named by ID.
+    User(u32),
+
+    /// The file’s group, as with the group permission bits.
+    OwningGroup,
+
+    /// A group named by ID.
+    Group(u32),
+
+    /// The most that any named user or group, or the owning group, is
+    /// allowed.
+    Mask,
+
+    /// Everyone else, as with the other permission bits.
+    Other,
+}
+
+/// One entry in an ACL.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Entry {
+    pub tag: Tag,
+
+    /// The read, write, and execute bits, as in the lowest three bits of
+    /// a file’s mode.
+    pub perms: u16,
+}
+
+impl Acl {
+    /// Reads the ACLs out of a file’s extended attributes, if it has any.
+    pub fn from_attributes(attributes: &[Attribute]) -> Option<Self> {
+        let entries = |name| {
+            attributes
+                .iter()
+                .find(|a| a.name == name)
+                .and_then(|a| parse(a.value.as_deref()?))
+                .unwrap_or_default()
+        };
+
+        let acl = Self {
+            access: entries(ACCESS),
+            default: entries(DEFAULT),
+        };
+
+        (!acl.access.is_empty() || !acl.default.is_empty()).then_some(acl)
+    }
+
+    /// Whether this ACL gives anyone more than the permission bits can
+    /// express, in which case the permissions get a `+` after them.
+    pub fn is_extended(&self) -> bool {
+        !self.default.is_empty()
+            || self
+                .access
+                .iter()
+                .any(|e| matches!(e.tag, Tag::User(_) | Tag::Group(_) | Tag::Mask))
+    }
+
+    /// The entries as `getfacl` lists them, with the default ACL’s entries
+    /// after the access ACL’s.
+    pub fn lines(&self, names: impl Fn(Tag) -> Option<String>) -> Vec<String> {
+        let mut lines = Vec::with_capacity(self.access.len() + self.default.len());
+        for (entries, prefix) in [(&self.access, ""), (&self.default, "default:")] {
+            let mask = entries.iter().find(|e| e.tag == Tag::Mask).map(|e| e.perms);
+            for entry in entries {
+                let name = names(entry.tag).unwrap_or_else(|| match entry.tag {
+                    Tag::User(id) | Tag::Group(id) => id.to_string(),
+                    _ => String::new(),
+                });
+
+                let mut line = format!("{prefix}{}:{name}:{}", entry.tag, Perms(entry.perms));
+
+                // Anything the mask takes away is only there for show.
+                let masked = matches!(entry.tag, Tag::User(_) | Tag::Group(_) | Tag::OwningGroup);
+                if let Some(effective) = mask.map(|m| m & entry.perms).filter(|_| masked) {
+                    if effective != entry.perms {
+                        line.push_str(&format!("  #effective:{}", Perms(effective)));
+                    }
+                }
+
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+/// Looks up the name of the user or group that an entry names.
+#[cfg(unix)]
+pub fn name_of(tag: Tag) -> Option<String> {
+    use uzers::{get_group_by_gid, get_user_by_uid};
+
+    match tag {
+        Tag::User(id) => get_user_by_uid(id).map(|u| u.name().to_string_lossy().into_owned()),
+        Tag::Group(id) => get_group_by_gid(id).map(|g| g.name().to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn name_of(_tag: Tag) -> Option<String> {
+    None
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Owner | Self::User(_) => "user",
+            Self::OwningGroup | Self::Group(_) => "group",
+            Self::Mask => "mask",
+            Self::Other => "other",
+        })
+    }
+}
+
+/// Read, write, and execute bits, written as `rwx`.
+struct Perms(u16);
+
+impl Display for Perms {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let bit = |mask, c| if self.0 & mask == 0 { '-' } else { c };
+        write!(f, "{}{}{}", bit(4, 'r'), bit(2, 'w'), bit(1, 'x'))
+    }
+}
+
+/// Parses the entries out of an ACL attribute’s value, or nothing if it
+/// isn’t in the format Linux uses.
+fn parse(value: &[u8]) -> Option<Vec<Entry>> {
+    let (version, entries) = value.split_first_chunk::<4>()?;
+    if u32::from_le_bytes(*version) != VERSION || entries.len() % 8 != 0 {
+        return None;
+    }
+
+    entries
+        .chunks_exact(8)
+        .map(|chunk| {
+            let tag = u16::from_le_bytes([chunk[0], chunk[1]]);
+            let perms = u16::from_le_bytes([chunk[2], chunk[3]]) & 0o7;
+            let id = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+            let tag = match tag {
+                0x01 => Tag::Owner,
+                0x02 => Tag::User(id),
+                0x04 => Tag::OwningGroup,
+                0x08 => Tag::Group(id),
+                0x10 => Tag::Mask,
+                0x20 => Tag::Other,
+                _ => return None,
+            };
+            Some(Entry { tag, perms })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attribute(name: &str, entries: &[(u16, u16, u32)]) -> Attribute {
+        let mut value = VERSION.to_le_bytes().to_vec();
+        for (tag, perms, id) in entries {
+            value.extend(tag.to_le_bytes());
+            value.extend(perms.to_le_bytes());
+            value.extend(id.to_le_bytes());
+        }
+        Attribute {
+            name: name.into(),
+            value: Some(value),
+        }
+    }
+
+    const NONE: u32 = u32::MAX;
+
+    #[test]
+    fn no_acl() {
+        let attributes = [Attribute {
+            name: "user.project".into(),
+            value: Some(b"apollo".to_vec()),
+        }];
+        assert_eq!(Acl::from_attributes(&attributes), None);
+    }
+
+    #[test]
+    fn minimal() {
+        let attributes = [attribute(
+            ACCESS,
+            &[(0x01, 6, NONE), (0x04, 4, NONE), (0x20, 4, NONE)],
+        )];
+        let acl = Acl::from_attributes(&attributes).unwrap();
+        assert!(!acl.is_extended());
+        assert_eq!(
+            acl.lines(|_| None),
+            vec!["user::rw-", "group::r--", "other::r--"]
+        );
+    }
+
+    #[test]
+    fn named_entries() {
+        let attributes = [attribute(
+            ACCESS,
+            &[
+                (0x01, 6, NONE),
+                (0x02, 7, 1000),
+                (0x04, 4, NONE),
+                (0x08, 6, 50),
+                (0x10, 5, NONE),
+                (0x20, 0, NONE),
+            ],
+        )];
+        let acl = Acl::from_attributes(&attributes).unwrap();
+        assert!(acl.is_extended());
+
+        let names = |tag| match tag {
+            Tag::User(1000) => Some("alice".into()),
+            _ => None,
+        };
+        assert_eq!(
+            acl.lines(names),
+            vec![
+                "user::rw-",
+                "user:alice:rwx  #effective:r-x",
+                "group::r--",
+                "group:50:rw-  #effective:r--",
+                "mask::r-x",
+                "other::---",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_acl() {
+        let attributes = [attribute(
+            DEFAULT,
+            &[(0x01, 7, NONE), (0x04, 5, NONE), (0x20, 5, NONE)],
+        )];
+        let acl = Acl::from_attributes(&attributes).unwrap();
+        assert!(acl.is_extended());
+        assert_eq!(
+            acl.lines(|_| None),
+            vec![
+                "default:user::rwx",
+                "default:group::r-x",
+                "default:other::r-x"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_version() {
+        let mut attribute = attribute(ACCESS, &[(0x01, 6, NONE)]);
+        attribute.value.as_mut().unwrap()[0] = 1;
+        assert_eq!(Acl::from_attributes(&[attribute]), None);
+    }
+}
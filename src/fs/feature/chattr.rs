@@ -0,0 +1,176 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The inode flags a Linux file has, which are what `chattr` sets and
+//! `lsattr` lists.
+//!
+//! These get read with the `FS_IOC_GETFLAGS` ioctl, which ext4, xfs, btrfs,
+//! and most other Linux filesystems support. That needs the file opening,
+//! so it’s only done for regular files and directories, as with `lsattr`;
+//! for anything else, the few attributes `statx` reports stand in for them.
+//!
+//! The `FS_IOC_FSGETXATTR` ioctl gets asked the same way for the ID of the
+//! project a file is in, which ext4 and xfs use for project quotas, as
+//! `lsattr -p` lists.
+
+use std::path::Path;
+
+pub const ENABLED: bool = cfg!(target_os = "linux");
+
+/// The flags that get listed, with the letters `lsattr` lists them as, in
+/// the order it lists them in. Flags the filesystem sets by itself, such as
+/// whether a file uses extents, are left out.
+#[cfg(any(target_os = "linux", test))]
+const LETTERS: &[(u32, &str)] = &[
+    (0x0000_0001, "s"), // secure deletion
+    (0x0000_0002, "u"), // undeletable
+    (0x0000_0008, "S"), // synchronous updates
+    (0x0001_0000, "D"), // synchronous directory updates
+    (0x0000_0010, "i"), // immutable
+    (0x0000_0020, "a"), // append only
+    (0x0000_0040, "d"), // no dump
+    (0x0000_0080, "A"), // no atime updates
+    (0x0000_0004, "c"), // compressed
+    (0x0200_0000, "x"), // direct access
+    (0x0000_0800, "E"), // encrypted
+    (0x0000_4000, "j"), // data journalling
+    (0x0000_8000, "t"), // no tail-merging
+    (0x0002_0000, "T"), // top of directory hierarchy
+    (0x0080_0000, "C"), // no copy on write
+    (0x4000_0000, "F"), // case-insensitive directory
+    (0x2000_0000, "P"), // project hierarchy
+    (0x0010_0000, "V"), // verity
+    (0x0000_0400, "m"), // don’t compress
+];
+
+/// The letters for the flags set in the given bits.
+#[cfg(any(target_os = "linux", test))]
+pub fn letters(flags: u32) -> impl Iterator<Item = &'static str> {
+    LETTERS
+        .iter()
+        .filter(move |(bit, _)| flags & bit != 0)
+        .map(|(_, letter)| *letter)
+}
+
+/// Turns the attributes `statx` reports into the flags they mirror. These
+/// share their bits, apart from direct access.
+#[cfg(any(target_os = "linux", test))]
+pub fn from_statx(attributes: u32) -> u32 {
+    // Compressed, immutable, append only, no dump, encrypted, and verity.
+    const SHARED: u32 =
+        0x0000_0004 | 0x0000_0010 | 0x0000_0020 | 0x0000_0040 | 0x0000_0800 | 0x0010_0000;
+    const STATX_ATTR_DAX: u32 = 0x0020_0000;
+    const FS_DAX_FL: u32 = 0x0200_0000;
+
+    let shared = attributes & SHARED;
+    if attributes & STATX_ATTR_DAX == 0 {
+        shared
+    } else {
+        shared | FS_DAX_FL
+    }
+}
+
+/// Reads the flags of the file or directory at the given path, without
+/// following it if it’s a symlink.
+#[cfg(target_os = "linux")]
+pub fn read(path: &Path) -> Option<u32> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = open(path)?;
+
+    // The kernel writes an `int`, whatever size the ioctl’s number says.
+    let mut flags: libc::c_int = 0;
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) };
+    (result == 0).then_some(flags as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Reads the project ID of the file or directory at the given path,
+/// without following it if it’s a symlink. Files that were never put in a
+/// project are in project 0.
+#[cfg(target_os = "linux")]
+pub fn project_id(path: &Path) -> Option<u32> {
+    use std::os::unix::io::AsRawFd;
+
+    // `_IOR('X', 31, struct fsxattr)`, which wraps around to a negative
+    // number with musl, where requests are an `int`.
+    #[allow(overflowing_literals)]
+    const FS_IOC_FSGETXATTR: libc::Ioctl = 0x801C_581F;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct FsXattr {
+        xflags: u32,
+        extsize: u32,
+        nextents: u32,
+        projid: u32,
+        cowextsize: u32,
+        pad: [u8; 8],
+    }
+
+    let file = open(path)?;
+    let mut attr = FsXattr::default();
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FSGETXATTR, &mut attr) };
+    (result == 0).then_some(attr.projid)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn project_id(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Opens a file or directory just to ask it for its attributes, without
+/// blocking on a FIFO or following a symlink.
+#[cfg(target_os = "linux")]
+fn open(path: &Path) -> Option<std::fs::File> {
+    use std::fs::OpenOptions;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK | libc::O_NOFOLLOW)
+        .open(path)
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_flags() {
+        assert_eq!(letters(0).count(), 0);
+    }
+
+    #[test]
+    fn letters_in_order() {
+        let letters: Vec<_> = letters(0x0080_0000 | 0x0000_0020 | 0x0000_0010).collect();
+        assert_eq!(letters, ["i", "a", "C"]);
+    }
+
+    #[test]
+    fn filesystem_flags() {
+        // EXT4_EXTENTS_FL is on nearly every ext4 file, so isn’t worth listing.
+        assert_eq!(letters(0x0008_0000).count(), 0);
+    }
+
+    #[test]
+    fn statx_attributes() {
+        // Immutable and no dump keep their bits; direct access moves.
+        assert_eq!(from_statx(0x0000_0050), 0x0000_0050);
+        assert_eq!(from_statx(0x0020_0000), 0x0200_0000);
+    }
+
+    #[test]
+    fn unknown_statx_attributes() {
+        // STATX_ATTR_MOUNT_ROOT isn’t an attribute of the file itself.
+        assert_eq!(from_statx(0x0000_2000), 0);
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Details of where a file lives on a copy-on-write filesystem.
+//!
+//! A btrfs filesystem is split into subvolumes, each with a numeric ID,
+//! and files on it can have a compression algorithm set as a property. A
+//! ZFS pool is split into datasets, each mounted separately, so the dataset
+//! a file is on is the source of the mount it’s under.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::fs::mounts::MountedFs;
+
+pub const ENABLED: bool = cfg!(target_os = "linux");
+
+/// The extended attribute btrfs keeps a file’s compression property in.
+pub const COMPRESSION_ATTRIBUTE: &str = "btrfs.compression";
+
+/// The mount the file at this absolute path is under, which is the mount
+/// on the closest of its ancestors, or on the path itself.
+pub fn containing_mount<'a>(
+    path: &Path,
+    mounts: &'a HashMap<PathBuf, MountedFs>,
+) -> Option<&'a MountedFs> {
+    path.ancestors().find_map(|dir| mounts.get(dir))
+}
+
+/// The ID of the btrfs subvolume the directory at this path is in, which
+/// anyone who can open the directory is allowed to ask for.
+#[cfg(target_os = "linux")]
+pub fn subvolume_id(dir: &Path) -> Option<u64> {
+    use std::os::unix::io::AsRawFd;
+
+    // `_IOWR(0x94, 18, struct btrfs_ioctl_ino_lookup_args)`, which wraps
+    // around to a negative number with musl, where requests are an `int`.
+    #[allow(overflowing_literals)]
+    const BTRFS_IOC_INO_LOOKUP: libc::Ioctl = 0xD000_9412;
+
+    // Looking up the first inode a subvolume can have, with no tree given,
+    // gives back the tree, which is the subvolume, of the open file.
+    const BTRFS_FIRST_FREE_OBJECTID: u64 = 256;
+
+    #[repr(C)]
+    struct InoLookupArgs {
+        treeid: u64,
+        objectid: u64,
+        name: [u8; 4080],
+    }
+
+    let dir = std::fs::File::open(dir).ok()?;
+    let mut args = InoLookupArgs {
+        treeid: 0,
+        objectid: BTRFS_FIRST_FREE_OBJECTID,
+        name: [0; 4080],
+    };
+
+    let result = unsafe { libc::ioctl(dir.as_raw_fd(), BTRFS_IOC_INO_LOOKUP, &mut args) };
+    (result == 0).then_some(args.treeid)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn subvolume_id(_dir: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mount(dest: &str, fstype: &str, source: &str) -> (PathBuf, MountedFs) {
+        let mount = MountedFs {
+            dest: PathBuf::from(dest),
+            fstype: fstype.into(),
+            source: source.into(),
+        };
+        (mount.dest.clone(), mount)
+    }
+
+    #[test]
+    fn closest_mount() {
+        let mounts = HashMap::from([
+            mount("/", "ext4", "/dev/sda1"),
+            mount("/tank", "zfs", "tank"),
+            mount("/tank/home", "zfs", "tank/home"),
+        ]);
+
+        let found = containing_mount(Path::new("/tank/home/me/notes.txt"), &mounts);
+        assert_eq!(found.map(|m| m.source.as_str()), Some("tank/home"));
+
+        let found = containing_mount(Path::new("/tank/media"), &mounts);
+        assert_eq!(found.map(|m| m.source.as_str()), Some("tank"));
+    }
+
+    #[test]
+    fn mount_point_itself() {
+        let mounts = HashMap::from([
+            mount("/", "ext4", "/dev/sda1"),
+            mount("/tank", "zfs", "tank"),
+        ]);
+
+        let found = containing_mount(Path::new("/tank"), &mounts);
+        assert_eq!(found.map(|m| m.source.as_str()), Some("tank"));
+    }
+
+    #[test]
+    fn no_mounts() {
+        assert!(containing_mount(Path::new("/home"), &HashMap::new()).is_none());
+    }
+}
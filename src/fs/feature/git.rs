@@ -6,14 +6,16 @@
 // SPDX-License-Identifier: MIT
 //! Getting the Git status of files and directories.
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 use std::ffi::OsStr;
 #[cfg(target_family = "unix")]
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use log::*;
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use crate::fs::fields as f;
 
@@ -22,25 +24,104 @@ use crate::fs::fields as f;
 /// This uses vectors to avoid the overhead of hashing: it’s not worth it when the
 /// expected number of Git repositories per exa invocation is 0 or 1...
 pub struct GitCache {
-    /// A list of discovered Git repositories and their paths.
-    repos: Vec<GitRepo>,
-
-    /// Paths that we’ve confirmed do not have Git repositories underneath them.
-    misses: Vec<PathBuf>,
+    /// A list of discovered Git repositories and their paths, which gets
+    /// added to as repositories nested inside them are found.
+    repos: RwLock<Vec<Arc<GitRepo>>>,
 }
 
 impl GitCache {
     pub fn has_anything_for(&self, index: &Path) -> bool {
-        self.repos.iter().any(|e| e.has_path(index))
+        self.repos.read().unwrap().iter().any(|e| e.has_path(index))
     }
 
     pub fn get(&self, index: &Path, prefix_lookup: bool) -> f::Git {
-        self.repos
-            .iter()
-            .find(|repo| repo.has_path(index))
+        self.repo_for(index)
             .map(|repo| repo.search(index, prefix_lookup))
             .unwrap_or_default()
     }
+
+    /// The repository a path is in, which is the one nearest to it when
+    /// repositories are nested inside each other. A nested repository’s own
+    /// directory is looked up in the one it’s inside, if that’s been found,
+    /// as that’s what says whether the directory is ignored.
+    fn repo_for(&self, index: &Path) -> Option<Arc<GitRepo>> {
+        self.repos
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|repo| Some((nearness(repo.nearest_root(index)?, index), repo)))
+            .max_by_key(|(nearness, _)| *nearness)
+            .map(|(_, repo)| Arc::clone(repo))
+    }
+
+    /// Adds the repository at the given directory, if it’s the root of one
+    /// that hasn’t been found yet, so that the files in it get looked up in
+    /// that repository rather than in the one it’s nested inside. This gets
+    /// done for each directory as it’s listed, as it’s only while recursing
+    /// that nested repositories turn up.
+    pub fn discover_nested(&self, dir: &Path) {
+        if !dir.join(".git").exists() {
+            return;
+        }
+        if self.repos.read().unwrap().iter().any(|e| e.has_root(dir)) {
+            return;
+        }
+
+        let flags = git2::RepositoryOpenFlags::NO_SEARCH;
+        let Ok(repo) = GitRepo::discover(dir.to_path_buf(), flags) else {
+            return;
+        };
+
+        // Another thread could have found it while this one was opening it.
+        let mut repos = self.repos.write().unwrap();
+        if repos
+            .iter()
+            .any(|e| e.has_root(dir) || e.has_workdir(&repo.workdir))
+        {
+            return;
+        }
+
+        debug!("Discovered nested Git repo {:?}", repo.workdir);
+        repos.push(Arc::new(repo));
+    }
+
+    /// Queries every repository for its statuses up front, in parallel,
+    /// rather than when the first file in each gets listed. Files get
+    /// listed in parallel too, so a large repository would otherwise hold
+    /// up every thread listing files in it while one thread queries it.
+    pub fn load_statuses(&self) {
+        let repos = self.repos.read().unwrap();
+        repos.par_iter().for_each(|repo| {
+            let mut contents = repo.contents.lock().unwrap();
+            repo.query(&mut contents, true);
+        });
+    }
+
+    /// The last commit to change the file or directory at the given path,
+    /// if it’s in a repository and has been committed.
+    pub fn last_commit(&self, index: &Path) -> Option<f::GitCommit> {
+        self.repo_for(index)
+            .and_then(|repo| repo.last_commit(index))
+    }
+
+    /// The author who wrote the most lines of the file at the given path,
+    /// if it’s in a repository and has been committed.
+    pub fn owner(&self, index: &Path) -> Option<String> {
+        self.repo_for(index).and_then(|repo| repo.owner(index))
+    }
+
+    /// The first tag to include the last commit to change the file or
+    /// directory at the given path, if it’s been tagged since.
+    pub fn since_tag(&self, index: &Path) -> Option<String> {
+        self.repo_for(index).and_then(|repo| repo.since_tag(index))
+    }
+
+    /// Whether the file at the given path is tracked, or, with prefix
+    /// lookup, whether anything inside the directory at it is.
+    pub fn is_tracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        self.repo_for(index)
+            .is_some_and(|repo| repo.is_tracked(index, prefix_lookup))
+    }
 }
 
 use std::iter::FromIterator;
@@ -50,10 +131,10 @@ impl FromIterator<PathBuf> for GitCache {
         I: IntoIterator<Item = PathBuf>,
     {
         let iter = iter.into_iter();
-        let mut git = Self {
-            repos: Vec::with_capacity(iter.size_hint().0),
-            misses: Vec::new(),
-        };
+        let mut repos: Vec<GitRepo> = Vec::with_capacity(iter.size_hint().0);
+
+        // Paths that we’ve confirmed do not have Git repositories underneath them.
+        let mut misses = Vec::new();
 
         if let Ok(path) = env::var("GIT_DIR") {
             // These flags are consistent with how `git` uses GIT_DIR:
@@ -61,24 +142,24 @@ impl FromIterator<PathBuf> for GitCache {
             match GitRepo::discover(path.into(), flags) {
                 Ok(repo) => {
                     debug!("Opened GIT_DIR repo");
-                    git.repos.push(repo);
+                    repos.push(repo);
                 }
                 Err(miss) => {
-                    git.misses.push(miss);
+                    misses.push(miss);
                 }
             }
         }
 
         for path in iter {
-            if git.misses.contains(&path) {
+            if misses.contains(&path) {
                 debug!("Skipping {:?} because it already came back Gitless", path);
-            } else if git.repos.iter().any(|e| e.has_path(&path)) {
+            } else if repos.iter().any(|e| e.has_path(&path)) {
                 debug!("Skipping {:?} because we already queried it", path);
             } else {
                 let flags = git2::RepositoryOpenFlags::FROM_ENV;
                 match GitRepo::discover(path, flags) {
                     Ok(r) => {
-                        if let Some(r2) = git.repos.iter_mut().find(|e| e.has_workdir(&r.workdir)) {
+                        if let Some(r2) = repos.iter_mut().find(|e| e.has_workdir(&r.workdir)) {
                             debug!(
                                 "Adding to existing repo (workdir matches with {:?})",
                                 r2.workdir
@@ -88,19 +169,28 @@ impl FromIterator<PathBuf> for GitCache {
                         }
 
                         debug!("Discovered new Git repo");
-                        git.repos.push(r);
+                        repos.push(r);
                     }
                     Err(miss) => {
-                        git.misses.push(miss);
+                        misses.push(miss);
                     }
                 }
             }
         }
 
-        git
+        Self {
+            repos: RwLock::new(repos.into_iter().map(Arc::new).collect()),
+        }
     }
 }
 
+/// How near a repository found by looking at the given root is to a path
+/// inside it, with nearer ones comparing as greater: deeper roots are
+/// nearer, but any root is nearer than the path itself.
+fn nearness(root: &Path, index: &Path) -> (bool, usize) {
+    (root != index, root.components().count())
+}
+
 /// A **Git repository** is one we’ve discovered somewhere on the filesystem.
 pub struct GitRepo {
     /// The queryable contents of the repository: either a `git2` repo, or the
@@ -119,6 +209,43 @@ pub struct GitRepo {
     /// Any other paths that were checked only to result in this same
     /// repository.
     extra_paths: Vec<PathBuf>,
+
+    /// The last commit to change each path in the repository, which is only
+    /// worked out when it’s first needed, as it means going through the
+    /// history.
+    commits: OnceLock<HashMap<PathBuf, (git2::Oid, f::GitCommit)>>,
+
+    /// The path of every file in the repository’s index, in order, which is
+    /// only read when it’s first needed.
+    tracked: OnceLock<Vec<PathBuf>>,
+
+    /// What’s been worked out from the history one file or commit at a
+    /// time, as it’s needed.
+    history: Mutex<History>,
+}
+
+/// What’s been looked up in a repository’s history so far, along with the
+/// repository to look up the rest in, which is only opened when the first
+/// lookup happens.
+#[derive(Default)]
+struct History {
+    repo: Option<git2::Repository>,
+
+    /// The author who wrote the most lines of each file that’s been blamed.
+    owners: HashMap<PathBuf, Option<String>>,
+
+    /// The repository’s tags, oldest first, once they’ve been read.
+    tags: Option<Vec<Tag>>,
+
+    /// The first tag to include each commit that’s been looked up.
+    since_tags: HashMap<git2::Oid, Option<String>>,
+}
+
+/// A tag, with the commit it points to and when that was made.
+struct Tag {
+    name: String,
+    id: git2::Oid,
+    time: i64,
 }
 
 /// A repository’s queried state.
@@ -147,20 +274,120 @@ impl GitRepo {
     /// repository is moved out, but before the results have been moved in!
     /// See <https://stackoverflow.com/q/45985827/3484614>
     fn search(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+        let mut contents = self.contents.lock().unwrap();
+        self.query(&mut contents, false)
+            .status(index, prefix_lookup)
+    }
+
+    /// Queries the repository for its statuses, if they haven’t been
+    /// already, splitting the work up between threads if it’s allowed to.
+    fn query<'c>(&self, contents: &'c mut GitContents, parallel: bool) -> &'c Git {
         use std::mem::replace;
 
-        let mut contents = self.contents.lock().unwrap();
-        if let GitContents::After { ref statuses } = *contents {
+        if let GitContents::Before { .. } = *contents {
+            debug!("Querying Git repo {:?} for the first time", &self.workdir);
+            let repo = replace(contents, GitContents::Processing).inner_repo();
+            let statuses = repo_to_statuses(&repo, &self.workdir, parallel);
+            let _processing = replace(contents, GitContents::After { statuses });
+        } else {
             debug!("Git repo {:?} has been found in cache", &self.workdir);
-            return statuses.status(index, prefix_lookup);
         }
 
-        debug!("Querying Git repo {:?} for the first time", &self.workdir);
-        let repo = replace(&mut *contents, GitContents::Processing).inner_repo();
-        let statuses = repo_to_statuses(&repo, &self.workdir);
-        let result = statuses.status(index, prefix_lookup);
-        let _processing = replace(&mut *contents, GitContents::After { statuses });
-        result
+        match contents {
+            GitContents::After { statuses } => statuses,
+            _ => unreachable!("Tried to search a repository being queried"),
+        }
+    }
+
+    /// Looks up the last commit to change a path, going through the
+    /// repository’s history the first time.
+    fn last_commit(&self, index: &Path) -> Option<f::GitCommit> {
+        self.last_commit_with_id(index)
+            .map(|(_, commit)| commit.clone())
+    }
+
+    fn last_commit_with_id(&self, index: &Path) -> Option<&(git2::Oid, f::GitCommit)> {
+        let commits = self.commits.get_or_init(|| {
+            debug!("Going through Git history of {:?}", &self.workdir);
+            repo_to_commits(&self.workdir).unwrap_or_else(|e| {
+                error!("Error going through Git history: {e:?}");
+                HashMap::new()
+            })
+        });
+
+        commits.get(&reorient(index))
+    }
+
+    /// Looks up who wrote the most lines of a file, blaming it the first
+    /// time it gets looked up.
+    fn owner(&self, index: &Path) -> Option<String> {
+        let mut history = self.history.lock().unwrap();
+        let History { repo, owners, .. } = &mut *history;
+
+        owners
+            .entry(reorient(index))
+            .or_insert_with_key(|path| {
+                let repo = open_once(repo, &self.workdir)?;
+                let relative = path.strip_prefix(&self.workdir).ok()?;
+                blame_owner(repo, relative).unwrap_or_else(|e| {
+                    debug!("Error blaming {path:?}: {e:?}");
+                    None
+                })
+            })
+            .clone()
+    }
+
+    /// Looks up the first tag to include the last commit to change a path,
+    /// reading the tags the first time.
+    fn since_tag(&self, index: &Path) -> Option<String> {
+        let (id, commit) = self.last_commit_with_id(index)?;
+
+        let mut history = self.history.lock().unwrap();
+        let History {
+            repo,
+            tags,
+            since_tags,
+            ..
+        } = &mut *history;
+
+        since_tags
+            .entry(*id)
+            .or_insert_with(|| {
+                let repo = open_once(repo, &self.workdir)?;
+                let tags = tags.get_or_insert_with(|| {
+                    debug!("Reading Git tags of {:?}", &self.workdir);
+                    repo_to_tags(repo).unwrap_or_else(|e| {
+                        error!("Error reading Git tags: {e:?}");
+                        Vec::new()
+                    })
+                });
+                first_tag_containing(repo, tags, *id, commit.time)
+            })
+            .clone()
+    }
+
+    /// Looks up whether a path is in the index, or, with prefix lookup,
+    /// whether any path inside it is, reading the index the first time.
+    fn is_tracked(&self, index: &Path, prefix_lookup: bool) -> bool {
+        let tracked = self.tracked.get_or_init(|| {
+            debug!("Reading Git index of {:?}", &self.workdir);
+            repo_to_tracked(&self.workdir).unwrap_or_else(|e| {
+                error!("Error reading Git index: {e:?}");
+                Vec::new()
+            })
+        });
+
+        // Paths sort by their components, so everything inside a directory
+        // comes straight after it.
+        let path = reorient(index);
+        let start = tracked.partition_point(|p| *p < path);
+        tracked.get(start).is_some_and(|p| {
+            if prefix_lookup {
+                p.starts_with(&path)
+            } else {
+                *p == path
+            }
+        })
     }
 
     /// Whether this repository has the given working directory.
@@ -174,6 +401,21 @@ impl GitRepo {
             || self.extra_paths.iter().any(|e| path.starts_with(e))
     }
 
+    /// Whether this repository was found by looking at the given path.
+    fn has_root(&self, path: &Path) -> bool {
+        self.original_path == path || self.extra_paths.iter().any(|e| e == path)
+    }
+
+    /// The deepest of the paths this repository was found by looking at
+    /// that the given path is inside.
+    fn nearest_root(&self, path: &Path) -> Option<&Path> {
+        std::iter::once(&self.original_path)
+            .chain(&self.extra_paths)
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(PathBuf::as_path)
+    }
+
     /// Open a Git repository. Depending on the flags, the path is either
     /// the repository's "gitdir" (or a "gitlink" to the gitdir), or the
     /// path is the start of a rootwards search for the repository.
@@ -196,6 +438,9 @@ impl GitRepo {
                 workdir,
                 original_path: path,
                 extra_paths: Vec::new(),
+                commits: OnceLock::new(),
+                tracked: OnceLock::new(),
+                history: Mutex::default(),
             })
         } else {
             warn!("Repository has no workdir?");
@@ -221,22 +466,26 @@ impl GitContents {
 /// mapping of files to their Git status.
 /// We will have already used the working directory at this point, so it gets
 /// passed in rather than deriving it from the `Repository` again.
-fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
+fn repo_to_statuses(repo: &git2::Repository, workdir: &Path, parallel: bool) -> Git {
     let mut statuses = Vec::new();
+    let mut submodules = Vec::new();
 
     info!("Getting Git statuses for repo with workdir {:?}", workdir);
-    match repo.statuses(None) {
+    let threads = rayon::current_num_threads();
+    let names = if parallel && threads > 1 {
+        top_level_names(repo, workdir, PARALLEL_STATUS_ENTRIES)
+    } else {
+        None
+    };
+
+    let result = match names {
+        Some(names) => batch_statuses(repo, workdir, batches(names, threads)),
+        None => path_statuses(repo, workdir, &[]),
+    };
+
+    match result {
         Ok(es) => {
-            for e in es.iter() {
-                #[cfg(target_family = "unix")]
-                let path = workdir.join(Path::new(OsStr::from_bytes(e.path_bytes())));
-                // TODO: handle non Unix systems better:
-                // https://github.com/ogham/exa/issues/698
-                #[cfg(not(target_family = "unix"))]
-                let path = workdir.join(Path::new(e.path().unwrap()));
-                let elem = (path, e.status());
-                statuses.push(elem);
-            }
+            statuses = es;
             // We manually add the `.git` at the root of the repo as ignored, since it is in practice.
             // Also we want to avoid `eza --tree --all --git-ignore` to display files inside `.git`.
             statuses.push((workdir.join(".git"), git2::Status::IGNORED));
@@ -246,7 +495,312 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
         }
     }
 
-    Git { statuses }
+    // Changes inside a submodule are already in the statuses above, so only
+    // the commit its `HEAD` is on needs to be compared with the pinned one.
+    match repo.submodules() {
+        Ok(sms) => {
+            for sm in sms {
+                let Some(name) = sm.name() else { continue };
+                match repo.submodule_status(name, git2::SubmoduleIgnore::Dirty) {
+                    Ok(s) => submodules.push((workdir.join(sm.path()), submodule_status(s))),
+                    Err(e) => error!("Error looking up submodule status: {e:?}"),
+                }
+            }
+        }
+        Err(e) => {
+            error!("Error looking up Git submodules: {:?}", e);
+        }
+    }
+
+    Git {
+        statuses,
+        submodules,
+    }
+}
+
+/// Repositories with more files than this in their index have their
+/// statuses looked up for a few top-level files or directories at a time,
+/// one batch for each thread, as going through a large working tree is what
+/// takes the time. Any smaller and opening the repository again for each
+/// batch isn’t worth it.
+const PARALLEL_STATUS_ENTRIES: usize = 20_000;
+
+/// Looks up the statuses of the files matching the given paths, or of every
+/// file if there aren’t any.
+///
+/// These are the same options libgit2 uses by default, which include
+/// ignored files, worked out the same way Git does: from `.gitignore` files,
+/// `.git/info/exclude`, and the file named by `core.excludesFile` or its
+/// default.
+fn path_statuses(
+    repo: &git2::Repository,
+    workdir: &Path,
+    pathspecs: &[String],
+) -> Result<Vec<(PathBuf, git2::Status)>, git2::Error> {
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(true)
+        .disable_pathspec_match(true);
+    for pathspec in pathspecs {
+        options.pathspec(pathspec);
+    }
+
+    let es = repo.statuses(Some(&mut options))?;
+    Ok(es
+        .iter()
+        .map(|e| {
+            #[cfg(target_family = "unix")]
+            let path = workdir.join(Path::new(OsStr::from_bytes(e.path_bytes())));
+            // TODO: handle non Unix systems better:
+            // https://github.com/ogham/exa/issues/698
+            #[cfg(not(target_family = "unix"))]
+            let path = workdir.join(Path::new(e.path().unwrap()));
+            (path, e.status())
+        })
+        .collect())
+}
+
+/// Looks up the statuses of each batch of paths at the same time, opening
+/// the repository again for each one, as a `Repository` can’t be shared
+/// between threads.
+fn batch_statuses(
+    repo: &git2::Repository,
+    workdir: &Path,
+    batches: Vec<Vec<String>>,
+) -> Result<Vec<(PathBuf, git2::Status)>, git2::Error> {
+    debug!("Getting Git statuses in parallel");
+    let git_dir = repo.path().to_path_buf();
+    batches
+        .into_par_iter()
+        .map(|pathspecs| {
+            let repo = git2::Repository::open(&git_dir)?;
+            repo.set_workdir(workdir, false)?;
+            path_statuses(&repo, workdir, &pathspecs)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|batches| batches.into_iter().flatten().collect())
+}
+
+/// The names of everything at the top of a repository that could have a
+/// status: what’s in the working tree, what’s in the index, and what was in
+/// the last commit, so deleted files aren’t missed. This returns nothing
+/// for repositories with fewer than the given number of index entries,
+/// which are small enough to look through all at once, and for any with
+/// names that aren’t UTF-8, which can’t be given as paths.
+fn top_level_names(
+    repo: &git2::Repository,
+    workdir: &Path,
+    min_entries: usize,
+) -> Option<BTreeSet<String>> {
+    let index = repo.index().ok()?;
+    if index.len() < min_entries {
+        return None;
+    }
+
+    let mut names = BTreeSet::new();
+    for entry in index.iter() {
+        let path = std::str::from_utf8(&entry.path).ok()?;
+        names.insert(path.split('/').next()?.to_string());
+    }
+    if let Ok(tree) = repo.head().and_then(|head| head.peel_to_tree()) {
+        for entry in &tree {
+            names.insert(entry.name()?.to_string());
+        }
+    }
+    for entry in std::fs::read_dir(workdir).ok()? {
+        names.insert(entry.ok()?.file_name().into_string().ok()?);
+    }
+
+    names.remove(".git");
+    Some(names)
+}
+
+/// Shares the names out between the given number of batches, leaving out
+/// any batches that end up empty.
+fn batches(names: BTreeSet<String>, count: usize) -> Vec<Vec<String>> {
+    let count = count.max(1);
+    let mut batches = vec![Vec::new(); count];
+    for (i, name) in names.into_iter().enumerate() {
+        batches[i % count].push(name);
+    }
+
+    batches.retain(|batch| !batch.is_empty());
+    batches
+}
+
+/// Blames a file as it is at `HEAD`, and picks out the author of the most
+/// lines in it. Ties go to whoever’s name sorts last, so that it’s the
+/// same one every time.
+fn blame_owner(repo: &git2::Repository, relative: &Path) -> Result<Option<String>, git2::Error> {
+    let blame = repo.blame_file(relative, None)?;
+
+    let mut lines = BTreeMap::<String, usize>::new();
+    for hunk in blame.iter() {
+        let name = hunk.final_signature().name().unwrap_or_default().to_owned();
+        *lines.entry(name).or_default() += hunk.lines_in_hunk();
+    }
+
+    Ok(lines
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(name, _)| name))
+}
+
+/// Opens the repository at the given working directory, unless it’s been
+/// opened already.
+fn open_once<'r>(
+    repo: &'r mut Option<git2::Repository>,
+    workdir: &Path,
+) -> Option<&'r git2::Repository> {
+    if repo.is_none() {
+        *repo = git2::Repository::open(workdir).ok();
+    }
+    repo.as_ref()
+}
+
+/// Reads the repository’s tags and the commits they point to, oldest
+/// first, leaving out any that point to something other than a commit.
+fn repo_to_tags(repo: &git2::Repository) -> Result<Vec<Tag>, git2::Error> {
+    let mut tags = Vec::new();
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let Ok(commit) = repo
+            .revparse_single(&format!("refs/tags/{name}"))
+            .and_then(|object| object.peel_to_commit())
+        else {
+            continue;
+        };
+
+        tags.push(Tag {
+            name: name.to_owned(),
+            id: commit.id(),
+            time: commit.time().seconds(),
+        });
+    }
+
+    tags.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.name.cmp(&b.name)));
+    Ok(tags)
+}
+
+/// Finds the oldest tag that includes the given commit, going by when the
+/// commits they point to were made. Tags on commits made before it can’t
+/// include it, so only the ones after it need looking through.
+fn first_tag_containing(
+    repo: &git2::Repository,
+    tags: &[Tag],
+    id: git2::Oid,
+    time: i64,
+) -> Option<String> {
+    tags.iter()
+        .skip_while(|tag| tag.time < time)
+        .find(|tag| tag.id == id || repo.graph_descendant_of(tag.id, id).unwrap_or(false))
+        .map(|tag| tag.name.clone())
+}
+
+/// Reads the path of every file in a repository’s index, sorted.
+fn repo_to_tracked(workdir: &Path) -> Result<Vec<PathBuf>, git2::Error> {
+    #[cfg(target_family = "unix")]
+    let path = |bytes: &[u8]| PathBuf::from(OsStr::from_bytes(bytes));
+    #[cfg(not(target_family = "unix"))]
+    let path = |bytes: &[u8]| PathBuf::from(String::from_utf8_lossy(bytes).as_ref());
+
+    let repo = git2::Repository::open(workdir)?;
+    let mut tracked = repo
+        .index()?
+        .iter()
+        .map(|entry| workdir.join(path(&entry.path)))
+        .collect::<Vec<_>>();
+
+    tracked.sort();
+    Ok(tracked)
+}
+
+/// Goes back through the history from `HEAD`, finding the last commit to
+/// change each file, and each directory above one, until every path in
+/// `HEAD` has been found.
+///
+/// Merge commits are skipped, so that changes made on a branch are put down
+/// to the commits that made them rather than the one that merged them in.
+fn repo_to_commits(
+    workdir: &Path,
+) -> Result<HashMap<PathBuf, (git2::Oid, f::GitCommit)>, git2::Error> {
+    let repo = git2::Repository::open(workdir)?;
+    let mut commits = HashMap::new();
+
+    let head = match repo.head() {
+        Ok(head) => head.peel_to_tree()?,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(commits),
+        Err(e) => return Err(e),
+    };
+
+    let mut remaining = 0_usize;
+    head.walk(git2::TreeWalkMode::PreOrder, |_, _| {
+        remaining += 1;
+        git2::TreeWalkResult::Ok
+    })?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+    revwalk.push_head()?;
+
+    for id in revwalk {
+        let commit = repo.find_commit(id?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+
+        let tree = commit.tree()?;
+        let parent = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent.as_ref(), Some(&tree), None)?;
+
+        let found = f::GitCommit {
+            hash: id_prefix(commit.id()),
+            subject: String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default())
+                .into_owned(),
+            author: String::from_utf8_lossy(commit.author().name_bytes()).into_owned(),
+            time: commit.time().seconds(),
+        };
+
+        for delta in diff.deltas() {
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+
+            // The directories above a file count as changed along with it.
+            for path in path.ancestors().take_while(|p| !p.as_os_str().is_empty()) {
+                let path = workdir.join(path);
+                if commits.contains_key(&path) {
+                    break;
+                }
+
+                if head
+                    .get_path(path.strip_prefix(workdir).unwrap_or(&path))
+                    .is_ok()
+                {
+                    remaining -= 1;
+                }
+                commits.insert(path, (commit.id(), found.clone()));
+            }
+        }
+
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Abbreviates a commit hash to its first seven characters, as Git does for
+/// all but the largest repositories.
+fn id_prefix(id: git2::Oid) -> String {
+    let mut hash = id.to_string();
+    hash.truncate(7);
+    hash
 }
 
 // The `repo.statuses` call above takes a long time. exa debug output:
@@ -260,6 +814,9 @@ fn repo_to_statuses(repo: &git2::Repository, workdir: &Path) -> Git {
 /// Container of Git statuses for all the files in this folder’s Git repository.
 struct Git {
     statuses: Vec<(PathBuf, git2::Status)>,
+
+    /// The working directory of each of the repository’s submodules.
+    submodules: Vec<(PathBuf, f::SubmoduleStatus)>,
 }
 
 impl Git {
@@ -267,11 +824,17 @@ impl Git {
     /// “Prefix lookup” means that it should report an aggregate status of all
     /// paths starting with the given prefix (in other words, a directory).
     fn status(&self, index: &Path, prefix_lookup: bool) -> f::Git {
-        if prefix_lookup {
+        let mut status = if prefix_lookup {
             self.dir_status(index)
         } else {
             self.file_status(index)
+        };
+
+        if !self.submodules.is_empty() {
+            let path = reorient(index);
+            status.submodule = self.submodules.iter().find(|s| s.0 == path).map(|s| s.1);
         }
+        status
     }
 
     /// Get the user-facing status of a file.
@@ -294,7 +857,11 @@ impl Git {
 
         let staged = index_status(s);
         let unstaged = working_tree_status(s);
-        f::Git { staged, unstaged }
+        f::Git {
+            staged,
+            unstaged,
+            submodule: None,
+        }
     }
 
     /// Get the combined, user-facing status of a directory.
@@ -319,7 +886,11 @@ impl Git {
 
         let staged = index_status(s);
         let unstaged = working_tree_status(s);
-        f::Git { staged, unstaged }
+        f::Git {
+            staged,
+            unstaged,
+            submodule: None,
+        }
     }
 }
 
@@ -367,6 +938,18 @@ fn working_tree_status(status: git2::Status) -> f::GitStatus {
     };
 }
 
+/// Whether a submodule has been cloned, and if so, whether its `HEAD` is on
+/// the commit its parent repository has pinned.
+fn submodule_status(status: git2::SubmoduleStatus) -> f::SubmoduleStatus {
+    if status.contains(git2::SubmoduleStatus::WD_UNINITIALIZED) {
+        f::SubmoduleStatus::Uninitialized
+    } else if status.contains(git2::SubmoduleStatus::WD_MODIFIED) {
+        f::SubmoduleStatus::Modified
+    } else {
+        f::SubmoduleStatus::Clean
+    }
+}
+
 /// The character to display if the file has been modified and the change
 /// has been staged.
 fn index_status(status: git2::Status) -> f::GitStatus {
@@ -398,39 +981,144 @@ fn current_branch(repo: &git2::Repository) -> Option<String> {
 
     if let Some(h) = head {
         if let Some(s) = h.shorthand() {
-            let branch_name = s.to_owned();
-            if branch_name.len() > 10 {
-                return Some(branch_name[..8].to_string() + "..");
-            }
-            return Some(branch_name);
+            return Some(shorten_branch(s));
         }
     }
     None
 }
 
+/// Counts how many commits the checked-out branch is ahead of and behind
+/// the branch it tracks, returning nothing if it doesn’t track one, or if
+/// the repository is in a detached `HEAD` state.
+fn ahead_behind(repo: &git2::Repository) -> Option<(usize, usize)> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+
+    let local = head.target()?;
+    let upstream = git2::Branch::wrap(head).upstream().ok()?;
+    let remote = upstream.get().target()?;
+    repo.graph_ahead_behind(local, remote).ok()
+}
+
+/// Cuts long branch names short, so they don’t make the column too wide.
+fn shorten_branch(branch_name: &str) -> String {
+    if branch_name.len() > 10 {
+        return branch_name[..8].to_string() + "..";
+    }
+    branch_name.to_owned()
+}
+
+/// What a repository’s `HEAD` file says is checked out.
+#[derive(PartialEq, Eq, Debug)]
+enum Head {
+    /// A branch with at least one commit on it.
+    Branch(String),
+
+    /// A commit rather than a branch, which `git2` calls `HEAD`.
+    Detached,
+
+    /// A branch without any commits, which doesn’t have a name until it does.
+    Unborn,
+}
+
+impl Head {
+    /// The name to show in the branch column.
+    fn into_branch_name(self) -> Option<String> {
+        match self {
+            Self::Branch(branch) => Some(shorten_branch(&branch)),
+            Self::Detached => Some(String::from("HEAD")),
+            Self::Unborn => None,
+        }
+    }
+}
+
+/// Reads the branch a repository has checked out straight from its `HEAD`
+/// file, without opening the repository, which is all that
+/// `--git-repos-no-status` needs. The `.git` in a worktree or submodule is a
+/// file pointing to the real Git directory instead, and a worktree’s
+/// branches are kept in the Git directory it was made from. This returns
+/// nothing if the directory has no `.git`.
+fn head_branch(dir: &Path) -> Option<Head> {
+    let dot_git = dir.join(".git");
+    let git_dir = if dot_git.is_file() {
+        let contents = std::fs::read_to_string(&dot_git).ok()?;
+        dir.join(contents.strip_prefix("gitdir:")?.trim())
+    } else {
+        dot_git
+    };
+
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let common_dir = match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir,
+    };
+    Some(parse_head(&common_dir, &head))
+}
+
+/// Works out what’s checked out from the contents of a `HEAD` file, which
+/// is either a reference to the branch, or a commit hash when detached.
+fn parse_head(git_dir: &Path, head: &str) -> Head {
+    let Some(reference) = head.trim().strip_prefix("ref:") else {
+        return Head::Detached;
+    };
+
+    let reference = reference.trim();
+    let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+    if git_dir.join(reference).exists() || packed_ref_exists(git_dir, reference) {
+        Head::Branch(branch.to_owned())
+    } else {
+        Head::Unborn
+    }
+}
+
+/// Whether a reference is in the repository’s `packed-refs` file, where
+/// `git gc` moves them to.
+fn packed_ref_exists(git_dir: &Path, reference: &str) -> bool {
+    std::fs::read_to_string(git_dir.join("packed-refs")).is_ok_and(|refs| {
+        refs.lines()
+            .any(|line| line.split_once(' ').is_some_and(|(_, r)| r == reference))
+    })
+}
+
 impl f::SubdirGitRepo {
     pub fn from_path(dir: &Path, status: bool) -> Self {
         let path = &reorient(dir);
 
+        if !status {
+            if let Some(head) = head_branch(path) {
+                return Self {
+                    status: None,
+                    branch: head.into_branch_name(),
+                    ..Self::default()
+                };
+            }
+        }
+
         if let Ok(repo) = git2::Repository::open(path) {
             let branch = current_branch(&repo);
             if !status {
                 return Self {
                     status: None,
                     branch,
+                    ..Self::default()
                 };
             }
             match repo.statuses(None) {
                 Ok(es) => {
-                    if es.iter().any(|s| s.status() != git2::Status::IGNORED) {
-                        return Self {
-                            status: Some(f::SubdirGitRepoStatus::GitDirty),
-                            branch,
-                        };
-                    }
+                    let status = if es.iter().any(|s| s.status() != git2::Status::IGNORED) {
+                        f::SubdirGitRepoStatus::GitDirty
+                    } else {
+                        f::SubdirGitRepoStatus::GitClean
+                    };
+                    let (ahead, behind) = ahead_behind(&repo).unwrap_or_default();
                     return Self {
-                        status: Some(f::SubdirGitRepoStatus::GitClean),
+                        status: Some(status),
                         branch,
+                        ahead,
+                        behind,
+                        stashed: repo.find_reference("refs/stash").is_ok(),
                     };
                 }
                 Err(e) => {
@@ -444,7 +1132,187 @@ impl f::SubdirGitRepo {
             } else {
                 None
             },
-            branch: None,
+            ..Self::default()
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn short_branch() {
+        assert_eq!(shorten_branch("main"), "main");
+        assert_eq!(shorten_branch("feature/long-name"), "feature/..");
+    }
+
+    #[test]
+    fn detached_head() {
+        let head = "0b8922e4cf3c55d3b01b5bd7e50f21d8b6e1d2a3\n";
+        assert_eq!(parse_head(Path::new("/nonexistent"), head), Head::Detached);
+    }
+
+    #[test]
+    fn submodule_states() {
+        use git2::SubmoduleStatus as S;
+
+        let cloned = S::IN_HEAD | S::IN_INDEX | S::IN_CONFIG | S::IN_WD;
+        assert_eq!(submodule_status(cloned), f::SubmoduleStatus::Clean);
+        assert_eq!(
+            submodule_status(cloned | S::WD_MODIFIED),
+            f::SubmoduleStatus::Modified
+        );
+        assert_eq!(
+            submodule_status(S::IN_HEAD | S::IN_INDEX | S::WD_UNINITIALIZED),
+            f::SubmoduleStatus::Uninitialized
+        );
+    }
+
+    #[test]
+    fn batches_shared_out() {
+        let names = ["docs", "src", "tests", "README.md", "Cargo.toml"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            batches(names, 2),
+            vec![
+                vec!["Cargo.toml", "docs", "tests"],
+                vec!["README.md", "src"],
+            ]
+        );
+    }
+
+    #[test]
+    fn fewer_names_than_batches() {
+        let names = BTreeSet::from([String::from("src")]);
+        assert_eq!(batches(names, 8), vec![vec!["src"]]);
+    }
+
+    /// Makes a repository in a new temporary directory, with the given
+    /// files committed to it, each holding its own name.
+    fn scratch_repo<S: AsRef<str>>(name: &str, files: &[S]) -> (git2::Repository, PathBuf) {
+        let workdir = env::temp_dir().join(format!("eza-git-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&workdir);
+        let repo = git2::Repository::init(&workdir).unwrap();
+        for file in files {
+            let path = workdir.join(file.as_ref());
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, file.as_ref()).unwrap();
+        }
+
+        {
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("eza", "eza@example.com").unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "Files", &tree, &[])
+                .unwrap();
+        }
+
+        (repo, workdir)
+    }
+
+    fn sorted(
+        result: Result<Vec<(PathBuf, git2::Status)>, git2::Error>,
+    ) -> Vec<(PathBuf, git2::Status)> {
+        let mut statuses = result.unwrap();
+        statuses.sort_by(|a, b| a.0.cmp(&b.0));
+        statuses
+    }
+
+    #[test]
+    fn batched_statuses_match() {
+        let files = [
+            "README.md",
+            "old.txt",
+            "src/main.rs",
+            "src/lib.rs",
+            "docs/guide.md",
+        ];
+        let (repo, workdir) = scratch_repo("batches", &files);
+        std::fs::write(workdir.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(workdir.join("src/main.rs"), "changed").unwrap();
+        std::fs::remove_file(workdir.join("old.txt")).unwrap();
+        std::fs::write(workdir.join("new.txt"), "new").unwrap();
+        std::fs::write(workdir.join("debug.log"), "ignored").unwrap();
+        std::fs::create_dir(workdir.join("build")).unwrap();
+        std::fs::write(workdir.join("build/out.log"), "ignored").unwrap();
+
+        let all = sorted(path_statuses(&repo, &workdir, &[]));
+        let names = top_level_names(&repo, &workdir, 0).unwrap();
+        let batched = sorted(batch_statuses(&repo, &workdir, batches(names, 3)));
+        std::fs::remove_dir_all(&workdir).unwrap();
+
+        assert_eq!(batched, all);
+        assert_eq!(
+            all.iter().map(|(_, status)| *status).collect::<Vec<_>>(),
+            vec![
+                git2::Status::WT_NEW,
+                git2::Status::IGNORED,
+                git2::Status::IGNORED,
+                git2::Status::WT_NEW,
+                git2::Status::WT_DELETED,
+                git2::Status::WT_MODIFIED,
+            ]
+        );
+    }
+
+    /// Times looking up every status at once against looking them up in
+    /// batches, in a repository big enough for batches to get used. This
+    /// takes a while, so it only runs when asked for, with
+    /// `cargo test --release batched_statuses_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn batched_statuses_benchmark() {
+        let files = (0..PARALLEL_STATUS_ENTRIES * 2)
+            .map(|i| format!("dir{}/file{i}", i % 100))
+            .collect::<Vec<_>>();
+        let (repo, workdir) = scratch_repo("benchmark", &files);
+        for file in files.iter().step_by(100) {
+            std::fs::write(workdir.join(file), "changed").unwrap();
+        }
+
+        let time = |f: &dyn Fn() -> Vec<(PathBuf, git2::Status)>| {
+            let start = std::time::Instant::now();
+            let statuses = f();
+            (start.elapsed(), statuses)
+        };
+        let (all_at_once, all) = time(&|| sorted(path_statuses(&repo, &workdir, &[])));
+        let (in_batches, batched) = time(&|| {
+            let names = top_level_names(&repo, &workdir, PARALLEL_STATUS_ENTRIES).unwrap();
+            let batches = batches(names, rayon::current_num_threads());
+            sorted(batch_statuses(&repo, &workdir, batches))
+        });
+        std::fs::remove_dir_all(&workdir).unwrap();
+
+        println!(
+            "{} files, {} threads: {all_at_once:?} all at once, {in_batches:?} in batches",
+            files.len(),
+            rayon::current_num_threads(),
+        );
+        assert_eq!(batched, all);
+    }
+
+    #[test]
+    fn nested_repo_nearer() {
+        let index = Path::new("./sub/file");
+        assert!(nearness(Path::new("./sub"), index) > nearness(Path::new("."), index));
+    }
+
+    #[test]
+    fn nested_repo_root_in_outer_repo() {
+        let index = Path::new("./sub");
+        assert!(nearness(Path::new("."), index) > nearness(Path::new("./sub"), index));
+    }
+
+    #[test]
+    fn unborn_branch() {
+        let head = "ref: refs/heads/main\n";
+        assert_eq!(parse_head(Path::new("/nonexistent"), head), Head::Unborn);
+    }
+}
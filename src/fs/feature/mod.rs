@@ -4,6 +4,14 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+pub mod acl;
+pub mod chattr;
+pub mod cow;
+pub mod reparse;
+pub mod security;
+pub mod statx;
+pub mod streams;
+pub mod version_info;
 pub mod xattr;
 
 #[cfg(feature = "git")]
@@ -35,6 +43,26 @@ pub mod git {
         pub fn get(&self, _index: &Path, _prefix_lookup: bool) -> f::Git {
             unreachable!();
         }
+
+        pub fn load_statuses(&self) {}
+
+        pub fn discover_nested(&self, _dir: &Path) {}
+
+        pub fn last_commit(&self, _index: &Path) -> Option<f::GitCommit> {
+            None
+        }
+
+        pub fn owner(&self, _index: &Path) -> Option<String> {
+            None
+        }
+
+        pub fn since_tag(&self, _index: &Path) -> Option<String> {
+            None
+        }
+
+        pub fn is_tracked(&self, _index: &Path, _prefix_lookup: bool) -> bool {
+            false
+        }
     }
 
     impl f::SubdirGitRepo {
@@ -43,3 +71,47 @@ pub mod git {
         }
     }
 }
+
+#[cfg(feature = "s3")]
+pub mod s3;
+
+#[cfg(not(feature = "s3"))]
+pub mod s3 {
+    use std::ffi::OsStr;
+    use std::io;
+
+    use crate::fs::manifest::Entry;
+
+    pub fn is_url(path: &OsStr) -> bool {
+        path.to_str().is_some_and(|p| p.starts_with("s3://"))
+    }
+
+    pub fn list(_url: &str, _recursive: bool) -> io::Result<Vec<Entry>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "S3 support was disabled in this build",
+        ))
+    }
+}
+
+#[cfg(feature = "sftp")]
+pub mod sftp;
+
+#[cfg(not(feature = "sftp"))]
+pub mod sftp {
+    use std::ffi::OsStr;
+    use std::io;
+
+    use crate::fs::manifest::Entry;
+
+    pub fn is_url(path: &OsStr) -> bool {
+        path.to_str().is_some_and(|p| p.starts_with("sftp://"))
+    }
+
+    pub fn list(_url: &str, _recursive: bool) -> io::Result<Vec<Entry>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "SFTP support was disabled in this build",
+        ))
+    }
+}
@@ -0,0 +1,277 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Telling apart the kinds of reparse point on Windows.
+//!
+//! A file with the reparse point attribute has a tag saying what filter
+//! handles it, and some data for that filter. Symlinks and junctions keep
+//! the path they lead to in it, as do the app execution aliases in
+//! `WindowsApps`, whereas the placeholders that `OneDrive` and other cloud
+//! sync services leave for files that haven’t been downloaded keep
+//! nothing worth showing.
+
+use std::path::{Path, PathBuf};
+
+pub const ENABLED: bool = cfg!(windows);
+
+/// What kind of reparse point a file is.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ReparseKind {
+    Symlink,
+    Junction,
+    AppExecLink,
+    Cloud,
+    Other,
+}
+
+/// A file’s reparse point, with the path it leads to, for the kinds that
+/// lead anywhere.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ReparsePoint {
+    pub kind: ReparseKind,
+    pub target: Option<PathBuf>,
+}
+
+#[cfg(any(windows, test))]
+const IO_REPARSE_TAG_MOUNT_POINT: u32 = 0xA000_0003;
+#[cfg(any(windows, test))]
+const IO_REPARSE_TAG_SYMLINK: u32 = 0xA000_000C;
+#[cfg(any(windows, test))]
+const IO_REPARSE_TAG_APPEXECLINK: u32 = 0x8000_001B;
+
+/// The tags cloud sync services use, which differ only in the four bits
+/// this mask leaves out.
+#[cfg(any(windows, test))]
+const IO_REPARSE_TAG_CLOUD: u32 = 0x9000_001A;
+#[cfg(any(windows, test))]
+const IO_REPARSE_TAG_CLOUD_MASK: u32 = 0xFFFF_0FFF;
+
+/// Works out the kind of reparse point from its tag.
+#[cfg(any(windows, test))]
+fn kind(tag: u32) -> ReparseKind {
+    match tag {
+        IO_REPARSE_TAG_SYMLINK => ReparseKind::Symlink,
+        IO_REPARSE_TAG_MOUNT_POINT => ReparseKind::Junction,
+        IO_REPARSE_TAG_APPEXECLINK => ReparseKind::AppExecLink,
+        tag if tag & IO_REPARSE_TAG_CLOUD_MASK == IO_REPARSE_TAG_CLOUD => ReparseKind::Cloud,
+        _ => ReparseKind::Other,
+    }
+}
+
+/// Parses a `REPARSE_DATA_BUFFER`, which starts with the tag and the
+/// length of the data, followed by a reserved field and then the data.
+#[cfg(any(windows, test))]
+fn parse(buffer: &[u8]) -> Option<ReparsePoint> {
+    let tag = u32::from_le_bytes(buffer.get(0..4)?.try_into().ok()?);
+    let length = u16::from_le_bytes(buffer.get(4..6)?.try_into().ok()?);
+    let data = buffer.get(8..8 + usize::from(length))?;
+
+    let kind = kind(tag);
+    let target = match kind {
+        // Symlinks have a flags field before their paths that junctions
+        // don’t have.
+        ReparseKind::Symlink => link_target(data, 12),
+        ReparseKind::Junction => link_target(data, 8),
+        ReparseKind::AppExecLink => app_exec_target(data),
+        ReparseKind::Cloud | ReparseKind::Other => None,
+    };
+
+    Some(ReparsePoint { kind, target })
+}
+
+/// Reads the path out of a symlink or junction’s data, which has the
+/// offsets and lengths of two names in its path buffer: the substitute
+/// name the filesystem follows, and the print name for showing people.
+/// The print name is used unless it’s missing, as it can be for
+/// junctions, in which case the substitute name gets its `\??\` prefix
+/// taken off.
+#[cfg(any(windows, test))]
+fn link_target(data: &[u8], path_buffer: usize) -> Option<PathBuf> {
+    let u16_at = |at: usize| -> Option<usize> {
+        Some(usize::from(u16::from_le_bytes(
+            data.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let name = |offset, length| -> Option<String> {
+        let start = path_buffer + offset;
+        let bytes = data.get(start..start + length)?;
+        Some(utf16(bytes))
+    };
+
+    let substitute = name(u16_at(0)?, u16_at(2)?)?;
+    let print = name(u16_at(4)?, u16_at(6)?)?;
+
+    if print.is_empty() {
+        let substitute = substitute.strip_prefix(r"\??\").unwrap_or(&substitute);
+        Some(PathBuf::from(substitute))
+    } else {
+        Some(PathBuf::from(print))
+    }
+}
+
+/// Reads the path out of an app execution alias’s data, which is a version
+/// number and then three null-terminated strings: the package’s family
+/// name, the app’s ID, and the executable it runs.
+#[cfg(any(windows, test))]
+fn app_exec_target(data: &[u8]) -> Option<PathBuf> {
+    let strings = utf16(data.get(4..)?);
+    let target = strings.split('\0').nth(2)?;
+    (!target.is_empty()).then(|| PathBuf::from(target))
+}
+
+#[cfg(any(windows, test))]
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Reads the reparse point of the file at the given path, without
+/// following it.
+#[cfg(windows)]
+pub fn read(path: &Path) -> Option<ReparsePoint> {
+    use std::fs::OpenOptions;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use std::ptr;
+
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    const FILE_FLAG_OPEN_REPARSE_POINT: u32 = 0x0020_0000;
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+    const FSCTL_GET_REPARSE_POINT: u32 = 0x0009_00A8;
+    const MAXIMUM_REPARSE_DATA_BUFFER_SIZE: usize = 16 * 1024;
+
+    // Opening a file to read its reparse point needs no access to it at
+    // all, and directories can only be opened with backup semantics.
+    let file = OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .ok()?;
+
+    let mut buffer = vec![0_u8; MAXIMUM_REPARSE_DATA_BUFFER_SIZE];
+    let mut returned = 0;
+    let result = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle(),
+            FSCTL_GET_REPARSE_POINT,
+            ptr::null(),
+            0,
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as u32,
+            &mut returned,
+            ptr::null_mut(),
+        )
+    };
+    if result == 0 {
+        return None;
+    }
+
+    buffer.truncate(returned as usize);
+    parse(&buffer)
+}
+
+#[cfg(not(windows))]
+pub fn read(_path: &Path) -> Option<ReparsePoint> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// Builds the buffer for a symlink or junction with the given names.
+    fn link_buffer(tag: u32, substitute: &str, print: &str) -> Vec<u8> {
+        let substitute = encode(substitute);
+        let print = encode(print);
+
+        let mut data = Vec::new();
+        for field in [0, substitute.len(), substitute.len(), print.len()] {
+            data.extend_from_slice(&u16::try_from(field).unwrap().to_le_bytes());
+        }
+        if tag == IO_REPARSE_TAG_SYMLINK {
+            data.extend_from_slice(&0_u32.to_le_bytes());
+        }
+        data.extend_from_slice(&substitute);
+        data.extend_from_slice(&print);
+
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&tag.to_le_bytes());
+        buffer.extend_from_slice(&u16::try_from(data.len()).unwrap().to_le_bytes());
+        buffer.extend_from_slice(&[0, 0]);
+        buffer.extend_from_slice(&data);
+        buffer
+    }
+
+    #[test]
+    fn symlink() {
+        let buffer = link_buffer(IO_REPARSE_TAG_SYMLINK, r"\??\C:\Target", r"C:\Target");
+        assert_eq!(
+            parse(&buffer),
+            Some(ReparsePoint {
+                kind: ReparseKind::Symlink,
+                target: Some(PathBuf::from(r"C:\Target")),
+            })
+        );
+    }
+
+    #[test]
+    fn junction_without_print_name() {
+        let buffer = link_buffer(IO_REPARSE_TAG_MOUNT_POINT, r"\??\D:\Projects", "");
+        assert_eq!(
+            parse(&buffer),
+            Some(ReparsePoint {
+                kind: ReparseKind::Junction,
+                target: Some(PathBuf::from(r"D:\Projects")),
+            })
+        );
+    }
+
+    #[test]
+    fn app_exec_link() {
+        let strings = "Microsoft.WindowsTerminal_8wekyb3d8bbwe\0App\0C:\\Program Files\\WindowsApps\\wt.exe\0";
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(&IO_REPARSE_TAG_APPEXECLINK.to_le_bytes());
+        let data_len = 4 + encode(strings).len();
+        buffer.extend_from_slice(&u16::try_from(data_len).unwrap().to_le_bytes());
+        buffer.extend_from_slice(&[0, 0]);
+        buffer.extend_from_slice(&3_u32.to_le_bytes());
+        buffer.extend_from_slice(&encode(strings));
+
+        assert_eq!(
+            parse(&buffer),
+            Some(ReparsePoint {
+                kind: ReparseKind::AppExecLink,
+                target: Some(PathBuf::from(r"C:\Program Files\WindowsApps\wt.exe")),
+            })
+        );
+    }
+
+    #[test]
+    fn cloud_placeholders() {
+        assert_eq!(kind(0x9000_001A), ReparseKind::Cloud);
+        assert_eq!(kind(0x9000_701A), ReparseKind::Cloud);
+    }
+
+    #[test]
+    fn unknown_tag() {
+        assert_eq!(kind(0x8000_0017), ReparseKind::Other);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut buffer = link_buffer(IO_REPARSE_TAG_SYMLINK, r"\??\C:\Target", r"C:\Target");
+        buffer.truncate(20);
+        assert_eq!(parse(&buffer), None);
+    }
+}
@@ -0,0 +1,308 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Owner and access control support for Windows files, read from their
+//! security descriptors.
+//!
+//! Windows has no user and group IDs: a file’s owner and group are security
+//! identifiers (SIDs), which get looked up to give `DOMAIN\name`, and what
+//! anyone may do with it is decided by its discretionary access control
+//! list (DACL) rather than by permission bits.
+
+use std::path::Path;
+
+use crate::fs::fields as f;
+use crate::fs::manifest::Owner;
+
+pub const ENABLED: bool = cfg!(windows);
+
+/// The parts of a file’s security descriptor that get listed.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Security {
+    pub user: Option<Owner>,
+    pub group: Option<Owner>,
+    pub access: f::Access,
+}
+
+/// Summing up access masks, which only needs doing on Windows.
+#[cfg(any(windows, test))]
+mod rights {
+    use crate::fs::fields as f;
+
+    // The generic rights can turn up in a mask as they were written in an
+    // entry, rather than mapped to the file-specific rights they stand for.
+    pub const FILE_ALL_ACCESS: u32 = 0x001F_01FF;
+    pub const FILE_GENERIC_READ: u32 = 0x0012_0089;
+    pub const FILE_GENERIC_WRITE: u32 = 0x0012_0116;
+    pub const GENERIC_ALL: u32 = 0x1000_0000;
+    pub const GENERIC_WRITE: u32 = 0x4000_0000;
+    pub const GENERIC_READ: u32 = 0x8000_0000;
+
+    /// Whether an access mask lets a file be read, written, or anything at
+    /// all.
+    pub fn summarise(mask: u32) -> f::Access {
+        if mask & GENERIC_ALL != 0 || mask & FILE_ALL_ACCESS == FILE_ALL_ACCESS {
+            return f::Access::Full;
+        }
+
+        let read = mask & GENERIC_READ != 0 || mask & FILE_GENERIC_READ == FILE_GENERIC_READ;
+        let write = mask & GENERIC_WRITE != 0 || mask & FILE_GENERIC_WRITE == FILE_GENERIC_WRITE;
+        match (read, write) {
+            (true, true) => f::Access::ReadWrite,
+            (true, false) => f::Access::Read,
+            (false, true) => f::Access::Write,
+            (false, false) => f::Access::None,
+        }
+    }
+}
+
+/// The last sub-authority in a SID, which tells apart the accounts in a
+/// domain, and stands in for a numeric ID. A SID is a revision byte, a
+/// count of sub-authorities, a six-byte authority, and then the 32-bit
+/// sub-authorities themselves.
+#[cfg(any(windows, test))]
+fn relative_id(sid: &[u8]) -> Option<u32> {
+    let count = usize::from(*sid.get(1)?);
+    let start = 8 + count.checked_sub(1)? * 4;
+    let rid = sid.get(start..start + 4)?;
+    Some(u32::from_le_bytes(rid.try_into().ok()?))
+}
+
+/// Reads the owner, group, and DACL out of the security descriptor of the
+/// file at the given path.
+#[cfg(windows)]
+pub fn read(path: &Path) -> Security {
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{LocalFree, ERROR_SUCCESS};
+    use windows_sys::Win32::Security::Authorization::{
+        BuildTrusteeWithSidW, GetEffectiveRightsFromAclW, GetNamedSecurityInfoW, SE_FILE_OBJECT,
+        TRUSTEE_W,
+    };
+    use windows_sys::Win32::Security::{
+        ACL, DACL_SECURITY_INFORMATION, GROUP_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION,
+    };
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut user = ptr::null_mut();
+    let mut group = ptr::null_mut();
+    let mut dacl: *mut ACL = ptr::null_mut();
+    let mut descriptor = ptr::null_mut();
+
+    let result = unsafe {
+        GetNamedSecurityInfoW(
+            path.as_ptr(),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION,
+            &mut user,
+            &mut group,
+            &mut dacl,
+            ptr::null_mut(),
+            &mut descriptor,
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return Security::default();
+    }
+
+    let current_user = sid::current_user();
+
+    // A file without a DACL lets everyone do anything with it.
+    let access = if dacl.is_null() {
+        f::Access::Full
+    } else if let Some(current_user) = current_user {
+        let mut trustee: TRUSTEE_W = unsafe { std::mem::zeroed() };
+        let mut mask = 0;
+        unsafe { BuildTrusteeWithSidW(&mut trustee, current_user.as_ptr().cast_mut().cast()) };
+        if unsafe { GetEffectiveRightsFromAclW(dacl, &trustee, &mut mask) } == ERROR_SUCCESS {
+            rights::summarise(mask)
+        } else {
+            f::Access::Unknown
+        }
+    } else {
+        f::Access::Unknown
+    };
+
+    let user = sid::copy(user).map(|sid| Owner {
+        id: relative_id(&sid).unwrap_or_default(),
+        name: sid::account_name(&sid),
+        yours: current_user == Some(&sid[..]),
+    });
+
+    let group = sid::copy(group).map(|sid| Owner {
+        id: relative_id(&sid).unwrap_or_default(),
+        name: sid::account_name(&sid),
+        yours: sid::is_member(&sid),
+    });
+
+    // The owner, group, and DACL all point into the descriptor, so it can
+    // only be freed once they’ve been copied.
+    unsafe { LocalFree(descriptor) };
+
+    Security {
+        user,
+        group,
+        access,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn read(_path: &Path) -> Security {
+    Security::default()
+}
+
+/// Looking up SIDs, which get copied out of the structures Windows hands
+/// back so they can outlive them.
+#[cfg(windows)]
+mod sid {
+    use std::collections::HashMap;
+    use std::ptr;
+    use std::sync::Mutex;
+
+    use once_cell::sync::Lazy;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::Security::{
+        CheckTokenMembership, GetLengthSid, GetTokenInformation, LookupAccountSidW, TokenUser,
+        PSID, TOKEN_QUERY, TOKEN_USER,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    /// Names of the SIDs that have already been looked up, as a listing
+    /// tends to have the same few owners over and over, and looking one up
+    /// can mean asking a domain controller.
+    static NAMES: Lazy<Mutex<HashMap<Vec<u8>, Option<String>>>> = Lazy::new(Default::default);
+
+    static CURRENT_USER: Lazy<Option<Vec<u8>>> = Lazy::new(|| unsafe {
+        let mut token = ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return None;
+        }
+
+        // The first call only finds out how big the buffer has to be.
+        let mut len = 0;
+        GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut len);
+        let mut buffer = vec![0_u8; len as usize];
+        let found =
+            GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len);
+        CloseHandle(token);
+
+        if found == 0 {
+            return None;
+        }
+        let user = buffer.as_ptr().cast::<TOKEN_USER>().read_unaligned();
+        copy(user.User.Sid)
+    });
+
+    /// The SID of the user running eza.
+    pub fn current_user() -> Option<&'static [u8]> {
+        CURRENT_USER.as_deref()
+    }
+
+    pub fn copy(sid: PSID) -> Option<Vec<u8>> {
+        if sid.is_null() {
+            return None;
+        }
+        let len = unsafe { GetLengthSid(sid) } as usize;
+        Some(unsafe { std::slice::from_raw_parts(sid.cast::<u8>(), len) }.to_vec())
+    }
+
+    /// Whether the user running eza is in the group with this SID.
+    pub fn is_member(sid: &[u8]) -> bool {
+        let mut member = 0;
+        let checked = unsafe {
+            CheckTokenMembership(ptr::null_mut(), sid.as_ptr().cast_mut().cast(), &mut member)
+        };
+        checked != 0 && member != 0
+    }
+
+    /// The account’s name as `DOMAIN\name`, or just its name for the
+    /// well-known SIDs that don’t belong to a domain, like `Everyone`.
+    pub fn account_name(sid: &[u8]) -> Option<String> {
+        let mut names = NAMES.lock().unwrap();
+        names
+            .entry(sid.to_vec())
+            .or_insert_with(|| look_up(sid))
+            .clone()
+    }
+
+    fn look_up(sid: &[u8]) -> Option<String> {
+        let sid: PSID = sid.as_ptr().cast_mut().cast();
+        let mut name = [0_u16; 256];
+        let mut domain = [0_u16; 256];
+        let mut name_len = name.len() as u32;
+        let mut domain_len = domain.len() as u32;
+        let mut kind = 0;
+
+        let found = unsafe {
+            LookupAccountSidW(
+                ptr::null(),
+                sid,
+                name.as_mut_ptr(),
+                &mut name_len,
+                domain.as_mut_ptr(),
+                &mut domain_len,
+                &mut kind,
+            )
+        };
+        if found == 0 {
+            return None;
+        }
+
+        let name = String::from_utf16_lossy(&name[..name_len as usize]);
+        let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+        if domain.is_empty() {
+            Some(name)
+        } else {
+            Some(format!(r"{domain}\{name}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::rights::*;
+    use super::*;
+
+    #[test]
+    fn full_control() {
+        assert_eq!(summarise(FILE_ALL_ACCESS), f::Access::Full);
+        assert_eq!(summarise(GENERIC_ALL), f::Access::Full);
+    }
+
+    #[test]
+    fn read_and_write() {
+        assert_eq!(
+            summarise(FILE_GENERIC_READ | FILE_GENERIC_WRITE),
+            f::Access::ReadWrite
+        );
+        assert_eq!(summarise(FILE_GENERIC_READ), f::Access::Read);
+        assert_eq!(summarise(GENERIC_WRITE), f::Access::Write);
+    }
+
+    #[test]
+    fn partial_rights() {
+        // Reading the attributes alone isn’t reading the file.
+        assert_eq!(summarise(0x80), f::Access::None);
+        assert_eq!(summarise(0), f::Access::None);
+    }
+
+    #[test]
+    fn rid() {
+        // S-1-5-21-1-2-3-1001
+        let mut sid = vec![1, 5, 0, 0, 0, 0, 0, 5];
+        for sub_authority in [21_u32, 1, 2, 3, 1001] {
+            sid.extend(sub_authority.to_le_bytes());
+        }
+        assert_eq!(relative_id(&sid), Some(1001));
+    }
+
+    #[test]
+    fn rid_of_truncated_sid() {
+        assert_eq!(relative_id(&[1, 2, 0, 0, 0, 0, 0, 5, 32, 0]), None);
+        assert_eq!(relative_id(&[1, 0, 0, 0, 0, 0, 0, 0]), None);
+    }
+}
@@ -0,0 +1,449 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Listing directories on other machines over SFTP, given as
+//! `sftp://[user@]host[:port]/path` arguments.
+//!
+//! Files get turned into the same entries as manifests, along with their
+//! permissions, owners, and timestamps, so they can be shown in any view.
+//! Owners get named from the remote machine’s `/etc/passwd` and
+//! `/etc/group`, if they can be read, rather than looked up locally.
+//!
+//! The host has to be in `~/.ssh/known_hosts` already, and logging in is
+//! done with the SSH agent, or with an unencrypted key in `~/.ssh`.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::ffi::OsStr;
+use std::io::{self, Read};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime};
+use log::*;
+use percent_encoding::percent_decode_str;
+use ssh2::{CheckResult, FileStat, KnownHostFileKind, Session, Sftp};
+
+use crate::fs::manifest::{Entry, Kind, Owner};
+
+/// The keys in `~/.ssh` to try logging in with, in order, if the SSH agent
+/// doesn’t have one that works.
+const KEY_FILES: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Whether a command-line argument names a directory on another machine,
+/// rather than a local file.
+pub fn is_url(path: &OsStr) -> bool {
+    path.to_str().is_some_and(|p| p.starts_with("sftp://"))
+}
+
+/// Lists the files in a directory on another machine, along with everything
+/// beneath it when recursing.
+pub fn list(url: &str, recursive: bool) -> io::Result<Vec<Entry>> {
+    let location = Location::parse(url)?;
+    let session = location.connect()?;
+    let sftp = session.sftp()?;
+
+    let home = sftp.realpath(Path::new("."))?;
+    let accounts = Accounts::read(&sftp, sftp.stat(&home).ok().and_then(|s| s.uid));
+
+    let mut entries = Vec::new();
+    let mut pending = vec![location.path.clone()];
+    while let Some(dir) = pending.pop() {
+        let children = match sftp.readdir(&dir) {
+            Ok(children) => children,
+            Err(e) if dir != location.path => {
+                warn!("Error reading {}: {e}", dir.display());
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        for (path, stat) in children {
+            if recursive && stat.is_dir() {
+                pending.push(path.clone());
+            }
+
+            let name = path.strip_prefix(&location.path).unwrap_or(&path);
+            entries.push(accounts.entry(&name.to_string_lossy(), &stat));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Which machine to connect to, and which directory on it to list.
+#[derive(PartialEq, Eq, Debug)]
+struct Location {
+    user: Option<String>,
+    host: String,
+    port: u16,
+
+    /// The directory to list, which is relative to the user’s home
+    /// directory if it was given as `~/path`, or if none was given at all.
+    path: PathBuf,
+}
+
+impl Location {
+    fn parse(url: &str) -> io::Result<Self> {
+        let rest = url.strip_prefix("sftp://").unwrap_or(url);
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, ""),
+        };
+
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) => (Some(decode(user)), host_port),
+            None => (None, authority),
+        };
+
+        // IPv6 addresses come in square brackets, so their colons don’t get
+        // mistaken for the start of the port.
+        let (host, port) = match host_port.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, port)) => (host, port.strip_prefix(':')),
+                None => return Err(invalid("bad host")),
+            },
+            None => match host_port.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_port, None),
+            },
+        };
+        if host.is_empty() {
+            return Err(invalid("no host name"));
+        }
+
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("bad port"))?,
+            None => 22,
+        };
+
+        let path = decode(path);
+        let path = match path.strip_prefix("/~") {
+            None if !path.is_empty() => PathBuf::from(path),
+            Some(relative) if !relative.is_empty() && !relative.starts_with('/') => {
+                PathBuf::from(path)
+            }
+            _ => match path.trim_start_matches("/~").trim_start_matches('/') {
+                "" => PathBuf::from("."),
+                relative => PathBuf::from(relative),
+            },
+        };
+
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// Connects to the machine, checks that it’s the one it’s meant to be,
+    /// and logs in.
+    fn connect(&self) -> io::Result<Session> {
+        let mut session = Session::new()?;
+        session.set_tcp_stream(TcpStream::connect((self.host.as_str(), self.port))?);
+        session.handshake()?;
+
+        self.check_host_key(&session)?;
+        self.authenticate(&session)?;
+        Ok(session)
+    }
+
+    fn check_host_key(&self, session: &Session) -> io::Result<()> {
+        let (key, _) = session
+            .host_key()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "no host key"))?;
+
+        let mut known_hosts = session.known_hosts()?;
+        if let Some(home) = dirs::home_dir() {
+            let path = home.join(".ssh").join("known_hosts");
+            if let Err(e) = known_hosts.read_file(&path, KnownHostFileKind::OpenSSH) {
+                debug!("Error reading {}: {e}", path.display());
+            }
+        }
+
+        match known_hosts.check_port(&self.host, self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::Mismatch => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "host key doesn’t match the one in ~/.ssh/known_hosts",
+            )),
+            CheckResult::NotFound | CheckResult::Failure => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "host isn’t in ~/.ssh/known_hosts",
+            )),
+        }
+    }
+
+    /// Logs in with whichever of the SSH agent’s keys works, or failing
+    /// that, with one of the usual keys in `~/.ssh`.
+    fn authenticate(&self, session: &Session) -> io::Result<()> {
+        let user = match &self.user {
+            Some(user) => user.clone(),
+            None => env::var("USER")
+                .or_else(|_| env::var("LOGNAME"))
+                .map_err(|_| invalid("no user name"))?,
+        };
+
+        if let Ok(mut agent) = session.agent() {
+            if agent.connect().is_ok() && agent.list_identities().is_ok() {
+                for identity in agent.identities().unwrap_or_default() {
+                    if agent.userauth(&user, &identity).is_ok() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+            for key in KEY_FILES.iter().map(|name| ssh_dir.join(name)) {
+                if key.exists()
+                    && session
+                        .userauth_pubkey_file(&user, None, &key, None)
+                        .is_ok()
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("couldn’t log in as {user}"),
+        ))
+    }
+}
+
+/// The users and groups on the other machine, for naming the owners of its
+/// files.
+#[derive(Default, Debug)]
+struct Accounts {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+
+    /// The ID of the user that’s logged in, if it could be worked out.
+    you: Option<u32>,
+
+    /// The groups that user is in.
+    your_groups: HashSet<u32>,
+}
+
+impl Accounts {
+    fn read(sftp: &Sftp, you: Option<u32>) -> Self {
+        let read = |path: &str| {
+            let mut text = String::new();
+            let result = sftp
+                .open(Path::new(path))
+                .map_err(io::Error::from)
+                .and_then(|mut file| file.read_to_string(&mut text));
+
+            if let Err(e) = result {
+                debug!("Error reading {path}: {e}");
+                text.clear();
+            }
+            text
+        };
+
+        Self::parse(&read("/etc/passwd"), &read("/etc/group"), you)
+    }
+
+    fn parse(passwd: &str, group: &str, you: Option<u32>) -> Self {
+        let mut accounts = Self {
+            you,
+            ..Self::default()
+        };
+
+        let mut your_name = None;
+        for fields in passwd
+            .lines()
+            .map(|line| line.split(':').collect::<Vec<_>>())
+        {
+            let (Some(&name), Some(Ok(uid))) = (fields.first(), fields.get(2).map(|u| u.parse()))
+            else {
+                continue;
+            };
+
+            if Some(uid) == you {
+                your_name = Some(name.to_string());
+                if let Some(Ok(gid)) = fields.get(3).map(|g| g.parse()) {
+                    accounts.your_groups.insert(gid);
+                }
+            }
+            accounts
+                .users
+                .entry(uid)
+                .or_insert_with(|| name.to_string());
+        }
+
+        for fields in group
+            .lines()
+            .map(|line| line.split(':').collect::<Vec<_>>())
+        {
+            let (Some(&name), Some(Ok(gid))) = (fields.first(), fields.get(2).map(|g| g.parse()))
+            else {
+                continue;
+            };
+
+            let members = fields.get(3).copied().unwrap_or_default();
+            if your_name
+                .as_deref()
+                .is_some_and(|you| members.split(',').any(|member| member == you))
+            {
+                accounts.your_groups.insert(gid);
+            }
+            accounts
+                .groups
+                .entry(gid)
+                .or_insert_with(|| name.to_string());
+        }
+
+        accounts
+    }
+
+    /// Turns what the server says about a file into an entry.
+    fn entry(&self, name: &str, stat: &FileStat) -> Entry {
+        let file_type = stat.file_type();
+        let kind = if file_type.is_dir() {
+            Kind::Directory
+        } else if file_type.is_symlink() {
+            Kind::Link
+        } else if file_type.is_file() {
+            Kind::File
+        } else {
+            Kind::Other
+        };
+
+        Entry {
+            accessed: stat.atime.and_then(from_seconds),
+            mode: stat.perm.map(|perm| perm & 0o7777),
+            user: stat.uid.map(|id| Owner {
+                id,
+                name: self.users.get(&id).cloned(),
+                yours: self.you == Some(id),
+            }),
+            group: stat.gid.map(|id| Owner {
+                id,
+                name: self.groups.get(&id).cloned(),
+                yours: self.your_groups.contains(&id),
+            }),
+            ..Entry::new(
+                name,
+                kind,
+                stat.size.unwrap_or(0),
+                stat.mtime.and_then(from_seconds),
+            )
+        }
+    }
+}
+
+fn from_seconds(secs: u64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp(i64::try_from(secs).ok()?, 0).map(|dt| dt.naive_utc())
+}
+
+fn decode(text: &str) -> String {
+    percent_decode_str(text).decode_utf8_lossy().into_owned()
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_location() {
+        assert_eq!(
+            Location::parse("sftp://eve@example.com:2222/srv/my%20files").unwrap(),
+            Location {
+                user: Some("eve".into()),
+                host: "example.com".into(),
+                port: 2222,
+                path: PathBuf::from("/srv/my files"),
+            }
+        );
+    }
+
+    #[test]
+    fn home_locations() {
+        for url in [
+            "sftp://example.com",
+            "sftp://example.com/~",
+            "sftp://example.com/~/",
+        ] {
+            assert_eq!(Location::parse(url).unwrap().path, PathBuf::from("."));
+        }
+        assert_eq!(
+            Location::parse("sftp://example.com/~/logs").unwrap().path,
+            PathBuf::from("logs")
+        );
+        assert_eq!(
+            Location::parse("sftp://example.com/").unwrap().path,
+            PathBuf::from("/")
+        );
+    }
+
+    #[test]
+    fn ipv6_location() {
+        let location = Location::parse("sftp://[::1]:2022/tmp").unwrap();
+        assert_eq!(location.host, "::1");
+        assert_eq!(location.port, 2022);
+    }
+
+    #[test]
+    fn bad_locations() {
+        assert!(Location::parse("sftp:///tmp").is_err());
+        assert!(Location::parse("sftp://host:ssh/tmp").is_err());
+    }
+
+    #[test]
+    fn accounts() {
+        let accounts = Accounts::parse(
+            "root:x:0:0::/root:/bin/sh\neve:x:1000:1000::/home/eve:/bin/sh\n",
+            "root:x:0:\neve:x:1000:\nwheel:x:10:root,eve\nother:x:20:root\n",
+            Some(1000),
+        );
+
+        assert_eq!(accounts.users.get(&1000).map(String::as_str), Some("eve"));
+        assert_eq!(accounts.groups.get(&10).map(String::as_str), Some("wheel"));
+        assert_eq!(accounts.your_groups, HashSet::from([1000, 10]));
+    }
+
+    #[test]
+    fn entries() {
+        let accounts = Accounts::parse("eve:x:1000:100::/home/eve:/bin/sh\n", "", Some(1000));
+        let stat = FileStat {
+            size: Some(4096),
+            uid: Some(1000),
+            gid: Some(100),
+            perm: Some(0o040_755),
+            atime: Some(1_700_000_000),
+            mtime: Some(1_700_000_000),
+        };
+
+        let entry = accounts.entry("logs", &stat);
+        assert_eq!(entry.kind, Kind::Directory);
+        assert_eq!(entry.mode, Some(0o755));
+        assert_eq!(
+            entry.user,
+            Some(Owner {
+                id: 1000,
+                name: Some("eve".into()),
+                yours: true,
+            })
+        );
+        assert_eq!(
+            entry.group,
+            Some(Owner {
+                id: 100,
+                name: None,
+                yours: true,
+            })
+        );
+        assert_eq!(entry.modified, from_seconds(1_700_000_000));
+    }
+}
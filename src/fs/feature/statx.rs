@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The details of a file that Linux only gives out through `statx`.
+//!
+//! As well as everything `stat` gives, `statx` can give a file’s birth time,
+//! on filesystems that keep one, and attributes such as whether it’s
+//! immutable, which are what `chattr` sets. Kernels older than 4.11 don’t
+//! have it, in which case everything falls back to `std::fs::Metadata`.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+pub const ENABLED: bool = cfg!(target_os = "linux");
+
+/// The attribute that’s set on files and directories encrypted with
+/// fscrypt, and everything inside them.
+pub const ATTR_ENCRYPTED: u32 = 0x0000_0800;
+
+/// What `statx` says about a file, beyond what `stat` does.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub struct Statx {
+    /// When the file was created, if its filesystem keeps track.
+    pub birth: Option<SystemTime>,
+
+    /// The file’s attributes, as `STATX_ATTR_*` bits.
+    pub attributes: u32,
+}
+
+/// Calls `statx` on the file at the given path, without following it if
+/// it’s a symlink. Returns nothing if the kernel doesn’t have `statx`, or
+/// it fails for any other reason, so `stat` can be used instead.
+#[cfg(target_os = "linux")]
+pub fn read(path: &Path) -> Option<Statx> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    const STATX_BTIME: u32 = 0x0800;
+
+    /// `struct statx_timestamp`
+    #[repr(C)]
+    struct Timestamp {
+        tv_sec: i64,
+        tv_nsec: u32,
+        reserved: i32,
+    }
+
+    /// `struct statx`, which is always 256 bytes, with space on the end for
+    /// fields added to it later.
+    #[repr(C)]
+    struct RawStatx {
+        mask: u32,
+        blksize: u32,
+        attributes: u64,
+        nlink: u32,
+        uid: u32,
+        gid: u32,
+        mode: u16,
+        pad: u16,
+        ino: u64,
+        size: u64,
+        blocks: u64,
+        attributes_mask: u64,
+        atime: Timestamp,
+        btime: Timestamp,
+        ctime: Timestamp,
+        mtime: Timestamp,
+        rdev_major: u32,
+        rdev_minor: u32,
+        dev_major: u32,
+        dev_minor: u32,
+        spare: [u64; 14],
+    }
+
+    /// Whether `statx` has turned out to be missing, so it doesn’t get
+    /// called again for every other file.
+    static MISSING: AtomicBool = AtomicBool::new(false);
+
+    if MISSING.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf: RawStatx = unsafe { std::mem::zeroed() };
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_statx,
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+            STATX_BTIME,
+            std::ptr::addr_of_mut!(buf),
+        )
+    };
+
+    if result != 0 {
+        if std::io::Error::last_os_error().raw_os_error() == Some(libc::ENOSYS) {
+            MISSING.store(true, Ordering::Relaxed);
+        }
+        return None;
+    }
+
+    let birth = (buf.mask & STATX_BTIME != 0).then(|| {
+        let since_epoch = Duration::new(buf.btime.tv_sec.unsigned_abs(), buf.btime.tv_nsec);
+        if buf.btime.tv_sec < 0 {
+            SystemTime::UNIX_EPOCH - since_epoch
+        } else {
+            SystemTime::UNIX_EPOCH + since_epoch
+        }
+    });
+
+    // Only the attributes the filesystem supports mean anything.
+    let attributes = buf.attributes & buf.attributes_mask;
+
+    Some(Statx {
+        birth,
+        attributes: u32::try_from(attributes & 0xFFFF_FFFF).unwrap_or_default(),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read(_path: &Path) -> Option<Statx> {
+    None
+}
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Alternate data stream support for files on NTFS.
+//!
+//! As well as its contents, a file on NTFS can have any number of named
+//! streams of data, which is where things like the `Zone.Identifier` that
+//! marks a downloaded file get kept.
+
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::Path;
+
+pub const ENABLED: bool = cfg!(windows);
+
+/// One of a file’s alternate data streams.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Stream {
+    pub name: String,
+    pub size: u64,
+}
+
+impl Display for Stream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: <length {}>", self.name, self.size)
+    }
+}
+
+/// The name of a stream, out of the `:name:$DATA` form Windows lists them
+/// in, or nothing for the file’s unnamed main stream, which isn’t an
+/// alternate stream at all.
+#[cfg(any(windows, test))]
+fn stream_name(listed: &str) -> Option<&str> {
+    let name = listed.strip_prefix(':')?;
+    let name = name.strip_suffix(":$DATA").unwrap_or(name);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Lists the alternate data streams of the file at the given path.
+#[cfg(windows)]
+pub fn list(path: &Path) -> io::Result<Vec<Stream>> {
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows_sys::Win32::Foundation::{GetLastError, ERROR_HANDLE_EOF, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard,
+        WIN32_FIND_STREAM_DATA,
+    };
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut data: WIN32_FIND_STREAM_DATA = unsafe { std::mem::zeroed() };
+
+    let handle = unsafe {
+        FindFirstStreamW(
+            path.as_ptr(),
+            FindStreamInfoStandard,
+            (&mut data as *mut WIN32_FIND_STREAM_DATA).cast(),
+            0,
+        )
+    };
+
+    // Filesystems other than NTFS have no streams to list.
+    if handle == INVALID_HANDLE_VALUE {
+        return match unsafe { GetLastError() } {
+            ERROR_HANDLE_EOF => Ok(Vec::new()),
+            _ => Err(io::Error::last_os_error()),
+        };
+    }
+
+    let mut streams = Vec::new();
+    loop {
+        let len = data
+            .cStreamName
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(data.cStreamName.len());
+        let listed = String::from_utf16_lossy(&data.cStreamName[..len]);
+        if let Some(name) = stream_name(&listed) {
+            streams.push(Stream {
+                name: name.to_owned(),
+                size: u64::try_from(data.StreamSize).unwrap_or_default(),
+            });
+        }
+
+        if unsafe { FindNextStreamW(handle, (&mut data as *mut WIN32_FIND_STREAM_DATA).cast()) }
+            == 0
+        {
+            break;
+        }
+    }
+
+    let error = unsafe { GetLastError() };
+    unsafe { FindClose(handle) };
+
+    match error {
+        ERROR_HANDLE_EOF => Ok(streams),
+        _ => Err(io::Error::from_raw_os_error(error as i32)),
+    }
+}
+
+#[cfg(not(windows))]
+#[allow(clippy::unnecessary_wraps)] // Needs to match Windows function
+pub fn list(_path: &Path) -> io::Result<Vec<Stream>> {
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn named_stream() {
+        assert_eq!(
+            stream_name(":Zone.Identifier:$DATA"),
+            Some("Zone.Identifier")
+        );
+    }
+
+    #[test]
+    fn main_stream() {
+        assert_eq!(stream_name("::$DATA"), None);
+    }
+
+    #[test]
+    fn display() {
+        let stream = Stream {
+            name: "Zone.Identifier".into(),
+            size: 26,
+        };
+        assert_eq!(stream.to_string(), "Zone.Identifier: <length 26>");
+    }
+}
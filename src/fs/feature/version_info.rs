@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Version resource support for Windows executables and libraries.
+
+use std::path::Path;
+
+use crate::fs::fields as f;
+
+pub const ENABLED: bool = cfg!(windows);
+
+/// Read the `FileVersion` and `ProductName` strings out of the version
+/// resource embedded in the file at the given path.
+#[cfg(windows)]
+pub fn read(path: &Path) -> f::FileVersion {
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows_sys::Win32::Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW};
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    let size = unsafe { GetFileVersionInfoSizeW(path.as_ptr(), std::ptr::null_mut()) };
+    if size == 0 {
+        return f::FileVersion::default();
+    }
+
+    let mut data = vec![0_u8; size as usize];
+    if unsafe { GetFileVersionInfoW(path.as_ptr(), 0, size, data.as_mut_ptr().cast()) } == 0 {
+        return f::FileVersion::default();
+    }
+
+    // The string tables are keyed by language and code page. Use the first
+    // translation the file lists, falling back to US English in Unicode.
+    // The translation table’s length is given in bytes.
+    let translation = query(&data, r"\VarFileInfo\Translation")
+        .filter(|&(_, len)| len >= 4)
+        .map_or_else(
+            || String::from("040904b0"),
+            |(value, _)| {
+                let value = value.cast::<u16>();
+                let (language, code_page) =
+                    unsafe { (value.read_unaligned(), value.add(1).read_unaligned()) };
+                format!("{language:04x}{code_page:04x}")
+            },
+        );
+
+    // String values have their length given in characters, including the
+    // trailing nul.
+    let string = |name: &str| {
+        query(&data, &format!(r"\StringFileInfo\{translation}\{name}"))
+            .map(|(value, len)| {
+                let value = unsafe { std::slice::from_raw_parts(value.cast::<u16>(), len) };
+                String::from_utf16_lossy(value)
+            })
+            .map(|value| value.trim_end_matches('\0').trim().to_owned())
+            .filter(|value| !value.is_empty())
+    };
+
+    f::FileVersion {
+        version: string("FileVersion"),
+        product: string("ProductName"),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn read(_path: &Path) -> f::FileVersion {
+    f::FileVersion::default()
+}
+
+/// Look up a value in a version resource block, returning a pointer into
+/// the block along with the length of the value.
+#[cfg(windows)]
+fn query(data: &[u8], sub_block: &str) -> Option<(*const std::ffi::c_void, usize)> {
+    use windows_sys::Win32::Storage::FileSystem::VerQueryValueW;
+
+    let sub_block: Vec<u16> = sub_block.encode_utf16().chain(Some(0)).collect();
+    let mut buffer = std::ptr::null_mut();
+    let mut len = 0_u32;
+
+    let found = unsafe {
+        VerQueryValueW(
+            data.as_ptr().cast(),
+            sub_block.as_ptr(),
+            &mut buffer,
+            &mut len,
+        )
+    };
+
+    if found == 0 || buffer.is_null() || len == 0 {
+        None
+    } else {
+        Some((buffer.cast_const(), len as usize))
+    }
+}
@@ -8,7 +8,7 @@
 
 #![allow(trivial_casts)] // for ARM
 
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
 use std::io;
 use std::path::Path;
 use std::str;
@@ -531,6 +531,11 @@ mod extended_attrs {
 
 const ATTRIBUTE_VALUE_MAX_HEX_LENGTH: usize = 16;
 
+/// The most characters of a text value, or bytes of a binary one, that
+/// `--xattrs=values` shows before cutting the value short.
+const ATTRIBUTE_VALUE_MAX_INLINE_LENGTH: usize = 64;
+const ATTRIBUTE_VALUE_MAX_INLINE_BYTES: usize = 32;
+
 // Display for an attribute.  Attribute values that have a custom display are
 // enclosed in curley brackets.
 impl Display for Attribute {
@@ -557,6 +562,93 @@ impl Display for Attribute {
     }
 }
 
+impl Attribute {
+    /// The attribute’s value on its own, for a column of its own: text is
+    /// written out as it is, and anything else as it is with `--extended`.
+    pub fn display_value(&self) -> String {
+        if let Some(value) = custom_attr_display(self) {
+            return value;
+        }
+
+        let Some(value) = &self.value else {
+            return String::new();
+        };
+
+        if let Some(val) = custom_value_display(value) {
+            val
+        } else if let Ok(v) = str::from_utf8(value) {
+            let mut text = String::with_capacity(v.len());
+            for c in v.trim_end_matches(char::from(0)).chars() {
+                if c.is_control() {
+                    text.extend(c.escape_default());
+                } else {
+                    text.push(c);
+                }
+            }
+            text
+        } else if value.len() <= ATTRIBUTE_VALUE_MAX_HEX_LENGTH {
+            format!("{value:02x?}")
+        } else {
+            format!("<length {}>", value.len())
+        }
+    }
+
+    /// The attribute’s name and how long its value is, without the value.
+    pub fn size_display(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{}: <length {}>", self.name, value.len()),
+            None => format!("{}: <empty>", self.name),
+        }
+    }
+
+    /// The attribute’s name and value, for `--xattrs=values`: text values
+    /// are quoted, binary ones are written in hex, and either gets cut short
+    /// past a limit, followed by how long it really is.
+    pub fn inline_display(&self) -> String {
+        if let Some(value) = custom_attr_display(self) {
+            return format!("{}: <{value}>", self.name);
+        }
+
+        let Some(value) = &self.value else {
+            return format!("{}: <empty>", self.name);
+        };
+
+        // Values that are text, apart from a trailing NUL, get shown as
+        // text; anything with other control characters in gets shown as hex.
+        let text = str::from_utf8(value)
+            .ok()
+            .map(|text| text.trim_end_matches(char::from(0)))
+            .filter(|text| {
+                text.chars()
+                    .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+            });
+
+        let (shown, cut) = if let Some(text) = text {
+            let mut chars = text.chars();
+            let shown: String = chars
+                .by_ref()
+                .take(ATTRIBUTE_VALUE_MAX_INLINE_LENGTH)
+                .collect();
+            (format!("{shown:?}"), chars.next().is_some())
+        } else {
+            let shown = value.iter().take(ATTRIBUTE_VALUE_MAX_INLINE_BYTES).fold(
+                String::from("0x"),
+                |mut hex, byte| {
+                    let _ = write!(hex, "{byte:02x}");
+                    hex
+                },
+            );
+            (shown, value.len() > ATTRIBUTE_VALUE_MAX_INLINE_BYTES)
+        };
+
+        if cut {
+            format!("{}: {shown}… <length {}>", self.name, value.len())
+        } else {
+            format!("{}: {shown}", self.name)
+        }
+    }
+}
+
 struct AttributeDisplay {
     pub attribute: &'static str,
     pub display: fn(&Attribute) -> Option<String>,
@@ -712,3 +804,64 @@ fn plist_value_display(value: &[u8]) -> Option<String> {
         .map(|s| format!("<plist version=\"1.0\">{}</plist>", s.replace('\n', "")))
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attribute(value: &[u8]) -> Attribute {
+        Attribute {
+            name: "user.test".into(),
+            value: Some(value.to_vec()),
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(attribute(b"hello").size_display(), "user.test: <length 5>");
+
+        let empty = Attribute {
+            name: "user.test".into(),
+            value: None,
+        };
+        assert_eq!(empty.size_display(), "user.test: <empty>");
+    }
+
+    #[test]
+    fn short_text() {
+        assert_eq!(
+            attribute(b"hello\0").inline_display(),
+            "user.test: \"hello\""
+        );
+        assert_eq!(attribute(b"a\tb").inline_display(), "user.test: \"a\\tb\"");
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(
+            attribute(&[0x01, 0x02, b'a']).inline_display(),
+            "user.test: 0x010261"
+        );
+    }
+
+    #[test]
+    fn long_text() {
+        let value = "x".repeat(100);
+        let expected = format!("user.test: \"{}\"… <length 100>", "x".repeat(64));
+        assert_eq!(attribute(value.as_bytes()).inline_display(), expected);
+    }
+
+    #[test]
+    fn short_binary() {
+        assert_eq!(
+            attribute(&[0xde, 0xad, 0xbe, 0xef, 0xff]).inline_display(),
+            "user.test: 0xdeadbeefff"
+        );
+    }
+
+    #[test]
+    fn long_binary() {
+        let expected = format!("user.test: 0x{}… <length 40>", "ff".repeat(32));
+        assert_eq!(attribute(&[0xff; 40]).inline_display(), expected);
+    }
+}
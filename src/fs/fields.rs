@@ -20,6 +20,9 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::struct_excessive_bools)]
 
+#[cfg(windows)]
+use crate::fs::feature::reparse::ReparseKind;
+
 /// The type of a file’s group ID.
 pub type gid_t = u32;
 
@@ -49,7 +52,7 @@ pub type flag_t = u32;
 /// regular file. (See the `filetype` module for those checks.)
 ///
 /// Its ordering is used when sorting by type.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
 pub enum Type {
     Directory,
     File,
@@ -68,7 +71,7 @@ impl Type {
 }
 
 /// The file’s Unix permission bitfield, with one entry per bit.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 #[rustfmt::skip]
 pub struct Permissions {
     pub user_read:      bool,
@@ -98,7 +101,7 @@ pub struct Attributes {
     pub readonly:        bool,
     pub hidden:          bool,
     pub system:          bool,
-    pub reparse_point:   bool,
+    pub reparse:         Option<ReparseKind>,
 }
 
 /// The three pieces of information that are displayed as a single column in
@@ -112,6 +115,8 @@ pub struct PermissionsPlus {
     #[cfg(windows)]
     pub attributes: Attributes,
     pub xattrs: bool,
+    pub acl: bool,
+    pub sparse: bool,
 }
 
 /// The permissions encoded as octal values
@@ -190,6 +195,10 @@ pub enum Size {
     /// This is what ls does as well. Without it, the devices will just have
     /// file sizes of zero.
     DeviceIDs(DeviceIDs),
+
+    /// This file is a Git LFS pointer, so the size is that of the object
+    /// it points to rather than of the pointer itself.
+    Lfs(u64),
 }
 
 /// The major and minor device IDs that gets displayed for device files.
@@ -213,7 +222,7 @@ pub struct Time {
 /// A file’s status in a Git repository. Whether a file is in a repository or
 /// not is handled by the Git module, rather than having a “null” variant in
 /// this enum.
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum GitStatus {
     /// This file hasn’t changed since the last commit.
     NotModified,
@@ -248,6 +257,9 @@ pub enum GitStatus {
 pub struct Git {
     pub staged: GitStatus,
     pub unstaged: GitStatus,
+
+    /// The state of the submodule checked out in a directory, if it is one.
+    pub submodule: Option<SubmoduleStatus>,
 }
 
 impl Default for Git {
@@ -256,12 +268,53 @@ impl Default for Git {
         Self {
             staged: GitStatus::NotModified,
             unstaged: GitStatus::NotModified,
+            submodule: None,
         }
     }
 }
 
+/// How the commit checked out in a submodule compares to the one its
+/// parent repository has pinned.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SubmoduleStatus {
+    /// The pinned commit is the one checked out.
+    Clean,
+
+    /// A different commit from the pinned one is checked out.
+    Modified,
+
+    /// The submodule hasn’t been cloned, so its directory is empty.
+    Uninitialized,
+}
+
+/// The last commit to change a file in its Git repository, or anything in
+/// it for a directory.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct GitCommit {
+    /// The commit’s hash, abbreviated.
+    pub hash: String,
+
+    /// The first line of the commit’s message.
+    pub subject: String,
+
+    /// The name of the commit’s author.
+    pub author: String,
+
+    /// When the commit was made, in seconds since the epoch.
+    pub time: i64,
+}
+
+/// The label a Linux security module has given a file.
 pub enum SecurityContextType<'a> {
+    /// A `SELinux` context, such as `system_u:object_r:bin_t:s0`.
     SELinux(&'a str),
+
+    /// A Smack label, such as `System`.
+    Smack(&'a str),
+
+    /// The label `AppArmor` attaches profiles to executables by.
+    AppArmor(&'a str),
+
     None,
 }
 
@@ -269,6 +322,111 @@ pub struct SecurityContext<'a> {
     pub context: SecurityContextType<'a>,
 }
 
+/// The storage class an object store keeps an object in, for files that
+/// came from an object store listing or a manifest.
+#[derive(Default)]
+pub struct StorageClass(pub Option<String>);
+
+/// The ID of the btrfs subvolume a file is in.
+#[derive(Default)]
+pub struct Subvolume(pub Option<u64>);
+
+/// The ID of the project a file is in, for project quotas on ext4 and xfs.
+#[derive(Default)]
+pub struct ProjectId(pub Option<u32>);
+
+/// The compression property set on a file on btrfs, such as `zstd`.
+#[derive(Default)]
+pub struct Compression(pub Option<String>);
+
+/// The name of the ZFS dataset a file is in, such as `tank/home`.
+#[derive(Default)]
+pub struct Dataset(pub Option<String>);
+
+/// A file’s MIME type, if one could be worked out.
+#[derive(Default)]
+pub struct Mime(pub Option<&'static str>);
+
+/// The width and height of an image, in pixels.
+#[derive(Default)]
+pub struct Dimensions(pub Option<(u32, u32)>);
+
+/// What an executable or shared library was built for.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct BinaryInfo {
+    pub format: BinaryFormat,
+
+    /// The name of the instruction set, such as `x86-64`.
+    pub architecture: &'static str,
+
+    /// Whether it’s a 32-bit or 64-bit binary.
+    pub bits: u8,
+
+    /// Whether it loads shared libraries when it’s run.
+    pub dynamic: bool,
+
+    /// Whether its symbol table has been taken out.
+    pub stripped: bool,
+}
+
+/// The formats that executables and shared libraries come in.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BinaryFormat {
+    /// The Executable and Linkable Format, used on Linux and the BSDs.
+    Elf,
+
+    /// The Portable Executable format, used on Windows.
+    Pe,
+}
+
+/// What a binary was built for, if the file is one.
+#[derive(Default)]
+pub struct Binary(pub Option<BinaryInfo>);
+
+/// The start of the first line of a text file.
+#[derive(Default)]
+pub struct Preview(pub Option<String>);
+
+/// The SHA-256 digest of a file’s contents.
+#[derive(Default)]
+pub enum Hash {
+    /// The digest, in lowercase hexadecimal.
+    Digest(String),
+
+    /// The digest is still being worked out, and will be filled in once
+    /// it’s known.
+    Pending,
+
+    /// The file isn’t a regular file, or couldn’t be read.
+    #[default]
+    None,
+}
+
+/// What the user running eza may do with a file, going by the file’s DACL
+/// on Windows.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum Access {
+    /// Anything at all, including changing its permissions.
+    Full,
+    ReadWrite,
+    Read,
+    Write,
+
+    /// Neither read nor write it.
+    None,
+
+    /// Its security descriptor couldn’t be read, or this isn’t Windows.
+    #[default]
+    Unknown,
+}
+
+/// The version strings embedded in a Windows executable or library.
+#[derive(Default)]
+pub struct FileVersion {
+    pub version: Option<String>,
+    pub product: Option<String>,
+}
+
 #[allow(dead_code)]
 #[derive(PartialEq, Copy, Clone)]
 pub enum SubdirGitRepoStatus {
@@ -281,6 +439,14 @@ pub enum SubdirGitRepoStatus {
 pub struct SubdirGitRepo {
     pub status: Option<SubdirGitRepoStatus>,
     pub branch: Option<String>,
+
+    /// How many commits the branch is ahead of and behind its upstream,
+    /// which are both zero when it doesn’t have one.
+    pub ahead: usize,
+    pub behind: usize,
+
+    /// Whether the repository has anything stashed.
+    pub stashed: bool,
 }
 
 impl Default for SubdirGitRepo {
@@ -288,6 +454,9 @@ impl Default for SubdirGitRepo {
         Self {
             status: Some(SubdirGitRepoStatus::NoRepo),
             branch: None,
+            ahead: 0,
+            behind: 0,
+            stashed: false,
         }
     }
 }
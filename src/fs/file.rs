@@ -7,7 +7,7 @@
 //! Files, and methods and fields to access their metadata.
 
 #[cfg(unix)]
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::FileType;
 use std::io;
 #[cfg(unix)]
@@ -28,15 +28,35 @@ use log::*;
 #[cfg(unix)]
 use once_cell::sync::Lazy;
 
+use crate::fs::binary;
+use crate::fs::dedupe::{self, Identity};
+use crate::fs::digest;
 use crate::fs::dir::Dir;
+use crate::fs::feature::acl::{self, Acl};
+use crate::fs::feature::chattr;
+use crate::fs::feature::cow;
+use crate::fs::feature::reparse::{self, ReparseKind, ReparsePoint};
+use crate::fs::feature::security::{self, Security};
+use crate::fs::feature::statx::{self, Statx};
+use crate::fs::feature::streams::{self, Stream};
+use crate::fs::feature::version_info;
 use crate::fs::feature::xattr;
 use crate::fs::feature::xattr::{Attribute, FileAttributes};
 use crate::fs::fields as f;
 use crate::fs::fields::SecurityContextType;
-use crate::fs::recursive_size::RecursiveSize;
+use crate::fs::image;
+use crate::fs::lfs;
+use crate::fs::manifest;
+use crate::fs::mime;
+use crate::fs::preview;
+use crate::fs::recursive_size::{RecursiveSize, SizeMeasure, TotalSize};
+use crate::fs::shortcut;
+use crate::fs::trash::{self, TrashInfo};
 
 use super::mounts::all_mounts;
 use super::mounts::MountedFs;
+use super::processes::{self, PipeAccess, Process};
+use super::sockets::{all_sockets, SocketState};
 
 // Maps (device_id, inode) => (size_in_bytes, size_in_blocks)
 // Mutex::new is const but HashMap::new is not const requiring us to use lazy
@@ -44,7 +64,13 @@ use super::mounts::MountedFs;
 // TODO: Replace with std::sync::LazyLock when it is stable.
 #[allow(clippy::type_complexity)]
 #[cfg(unix)]
-static DIRECTORY_SIZE_CACHE: Lazy<Mutex<HashMap<(u64, u64), (u64, u64)>>> =
+static DIRECTORY_SIZE_CACHE: Lazy<Mutex<HashMap<(u64, u64, Option<usize>), (u64, u64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The path of the first file listed with other hard links to it, by the
+/// device and inode they share.
+#[cfg(unix)]
+static HARD_LINKS: Lazy<Mutex<HashMap<(u64, u64), PathBuf>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// A **File** is a wrapper around one of Rust’s `PathBuf` values, along with
@@ -109,6 +135,9 @@ pub struct File<'dir> {
     /// instead.
     pub deref_links: bool,
 
+    /// How to work out the recursive directory size, if it should be.
+    total_size: Option<TotalSize>,
+
     /// The recursive directory size when `total_size` is used.
     recursive_size: RecursiveSize,
 
@@ -117,6 +146,37 @@ pub struct File<'dir> {
 
     /// The absolute value of this path, used to look up mount points.
     absolute_path: OnceLock<Option<PathBuf>>,
+
+    /// The MIME type of this file, which is only sniffed from its contents
+    /// when the column is shown.
+    mime_type: OnceLock<Option<&'static str>>,
+
+    /// Where this file was deleted from, and when, which is only read for
+    /// files in a trash directory.
+    trash_info: OnceLock<Option<TrashInfo>>,
+
+    /// The size of the object this file points to, if it’s a Git LFS
+    /// pointer file.
+    lfs_size: OnceLock<Option<u64>>,
+
+    /// The owner, group, and access rights from this file’s security
+    /// descriptor, which is only read on Windows.
+    security: OnceLock<Security>,
+
+    /// What `statx` says about this file, which is only asked on Linux.
+    statx: OnceLock<Option<Statx>>,
+
+    /// What kind of reparse point this file is, and where it leads, which
+    /// is only read on Windows.
+    reparse_point: OnceLock<Option<ReparsePoint>>,
+
+    /// The manifest entry this file was made from, for a file that isn’t
+    /// on the filesystem at all.
+    manifest_entry: Option<Box<manifest::Entry>>,
+
+    /// The paths given as arguments that lead to this file apart from its
+    /// own, which `--dedupe` only lists it once for.
+    pub aliases: Vec<PathBuf>,
 }
 
 impl<'dir> File<'dir> {
@@ -125,7 +185,7 @@ impl<'dir> File<'dir> {
         parent_dir: PD,
         filename: FN,
         deref_links: bool,
-        total_size: bool,
+        total_size: Option<TotalSize>,
         filetype: Option<std::fs::FileType>,
     ) -> File<'dir>
     where
@@ -137,7 +197,7 @@ impl<'dir> File<'dir> {
         let ext = File::ext(&path);
 
         let is_all_all = false;
-        let recursive_size = if total_size {
+        let recursive_size = if total_size.is_some() {
             RecursiveSize::Unknown
         } else {
             RecursiveSize::None
@@ -159,15 +219,24 @@ impl<'dir> File<'dir> {
             parent_dir,
             is_all_all,
             deref_links,
+            total_size,
             recursive_size,
             filetype,
             metadata: OnceLock::new(),
             extended_attributes: OnceLock::new(),
             absolute_path: OnceLock::new(),
+            mime_type: OnceLock::new(),
+            trash_info: OnceLock::new(),
+            lfs_size: OnceLock::new(),
+            security: OnceLock::new(),
+            statx: OnceLock::new(),
+            reparse_point: OnceLock::new(),
+            manifest_entry: None,
+            aliases: Vec::new(),
         };
 
-        if total_size {
-            file.recursive_size = file.recursive_directory_size();
+        if let Some(total_size) = total_size {
+            file.recursive_size = file.recursive_directory_size(total_size);
         }
 
         file
@@ -177,13 +246,13 @@ impl<'dir> File<'dir> {
         path: PathBuf,
         parent_dir: &'dir Dir,
         name: &'static str,
-        total_size: bool,
+        total_size: Option<TotalSize>,
     ) -> File<'dir> {
         let ext = File::ext(&path);
 
         let is_all_all = true;
         let parent_dir = Some(parent_dir);
-        let recursive_size = if total_size {
+        let recursive_size = if total_size.is_some() {
             RecursiveSize::Unknown
         } else {
             RecursiveSize::None
@@ -196,25 +265,270 @@ impl<'dir> File<'dir> {
             parent_dir,
             is_all_all,
             deref_links: false,
+            total_size,
             recursive_size,
             metadata: OnceLock::new(),
             absolute_path: OnceLock::new(),
+            mime_type: OnceLock::new(),
+            trash_info: OnceLock::new(),
+            lfs_size: OnceLock::new(),
+            security: OnceLock::new(),
+            statx: OnceLock::new(),
+            reparse_point: OnceLock::new(),
             extended_attributes: OnceLock::new(),
             filetype: OnceLock::new(),
+            manifest_entry: None,
+            aliases: Vec::new(),
         };
 
-        if total_size {
-            file.recursive_size = file.recursive_directory_size();
+        if let Some(total_size) = total_size {
+            file.recursive_size = file.recursive_directory_size(total_size);
         }
 
         file
     }
 
-    pub fn new_aa_current(parent_dir: &'dir Dir, total_size: bool) -> File<'dir> {
+    /// Creates a file from an entry in a manifest given to
+    /// `--from-manifest`. Nothing about it gets looked up on the
+    /// filesystem: everything comes from the entry, and anything the entry
+    /// doesn’t say is left unknown.
+    pub fn from_manifest(entry: manifest::Entry) -> File<'static> {
+        let path = PathBuf::from(&entry.name);
+
+        File {
+            name: File::filename(&path),
+            ext: File::ext(&path),
+            path,
+            parent_dir: None,
+            is_all_all: false,
+            deref_links: false,
+            total_size: None,
+            recursive_size: RecursiveSize::None,
+            filetype: OnceLock::from(None),
+            metadata: OnceLock::from(Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "not on the filesystem",
+            ))),
+            extended_attributes: OnceLock::from(Vec::new()),
+            absolute_path: OnceLock::from(None),
+            mime_type: OnceLock::new(),
+            trash_info: OnceLock::new(),
+            lfs_size: OnceLock::new(),
+            security: OnceLock::new(),
+            statx: OnceLock::new(),
+            reparse_point: OnceLock::new(),
+            manifest_entry: Some(Box::new(entry)),
+            aliases: Vec::new(),
+        }
+    }
+
+    /// The storage class of a file that came from an object store, which
+    /// only manifests and object store listings know.
+    pub fn storage_class(&self) -> f::StorageClass {
+        f::StorageClass(
+            self.manifest_entry
+                .as_ref()
+                .and_then(|entry| entry.storage_class.clone()),
+        )
+    }
+
+    /// The MIME type of this file. Regular files have the start of their
+    /// contents read the first time this is asked for; anything else is
+    /// named after its kind, and files that aren’t on the filesystem can
+    /// only go by their extensions.
+    pub fn mime_type(&self) -> f::Mime {
+        f::Mime(*self.mime_type.get_or_init(|| self.gather_mime_type()))
+    }
+
+    fn gather_mime_type(&self) -> Option<&'static str> {
+        if self.is_directory() {
+            return Some("inode/directory");
+        }
+        if self.is_link() {
+            return Some("inode/symlink");
+        }
+        if self.manifest_entry.is_some() {
+            return self.ext.as_deref().and_then(mime::from_extension);
+        }
+
+        #[cfg(unix)]
+        {
+            if self.is_pipe() {
+                return Some("inode/fifo");
+            }
+            if self.is_socket() {
+                return Some("inode/socket");
+            }
+            if self.is_block_device() {
+                return Some("inode/blockdevice");
+            }
+            if self.is_char_device() {
+                return Some("inode/chardevice");
+            }
+        }
+
+        if !self.is_file() {
+            return None;
+        }
+        match mime::sniff(&self.path, self.ext.as_deref()) {
+            Ok(mime) => Some(mime),
+            Err(e) => {
+                debug!("Failed to sniff MIME type of {:?}: {e}", self.path);
+                None
+            }
+        }
+    }
+
+    /// Whether this file has contents to hash, which only regular files on
+    /// the filesystem do.
+    pub fn is_hashable(&self) -> bool {
+        self.manifest_entry.is_none() && self.is_file()
+    }
+
+    /// The SHA-256 digest of this file’s contents, which reads the whole
+    /// file, so it’s only worked out when asked for.
+    pub fn hash(&self) -> f::Hash {
+        if !self.is_hashable() {
+            return f::Hash::None;
+        }
+        match digest::of_file(&self.path) {
+            Ok(digest) => f::Hash::Digest(digest),
+            Err(e) => {
+                debug!("Failed to hash {:?}: {e}", self.path);
+                f::Hash::None
+            }
+        }
+    }
+
+    /// What this file was built for, if it’s an executable or a shared
+    /// library.
+    pub fn binary_info(&self) -> f::Binary {
+        if !self.is_file() || self.is_virtual() {
+            return f::Binary(None);
+        }
+        match binary::info(&self.path) {
+            Ok(info) => f::Binary(info),
+            Err(e) => {
+                debug!("Failed to read binary info of {:?}: {e}", self.path);
+                f::Binary(None)
+            }
+        }
+    }
+
+    /// Whether this file is encrypted by the filesystem. On Linux, only
+    /// directories encrypted with fscrypt count, as everything inside one
+    /// is encrypted too; on Windows, anything encrypted with EFS does.
+    #[cfg(target_os = "linux")]
+    pub fn is_encrypted(&self) -> bool {
+        self.is_directory()
+            && self
+                .statx()
+                .is_some_and(|statx| statx.attributes & statx::ATTR_ENCRYPTED != 0)
+    }
+
+    #[cfg(windows)]
+    pub fn is_encrypted(&self) -> bool {
+        const FILE_ATTRIBUTE_ENCRYPTED: u32 = 0x0000_4000;
+        self.metadata()
+            .is_ok_and(|md| md.file_attributes() & FILE_ATTRIBUTE_ENCRYPTED != 0)
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    pub fn is_encrypted(&self) -> bool {
+        false
+    }
+
+    /// Whether this file’s contents are somewhere other than on this
+    /// computer, such as a placeholder for a file kept by a cloud sync
+    /// service, which only Windows marks files as.
+    #[cfg(windows)]
+    pub fn is_offline(&self) -> bool {
+        const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+        const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+        const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+        const OFFLINE: u32 = FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+        self.metadata()
+            .is_ok_and(|md| md.file_attributes() & OFFLINE != 0)
+    }
+
+    #[cfg(not(windows))]
+    pub fn is_offline(&self) -> bool {
+        false
+    }
+
+    /// Where this file was deleted from, and when, if it’s in a trash
+    /// directory.
+    pub fn trash_info(&self) -> Option<&TrashInfo> {
+        self.trash_info
+            .get_or_init(|| {
+                if self.is_virtual() {
+                    return None;
+                }
+                trash::info(&self.path).unwrap_or_else(|e| {
+                    debug!("Failed to read trash info of {:?}: {e}", self.path);
+                    None
+                })
+            })
+            .as_ref()
+    }
+
+    /// The width and height of this file, if it’s an image in a format
+    /// whose headers can be read.
+    pub fn image_dimensions(&self) -> f::Dimensions {
+        if !self.is_file() || self.is_virtual() {
+            return f::Dimensions(None);
+        }
+        match image::dimensions(&self.path) {
+            Ok(dimensions) => f::Dimensions(dimensions),
+            Err(e) => {
+                debug!("Failed to read image dimensions of {:?}: {e}", self.path);
+                f::Dimensions(None)
+            }
+        }
+    }
+
+    /// The first line of a regular file’s contents, up to this many
+    /// characters, if it looks like text.
+    pub fn preview(&self, length: usize) -> f::Preview {
+        if !self.is_file() || self.is_virtual() {
+            return f::Preview(None);
+        }
+        match preview::of_file(&self.path, length) {
+            Ok(text) => f::Preview(text),
+            Err(e) => {
+                debug!("Failed to preview {:?}: {e}", self.path);
+                f::Preview(None)
+            }
+        }
+    }
+
+    /// The user that owns a file on another system, which has to be shown
+    /// as that system names it rather than looked up locally.
+    pub fn remote_user(&self) -> Option<&manifest::Owner> {
+        self.manifest_entry.as_ref()?.user.as_ref()
+    }
+
+    /// The group that owns a file on another system.
+    pub fn remote_group(&self) -> Option<&manifest::Owner> {
+        self.manifest_entry.as_ref()?.group.as_ref()
+    }
+
+    /// Whether this file came from a manifest rather than the filesystem.
+    pub fn is_virtual(&self) -> bool {
+        self.manifest_entry.is_some()
+    }
+
+    pub fn new_aa_current(parent_dir: &'dir Dir, total_size: Option<TotalSize>) -> File<'dir> {
         File::new_aa(parent_dir.path.clone(), parent_dir, ".", total_size)
     }
 
-    pub fn new_aa_parent(path: PathBuf, parent_dir: &'dir Dir, total_size: bool) -> File<'dir> {
+    pub fn new_aa_parent(
+        path: PathBuf,
+        parent_dir: &'dir Dir,
+        total_size: Option<TotalSize>,
+    ) -> File<'dir> {
         File::new_aa(path, parent_dir, "..", total_size)
     }
 
@@ -292,6 +606,9 @@ impl<'dir> File<'dir> {
 
     /// Whether this file is a directory on the filesystem.
     pub fn is_directory(&self) -> bool {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.kind == manifest::Kind::Directory;
+        }
         self.filetype().map_or(false, std::fs::FileType::is_dir)
     }
 
@@ -317,7 +634,17 @@ impl<'dir> File<'dir> {
     ///
     /// Returns an IO error upon failure, but this shouldn’t be used to check
     /// if a `File` is a directory or not! For that, just use `is_directory()`.
+    ///
+    /// A directory from a manifest has nothing to read, and holds the
+    /// entries the manifest puts beneath it instead.
     pub fn to_dir(&self) -> io::Result<Dir> {
+        if let Some(entry) = &self.manifest_entry {
+            return Ok(Dir::from_manifest(
+                self.path.clone(),
+                entry.children.clone(),
+            ));
+        }
+
         trace!("to_dir: reading dir");
         Dir::read_dir(self.path.clone())
     }
@@ -325,6 +652,9 @@ impl<'dir> File<'dir> {
     /// Whether this file is a regular file on the filesystem — that is, not a
     /// directory, a link, or anything else treated specially.
     pub fn is_file(&self) -> bool {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.kind == manifest::Kind::File;
+        }
         self.filetype().map_or(false, std::fs::FileType::is_file)
     }
 
@@ -337,6 +667,9 @@ impl<'dir> File<'dir> {
         if !self.is_file() {
             return false;
         }
+        if let Some(entry) = &self.manifest_entry {
+            return entry.mode.is_some_and(|mode| mode & bit == bit);
+        }
         let Ok(md) = self.metadata() else {
             return false;
         };
@@ -345,7 +678,34 @@ impl<'dir> File<'dir> {
 
     /// Whether this file is a symlink on the filesystem.
     pub fn is_link(&self) -> bool {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.kind == manifest::Kind::Link;
+        }
         self.filetype().map_or(false, FileType::is_symlink)
+            || self
+                .reparse_point()
+                .is_some_and(|r| r.kind == ReparseKind::AppExecLink)
+    }
+
+    /// What kind of reparse point this file is, and where it leads, or
+    /// nothing if it isn’t one or isn’t on Windows.
+    pub fn reparse_point(&self) -> Option<&ReparsePoint> {
+        self.reparse_point
+            .get_or_init(|| {
+                #[cfg(windows)]
+                let is_reparse_point = self
+                    .metadata()
+                    .is_ok_and(|md| md.file_attributes() & 0x400 != 0);
+                #[cfg(not(windows))]
+                let is_reparse_point = false;
+
+                if reparse::ENABLED && !self.is_virtual() && is_reparse_point {
+                    reparse::read(&self.path)
+                } else {
+                    None
+                }
+            })
+            .as_ref()
     }
 
     /// Whether this file is a named pipe on the filesystem.
@@ -372,6 +732,23 @@ impl<'dir> File<'dir> {
         self.filetype().map_or(false, FileTypeExt::is_socket)
     }
 
+    /// Whether this file is a door, which only exist on Solaris and illumos.
+    #[cfg(unix)]
+    pub fn is_door(&self) -> bool {
+        const S_IFMT: u32 = 0o170_000;
+        const S_IFDOOR: u32 = 0o150_000;
+        self.metadata()
+            .is_ok_and(|md| md.mode() & S_IFMT == S_IFDOOR)
+    }
+
+    /// Whether this file has capabilities, which let a program do some of
+    /// the things only root can without it having to be setuid.
+    pub fn has_capabilities(&self) -> bool {
+        self.extended_attributes()
+            .iter()
+            .any(|attr| attr.name == "security.capability")
+    }
+
     /// Determine the full path resolving all symbolic links on demand.
     pub fn absolute_path(&self) -> Option<&PathBuf> {
         self.absolute_path
@@ -400,6 +777,65 @@ impl<'dir> File<'dir> {
                 .is_some_and(|p| all_mounts().contains_key(p))
     }
 
+    /// The human-readable target of a desktop entry or a Windows shortcut:
+    /// the application name for the former, and the path it points to for
+    /// the latter. Anything else, including unreadable files, has none.
+    pub fn shortcut_target(&self) -> Option<String> {
+        // Launcher files are tiny, so don’t go reading anything huge that
+        // just happens to have the same extension.
+        const MAX_SIZE: u64 = 64 * 1024;
+
+        let parse: fn(&[u8]) -> Option<String> = match self.ext.as_deref() {
+            Some("desktop") => {
+                |data: &[u8]| shortcut::desktop_entry_name(str::from_utf8(data).ok()?)
+            }
+            Some("lnk") => shortcut::lnk_target,
+            _ => return None,
+        };
+
+        if !self.is_file() || self.metadata().ok()?.len() > MAX_SIZE {
+            return None;
+        }
+
+        parse(&std::fs::read(&self.path).ok()?)
+    }
+
+    /// What the kernel’s socket table says about this socket: whether
+    /// anything is listening on it, and how many connections it has. This is
+    /// only known on Linux, and a socket missing from the table has neither.
+    #[cfg(unix)]
+    pub fn socket_state(&self) -> Option<SocketState> {
+        if !self.is_socket() {
+            return None;
+        }
+
+        let sockets = all_sockets()?;
+        let state = sockets
+            .get(&self.path)
+            .or_else(|| self.absolute_path().and_then(|p| sockets.get(p)));
+        Some(state.copied().unwrap_or_default())
+    }
+
+    /// The processes that are accepting connections on this socket, or
+    /// nothing if nothing is or they can’t be looked for.
+    #[cfg(unix)]
+    pub fn socket_listeners(&self) -> Option<Vec<Process>> {
+        let inode = self.socket_state()?.listener?;
+        processes::holding_socket(inode)
+    }
+
+    /// The processes that have this named pipe open, and whether they’re
+    /// reading from it, writing to it, or both, or nothing if they can’t be
+    /// looked for, which is only possible on Linux.
+    #[cfg(unix)]
+    pub fn pipe_holders(&self) -> Option<Vec<(Process, PipeAccess)>> {
+        if !self.is_pipe() {
+            return None;
+        }
+
+        processes::holding_pipe(self.absolute_path()?)
+    }
+
     /// The filesystem device and type for a mount point
     pub fn mount_point_info(&self) -> Option<&MountedFs> {
         if cfg!(any(target_os = "linux", target_os = "macos")) {
@@ -434,14 +870,30 @@ impl<'dir> File<'dir> {
     /// existed. If this file cannot be read at all, returns the error that
     /// we got when we tried to read it.
     pub fn link_target(&self) -> FileTarget<'dir> {
+        // A manifest doesn’t say where its links lead.
+        if self.is_virtual() {
+            return FileTarget::Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "not on the filesystem",
+            ));
+        }
+
         // We need to be careful to treat the path actually pointed to by
         // this file — which could be absolute or relative — to the path
         // we actually look up and turn into a `File` — which needs to be
         // absolute to be accessible from any directory.
+        //
+        // On Windows, the path a reparse point gives for people to read is
+        // used in preference to the one the filesystem follows, which for
+        // junctions starts with `\\?\`, and is the only way to find where
+        // app execution aliases lead.
         debug!("Reading link {:?}", &self.path);
-        let path = match std::fs::read_link(&self.path) {
-            Ok(p) => p,
-            Err(e) => return FileTarget::Err(e),
+        let path = match self.reparse_point().and_then(|r| r.target.clone()) {
+            Some(p) => p,
+            None => match std::fs::read_link(&self.path) {
+                Ok(p) => p,
+                Err(e) => return FileTarget::Err(e),
+            },
         };
 
         let absolute_path = self.reorient_target_path(&path);
@@ -465,7 +917,16 @@ impl<'dir> File<'dir> {
                     deref_links: self.deref_links,
                     extended_attributes,
                     absolute_path: absolute_path_cell,
+                    mime_type: OnceLock::new(),
+                    trash_info: OnceLock::new(),
+                    lfs_size: OnceLock::new(),
+                    security: OnceLock::new(),
+                    statx: OnceLock::new(),
+                    reparse_point: OnceLock::new(),
+                    total_size: None,
                     recursive_size: RecursiveSize::None,
+                    manifest_entry: None,
+                    aliases: Vec::new(),
                 };
                 FileTarget::Ok(Box::new(file))
             }
@@ -497,6 +958,19 @@ impl<'dir> File<'dir> {
         target
     }
 
+    /// The file at the other end of this symlink, if this is a symlink that
+    /// leads to a file that exists, so its own details can be looked at.
+    pub fn link_target_file(&self) -> Option<Box<File<'dir>>> {
+        if !self.is_link() {
+            return None;
+        }
+
+        match self.link_target() {
+            FileTarget::Ok(target) => Some(target),
+            FileTarget::Broken(_) | FileTarget::Err(_) => None,
+        }
+    }
+
     /// This file’s number of hard links.
     ///
     /// It also reports whether this is both a regular file, and a file with
@@ -514,12 +988,37 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// The path of the first file listed that’s a hard link to the same
+    /// inode as this one, if that isn’t this file. A file with other links
+    /// to it gets remembered the first time this is asked for, so it has to
+    /// be asked in the order files get listed.
+    #[cfg(unix)]
+    pub fn first_hard_link(&self) -> Option<PathBuf> {
+        let metadata = self.metadata().ok()?;
+        if !metadata.is_file() || metadata.nlink() < 2 {
+            return None;
+        }
+
+        let mut hard_links = HARD_LINKS.lock().unwrap();
+        let first = hard_links
+            .entry((metadata.dev(), metadata.ino()))
+            .or_insert_with(|| self.path.clone());
+        (*first != self.path).then(|| first.clone())
+    }
+
     /// This file’s inode.
     #[cfg(unix)]
     pub fn inode(&self) -> f::Inode {
         f::Inode(self.metadata().map_or(0, MetadataExt::ino))
     }
 
+    /// What identifies the file at the end of this path, following any
+    /// symlinks, so that the same file can be spotted when it’s reached
+    /// through different paths.
+    pub fn identity(&self) -> Option<Identity> {
+        dedupe::identity(&self.path)
+    }
+
     /// This actual size the file takes up on disk, in bytes.
     #[cfg(unix)]
     pub fn blocksize(&self) -> f::Blocksize {
@@ -543,10 +1042,21 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// Whether this is a sparse file: a regular file with fewer bytes of
+    /// blocks allocated to it than its size, such as a disk image with holes
+    /// in it.
+    #[cfg(unix)]
+    pub fn is_sparse(&self) -> bool {
+        self.is_file() && self.metadata().is_ok_and(|md| md.blocks() * 512 < md.len())
+    }
+
     /// The ID of the user that own this file. If dereferencing links, the links
     /// may be broken, in which case `None` will be returned.
     #[cfg(unix)]
     pub fn user(&self) -> Option<f::User> {
+        if self.is_virtual() {
+            return None;
+        }
         if self.is_link() && self.deref_links {
             return match self.link_target_recurse() {
                 FileTarget::Ok(f) => f.user(),
@@ -559,6 +1069,9 @@ impl<'dir> File<'dir> {
     /// The ID of the group that owns this file.
     #[cfg(unix)]
     pub fn group(&self) -> Option<f::Group> {
+        if self.is_virtual() {
+            return None;
+        }
         if self.is_link() && self.deref_links {
             return match self.link_target_recurse() {
                 FileTarget::Ok(f) => f.group(),
@@ -581,6 +1094,9 @@ impl<'dir> File<'dir> {
     /// links) if dereferencing is enabled, otherwise None.
     #[cfg(unix)]
     pub fn size(&self) -> f::Size {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.size();
+        }
         if self.deref_links && self.is_link() {
             match self.link_target() {
                 FileTarget::Ok(f) => f.size(),
@@ -604,7 +1120,10 @@ impl<'dir> File<'dir> {
                 minor: unsafe { libc::minor(device_id.try_into().unwrap()) } as u32,
             })
         } else if self.is_file() {
-            f::Size::Some(self.metadata().map_or(0, std::fs::Metadata::len))
+            match self.lfs_size() {
+                Some(size) => f::Size::Lfs(size),
+                None => f::Size::Some(self.metadata().map_or(0, std::fs::Metadata::len)),
+            }
         } else {
             // symlink
             f::Size::None
@@ -617,51 +1136,100 @@ impl<'dir> File<'dir> {
     /// return `Size::None`.
     #[cfg(windows)]
     pub fn size(&self) -> f::Size {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.size();
+        }
         if self.is_directory() {
             f::Size::None
+        } else if let Some(size) = self.lfs_size() {
+            f::Size::Lfs(size)
         } else {
             f::Size::Some(self.metadata().map_or(0, std::fs::Metadata::len))
         }
     }
 
+    /// The size of the object this file points to, if it’s a Git LFS
+    /// pointer file rather than the file itself.
+    fn lfs_size(&self) -> Option<u64> {
+        *self.lfs_size.get_or_init(|| {
+            let len = self.metadata().map_or(0, std::fs::Metadata::len);
+            lfs::object_size(&self.path, len)
+        })
+    }
+
     /// Calculate the total directory size recursively.  If not a directory `None`
     /// will be returned.  The directory size is cached for recursive directory
     /// listing.
     #[cfg(unix)]
-    fn recursive_directory_size(&self) -> RecursiveSize {
-        if self.is_directory() {
-            let key = (
-                self.metadata().map_or(0, MetadataExt::dev),
-                self.metadata().map_or(0, MetadataExt::ino),
-            );
-            if let Some(size) = DIRECTORY_SIZE_CACHE.lock().unwrap().get(&key) {
-                return RecursiveSize::Some(size.0, size.1);
+    fn recursive_directory_size(&self, total_size: TotalSize) -> RecursiveSize {
+        if !self.is_directory() {
+            return RecursiveSize::None;
+        }
+
+        match self.directory_size(total_size.max_depth) {
+            Some((size, blocks)) => match total_size.measure {
+                SizeMeasure::Apparent => RecursiveSize::Some(size, blocks),
+                SizeMeasure::Allocated => RecursiveSize::Some(blocks * 512, blocks),
+            },
+            None => RecursiveSize::Unknown,
+        }
+    }
+
+    /// Adds up the sizes and blocks of this directory and everything in it,
+    /// going no more than the given number of levels down, or nothing if it
+    /// can’t be read. Directories past the last level count as empty.
+    #[cfg(unix)]
+    fn directory_size(&self, max_depth: Option<usize>) -> Option<(u64, u64)> {
+        let key = (
+            self.metadata().map_or(0, MetadataExt::dev),
+            self.metadata().map_or(0, MetadataExt::ino),
+            max_depth,
+        );
+        if let Some(size) = DIRECTORY_SIZE_CACHE.lock().unwrap().get(&key) {
+            return Some(*size);
+        }
+
+        let size = self.tree_size(max_depth, &mut HashSet::new())?;
+        DIRECTORY_SIZE_CACHE.lock().unwrap().insert(key, size);
+        Some(size)
+    }
+
+    /// Adds up the sizes and blocks of this directory and everything in it,
+    /// the way `du` does: each directory’s own size counts, and a file with
+    /// other hard links to it only counts the first time it gets `seen`.
+    /// Subdirectories that can’t be read count as empty. Sizes found this
+    /// way aren’t cached, as they depend on what was seen before them.
+    #[cfg(unix)]
+    fn tree_size(
+        &self,
+        max_depth: Option<usize>,
+        seen: &mut HashSet<(u64, u64)>,
+    ) -> Option<(u64, u64)> {
+        let metadata = self.metadata().ok()?;
+        let mut size = metadata.len();
+        let mut blocks = metadata.blocks();
+        if max_depth == Some(0) {
+            return Some((size, blocks));
+        }
+
+        let dir = Dir::read_dir(self.path.clone()).ok()?;
+        for file in dir.files(super::DotFilter::Dotfiles, None, false, false, None) {
+            let Ok(metadata) = file.metadata() else {
+                continue;
+            };
+            if file.is_directory() {
+                let (bytes, blks) = file
+                    .tree_size(max_depth.map(|d| d - 1), seen)
+                    .unwrap_or((metadata.len(), metadata.blocks()));
+                size += bytes;
+                blocks += blks;
+            } else if metadata.nlink() < 2 || seen.insert((metadata.dev(), metadata.ino())) {
+                size += file.length();
+                blocks += metadata.blocks();
             }
-            Dir::read_dir(self.path.clone()).map_or(RecursiveSize::Unknown, |dir| {
-                let mut size = 0;
-                let mut blocks = 0;
-                for file in dir.files(super::DotFilter::Dotfiles, None, false, false, true) {
-                    match file.recursive_directory_size() {
-                        RecursiveSize::Some(bytes, blks) => {
-                            size += bytes;
-                            blocks += blks;
-                        }
-                        RecursiveSize::Unknown => {}
-                        RecursiveSize::None => {
-                            size += file.metadata().map_or(0, MetadataExt::size);
-                            blocks += file.metadata().map_or(0, MetadataExt::blocks);
-                        }
-                    }
-                }
-                DIRECTORY_SIZE_CACHE
-                    .lock()
-                    .unwrap()
-                    .insert(key, (size, blocks));
-                RecursiveSize::Some(size, blocks)
-            })
-        } else {
-            RecursiveSize::None
         }
+
+        Some((size, blocks))
     }
 
     /// Windows version always returns None.  The metadata for
@@ -669,22 +1237,32 @@ impl<'dir> File<'dir> {
     /// not cache the sizes.  Without caching we could end up walking the
     /// directory structure several times.
     #[cfg(windows)]
-    fn recursive_directory_size(&self) -> RecursiveSize {
+    fn recursive_directory_size(&self, _total_size: TotalSize) -> RecursiveSize {
         RecursiveSize::None
     }
 
     /// Returns the same value as `self.metadata.len()` or the recursive size
-    /// of a directory when `total_size` is used.
+    /// of a directory when `total_size` is used, or the size of the object a
+    /// Git LFS pointer points to.
     #[inline]
     pub fn length(&self) -> u64 {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.size;
+        }
+        if self.is_file() {
+            if let Some(size) = self.lfs_size() {
+                return size;
+            }
+        }
         self.recursive_size
             .unwrap_bytes_or(self.metadata().map_or(0, std::fs::Metadata::len))
     }
 
-    /// Is the file is using recursive size calculation
+    /// How this file’s recursive size is worked out, if it is, so the
+    /// files inside it can have theirs worked out the same way.
     #[inline]
-    pub fn is_recursive_size(&self) -> bool {
-        !self.recursive_size.is_none()
+    pub fn total_size(&self) -> Option<TotalSize> {
+        self.total_size
     }
 
     /// Determines if the directory is empty or not.
@@ -741,7 +1319,7 @@ impl<'dir> File<'dir> {
         match Dir::read_dir(self.path.clone()) {
             // . & .. are skipped, if the returned iterator has .next(), it's not empty
             Ok(has_files) => has_files
-                .files(super::DotFilter::Dotfiles, None, false, false, false)
+                .files(super::DotFilter::Dotfiles, None, false, false, None)
                 .next()
                 .is_none(),
             Err(_) => false,
@@ -764,6 +1342,9 @@ impl<'dir> File<'dir> {
 
     /// This file’s last modified timestamp, if available on this platform.
     pub fn modified_time(&self) -> Option<NaiveDateTime> {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.modified;
+        }
         if self.is_link() && self.deref_links {
             return match self.link_target_recurse() {
                 FileTarget::Ok(f) => f.modified_time(),
@@ -779,6 +1360,9 @@ impl<'dir> File<'dir> {
     /// This file’s last changed timestamp, if available on this platform.
     #[cfg(unix)]
     pub fn changed_time(&self) -> Option<NaiveDateTime> {
+        if self.is_virtual() {
+            return None;
+        }
         if self.is_link() && self.deref_links {
             return match self.link_target_recurse() {
                 FileTarget::Ok(f) => f.changed_time(),
@@ -800,6 +1384,9 @@ impl<'dir> File<'dir> {
 
     /// This file’s last accessed timestamp, if available on this platform.
     pub fn accessed_time(&self) -> Option<NaiveDateTime> {
+        if let Some(entry) = &self.manifest_entry {
+            return entry.accessed;
+        }
         if self.is_link() && self.deref_links {
             return match self.link_target_recurse() {
                 FileTarget::Ok(f) => f.accessed_time(),
@@ -820,10 +1407,29 @@ impl<'dir> File<'dir> {
                 _ => None,
             };
         }
-        let btime = self.metadata().ok()?.created().ok()?;
+        // Where `statx` is around, it has the final say on whether there’s
+        // a birth time at all.
+        let btime = match self.statx() {
+            Some(statx) => statx.birth?,
+            None => self.metadata().ok()?.created().ok()?,
+        };
         Self::systemtime_to_naivedatetime(btime)
     }
 
+    /// What `statx` says about this file, or nothing where there’s no
+    /// `statx` to ask.
+    fn statx(&self) -> Option<&Statx> {
+        self.statx
+            .get_or_init(|| {
+                if statx::ENABLED && !self.is_virtual() {
+                    statx::read(&self.path)
+                } else {
+                    None
+                }
+            })
+            .as_ref()
+    }
+
     /// This file’s ‘type’.
     ///
     /// This is used a the leftmost character of the permissions column.
@@ -873,7 +1479,10 @@ impl<'dir> File<'dir> {
                 _ => None,
             };
         }
-        let bits = self.metadata().map_or(0, MetadataExt::mode);
+        let bits = match &self.manifest_entry {
+            Some(entry) => entry.mode.unwrap_or(0),
+            None => self.metadata().map_or(0, MetadataExt::mode),
+        };
         let has_bit = |bit| bits & bit == bit;
 
         Some(f::Permissions {
@@ -907,26 +1516,154 @@ impl<'dir> File<'dir> {
             readonly: has_bit(0x1),
             hidden: has_bit(0x2),
             system: has_bit(0x4),
-            reparse_point: has_bit(0x400),
+            reparse: has_bit(0x400).then(|| {
+                self.reparse_point()
+                    .map_or(ReparseKind::Other, |reparse| reparse.kind)
+            }),
         })
     }
 
-    /// This file’s security context field.
-    #[cfg(unix)]
-    pub fn security_context(&self) -> f::SecurityContext<'_> {
-        let context = match self
+    /// This file’s alternate data streams, on NTFS.
+    pub fn alternate_streams(&self) -> io::Result<Vec<Stream>> {
+        if self.is_virtual() {
+            return Ok(Vec::new());
+        }
+        streams::list(&self.path)
+    }
+
+    /// The user that owns this file, from its security descriptor.
+    #[cfg(windows)]
+    pub fn security_user(&self) -> Option<&manifest::Owner> {
+        self.security().user.as_ref()
+    }
+
+    /// The group that owns this file, from its security descriptor.
+    #[cfg(windows)]
+    pub fn security_group(&self) -> Option<&manifest::Owner> {
+        self.security().group.as_ref()
+    }
+
+    /// What the user running eza may do with this file.
+    pub fn access_rights(&self) -> f::Access {
+        self.security().access
+    }
+
+    fn security(&self) -> &Security {
+        self.security.get_or_init(|| {
+            if security::ENABLED && !self.is_virtual() {
+                security::read(&self.path)
+            } else {
+                Security::default()
+            }
+        })
+    }
+
+    /// The ID of the btrfs subvolume this file is in.
+    pub fn subvolume(&self) -> f::Subvolume {
+        f::Subvolume(
+            self.cow_location()
+                .filter(|(_, mount)| mount.fstype == "btrfs")
+                .and_then(|(dir, _)| cow::subvolume_id(&dir)),
+        )
+    }
+
+    /// The ID of the project this file is in, for project quotas. Only
+    /// regular files and directories can be asked for it, as with `flags`.
+    pub fn project_id(&self) -> f::ProjectId {
+        if !chattr::ENABLED || self.is_virtual() || !(self.is_file() || self.is_directory()) {
+            return f::ProjectId(None);
+        }
+
+        let id = chattr::project_id(&self.path);
+        if id.is_none() {
+            debug!("Failed to read project ID of {:?}", self.path);
+        }
+        f::ProjectId(id)
+    }
+
+    /// The compression property set on this file, on btrfs.
+    pub fn compression(&self) -> f::Compression {
+        let attribute = self
             .extended_attributes()
             .iter()
-            .find(|a| a.name == "security.selinux")
-        {
-            Some(attr) => match &attr.value {
-                None => SecurityContextType::None,
-                Some(value) => match str::from_utf8(value) {
-                    Ok(v) => SecurityContextType::SELinux(v.trim_end_matches(char::from(0))),
-                    Err(_) => SecurityContextType::None,
-                },
-            },
-            None => SecurityContextType::None,
+            .find(|a| a.name == cow::COMPRESSION_ATTRIBUTE);
+
+        f::Compression(
+            attribute
+                .and_then(|a| a.value.as_deref())
+                .map(|value| {
+                    String::from_utf8_lossy(value)
+                        .trim_end_matches('\0')
+                        .to_owned()
+                })
+                .filter(|value| !value.is_empty()),
+        )
+    }
+
+    /// The name of the ZFS dataset this file is in.
+    pub fn dataset(&self) -> f::Dataset {
+        f::Dataset(
+            self.cow_location()
+                .filter(|(_, mount)| mount.fstype == "zfs")
+                .map(|(_, mount)| mount.source.clone()),
+        )
+    }
+
+    /// The directory that tells which subvolume or dataset this file is in,
+    /// along with the mount it’s under. That’s the file itself for a
+    /// directory, which can be the root of one, and the directory holding
+    /// it for anything else, so a symlink isn’t taken to be wherever it
+    /// points.
+    fn cow_location(&self) -> Option<(PathBuf, &'static MountedFs)> {
+        if !cow::ENABLED || self.is_virtual() {
+            return None;
+        }
+
+        let dir = if self.is_directory() {
+            self.absolute_path()?.clone()
+        } else {
+            let parent = self.path.parent().filter(|p| !p.as_os_str().is_empty());
+            std::fs::canonicalize(parent.unwrap_or(Path::new("."))).ok()?
+        };
+
+        let mount = cow::containing_mount(&dir, all_mounts())?;
+        Some((dir, mount))
+    }
+
+    /// This file’s access control lists, read out of its extended
+    /// attributes, if it has any.
+    pub fn acl(&self) -> Option<Acl> {
+        if !acl::ENABLED {
+            return None;
+        }
+        Acl::from_attributes(self.extended_attributes())
+    }
+
+    /// Whether this file has an ACL that grants more than its permission
+    /// bits show, which gets a `+` after the permissions.
+    pub fn has_extended_acl(&self) -> bool {
+        self.acl().is_some_and(|acl| acl.is_extended())
+    }
+
+    /// This file’s security context field, which is the label from
+    /// whichever of `SELinux`, Smack, or `AppArmor` has given it one, in that
+    /// order.
+    #[cfg(unix)]
+    pub fn security_context(&self) -> f::SecurityContext<'_> {
+        let label = |name: &str| {
+            let attr = self.extended_attributes().iter().find(|a| a.name == name)?;
+            let value = str::from_utf8(attr.value.as_ref()?).ok()?;
+            Some(value.trim_end_matches(char::from(0)))
+        };
+
+        let context = if let Some(context) = label("security.selinux") {
+            SecurityContextType::SELinux(context)
+        } else if let Some(context) = label("security.SMACK64") {
+            SecurityContextType::Smack(context)
+        } else if let Some(context) = label("security.apparmor") {
+            SecurityContextType::AppArmor(context)
+        } else {
+            SecurityContextType::None
         };
 
         f::SecurityContext { context }
@@ -939,6 +1676,18 @@ impl<'dir> File<'dir> {
         }
     }
 
+    /// This file’s version information. Only executables and libraries on
+    /// Windows carry a version resource, so everything else gets nothing.
+    pub fn file_version(&self) -> f::FileVersion {
+        let versioned = matches!(self.ext.as_deref(), Some("exe" | "dll"));
+
+        if versioned && self.is_file() {
+            version_info::read(&self.path)
+        } else {
+            f::FileVersion::default()
+        }
+    }
+
     /// User file flags.
     #[cfg(any(
         target_os = "macos",
@@ -970,6 +1719,9 @@ impl<'dir> File<'dir> {
         f::Flags(self.metadata().map_or(0, |md| md.file_attributes()))
     }
 
+    /// On Linux, the flags `chattr` sets, such as whether the file is
+    /// immutable. Only regular files and directories can be asked for
+    /// these, so anything else gets the ones `statx` mirrors.
     #[cfg(not(any(
         target_os = "macos",
         target_os = "freebsd",
@@ -979,7 +1731,17 @@ impl<'dir> File<'dir> {
         target_os = "windows"
     )))]
     pub fn flags(&self) -> f::Flags {
-        f::Flags(0)
+        let flags =
+            if chattr::ENABLED && !self.is_virtual() && (self.is_file() || self.is_directory()) {
+                chattr::read(&self.path)
+            } else {
+                None
+            };
+
+        f::Flags(flags.unwrap_or_else(|| {
+            self.statx()
+                .map_or(0, |statx| chattr::from_statx(statx.attributes))
+        }))
     }
 }
 
@@ -1098,3 +1860,35 @@ mod filename_test {
         assert_eq!("/", File::filename(Path::new("/")));
     }
 }
+
+#[cfg(all(test, unix))]
+mod directory_size_test {
+    use super::File;
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+
+    #[test]
+    fn hard_links_and_sparse_files() {
+        let dir = std::env::temp_dir().join(format!("eza-size-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("file"), [1; 100]).unwrap();
+        fs::hard_link(dir.join("file"), dir.join("sub/link")).unwrap();
+        fs::File::create(dir.join("sparse"))
+            .unwrap()
+            .set_len(1 << 20)
+            .unwrap();
+
+        let stat = |name: &str| fs::symlink_metadata(dir.join(name)).unwrap();
+        let size = File::from_args(dir.clone(), None, None, false, None, None).directory_size(None);
+        let sparse = stat("sparse");
+        let expected = (
+            stat("").len() + stat("sub").len() + 100 + sparse.len(),
+            stat("").blocks() + stat("sub").blocks() + stat("file").blocks() + sparse.blocks(),
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(size, Some(expected));
+        assert!(sparse.blocks() * 512 < sparse.len());
+    }
+}
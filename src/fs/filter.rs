@@ -10,9 +10,17 @@ use std::cmp::Ordering;
 use std::iter::FromIterator;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 
+use chrono::NaiveDateTime;
+use regex::{Regex, RegexBuilder};
+
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+use crate::fs::ignore_files;
 use crate::fs::DotFilter;
 use crate::fs::File;
+use crate::output::table::TimeType;
 
 /// Flags used to manage the **file filter** process
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -34,6 +42,9 @@ pub enum FileFilterFlags {
     /// Whether to explicitly show symlinks
     ShowSymlinks,
 
+    /// Whether to only show symlinks whose targets don’t exist.
+    OnlyBrokenLinks,
+
     /// Whether directories should be listed first, and other types of file
     /// second. Some users prefer it like this.
     ListDirsFirst,
@@ -41,6 +52,15 @@ pub enum FileFilterFlags {
     /// Whether directories should be listed as the last items, after other
     /// types of file. Some users prefer it like this.
     ListDirsLast,
+
+    /// Whether files that the sort field considers equal should be put in
+    /// order of their names’ bytes, rather than left in the order they
+    /// were read in, so listings come out the same every time.
+    BreakTies,
+
+    /// Whether a file or directory that several of the paths given as
+    /// arguments lead to should only be listed once.
+    Dedupe,
 }
 
 /// The **file filter** processes a list of files before displaying them to
@@ -82,9 +102,39 @@ pub struct FileFilter {
     /// patterns won’t be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
 
+    /// Glob patterns of directories not to recurse into. They still get
+    /// displayed, but their contents don’t.
+    pub prune_patterns: IgnorePatterns,
+
+    /// A regular expression that files have to match to be displayed.
+    pub regex: Option<RegexFilter>,
+
+    /// Limits on how new or old files have to be to be displayed.
+    pub time_filter: Option<TimeFilter>,
+
+    /// The kinds of file to display, if not all of them.
+    pub type_filter: Option<TypeFilter>,
+
+    /// The extensions of the files to display, if not all of them.
+    pub extension_filter: Option<ExtensionFilter>,
+
+    /// Whether to display only empty files and directories, or only ones
+    /// that aren’t empty.
+    pub empty_filter: Option<EmptyFilter>,
+
+    /// The permission bits files need to have to be displayed, if any.
+    pub perm_filter: Option<PermFilter>,
+
     /// Whether to ignore Git-ignored patterns.
     pub git_ignore: GitIgnore,
 
+    /// Which files to list out of the ones Git knows about, if only those.
+    pub git_only: Option<GitOnly>,
+
+    /// Whether to ignore the files that `.ignore` and `.fdignore` files
+    /// list, as ripgrep and fd do.
+    pub ignore_files: bool,
+
     /// Whether to ignore symlinks
     pub no_symlinks: bool,
 
@@ -100,6 +150,37 @@ impl FileFilter {
         use FileFilterFlags::{NoSymlinks, OnlyDirs, OnlyFiles, ShowSymlinks};
 
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        if self.ignore_files {
+            files.retain(|f| !ignore_files::is_ignored(&f.path, f.is_directory()));
+        }
+        if let Some(regex) = &self.regex {
+            // Directories have to be kept when recursing, so the files in
+            // them can be matched.
+            files.retain(|f| (is_recurse && f.is_directory()) || regex.matches(&f.name, &f.path));
+        }
+        if let Some(time_filter) = &self.time_filter {
+            files.retain(|f| (is_recurse && f.is_directory()) || time_filter.matches(f));
+        }
+        if let Some(type_filter) = &self.type_filter {
+            files.retain(|f| (is_recurse && f.is_directory()) || type_filter.matches(f));
+        }
+        if let Some(extension_filter) = &self.extension_filter {
+            files.retain(|f| (is_recurse && f.is_directory()) || extension_filter.matches(&f.name));
+        }
+        if self.flags.contains(&FileFilterFlags::OnlyBrokenLinks) {
+            files.retain(|f| (is_recurse && f.is_directory()) || (f.is_link() && f.link_target().is_broken()));
+        }
+        if let Some(perm_filter) = self.perm_filter {
+            files.retain(|f| (is_recurse && f.is_directory()) || perm_filter.matches(f));
+        }
+        if let Some(empty_filter) = self.empty_filter {
+            // Directories with things in are kept when recursing, so the
+            // empty ones in them can be found, but empty ones can always go.
+            files.retain(|f| match empty_filter {
+                EmptyFilter::OnlyEmpty => (is_recurse && f.is_directory()) || self.is_empty(f),
+                EmptyFilter::NoEmpty   => !self.is_empty(f),
+            });
+        }
         files.retain(|f| {
             match (
                 self.flags.contains(&OnlyDirs),
@@ -110,15 +191,28 @@ impl FileFilter {
                 (true, false, false, false) => f.is_directory(),
                 (true, false, true, false) => f.is_directory(),
                 (true, false, false, true) => f.is_directory() || f.points_to_directory(),
-                (false, true, false, false) => if is_recurse { true } else {f.is_file() },
-                (false, true, false, true) => if is_recurse { true } else { f.is_file() || f.is_link() && !f.points_to_directory()
-                },
+                // Directories have to be kept when recursing, so the files
+                // in them can be listed.
+                (false, true, _, false) => (is_recurse && f.is_directory()) || f.is_file(),
+                (false, true, false, true) => (is_recurse && f.is_directory()) || f.is_file() || f.is_link() && !f.points_to_directory(),
                 (false, false, true, false) => !f.is_link(),
                 _ => true,
             }
         });
     }
 
+    /// Remove every file in the given vector that Git doesn’t track, or
+    /// that hasn’t changed, depending on `--git-only`. Directories are only
+    /// kept if something inside them would be, which prunes the subtrees
+    /// with nothing to show when recursing.
+    pub fn filter_git_files(&self, git: Option<&GitCache>, files: &mut Vec<File<'_>>) {
+        let (Some(git_only), Some(git)) = (self.git_only, git) else {
+            return;
+        };
+
+        files.retain(|f| git_only.matches(git, f));
+    }
+
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for file names specified on the command-line.
     ///
@@ -130,6 +224,55 @@ impl FileFilter {
     /// from the glob, even though the globbing is done by the shell!
     pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>) {
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        // Directories are kept, as with `--tree` they’re where the listing
+        // starts rather than something to match.
+        if let Some(regex) = &self.regex {
+            files.retain(|f| f.is_directory() || regex.matches(&f.name, &f.path));
+        }
+        if let Some(time_filter) = &self.time_filter {
+            files.retain(|f| f.is_directory() || time_filter.matches(f));
+        }
+        if let Some(type_filter) = &self.type_filter {
+            files.retain(|f| f.is_directory() || type_filter.matches(f));
+        }
+        if let Some(extension_filter) = &self.extension_filter {
+            files.retain(|f| f.is_directory() || extension_filter.matches(&f.name));
+        }
+        if self.flags.contains(&FileFilterFlags::OnlyBrokenLinks) {
+            files.retain(|f| f.is_directory() || (f.is_link() && f.link_target().is_broken()));
+        }
+        if let Some(perm_filter) = self.perm_filter {
+            files.retain(|f| f.is_directory() || perm_filter.matches(f));
+        }
+        if let Some(empty_filter) = self.empty_filter {
+            files.retain(|f| {
+                f.is_directory() || (empty_filter == EmptyFilter::OnlyEmpty) == self.is_empty(f)
+            });
+        }
+    }
+
+    /// Whether the given file is empty: a regular file with nothing in it,
+    /// or a directory with nothing in it that would be listed, which leaves
+    /// out hidden files unless they’re being shown, and ignored files.
+    fn is_empty(&self, file: &File<'_>) -> bool {
+        if file.is_file() {
+            return file.length() == 0;
+        } else if !file.is_directory() {
+            return false;
+        }
+
+        let Ok(dir) = file.to_dir() else {
+            return false;
+        };
+
+        dir.files(self.dot_filter, None, false, false, None)
+            .all(|f| f.is_all_all || self.ignore_patterns.is_ignored(&f.name))
+    }
+
+    /// Whether the contents of the given directory should be left out when
+    /// recursing, because its name matches one of the prune patterns.
+    pub fn is_pruned(&self, dir: &File<'_>) -> bool {
+        self.prune_patterns.is_ignored(&dir.name)
     }
 
     /// Sort the files in the given vector based on the sort field option.
@@ -137,9 +280,32 @@ impl FileFilter {
     where
         F: AsRef<File<'a>>,
     {
-        files.sort_by(|a, b| self.sort_field.compare_files(a.as_ref(), b.as_ref()));
+        let break_ties = self.flags.contains(&FileFilterFlags::BreakTies);
+        let reverse = self.flags.contains(&FileFilterFlags::Reverse);
+
+        // Reversing the comparison, rather than the sorted list, keeps files
+        // that compare equal in the order they were read, so `--reverse`
+        // is as stable as the sort it reverses.
+        files.sort_by(|a, b| {
+            let (a, b) = (a.as_ref(), b.as_ref());
+            let order = self.sort_field.compare_files(a, b);
+
+            let order = if break_ties {
+                order.then_with(|| a.name.cmp(&b.name))
+            } else {
+                order
+            };
+
+            if reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
 
-        if self.flags.contains(&FileFilterFlags::Reverse) {
+        // Unsorted files all compare equal, so the only way to reverse them
+        // is to reverse the list itself.
+        if reverse && self.sort_field == SortField::Unsorted {
             files.reverse();
         }
 
@@ -231,6 +397,11 @@ pub enum SortField {
     /// The file's name, however if the name of the file begins with `.`
     /// ignore the leading `.` and then sort as Name
     NameMixHidden(SortCase),
+
+    /// A shuffled order, worked out from a hash of each file’s name and
+    /// the seed. The same seed always gives the same order for the same
+    /// names, so a listing can be shuffled the same way again.
+    Random(u64),
 }
 
 /// Whether a field should be sorted case-sensitively or case-insensitively.
@@ -306,9 +477,30 @@ impl SortField {
                 Self::strip_dot(&a.name),
                 Self::strip_dot(&b.name)
             ),
+
+            Self::Random(seed) => Self::random_key(seed, &a.name)
+                .cmp(&Self::random_key(seed, &b.name)),
         };
     }
 
+    /// Hashes a file name with the seed, using FNV-1a followed by the
+    /// `SplitMix64` finaliser to spread similar names apart. This is written
+    /// out rather than using the standard library’s hasher, whose output
+    /// isn’t guaranteed to stay the same between Rust versions.
+    fn random_key(seed: u64, name: &str) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325 ^ seed;
+        for byte in name.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+
+        hash ^= hash >> 30;
+        hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash ^= hash >> 27;
+        hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^ (hash >> 31)
+    }
+
     fn strip_dot(n: &str) -> &str {
         match n.strip_prefix('.') {
             Some(s) => s,
@@ -372,11 +564,241 @@ impl IgnorePatterns {
     }
 
     /// Test whether the given file should be hidden from the results.
-    fn is_ignored(&self, file: &str) -> bool {
+    pub fn is_ignored(&self, file: &str) -> bool {
         self.patterns.iter().any(|p| p.matches(file))
     }
 }
 
+/// The **regex filter** is a regular expression that each file has to match
+/// to be displayed, tested against either its name or its whole path, so
+/// that files can be picked out with more than a glob can express.
+#[derive(Debug, Clone)]
+pub struct RegexFilter {
+    regex: Regex,
+
+    /// Whether the regex ignores case.
+    ignore_case: bool,
+
+    /// Whether to match against the path the file was found at, rather
+    /// than just its name.
+    full_path: bool,
+}
+
+impl RegexFilter {
+    /// Builds the regex for the given pattern, returning an error if it
+    /// doesn’t parse.
+    pub fn new(pattern: &str, ignore_case: bool, full_path: bool) -> Result<Self, regex::Error> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()?;
+
+        Ok(Self {
+            regex,
+            ignore_case,
+            full_path,
+        })
+    }
+
+    /// Test whether the file with the given name and path should be shown.
+    pub fn matches(&self, name: &str, path: &Path) -> bool {
+        if self.full_path {
+            self.regex.is_match(&path.to_string_lossy())
+        } else {
+            self.regex.is_match(name)
+        }
+    }
+}
+
+// Regexes can’t be compared, but the patterns they were built from can.
+impl PartialEq for RegexFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.ignore_case == other.ignore_case
+            && self.full_path == other.full_path
+    }
+}
+
+impl Eq for RegexFilter {}
+
+/// The **time filter** limits the files displayed to those with one of
+/// their timestamps after or before a given time, so recent changes can be
+/// picked out. Files without that timestamp are never displayed.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TimeFilter {
+    /// Which of the file’s timestamps to test.
+    pub time_type: TimeType,
+
+    /// The time files have to be newer than, in UTC.
+    pub newer_than: Option<NaiveDateTime>,
+
+    /// The time files have to be older than, in UTC.
+    pub older_than: Option<NaiveDateTime>,
+}
+
+impl TimeFilter {
+    /// Test whether the given file should be shown.
+    pub fn matches(&self, file: &File<'_>) -> bool {
+        match self.time_type.get_corresponding_time(file) {
+            Some(time) => self.contains(time),
+            None => false,
+        }
+    }
+
+    /// Test whether the given time is within the limits.
+    fn contains(&self, time: NaiveDateTime) -> bool {
+        let newer = match self.newer_than {
+            Some(limit) => time > limit,
+            None => true,
+        };
+        let older = match self.older_than {
+            Some(limit) => time < limit,
+            None => true,
+        };
+        newer && older
+    }
+}
+
+/// The **type filter** is the set of kinds of file to display, so that,
+/// say, only symlinks and sockets get listed.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TypeFilter {
+    types: Vec<f::Type>,
+}
+
+impl FromIterator<f::Type> for TypeFilter {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = f::Type>,
+    {
+        let types = iter.into_iter().collect();
+        Self { types }
+    }
+}
+
+impl TypeFilter {
+    /// Test whether the given file should be shown.
+    pub fn matches(&self, file: &File<'_>) -> bool {
+        self.types.contains(&file.type_char())
+    }
+}
+
+/// The **extension filter** is the set of file extensions to display, so
+/// that, say, only Rust sources and their manifests get listed.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ExtensionFilter {
+    /// The extensions, in lowercase and with a `.` in front, so a name can
+    /// be checked for ending in one.
+    suffixes: Vec<String>,
+}
+
+impl<S: AsRef<str>> FromIterator<S> for ExtensionFilter {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let suffixes = iter
+            .into_iter()
+            .map(|ext| {
+                let ext = ext.as_ref();
+                format!(".{}", ext.strip_prefix('.').unwrap_or(ext).to_lowercase())
+            })
+            .collect();
+        Self { suffixes }
+    }
+}
+
+impl ExtensionFilter {
+    /// Test whether the file with the given name should be shown. Names are
+    /// checked for ending in an extension, rather than having it as their
+    /// last one, so extensions with dots in such as `tar.gz` work.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.suffixes.iter().any(|suffix| name.ends_with(suffix))
+    }
+}
+
+/// Whether to display only empty files and directories, or only the ones
+/// with something in them.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum EmptyFilter {
+    /// Display only empty files and directories.
+    OnlyEmpty,
+
+    /// Display only files and directories that aren’t empty.
+    NoEmpty,
+}
+
+/// The **permission filter** is a set of permission bits, and how a file’s
+/// permissions have to match them to be displayed, as with `find -perm`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct PermFilter {
+    pub mode: u32,
+    pub matching: PermMatching,
+}
+
+/// How a file’s permissions have to match the permission filter’s bits.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum PermMatching {
+    /// The file’s permissions have to be exactly these bits.
+    Exact,
+
+    /// The file has to have all of these bits set, and maybe others.
+    All,
+
+    /// The file has to have any of these bits set, or there have to be no
+    /// bits at all.
+    Any,
+}
+
+impl PermFilter {
+    /// Test whether the given file should be shown. Files on systems
+    /// without Unix permissions never match.
+    #[cfg(unix)]
+    pub fn matches(self, file: &File<'_>) -> bool {
+        file.permissions()
+            .is_some_and(|permissions| self.matches_mode(Self::mode_of(permissions)))
+    }
+
+    #[cfg(not(unix))]
+    pub fn matches(self, _file: &File<'_>) -> bool {
+        false
+    }
+
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn matches_mode(self, mode: u32) -> bool {
+        match self.matching {
+            PermMatching::Exact => mode == self.mode,
+            PermMatching::All => mode & self.mode == self.mode,
+            PermMatching::Any => self.mode == 0 || mode & self.mode != 0,
+        }
+    }
+
+    /// Puts the bits of a file’s permissions back together.
+    #[cfg(unix)]
+    fn mode_of(permissions: f::Permissions) -> u32 {
+        #[rustfmt::skip]
+        let bits = [
+            (permissions.setuid,        0o4000),
+            (permissions.setgid,        0o2000),
+            (permissions.sticky,        0o1000),
+            (permissions.user_read,     0o400),
+            (permissions.user_write,    0o200),
+            (permissions.user_execute,  0o100),
+            (permissions.group_read,    0o040),
+            (permissions.group_write,   0o020),
+            (permissions.group_execute, 0o010),
+            (permissions.other_read,    0o004),
+            (permissions.other_write,   0o002),
+            (permissions.other_execute, 0o001),
+        ];
+
+        bits.iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, bit)| bit)
+            .sum()
+    }
+}
+
 /// Whether to ignore or display files that Git would ignore.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum GitIgnore {
@@ -387,6 +809,38 @@ pub enum GitIgnore {
     Off,
 }
 
+/// Which files to list when only listing the ones Git knows about.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum GitOnly {
+    /// List the files in the index, whether they’ve changed or not.
+    Tracked,
+
+    /// List the files with changes that haven’t been committed, including
+    /// untracked ones.
+    Dirty,
+}
+
+impl GitOnly {
+    fn matches(self, git: &GitCache, file: &File<'_>) -> bool {
+        if !git.has_anything_for(&file.path) {
+            return false;
+        }
+
+        let prefix_lookup = file.is_directory();
+        match self {
+            Self::Tracked => git.is_tracked(&file.path, prefix_lookup),
+            Self::Dirty => {
+                let status = git.get(&file.path, prefix_lookup);
+                status.staged != f::GitStatus::NotModified
+                    || !matches!(
+                        status.unstaged,
+                        f::GitStatus::NotModified | f::GitStatus::Ignored
+                    )
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_ignores {
     use super::*;
@@ -422,3 +876,143 @@ mod test_ignores {
         assert!(pats.is_ignored("test.mp3"));
     }
 }
+
+#[cfg(test)]
+mod test_regexes {
+    use super::*;
+
+    #[test]
+    fn matches_names() {
+        let filter = RegexFilter::new(r"^test\.(mp3|ogg)$", false, false).unwrap();
+        assert!(filter.matches("test.mp3", Path::new("music/test.mp3")));
+        assert!(!filter.matches("test.flac", Path::new("music/test.flac")));
+        assert!(!filter.matches("test.MP3", Path::new("music/test.MP3")));
+    }
+
+    #[test]
+    fn matches_paths() {
+        let filter = RegexFilter::new("^music/", false, true).unwrap();
+        assert!(filter.matches("test.mp3", Path::new("music/test.mp3")));
+        assert!(!filter.matches("music", Path::new("videos/music")));
+    }
+
+    #[test]
+    fn ignores_case() {
+        let filter = RegexFilter::new(r"\.mp3$", true, false).unwrap();
+        assert!(filter.matches("TEST.MP3", Path::new("TEST.MP3")));
+    }
+}
+
+#[cfg(test)]
+mod test_times {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn newer_than() {
+        let filter = TimeFilter {
+            time_type: TimeType::Modified,
+            newer_than: Some(date(2024, 1, 1)),
+            older_than: None,
+        };
+        assert!(filter.contains(date(2024, 6, 1)));
+        assert!(!filter.contains(date(2023, 6, 1)));
+    }
+
+    #[test]
+    fn between() {
+        let filter = TimeFilter {
+            time_type: TimeType::Modified,
+            newer_than: Some(date(2024, 1, 1)),
+            older_than: Some(date(2025, 1, 1)),
+        };
+        assert!(filter.contains(date(2024, 6, 1)));
+        assert!(!filter.contains(date(2023, 6, 1)));
+        assert!(!filter.contains(date(2025, 6, 1)));
+    }
+}
+
+#[cfg(test)]
+mod test_extensions {
+    use super::*;
+
+    #[test]
+    fn ignores_case() {
+        let filter = ExtensionFilter::from_iter(["rs", "MD"]);
+        assert!(filter.matches("main.rs"));
+        assert!(filter.matches("README.md"));
+        assert!(filter.matches("Shout.RS"));
+        assert!(!filter.matches("Cargo.toml"));
+    }
+
+    #[test]
+    fn whole_extensions_only() {
+        let filter = ExtensionFilter::from_iter(["rs"]);
+        assert!(!filter.matches("rs"));
+        assert!(!filter.matches("main.lrs"));
+    }
+
+    #[test]
+    fn several_dots() {
+        let filter = ExtensionFilter::from_iter([".tar.gz"]);
+        assert!(filter.matches("release.tar.gz"));
+        assert!(!filter.matches("release.gz"));
+    }
+}
+
+#[cfg(test)]
+mod test_perms {
+    use super::*;
+
+    fn filter(mode: u32, matching: PermMatching) -> PermFilter {
+        PermFilter { mode, matching }
+    }
+
+    #[test]
+    fn exact() {
+        assert!(filter(0o644, PermMatching::Exact).matches_mode(0o644));
+        assert!(!filter(0o644, PermMatching::Exact).matches_mode(0o664));
+    }
+
+    #[test]
+    fn all_bits() {
+        assert!(filter(0o002, PermMatching::All).matches_mode(0o777));
+        assert!(filter(0o022, PermMatching::All).matches_mode(0o666));
+        assert!(!filter(0o022, PermMatching::All).matches_mode(0o644));
+    }
+
+    #[test]
+    fn any_bit() {
+        assert!(filter(0o022, PermMatching::Any).matches_mode(0o664));
+        assert!(!filter(0o022, PermMatching::Any).matches_mode(0o644));
+        assert!(filter(0, PermMatching::Any).matches_mode(0o600));
+    }
+}
+
+#[cfg(test)]
+mod test_random {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_key() {
+        assert_eq!(
+            SortField::random_key(42, "Cargo.toml"),
+            SortField::random_key(42, "Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn different_seed_different_key() {
+        assert_ne!(
+            SortField::random_key(42, "Cargo.toml"),
+            SortField::random_key(43, "Cargo.toml")
+        );
+    }
+}
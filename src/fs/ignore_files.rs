@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Reading the `.ignore` and `.fdignore` files that ripgrep and fd leave
+//! files out with, for `--ignore-files`.
+//!
+//! They’re written the same way as `.gitignore` files: each line is a glob
+//! pattern, with `!` in front to show what an earlier pattern ignored, and
+//! `/` at the end to match only directories. Patterns with a `/` anywhere
+//! else are matched against the path from the directory the ignore file is
+//! in, and the rest against file names at any depth. Where patterns
+//! disagree, the last one wins, with the files in deeper directories read
+//! after those above them, and `.fdignore` read after `.ignore`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use glob::{MatchOptions, Pattern};
+
+/// The names of the ignore files, in the order they’re read.
+const FILE_NAMES: &[&str] = &[".ignore", ".fdignore"];
+
+/// Patterns without a `/` in them have to match a whole file name, and
+/// ones with one can’t have a `*` match across directories.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// One line of an ignore file.
+#[derive(Debug)]
+struct Rule {
+    pattern: Pattern,
+
+    /// Whether this rule shows files rather than ignoring them.
+    negated: bool,
+
+    /// Whether this rule only matches directories.
+    dir_only: bool,
+
+    /// Whether this rule is matched against the path from the ignore file’s
+    /// directory, rather than just the file name.
+    anchored: bool,
+}
+
+impl Rule {
+    /// Parses a line of an ignore file, returning nothing for blank lines,
+    /// comments, and invalid patterns.
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        // A backslash lets a pattern start with a `#` or `!`.
+        let line = if line.starts_with("\\#") || line.starts_with("\\!") {
+            &line[1..]
+        } else {
+            line
+        };
+
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            pattern: Pattern::new(line).ok()?,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// Whether this rule matches the file at the given path, relative to
+    /// the directory the ignore file is in.
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            self.pattern.matches_path_with(relative, MATCH_OPTIONS)
+        } else {
+            relative.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_with(&name.to_string_lossy(), MATCH_OPTIONS)
+            })
+        }
+    }
+}
+
+/// Reads the rules in the ignore files directly inside the given directory,
+/// in the order they apply.
+fn read_rules(dir: &Path) -> Vec<Rule> {
+    FILE_NAMES
+        .iter()
+        .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .flat_map(|contents| contents.lines().filter_map(Rule::parse).collect::<Vec<_>>())
+        .collect()
+}
+
+// A lazily initialised static cache of the rules in each directory, as
+// every file in a directory gets checked against the same ignore files,
+// and every file beneath it against the ones above.
+fn rules_in(dir: &Path) -> Arc<Vec<Rule>> {
+    static RULES: OnceLock<Mutex<HashMap<PathBuf, Arc<Vec<Rule>>>>> = OnceLock::new();
+
+    let mut rules = RULES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    Arc::clone(
+        rules
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| Arc::new(read_rules(dir))),
+    )
+}
+
+/// Whether the file at the given path is ignored by the ignore files in the
+/// directories above it.
+pub fn is_ignored(path: &Path, is_dir: bool) -> bool {
+    static CURRENT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+    let current_dir = CURRENT_DIR.get_or_init(|| std::env::current_dir().unwrap_or_default());
+    let path = path_clean::clean(current_dir.join(path));
+    is_ignored_by(&path, is_dir, rules_in)
+}
+
+/// Works out whether the file at the given absolute path is ignored, by
+/// going through the rules in each directory above it from the top down.
+fn is_ignored_by<F>(path: &Path, is_dir: bool, rules_in: F) -> bool
+where
+    F: Fn(&Path) -> Arc<Vec<Rule>>,
+{
+    let dirs = path.ancestors().skip(1).collect::<Vec<_>>();
+
+    let mut ignored = false;
+    for dir in dirs.into_iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+
+        for rule in rules_in(dir).iter() {
+            if rule.matches(relative, is_dir) {
+                ignored = !rule.negated;
+            }
+        }
+    }
+
+    ignored
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rules(lines: &[&str]) -> Arc<Vec<Rule>> {
+        Arc::new(lines.iter().filter_map(|line| Rule::parse(line)).collect())
+    }
+
+    #[test]
+    fn comments_and_blanks() {
+        assert!(Rule::parse("").is_none());
+        assert!(Rule::parse("   ").is_none());
+        assert!(Rule::parse("# target").is_none());
+        assert!(Rule::parse("\\#target").is_some());
+    }
+
+    #[test]
+    fn names_at_any_depth() {
+        let rule = Rule::parse("*.log").unwrap();
+        assert!(rule.matches(Path::new("debug.log"), false));
+        assert!(rule.matches(Path::new("logs/debug.log"), false));
+        assert!(!rule.matches(Path::new("debug.txt"), false));
+    }
+
+    #[test]
+    fn anchored_paths() {
+        let rule = Rule::parse("/build").unwrap();
+        assert!(rule.matches(Path::new("build"), true));
+        assert!(!rule.matches(Path::new("src/build"), true));
+
+        let rule = Rule::parse("docs/*.html").unwrap();
+        assert!(rule.matches(Path::new("docs/index.html"), false));
+        assert!(!rule.matches(Path::new("docs/api/index.html"), false));
+    }
+
+    #[test]
+    fn directories_only() {
+        let rule = Rule::parse("target/").unwrap();
+        assert!(rule.matches(Path::new("target"), true));
+        assert!(!rule.matches(Path::new("target"), false));
+    }
+
+    #[test]
+    fn deeper_files_take_precedence() {
+        let rules_in = |dir: &Path| match dir.to_str() {
+            Some("/repo") => rules(&["*.log"]),
+            Some("/repo/keep") => rules(&["!important.log"]),
+            _ => rules(&[]),
+        };
+
+        assert!(is_ignored_by(Path::new("/repo/debug.log"), false, rules_in));
+        assert!(is_ignored_by(
+            Path::new("/repo/keep/debug.log"),
+            false,
+            rules_in
+        ));
+        assert!(!is_ignored_by(
+            Path::new("/repo/keep/important.log"),
+            false,
+            rules_in
+        ));
+        assert!(!is_ignored_by(
+            Path::new("/repo/README.md"),
+            false,
+            rules_in
+        ));
+    }
+
+    #[test]
+    fn later_rules_take_precedence() {
+        let rules_in = |dir: &Path| match dir.to_str() {
+            Some("/repo") => rules(&["/vendor", "!/vendor", "/vendor/"]),
+            _ => rules(&[]),
+        };
+
+        assert!(is_ignored_by(Path::new("/repo/vendor"), true, rules_in));
+        assert!(!is_ignored_by(Path::new("/repo/vendor"), false, rules_in));
+    }
+}
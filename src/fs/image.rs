@@ -0,0 +1,238 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out the dimensions of images for `--image-info`.
+//!
+//! Only the headers get read, so this is quick even for huge images. PNG,
+//! GIF, and `WebP` images keep their dimensions in their first few dozen
+//! bytes, while JPEG images keep them in the frame header, which can come
+//! after any amount of metadata, so JPEG files get stepped through one
+//! segment at a time until it turns up.
+
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+/// Reads the width and height of the image at the given path, or nothing
+/// if it isn’t an image in one of the formats that get recognised.
+pub fn dimensions(path: &Path) -> io::Result<Option<(u32, u32)>> {
+    from_reader(BufReader::new(fs::File::open(path)?))
+}
+
+fn from_reader(mut reader: impl Read) -> io::Result<Option<(u32, u32)>> {
+    let mut header = [0; 30];
+    let length = read_up_to(&mut reader, &mut header)?;
+    let header = &header[..length];
+
+    if header.starts_with(&[0xFF, 0xD8]) {
+        return jpeg((&header[2..]).chain(reader));
+    }
+    Ok(png(header).or_else(|| gif(header)).or_else(|| webp(header)))
+}
+
+/// Reads as much of the buffer as there is, which is all of it unless the
+/// file is shorter.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut length = 0;
+    while length < buf.len() {
+        match reader.read(&mut buf[length..]) {
+            Ok(0) => break,
+            Ok(n) => length += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(length)
+}
+
+fn u16_be(bytes: &[u8]) -> u32 {
+    u32::from(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn u16_le(bytes: &[u8]) -> u32 {
+    u32::from(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u24_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+/// PNG images start with their `IHDR` chunk, which starts with the width
+/// and height.
+fn png(header: &[u8]) -> Option<(u32, u32)> {
+    if !header.starts_with(b"\x89PNG\r\n\x1a\n") || header.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(header.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(header.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+/// GIF images have the size of their logical screen straight after the
+/// signature.
+fn gif(header: &[u8]) -> Option<(u32, u32)> {
+    if !header.starts_with(b"GIF87a") && !header.starts_with(b"GIF89a") {
+        return None;
+    }
+    let size = header.get(6..10)?;
+    Some((u16_le(&size[0..2]), u16_le(&size[2..4])))
+}
+
+/// `WebP` images come in three kinds, each with a differently laid out first
+/// chunk: lossy, lossless, and extended.
+fn webp(header: &[u8]) -> Option<(u32, u32)> {
+    if !header.starts_with(b"RIFF") || header.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    match header.get(12..16)? {
+        b"VP8 " => {
+            let size = header.get(26..30)?;
+            Some((u16_le(&size[0..2]) & 0x3FFF, u16_le(&size[2..4]) & 0x3FFF))
+        }
+        b"VP8L" => {
+            let bits = u32::from_le_bytes(header.get(21..25)?.try_into().ok()?);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" => {
+            let size = header.get(24..30)?;
+            Some((u24_le(&size[0..3]) + 1, u24_le(&size[3..6]) + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Steps through the segments of a JPEG image, after its start-of-image
+/// marker, until it gets to a frame header.
+fn jpeg(mut reader: impl Read) -> io::Result<Option<(u32, u32)>> {
+    loop {
+        let mut byte = [0; 1];
+        if read_up_to(&mut reader, &mut byte)? == 0 || byte[0] != 0xFF {
+            return Ok(None);
+        }
+
+        // Markers can be padded with any number of extra 0xFF bytes.
+        let marker = loop {
+            if read_up_to(&mut reader, &mut byte)? == 0 {
+                return Ok(None);
+            }
+            if byte[0] != 0xFF {
+                break byte[0];
+            }
+        };
+
+        match marker {
+            // Restart markers, and the temporary one, have no segment.
+            0x01 | 0xD0..=0xD7 => continue,
+            // The end of the image, or the start of the scan data, which
+            // means there was no frame header.
+            0xD9 | 0xDA => return Ok(None),
+            _ => {}
+        }
+
+        let mut length = [0; 2];
+        if read_up_to(&mut reader, &mut length)? < 2 {
+            return Ok(None);
+        }
+        let Some(length) = u16_be(&length).checked_sub(2) else {
+            return Ok(None);
+        };
+
+        // Every start-of-frame marker has a frame header, apart from the
+        // three in the same range that mean something else.
+        if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let mut frame = [0; 5];
+            if read_up_to(&mut reader, &mut frame)? < 5 {
+                return Ok(None);
+            }
+            return Ok(Some((u16_be(&frame[3..5]), u16_be(&frame[1..3]))));
+        }
+
+        io::copy(
+            &mut reader.by_ref().take(u64::from(length)),
+            &mut io::sink(),
+        )?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dimensions_of(bytes: &[u8]) -> Option<(u32, u32)> {
+        from_reader(bytes).unwrap()
+    }
+
+    #[test]
+    fn png() {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend_from_slice(&640_u32.to_be_bytes());
+        bytes.extend_from_slice(&480_u32.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        assert_eq!(dimensions_of(&bytes), Some((640, 480)));
+    }
+
+    #[test]
+    fn gif() {
+        assert_eq!(
+            dimensions_of(b"GIF89a\x20\x03\x58\x02\xf7\0\0"),
+            Some((800, 600))
+        );
+    }
+
+    #[test]
+    fn lossy_webp() {
+        let mut bytes = b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0\x30\x01\0\x9d\x01\x2a".to_vec();
+        bytes.extend_from_slice(&1024_u16.to_le_bytes());
+        bytes.extend_from_slice(&768_u16.to_le_bytes());
+        assert_eq!(dimensions_of(&bytes), Some((1024, 768)));
+    }
+
+    #[test]
+    fn lossless_webp() {
+        let bits: u32 = (100 - 1) | ((50 - 1) << 14);
+        let mut bytes = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(&[0; 5]);
+        assert_eq!(dimensions_of(&bytes), Some((100, 50)));
+    }
+
+    #[test]
+    fn extended_webp() {
+        let mut bytes = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\x10\0\0\0".to_vec();
+        bytes.extend_from_slice(&(4000_u32 - 1).to_le_bytes()[..3]);
+        bytes.extend_from_slice(&(3000_u32 - 1).to_le_bytes()[..3]);
+        assert_eq!(dimensions_of(&bytes), Some((4000, 3000)));
+    }
+
+    #[test]
+    fn jpeg() {
+        let mut bytes = vec![0xFF, 0xD8];
+        // An APP0 segment, which gets skipped over...
+        bytes.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x10]);
+        bytes.extend_from_slice(b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        // ...then a baseline frame header.
+        bytes.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08]);
+        bytes.extend_from_slice(&1080_u16.to_be_bytes());
+        bytes.extend_from_slice(&1920_u16.to_be_bytes());
+        bytes.extend_from_slice(&[0; 12]);
+        assert_eq!(dimensions_of(&bytes), Some((1920, 1080)));
+    }
+
+    #[test]
+    fn jpeg_without_frame() {
+        assert_eq!(dimensions_of(&[0xFF, 0xD8, 0xFF, 0xD9]), None);
+    }
+
+    #[test]
+    fn not_an_image() {
+        assert_eq!(dimensions_of(b"#!/bin/sh\necho hello\n"), None);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(dimensions_of(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0"), None);
+    }
+}
@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Reading the pointer files that Git LFS leaves in the working tree in
+//! place of the large files it stores elsewhere.
+//!
+//! A pointer is a short text file of `key value` lines, starting with the
+//! version of the spec it follows, and with the object’s hash and size in
+//! it. They’re meant to be under a kilobyte, so only files that small get
+//! opened to see whether they’re pointers, and only with `--lfs`, as that
+//! still means opening most small files in a listing.
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether pointer files count as the size of the object they point to,
+/// which `--lfs` turns on. This goes for their size everywhere: in the
+/// size column, when sorting, and in totals.
+pub static OBJECT_SIZES: AtomicBool = AtomicBool::new(false);
+
+/// The largest a pointer file can be.
+const MAX_POINTER_SIZE: u64 = 1024;
+
+/// The line every pointer file starts with.
+const VERSION_LINE: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// The size of the object the file at the given path points to, or nothing
+/// if it isn’t a pointer file or pointers aren’t being looked for. This gets
+/// passed the file’s length so that larger files don’t get opened at all.
+pub fn object_size(path: &Path, len: u64) -> Option<u64> {
+    if !OBJECT_SIZES.load(Ordering::Relaxed)
+        || len < VERSION_LINE.len() as u64
+        || len > MAX_POINTER_SIZE
+    {
+        return None;
+    }
+
+    let mut contents = String::new();
+    fs::File::open(path)
+        .ok()?
+        .take(MAX_POINTER_SIZE)
+        .read_to_string(&mut contents)
+        .ok()?;
+    parse_pointer(&contents)
+}
+
+/// Reads the object size out of a pointer file’s contents, which need the
+/// version line first and an object ID somewhere after it.
+fn parse_pointer(contents: &str) -> Option<u64> {
+    let mut lines = contents.lines();
+    if lines.next()? != VERSION_LINE {
+        return None;
+    }
+
+    let mut oid = false;
+    let mut size = None;
+    for line in lines {
+        match line.split_once(' ')? {
+            ("oid", value) => oid = value.starts_with("sha256:"),
+            ("size", value) => size = Some(value.parse().ok()?),
+            _ => {}
+        }
+    }
+
+    if oid {
+        size
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pointer() {
+        let contents = "version https://git-lfs.github.com/spec/v1\n\
+                        oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                        size 12345\n";
+        assert_eq!(parse_pointer(contents), Some(12345));
+    }
+
+    #[test]
+    fn pointer_with_extensions() {
+        let contents = "version https://git-lfs.github.com/spec/v1\n\
+                        ext-0-foo sha256:ffd7c59d6cce9fc3ee5f1e0d5c4fd6a7b1f5e0f6e1e4a1b5c8a0d4b3c2b1a0f9\n\
+                        oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                        size 3000000000\n";
+        assert_eq!(parse_pointer(contents), Some(3_000_000_000));
+    }
+
+    #[test]
+    fn not_a_pointer() {
+        assert_eq!(parse_pointer("size 12345\n"), None);
+        assert_eq!(parse_pointer("hello world\n"), None);
+    }
+
+    #[test]
+    fn pointer_without_oid() {
+        let contents = "version https://git-lfs.github.com/spec/v1\nsize 12345\n";
+        assert_eq!(parse_pointer(contents), None);
+    }
+
+    #[test]
+    fn pointer_with_bad_size() {
+        let contents = "version https://git-lfs.github.com/spec/v1\n\
+                        oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+                        size lots\n";
+        assert_eq!(parse_pointer(contents), None);
+    }
+}
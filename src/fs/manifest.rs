@@ -0,0 +1,453 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Manifests listing files that aren’t on the local filesystem, such as the
+//! inventories written by backup tools or object stores, which get listed
+//! by `--from-manifest` as though they were.
+//!
+//! A manifest is either a JSON array of objects, or a CSV file with a
+//! header row, giving each entry’s `name`, `size`, `mtime`, `type`, and
+//! `storage_class`. Only the name is required. Modification times can be given as seconds
+//! since the epoch or as ISO 8601 dates, which are taken to be in UTC.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime};
+use serde::Deserialize;
+
+use crate::fs::fields as f;
+
+/// One entry in a manifest.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry {
+    /// The entry’s name, which may be a whole path.
+    pub name: String,
+
+    /// What kind of file the entry is.
+    pub kind: Kind,
+
+    /// The size of the entry, in bytes.
+    pub size: u64,
+
+    /// When the entry was last modified, if the manifest says.
+    pub modified: Option<NaiveDateTime>,
+
+    /// The storage class an object store keeps the entry in, such as
+    /// `STANDARD` or `GLACIER`, if the manifest says.
+    pub storage_class: Option<String>,
+
+    /// When the entry was last accessed, if known.
+    pub accessed: Option<NaiveDateTime>,
+
+    /// The entry’s permission bits, if known.
+    pub mode: Option<u32>,
+
+    /// The user and group that own the entry on the system it’s on, if
+    /// known.
+    pub user: Option<Owner>,
+    pub group: Option<Owner>,
+
+    /// The entries inside a directory, once they’ve been sorted into it by
+    /// [`nest`].
+    pub children: Vec<Entry>,
+}
+
+/// A user or group that owns an entry on another system, which can’t be
+/// looked up locally.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Owner {
+    /// The numeric ID of the user or group.
+    pub id: u32,
+
+    /// Its name, if the other system says.
+    pub name: Option<String>,
+
+    /// Whether it’s the user that’s listing, or one of their groups.
+    pub yours: bool,
+}
+
+impl Entry {
+    /// Creates an entry, dropping the trailing slash from the name of a
+    /// directory, as one gets added back when classifying.
+    pub fn new(name: &str, kind: Kind, size: u64, modified: Option<NaiveDateTime>) -> Self {
+        let name = match name.strip_suffix('/') {
+            Some(stripped) if kind == Kind::Directory && !stripped.is_empty() => stripped,
+            _ => name,
+        };
+
+        Self {
+            name: name.to_string(),
+            kind,
+            size,
+            modified,
+            storage_class: None,
+            accessed: None,
+            mode: None,
+            user: None,
+            group: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// The size to show for this entry, which only regular files have.
+    pub fn size(&self) -> f::Size {
+        match self.kind {
+            Kind::File => f::Size::Some(self.size),
+            Kind::Directory | Kind::Link | Kind::Other => f::Size::None,
+        }
+    }
+}
+
+/// The kinds of file a manifest can list.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Kind {
+    File,
+    Directory,
+    Link,
+    Other,
+}
+
+impl Kind {
+    /// Works out the kind of an entry from its `type` field, falling back
+    /// to treating names that end in a slash as directories, the way object
+    /// stores write them.
+    fn parse(kind: Option<&str>, name: &str) -> Self {
+        match kind.map(|k| k.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") if name.ends_with('/') => Self::Directory,
+            None | Some("" | "f" | "file" | "regular" | "object") => Self::File,
+            Some("d" | "dir" | "directory" | "folder" | "prefix") => Self::Directory,
+            Some("l" | "link" | "symlink") => Self::Link,
+            Some(_) => Self::Other,
+        }
+    }
+}
+
+/// An entry as it gets written in a JSON manifest.
+#[derive(Deserialize)]
+struct JsonEntry {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    mtime: Option<JsonTime>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    storage_class: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonTime {
+    Seconds(i64),
+    Date(String),
+}
+
+/// Reads the entries in a manifest, which is taken to be CSV if its name
+/// says so or if it doesn’t start like a JSON array.
+pub fn load(path: &Path) -> io::Result<Vec<Entry>> {
+    let text = fs::read_to_string(path)?;
+    let is_csv = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => true,
+        Some(ext) if ext.eq_ignore_ascii_case("json") => false,
+        _ => !text.trim_start().starts_with('['),
+    };
+
+    if is_csv {
+        parse_csv(&text)
+    } else {
+        parse_json(&text)
+    }
+}
+
+/// Sorts entries whose names are paths into the directories those paths
+/// lead through, so they can be listed and recursed into like a real
+/// directory’s contents. Directories that no entry of their own is given
+/// for get made up. The entries at the top are returned.
+pub fn nest(entries: Vec<Entry>) -> Vec<Entry> {
+    let mut by_name = BTreeMap::new();
+    for mut entry in entries {
+        entry.name = entry.name.trim_matches('/').to_string();
+        if !entry.name.is_empty() {
+            by_name.insert(entry.name.clone(), entry);
+        }
+    }
+
+    let names = by_name.keys().cloned().collect::<Vec<_>>();
+    for name in names {
+        let mut parent = name.as_str();
+        while let Some((above, _)) = parent.rsplit_once('/') {
+            parent = above;
+            by_name
+                .entry(parent.to_string())
+                .or_insert_with(|| Entry::new(parent, Kind::Directory, 0, None));
+        }
+    }
+
+    // The deepest entries go into their directories first, so each
+    // directory is complete by the time it goes into its own.
+    let mut names = by_name.keys().cloned().collect::<Vec<_>>();
+    names.sort_by_key(|name| std::cmp::Reverse(name.matches('/').count()));
+
+    let mut top = Vec::new();
+    for name in names {
+        let Some(entry) = by_name.remove(&name) else {
+            continue;
+        };
+        match name
+            .rsplit_once('/')
+            .and_then(|(parent, _)| by_name.get_mut(parent))
+        {
+            Some(dir) => dir.children.push(entry),
+            None => top.push(entry),
+        }
+    }
+
+    top
+}
+
+fn parse_json(text: &str) -> io::Result<Vec<Entry>> {
+    let entries: Vec<JsonEntry> =
+        serde_json::from_str(text).map_err(|e| invalid(format!("bad manifest: {e}")))?;
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let modified = match entry.mtime {
+                None => None,
+                Some(mtime) => Some(
+                    match mtime {
+                        JsonTime::Seconds(secs) => from_seconds(secs),
+                        JsonTime::Date(date) => parse_date(&date),
+                    }
+                    .ok_or_else(|| invalid(format!("bad mtime in entry {}", index + 1)))?,
+                ),
+            };
+
+            let kind = Kind::parse(entry.kind.as_deref(), &entry.name);
+            Ok(Entry {
+                storage_class: entry.storage_class,
+                ..Entry::new(&entry.name, kind, entry.size, modified)
+            })
+        })
+        .collect()
+}
+
+fn parse_csv(text: &str) -> io::Result<Vec<Entry>> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+
+    let header = lines
+        .next()
+        .map(|(_, line)| split_csv_line(line))
+        .ok_or_else(|| invalid("empty manifest".into()))?;
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.trim().to_ascii_lowercase().as_str()))
+    };
+
+    let name_column =
+        column(&["name", "path", "key"]).ok_or_else(|| invalid("no name column".into()))?;
+    let size_column = column(&["size"]);
+    let mtime_column = column(&["mtime", "modified"]);
+    let type_column = column(&["type"]);
+    let class_column = column(&["storage_class", "storageclass"]);
+
+    let mut entries = Vec::new();
+    for (index, line) in lines {
+        let fields = split_csv_line(line);
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|c| fields.get(c))
+                .map(|f| f.trim())
+                .filter(|f| !f.is_empty())
+        };
+        let bad = |what: &str| invalid(format!("bad {what} on line {}", index + 1));
+
+        let name = field(Some(name_column)).ok_or_else(|| bad("name"))?;
+        let size = match field(size_column) {
+            Some(size) => size.parse().map_err(|_| bad("size"))?,
+            None => 0,
+        };
+        let modified = match field(mtime_column) {
+            Some(mtime) => Some(
+                mtime
+                    .parse()
+                    .ok()
+                    .and_then(from_seconds)
+                    .or_else(|| parse_date(mtime))
+                    .ok_or_else(|| bad("mtime"))?,
+            ),
+            None => None,
+        };
+
+        let kind = Kind::parse(field(type_column), name);
+        entries.push(Entry {
+            storage_class: field(class_column).map(String::from),
+            ..Entry::new(name, kind, size, modified)
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Splits a line of CSV into its fields, handling quoted fields and the
+/// doubled quotes inside them.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+
+    fields
+}
+
+fn from_seconds(secs: i64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp(secs, 0).map(|dt| dt.naive_utc())
+}
+
+/// Parses an ISO 8601 date and time, with or without a time zone, or just
+/// a date.
+pub fn parse_date(date: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date) {
+        return Some(dt.naive_utc());
+    }
+
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(s: &str) -> Option<NaiveDateTime> {
+        parse_date(s)
+    }
+
+    #[test]
+    fn json() {
+        let entries = parse_json(
+            r#"[
+                {"name": "photos/", "type": "directory"},
+                {"name": "photos/cat.jpg", "size": 2048, "mtime": 1700000000, "storage_class": "GLACIER"},
+                {"name": "notes.txt", "size": 5, "mtime": "2023-11-14T22:13:20Z", "etag": "x"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry::new("photos", Kind::Directory, 0, None),
+                Entry {
+                    storage_class: Some("GLACIER".into()),
+                    ..Entry::new(
+                        "photos/cat.jpg",
+                        Kind::File,
+                        2048,
+                        date("2023-11-14T22:13:20")
+                    )
+                },
+                Entry::new("notes.txt", Kind::File, 5, date("2023-11-14 22:13:20")),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_without_names() {
+        assert!(parse_json(r#"[{"size": 1}]"#).is_err());
+    }
+
+    #[test]
+    fn json_not_yaml() {
+        assert!(parse_json("- name: notes.txt").is_err());
+    }
+
+    #[test]
+    fn nested() {
+        let entries = nest(vec![
+            Entry::new("photos/2023/cat.jpg", Kind::File, 2048, None),
+            Entry::new("notes.txt", Kind::File, 5, None),
+            Entry::new("photos/", Kind::Directory, 0, date("2023-11-14T22:13:20")),
+            Entry::new("photos/dog.jpg", Kind::File, 1024, None),
+        ]);
+
+        let cat = Entry::new("photos/2023/cat.jpg", Kind::File, 2048, None);
+        let year = Entry {
+            children: vec![cat],
+            ..Entry::new("photos/2023", Kind::Directory, 0, None)
+        };
+        let dog = Entry::new("photos/dog.jpg", Kind::File, 1024, None);
+        let photos = Entry {
+            children: vec![year, dog],
+            ..Entry::new("photos", Kind::Directory, 0, date("2023-11-14T22:13:20"))
+        };
+        assert_eq!(
+            entries,
+            vec![Entry::new("notes.txt", Kind::File, 5, None), photos]
+        );
+    }
+
+    #[test]
+    fn csv() {
+        let entries = parse_csv(
+            "type,name,size,mtime\n\
+             l,\"a, \"\"quoted\"\" link\",0,\n\
+             d,backups/,,2024-01-02\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                Entry::new("a, \"quoted\" link", Kind::Link, 0, None),
+                Entry::new("backups", Kind::Directory, 0, date("2024-01-02 00:00:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_bad_size() {
+        let error = parse_csv("name,size\na,big\n").unwrap_err();
+        assert_eq!(error.to_string(), "bad size on line 2");
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(Kind::parse(None, "dir/"), Kind::Directory);
+        assert_eq!(Kind::parse(Some("Symlink"), "x"), Kind::Link);
+        assert_eq!(Kind::parse(Some("fifo"), "x"), Kind::Other);
+        assert_eq!(Kind::parse(None, "x"), Kind::File);
+    }
+}
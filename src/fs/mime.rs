@@ -0,0 +1,368 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out files’ MIME types for `--mime`, from the first few bytes of
+//! their contents, falling back to their extensions.
+//!
+//! Formats with a distinctive signature get recognised by it first. Anything
+//! else goes by its extension, and failing that, gets called plain text or
+//! binary data depending on what it looks like. Only the start of each file
+//! gets read, and only when the column is shown.
+//!
+//! # Contributors
+//! Please keep the extension list sorted. If you're using vim, :sort i
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use phf::{phf_map, Map};
+
+/// How much of a file to read when sniffing it. The tar signature is the
+/// furthest into a file that gets checked.
+const HEADER_LENGTH: u64 = 512;
+
+/// Signatures that identify a format wherever they appear, given as the
+/// offset they appear at, the bytes, and the type.
+const MAGIC: &[(usize, &[u8], &str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    (0, b"\xff\xd8\xff", "image/jpeg"),
+    (0, b"GIF87a", "image/gif"),
+    (0, b"GIF89a", "image/gif"),
+    (0, b"BM", "image/bmp"),
+    (0, b"II*\0", "image/tiff"),
+    (0, b"MM\0*", "image/tiff"),
+    (0, b"\0\0\x01\0", "image/vnd.microsoft.icon"),
+    (0, b"8BPS", "image/vnd.adobe.photoshop"),
+    (0, b"%PDF-", "application/pdf"),
+    (0, b"%!PS", "application/postscript"),
+    (0, b"PK\x03\x04", "application/zip"),
+    (0, b"PK\x05\x06", "application/zip"),
+    (0, b"\x1f\x8b", "application/gzip"),
+    (0, b"BZh", "application/x-bzip2"),
+    (0, b"\xfd7zXZ\0", "application/x-xz"),
+    (0, b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (0, b"Rar!\x1a\x07", "application/vnd.rar"),
+    (0, b"\x04\x22\x4d\x18", "application/x-lz4"),
+    (257, b"ustar", "application/x-tar"),
+    (0, b"\x7fELF", "application/x-executable"),
+    (0, b"\xfe\xed\xfa\xce", "application/x-mach-binary"),
+    (0, b"\xfe\xed\xfa\xcf", "application/x-mach-binary"),
+    (0, b"\xce\xfa\xed\xfe", "application/x-mach-binary"),
+    (0, b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+    (0, b"MZ", "application/vnd.microsoft.portable-executable"),
+    (0, b"\0asm", "application/wasm"),
+    (0, b"SQLite format 3\0", "application/vnd.sqlite3"),
+    (0, b"!<arch>\n", "application/x-archive"),
+    (0, b"\xed\xab\xee\xdb", "application/x-rpm"),
+    (0, b"ID3", "audio/mpeg"),
+    (0, b"fLaC", "audio/flac"),
+    (0, b"OggS", "audio/ogg"),
+    (0, b"MThd", "audio/midi"),
+    (0, b"\x1a\x45\xdf\xa3", "video/x-matroska"),
+    (0, b"wOFF", "font/woff"),
+    (0, b"wOF2", "font/woff2"),
+    (0, b"OTTO", "font/otf"),
+];
+
+/// Formats inside a RIFF container, which say what they are at offset 8.
+const RIFF: &[(&[u8], &str)] = &[
+    (b"WAVE", "audio/wav"),
+    (b"AVI ", "video/x-msvideo"),
+    (b"WEBP", "image/webp"),
+];
+
+/// Formats inside an ISO base media file, which give their brand after
+/// `ftyp` at offset 4.
+const FTYP: &[(&[u8], &str)] = &[
+    (b"avif", "image/avif"),
+    (b"heic", "image/heic"),
+    (b"M4A ", "audio/mp4"),
+    (b"qt  ", "video/quicktime"),
+];
+
+/// Mapping from lowercase extensions to MIME types, for formats without a
+/// signature to sniff, which are mostly text.
+const EXTENSION_TYPES: Map<&'static str, &'static str> = phf_map! {
+    "7z"       => "application/x-7z-compressed",
+    "bash"     => "text/x-shellscript",
+    "bmp"      => "image/bmp",
+    "c"        => "text/x-c",
+    "cc"       => "text/x-c++",
+    "cpp"      => "text/x-c++",
+    "css"      => "text/css",
+    "csv"      => "text/csv",
+    "doc"      => "application/msword",
+    "docx"     => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "epub"     => "application/epub+zip",
+    "flac"     => "audio/flac",
+    "gif"      => "image/gif",
+    "go"       => "text/x-go",
+    "gz"       => "application/gzip",
+    "h"        => "text/x-c",
+    "hpp"      => "text/x-c++",
+    "htm"      => "text/html",
+    "html"     => "text/html",
+    "ico"      => "image/vnd.microsoft.icon",
+    "ics"      => "text/calendar",
+    "jar"      => "application/java-archive",
+    "java"     => "text/x-java",
+    "jpeg"     => "image/jpeg",
+    "jpg"      => "image/jpeg",
+    "js"       => "text/javascript",
+    "json"     => "application/json",
+    "jsonld"   => "application/ld+json",
+    "md"       => "text/markdown",
+    "mjs"      => "text/javascript",
+    "mkv"      => "video/x-matroska",
+    "mov"      => "video/quicktime",
+    "mp3"      => "audio/mpeg",
+    "mp4"      => "video/mp4",
+    "odp"      => "application/vnd.oasis.opendocument.presentation",
+    "ods"      => "application/vnd.oasis.opendocument.spreadsheet",
+    "odt"      => "application/vnd.oasis.opendocument.text",
+    "ogg"      => "audio/ogg",
+    "pdf"      => "application/pdf",
+    "php"      => "application/x-httpd-php",
+    "pl"       => "text/x-perl",
+    "png"      => "image/png",
+    "ppt"      => "application/vnd.ms-powerpoint",
+    "pptx"     => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "py"       => "text/x-python",
+    "rb"       => "text/x-ruby",
+    "rs"       => "text/rust",
+    "rtf"      => "application/rtf",
+    "sh"       => "text/x-shellscript",
+    "sql"      => "application/sql",
+    "svg"      => "image/svg+xml",
+    "tar"      => "application/x-tar",
+    "tif"      => "image/tiff",
+    "tiff"     => "image/tiff",
+    "toml"     => "application/toml",
+    "ts"       => "text/typescript",
+    "tsv"      => "text/tab-separated-values",
+    "txt"      => "text/plain",
+    "wasm"     => "application/wasm",
+    "wav"      => "audio/wav",
+    "webm"     => "video/webm",
+    "webp"     => "image/webp",
+    "woff"     => "font/woff",
+    "woff2"    => "font/woff2",
+    "xhtml"    => "application/xhtml+xml",
+    "xls"      => "application/vnd.ms-excel",
+    "xlsx"     => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "xml"      => "application/xml",
+    "xz"       => "application/x-xz",
+    "yaml"     => "application/yaml",
+    "yml"      => "application/yaml",
+    "zip"      => "application/zip",
+    "zsh"      => "text/x-shellscript",
+    "zst"      => "application/zstd",
+};
+
+/// Works out the MIME type of the regular file at the given path, with the
+/// given lowercase extension, reading the start of it.
+pub fn sniff(path: &Path, ext: Option<&str>) -> io::Result<&'static str> {
+    let mut header = Vec::new();
+    fs::File::open(path)?
+        .take(HEADER_LENGTH)
+        .read_to_end(&mut header)?;
+
+    Ok(from_contents(&header, ext))
+}
+
+/// Works out a MIME type from the start of a file’s contents and its
+/// extension.
+pub fn from_contents(header: &[u8], ext: Option<&str>) -> &'static str {
+    if header.is_empty() {
+        return "inode/x-empty";
+    }
+
+    if let Some(mime) = from_magic(header) {
+        return mime;
+    }
+
+    if let Some(mime) = ext.and_then(from_extension) {
+        return mime;
+    }
+
+    if !looks_like_text(header) {
+        return "application/octet-stream";
+    }
+
+    if let Some(interpreter) = header.strip_prefix(b"#!") {
+        return from_interpreter(interpreter);
+    }
+
+    let start = trim_start(header);
+    if starts_with_ignoring_case(start, b"<!doctype html")
+        || starts_with_ignoring_case(start, b"<html")
+    {
+        "text/html"
+    } else if start.starts_with(b"<?xml") {
+        "text/xml"
+    } else {
+        "text/plain"
+    }
+}
+
+/// Looks up a MIME type by extension alone, for when there are no
+/// contents to read.
+pub fn from_extension(ext: &str) -> Option<&'static str> {
+    EXTENSION_TYPES.get(ext).copied()
+}
+
+fn from_magic(header: &[u8]) -> Option<&'static str> {
+    let at = |offset: usize, bytes: &[u8]| header.get(offset..offset + bytes.len()) == Some(bytes);
+
+    if at(0, b"RIFF") {
+        return RIFF
+            .iter()
+            .find(|(format, _)| at(8, format))
+            .map(|&(_, mime)| mime);
+    }
+
+    if at(4, b"ftyp") {
+        let brand = FTYP.iter().find(|(brand, _)| at(8, brand));
+        return Some(brand.map_or("video/mp4", |&(_, mime)| mime));
+    }
+
+    MAGIC
+        .iter()
+        .find(|(offset, bytes, _)| at(*offset, bytes))
+        .map(|&(_, _, mime)| mime)
+}
+
+/// Picks a type for a script from the interpreter named on its `#!` line,
+/// looking past `env` to the program it runs.
+fn from_interpreter(line: &[u8]) -> &'static str {
+    let line = line.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    let mut words = line.split_whitespace();
+
+    let mut program = words.next().unwrap_or_default();
+    if program.rsplit('/').next() == Some("env") {
+        program = words
+            .find(|word| !word.starts_with('-'))
+            .unwrap_or_default();
+    }
+
+    let program = program.rsplit('/').next().unwrap_or_default();
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match program {
+        "sh" | "bash" | "dash" | "ksh" | "zsh" | "fish" => "text/x-shellscript",
+        "python" => "text/x-python",
+        "perl" => "text/x-perl",
+        "ruby" => "text/x-ruby",
+        "node" | "deno" | "bun" => "text/javascript",
+        "php" => "application/x-httpd-php",
+        _ => "text/plain",
+    }
+}
+
+/// Whether the start of a file looks like text: UTF-8 without any nul
+/// bytes, allowing for a character cut off at the end of the header.
+fn looks_like_text(header: &[u8]) -> bool {
+    if header.contains(&0) {
+        return false;
+    }
+
+    match std::str::from_utf8(header) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none() && header.len() - e.valid_up_to() < 4,
+    }
+}
+
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn starts_with_ignoring_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn magic() {
+        assert_eq!(
+            from_contents(b"\x89PNG\r\n\x1a\n\0\0", Some("txt")),
+            "image/png"
+        );
+        assert_eq!(from_contents(b"%PDF-1.7\n", None), "application/pdf");
+        assert_eq!(
+            from_contents(b"\x7fELF\x02\x01", None),
+            "application/x-executable"
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(from_contents(b"RIFF\0\0\0\0WEBPVP8 ", None), "image/webp");
+        assert_eq!(from_contents(b"\0\0\0\x18ftypisom", None), "video/mp4");
+        assert_eq!(from_contents(b"\0\0\0\x18ftypavif", None), "image/avif");
+    }
+
+    #[test]
+    fn tar() {
+        let mut header = vec![b'a'; 257];
+        header.extend_from_slice(b"ustar\0");
+        assert_eq!(from_contents(&header, None), "application/x-tar");
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(
+            from_contents(b"{\"a\": 1}", Some("json")),
+            "application/json"
+        );
+        assert_eq!(from_contents(b"fn main() {}", Some("rs")), "text/rust");
+        assert_eq!(from_extension("svg"), Some("image/svg+xml"));
+        assert_eq!(from_extension("nope"), None);
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(
+            from_contents(b"#!/bin/sh\necho hi\n", None),
+            "text/x-shellscript"
+        );
+        assert_eq!(
+            from_contents(b"#!/usr/bin/env -S python3.12\n", None),
+            "text/x-python"
+        );
+        assert_eq!(from_contents(b"#!/opt/thing\n", None), "text/plain");
+    }
+
+    #[test]
+    fn markup() {
+        assert_eq!(from_contents(b"\n  <!DOCTYPE html>", None), "text/html");
+        assert_eq!(from_contents(b"<?xml version=\"1.0\"?>", None), "text/xml");
+    }
+
+    #[test]
+    fn text_or_binary() {
+        assert_eq!(from_contents(b"hello\n", None), "text/plain");
+        assert_eq!(from_contents("caf\u{e9}".as_bytes(), None), "text/plain");
+        assert_eq!(from_contents(b"caf\xc3", None), "text/plain");
+        assert_eq!(from_contents(b"a\0b", None), "application/octet-stream");
+        assert_eq!(
+            from_contents(b"\xff\xfe\xfd", None),
+            "application/octet-stream"
+        );
+        assert_eq!(from_contents(b"", Some("png")), "inode/x-empty");
+    }
+}
@@ -10,9 +10,24 @@ pub use self::dir::{Dir, DotFilter};
 mod file;
 pub use self::file::{File, FileTarget};
 
+pub mod binary;
+pub mod dedupe;
+pub mod digest;
 pub mod dir_action;
 pub mod feature;
 pub mod fields;
 pub mod filter;
+pub mod ignore_files;
+pub mod image;
+pub mod lfs;
+pub mod manifest;
+pub mod mime;
 pub mod mounts;
+pub mod preview;
+pub mod processes;
 pub mod recursive_size;
+pub mod shortcut;
+pub mod snapshot;
+pub mod sockets;
+pub mod trash;
+pub mod vcs;
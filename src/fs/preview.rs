@@ -0,0 +1,74 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Reading the start of text files for `--preview`.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How many characters get shown when `--preview` isn’t given a number.
+pub const DEFAULT_LENGTH: usize = 40;
+
+/// Reads the first line of the file at the given path, up to the given
+/// number of characters. Files that look binary have nothing to preview.
+pub fn of_file(path: &Path, length: usize) -> io::Result<Option<String>> {
+    // No character takes up more than four bytes, so this is always enough.
+    let limit = u64::try_from(length.saturating_mul(4)).unwrap_or(u64::MAX);
+
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.take(limit).read_to_end(&mut bytes)?;
+    Ok(first_line(&bytes, length))
+}
+
+fn first_line(bytes: &[u8], length: usize) -> Option<String> {
+    // A NUL byte is as sure a sign of a binary file as any.
+    if bytes.contains(&0) {
+        return None;
+    }
+
+    let line = bytes.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    Some(String::from_utf8_lossy(line).chars().take(length).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_line_only() {
+        assert_eq!(
+            first_line(b"[core]\n\tbare = false\n", 40).as_deref(),
+            Some("[core]")
+        );
+    }
+
+    #[test]
+    fn windows_line_ending() {
+        assert_eq!(first_line(b"hello\r\nworld", 40).as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn cut_short() {
+        assert_eq!(first_line("ünïcödé".as_bytes(), 3).as_deref(), Some("ünï"));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(first_line(b"", 40).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(first_line(b"\x7fELF\x02\x01\x01\0", 40), None);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert_eq!(first_line(b"caf\xe9", 40).as_deref(), Some("caf\u{fffd}"));
+    }
+}
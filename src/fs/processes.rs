@@ -0,0 +1,212 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out which processes have a socket or named pipe open, by looking
+//! through their file descriptors in `/proc`.
+//!
+//! Each of a process’s file descriptors is a symlink in `/proc/PID/fd`,
+//! pointing to `socket:[INODE]` for a socket, or to the path of a file it
+//! opened, and `/proc/PID/fdinfo` says how it was opened. Only processes
+//! whose file descriptors can be read are found, which without root means
+//! only the current user’s.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// A process that has a file open.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Process {
+    pub pid: u32,
+
+    /// The name of the process’s command, as `ps` shows it.
+    pub name: String,
+}
+
+/// How a process has a named pipe open.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum PipeAccess {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// What a file descriptor points to.
+#[derive(PartialEq, Eq, Debug)]
+enum Target {
+    Socket(u64),
+    Path(PathBuf),
+}
+
+/// Every file descriptor open in every process that could be read, indexed
+/// by what it points to.
+#[derive(Default)]
+struct OpenFiles {
+    sockets: HashMap<u64, Vec<Process>>,
+
+    /// Each process that has a path open, with the number of the file
+    /// descriptor, so how it was opened can be read.
+    paths: HashMap<PathBuf, Vec<(Process, u32)>>,
+}
+
+// A lazily initialised static list of every open file descriptor. This is
+// `None` when `/proc` can’t be read, such as when not running on Linux, in
+// which case nothing is known about which processes have what open.
+fn open_files() -> Option<&'static OpenFiles> {
+    static OPEN_FILES: OnceLock<Option<OpenFiles>> = OnceLock::new();
+
+    OPEN_FILES
+        .get_or_init(|| {
+            if cfg!(target_os = "linux") {
+                read_open_files()
+            } else {
+                None
+            }
+        })
+        .as_ref()
+}
+
+fn read_open_files() -> Option<OpenFiles> {
+    let mut open_files = OpenFiles::default();
+
+    for entry in fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+
+        // Processes can exit, and other users’ can’t be looked into, at
+        // any point, so anything that can’t be read is skipped.
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let name = fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+        let process = Process {
+            pid,
+            name: name.trim_end_matches('\n').to_string(),
+        };
+
+        for fd in fds.flatten() {
+            let Some(number) = fd.file_name().to_str().and_then(|n| n.parse().ok()) else {
+                continue;
+            };
+            let Ok(link) = fs::read_link(fd.path()) else {
+                continue;
+            };
+
+            match parse_target(link) {
+                Target::Socket(inode) => {
+                    let holders = open_files.sockets.entry(inode).or_default();
+                    if !holders.contains(&process) {
+                        holders.push(process.clone());
+                    }
+                }
+                Target::Path(path) => {
+                    let holders = open_files.paths.entry(path).or_default();
+                    holders.push((process.clone(), number));
+                }
+            }
+        }
+    }
+
+    Some(open_files)
+}
+
+/// Works out what a file descriptor’s symlink points to.
+fn parse_target(link: PathBuf) -> Target {
+    let inode = link
+        .to_str()
+        .and_then(|l| l.strip_prefix("socket:["))
+        .and_then(|l| l.strip_suffix(']'))
+        .and_then(|l| l.parse().ok());
+
+    match inode {
+        Some(inode) => Target::Socket(inode),
+        None => Target::Path(link),
+    }
+}
+
+/// Works out how a file descriptor was opened from the `flags` line of its
+/// `fdinfo`, which has its open flags in octal.
+fn parse_access(fdinfo: &str) -> Option<PipeAccess> {
+    let flags = fdinfo
+        .lines()
+        .find_map(|line| line.strip_prefix("flags:"))?;
+
+    match u32::from_str_radix(flags.trim(), 8).ok()? & 0o3 {
+        0 => Some(PipeAccess::Read),
+        1 => Some(PipeAccess::Write),
+        2 => Some(PipeAccess::ReadWrite),
+        _ => None,
+    }
+}
+
+/// The processes that have the socket with the given inode open, or
+/// nothing if they can’t be looked for.
+pub fn holding_socket(inode: u64) -> Option<Vec<Process>> {
+    let open_files = open_files()?;
+    Some(open_files.sockets.get(&inode).cloned().unwrap_or_default())
+}
+
+/// The processes that have the named pipe at the given absolute path open,
+/// and how, or nothing if they can’t be looked for.
+pub fn holding_pipe(path: &Path) -> Option<Vec<(Process, PipeAccess)>> {
+    let open_files = open_files()?;
+    let Some(holders) = open_files.paths.get(path) else {
+        return Some(Vec::new());
+    };
+
+    let accesses = holders
+        .iter()
+        .filter_map(|(process, fd)| {
+            let fdinfo = fs::read_to_string(format!("/proc/{}/fdinfo/{fd}", process.pid)).ok()?;
+            Some((process.clone(), parse_access(&fdinfo)?))
+        })
+        .collect();
+    Some(accesses)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn socket_target() {
+        assert_eq!(
+            parse_target(PathBuf::from("socket:[31337]")),
+            Target::Socket(31337)
+        );
+    }
+
+    #[test]
+    fn path_target() {
+        assert_eq!(
+            parse_target(PathBuf::from("/run/my.fifo")),
+            Target::Path(PathBuf::from("/run/my.fifo"))
+        );
+        assert_eq!(
+            parse_target(PathBuf::from("pipe:[31337]")),
+            Target::Path(PathBuf::from("pipe:[31337]"))
+        );
+    }
+
+    #[test]
+    fn fdinfo_access() {
+        let fdinfo = "pos:\t0\nflags:\t0100000\nmnt_id:\t29\nino:\t1234\n";
+        assert_eq!(parse_access(fdinfo), Some(PipeAccess::Read));
+
+        let fdinfo = "pos:\t0\nflags:\t02000001\nmnt_id:\t29\n";
+        assert_eq!(parse_access(fdinfo), Some(PipeAccess::Write));
+
+        let fdinfo = "pos:\t0\nflags:\t0100002\n";
+        assert_eq!(parse_access(fdinfo), Some(PipeAccess::ReadWrite));
+    }
+
+    #[test]
+    fn fdinfo_without_flags() {
+        assert_eq!(parse_access("pos:\t0\n"), None);
+    }
+}
@@ -4,6 +4,26 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+/// How to work out the sizes of directories when `--total-size` is used.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TotalSize {
+    pub measure: SizeMeasure,
+
+    /// How many levels of directories to count the contents of, or `None`
+    /// to count everything in them, however deep.
+    pub max_depth: Option<usize>,
+}
+
+/// Which of the sizes of the files in a directory to add up.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SizeMeasure {
+    /// The lengths of the files, as with `du -b`.
+    Apparent,
+
+    /// The space allocated to the files on disk, as with `du`.
+    Allocated,
+}
+
 /// Used to represent a the size of a recursive directory traversal.  `None`
 /// should be used when the file does not represent a directory or the recursive
 /// size should not be calculated.
@@ -20,27 +40,6 @@ pub enum RecursiveSize {
 }
 
 impl RecursiveSize {
-    /// Returns `true` if `None`
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use eza::fs::recursive_size::RecursiveSize;
-    ///
-    /// let x = RecursiveSize::None;
-    /// assert_eq!(x.is_none(), true);
-    ///
-    /// let x = RecursiveSize::Unknown;
-    /// assert_eq!(x.is_none(), false);
-    ///
-    /// let x = RecursiveSize::Some(0, 0);
-    /// assert_eq!(x.is_none(), false);
-    /// ```
-    #[inline]
-    pub const fn is_none(&self) -> bool {
-        matches!(*self, Self::None)
-    }
-
     /// Returns the contained [`Some`] value or a provided default.
     ///
     /// # Examples
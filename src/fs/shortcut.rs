@@ -0,0 +1,235 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Parsing of launcher files that point somewhere else without being
+//! symlinks: freedesktop `.desktop` entries and Windows `.lnk` shortcuts.
+
+/// Returns the `Name` key from the `[Desktop Entry]` group of a desktop
+/// entry file, ignoring any localised variants of it.
+pub fn desktop_entry_name(contents: &str) -> Option<String> {
+    let mut in_entry = false;
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+        } else if in_entry {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim_end() == "Name" {
+                    let value = value.trim();
+                    return (!value.is_empty()).then(|| value.to_owned());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Flags from the shell link header, from the `[MS-SHLLINK]` specification.
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const IS_UNICODE: u32 = 0x80;
+
+// Flags from the link info structure.
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+
+/// The size of the shell link header, which is also the first field in it.
+const HEADER_SIZE: usize = 0x4C;
+
+/// Returns the path that a Windows shell link points to. This is the local
+/// path stored in its link info if there is one, and the relative path
+/// from its string data otherwise.
+pub fn lnk_target(data: &[u8]) -> Option<String> {
+    if read_u32(data, 0)? as usize != HEADER_SIZE {
+        return None;
+    }
+
+    let flags = read_u32(data, 20)?;
+    let mut offset = HEADER_SIZE;
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        offset += 2 + read_u16(data, offset)? as usize;
+    }
+
+    if flags & HAS_LINK_INFO != 0 {
+        let info = data.get(offset..)?;
+        let info_size = read_u32(info, 0)? as usize;
+
+        if let Some(path) = link_info_path(info.get(..info_size)?) {
+            return Some(path);
+        }
+
+        offset += info_size;
+    }
+
+    // The string data follows the link info, in a fixed order.
+    let unicode = flags & IS_UNICODE != 0;
+    if flags & HAS_NAME != 0 {
+        offset = read_string_data(data, offset, unicode)?.1;
+    }
+
+    if flags & HAS_RELATIVE_PATH != 0 {
+        let (path, _) = read_string_data(data, offset, unicode)?;
+        return (!path.is_empty()).then_some(path);
+    }
+
+    None
+}
+
+/// Reads the local base path and common path suffix out of a link info
+/// structure, preferring their Unicode versions where present.
+fn link_info_path(info: &[u8]) -> Option<String> {
+    let header_size = read_u32(info, 4)? as usize;
+    let info_flags = read_u32(info, 8)?;
+
+    if info_flags & VOLUME_ID_AND_LOCAL_BASE_PATH == 0 {
+        return None;
+    }
+
+    let (base, suffix) = if header_size >= 0x24 {
+        (
+            read_utf16z(info, read_u32(info, 28)? as usize)?,
+            read_utf16z(info, read_u32(info, 32)? as usize).unwrap_or_default(),
+        )
+    } else {
+        (
+            read_ansiz(info, read_u32(info, 16)? as usize)?,
+            read_ansiz(info, read_u32(info, 24)? as usize).unwrap_or_default(),
+        )
+    };
+
+    let path = base + &suffix;
+    (!path.is_empty()).then_some(path)
+}
+
+/// Reads a counted string from the string data section, returning it along
+/// with the offset just past it.
+fn read_string_data(data: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = read_u16(data, offset)? as usize;
+    let start = offset + 2;
+
+    if unicode {
+        let bytes = data.get(start..start + count * 2)?;
+        Some((utf16(bytes), start + count * 2))
+    } else {
+        let bytes = data.get(start..start + count)?;
+        Some((String::from_utf8_lossy(bytes).into_owned(), start + count))
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a nul-terminated string in the system code page. Anything that
+/// isn’t valid UTF-8 gets replaced, as the code page isn’t known here.
+fn read_ansiz(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Reads a nul-terminated UTF-16 string.
+fn read_utf16z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.chunks_exact(2).position(|c| c == [0, 0])?;
+    Some(utf16(&bytes[..end * 2]))
+}
+
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn desktop_name() {
+        let entry = "[Desktop Entry]\nType=Application\nName[de]=Rechner\nName=Calculator\n";
+        assert_eq!(desktop_entry_name(entry), Some("Calculator".into()));
+    }
+
+    #[test]
+    fn desktop_name_in_other_group() {
+        let entry = "[Desktop Action new]\nName=New Window\n\n[Desktop Entry]\nExec=calc\n";
+        assert_eq!(desktop_entry_name(entry), None);
+    }
+
+    #[test]
+    fn desktop_not_an_entry() {
+        assert_eq!(desktop_entry_name("Name=Nope"), None);
+    }
+
+    fn header(flags: u32) -> Vec<u8> {
+        let mut data = vec![0; HEADER_SIZE];
+        data[0] = HEADER_SIZE as u8;
+        data[20..24].copy_from_slice(&flags.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn lnk_local_base_path() {
+        let mut data = header(HAS_LINK_TARGET_ID_LIST | HAS_LINK_INFO);
+
+        // An empty ID list
+        data.extend_from_slice(&2_u16.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+
+        let base = b"C:\\Windows\\notepad.exe\0";
+        let mut info = Vec::new();
+        info.extend_from_slice(&((0x1C + base.len() + 1) as u32).to_le_bytes());
+        info.extend_from_slice(&0x1C_u32.to_le_bytes());
+        info.extend_from_slice(&VOLUME_ID_AND_LOCAL_BASE_PATH.to_le_bytes());
+        info.extend_from_slice(&0_u32.to_le_bytes());
+        info.extend_from_slice(&0x1C_u32.to_le_bytes());
+        info.extend_from_slice(&0_u32.to_le_bytes());
+        info.extend_from_slice(&((0x1C + base.len()) as u32).to_le_bytes());
+        info.extend_from_slice(base);
+        info.push(0);
+        data.extend_from_slice(&info);
+
+        assert_eq!(lnk_target(&data), Some("C:\\Windows\\notepad.exe".into()));
+    }
+
+    #[test]
+    fn lnk_relative_path() {
+        let mut data = header(HAS_NAME | HAS_RELATIVE_PATH | IS_UNICODE);
+
+        for string in ["A comment", "..\\docs\\readme.txt"] {
+            let units: Vec<u16> = string.encode_utf16().collect();
+            data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+            for unit in units {
+                data.extend_from_slice(&unit.to_le_bytes());
+            }
+        }
+
+        assert_eq!(lnk_target(&data), Some("..\\docs\\readme.txt".into()));
+    }
+
+    #[test]
+    fn lnk_truncated() {
+        let data = header(HAS_RELATIVE_PATH);
+        assert_eq!(lnk_target(&data), None);
+    }
+
+    #[test]
+    fn lnk_not_a_link() {
+        assert_eq!(lnk_target(b"MZ\x90\x00"), None);
+    }
+}
@@ -0,0 +1,503 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Snapshots of everything beneath some paths, saved by `--save-snapshot`
+//! and compared against by `--changed-since`, so that eza can tell which
+//! files have been added, removed, or modified in between.
+//!
+//! A snapshot gets saved as a text file, with a line for each entry giving
+//! its type, size, modification time, and a hash of its contents, followed
+//! by its path. Paths are relative to the directory they were found in, so
+//! a snapshot can be compared against from anywhere, and when a snapshot is
+//! taken of more than one path, they start with the name of that path.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use chrono::DateTime;
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
+
+use crate::fs::filter::FileFilter;
+use crate::fs::ignore_files;
+use crate::fs::manifest;
+
+/// The first line of every snapshot file.
+const HEADER: &str = "# eza snapshot 1";
+
+/// The characters that need encoding when a path gets written to a
+/// snapshot: anything that would split up its line or its fields.
+const PATH: &AsciiSet = &CONTROLS.add(b'%');
+
+/// What a snapshot knows about one entry.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Entry {
+    /// The type of the entry: `d` for directories, `l` for symlinks, `f`
+    /// for regular files, and `o` for anything else.
+    pub kind: char,
+
+    /// The size of a file, in bytes. Directories always have a size of
+    /// zero, as what the file system reports for them changes whenever
+    /// anything gets added to them.
+    pub size: u64,
+
+    /// When a file was last modified, in nanoseconds since the epoch.
+    /// This is zero for directories, for the same reason as the size.
+    pub modified: i128,
+
+    /// A hash of a file’s contents, or of a symlink’s target, if it could
+    /// be read.
+    pub hash: Option<u64>,
+}
+
+/// How an entry has changed since a snapshot was taken.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// The entries beneath some paths, keyed by their paths relative to the
+/// path they were found beneath.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct Snapshot {
+    entries: BTreeMap<PathBuf, Entry>,
+
+    /// The names that the paths of entries start with, and the paths on the
+    /// filesystem they stand for, for a snapshot that was just taken rather
+    /// than loaded.
+    roots: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Snapshot {
+    /// Takes a snapshot of the given paths and everything beneath them,
+    /// leaving out the files that the filter’s ignore patterns and dotfile
+    /// setting would hide from a listing.
+    pub fn take<P: AsRef<Path>>(paths: &[P], filter: &FileFilter) -> Self {
+        let mut snapshot = Self::default();
+
+        for path in paths {
+            let path = path.as_ref();
+            let name = if paths.len() > 1 || !path.is_dir() || path.is_symlink() {
+                root_name(path)
+            } else {
+                PathBuf::new()
+            };
+
+            if path.is_dir() && !path.is_symlink() {
+                snapshot.walk(path, &name, filter);
+            } else if let Some(entry) = Entry::of(path) {
+                snapshot.entries.insert(name.clone(), entry);
+            }
+            snapshot.roots.push((name, path.to_path_buf()));
+        }
+
+        snapshot
+    }
+
+    fn walk(&mut self, dir: &Path, dir_name: &Path, filter: &FileFilter) {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for child in read_dir.flatten() {
+            let name = child.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') && !filter.dot_filter.shows_dotfiles() {
+                continue;
+            }
+            if filter.ignore_patterns.is_ignored(&name) {
+                continue;
+            }
+
+            let path = child.path();
+            let Some(entry) = Entry::of(&path) else {
+                continue;
+            };
+            if filter.ignore_files && ignore_files::is_ignored(&path, entry.kind == 'd') {
+                continue;
+            }
+
+            let name = dir_name.join(child.file_name());
+            if entry.kind == 'd' {
+                self.walk(&path, &name, filter);
+            }
+            self.entries.insert(name, entry);
+        }
+    }
+
+    /// Returns the entries that have been added, removed, or modified in
+    /// this snapshot since an earlier one, in order of their paths, with
+    /// what’s known about them now, or what was known before they were
+    /// removed.
+    pub fn changes_since<'a>(&'a self, earlier: &'a Self) -> Vec<(Change, &'a Path, &'a Entry)> {
+        let mut changes = Vec::new();
+
+        for (path, entry) in &self.entries {
+            match earlier.entries.get(path) {
+                None => changes.push((Change::Added, path.as_path(), entry)),
+                Some(before) if before != entry => {
+                    changes.push((Change::Modified, path.as_path(), entry));
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (path, entry) in &earlier.entries {
+            if !self.entries.contains_key(path) {
+                changes.push((Change::Removed, path.as_path(), entry));
+            }
+        }
+
+        changes.sort_by(|a, b| a.1.cmp(b.1));
+        changes
+    }
+
+    /// Where an entry with the given path is, or would be, beneath the
+    /// paths this snapshot was just taken of.
+    pub fn location(&self, path: &Path) -> Option<PathBuf> {
+        self.roots.iter().find_map(|(name, root)| {
+            let rest = path.strip_prefix(name).ok()?;
+            if rest.as_os_str().is_empty() {
+                Some(root.clone())
+            } else {
+                Some(root.join(rest))
+            }
+        })
+    }
+
+    /// Reads a snapshot that was saved earlier.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut lines = BufReader::new(fs::File::open(path)?).lines();
+
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("not an eza snapshot".into()));
+        }
+
+        let mut snapshot = Self::default();
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let (path, entry) = parse_line(&line)
+                .ok_or_else(|| invalid(format!("bad entry on line {}", index + 2)))?;
+            snapshot.entries.insert(path, entry);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Writes this snapshot out, to be compared against later.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+
+        writeln!(file, "{HEADER}")?;
+        for (path, entry) in &self.entries {
+            let hash = entry
+                .hash
+                .map_or_else(|| String::from("-"), |h| format!("{h:016x}"));
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                entry.kind,
+                entry.size,
+                entry.modified,
+                hash,
+                encode_path(path)
+            )?;
+        }
+
+        file.flush()
+    }
+}
+
+impl Entry {
+    /// Turns what a snapshot knows about an entry into a manifest entry, so
+    /// it can be listed without being on the filesystem.
+    pub fn to_manifest(self, path: &Path) -> manifest::Entry {
+        let kind = match self.kind {
+            'd' => manifest::Kind::Directory,
+            'l' => manifest::Kind::Link,
+            'f' => manifest::Kind::File,
+            _ => manifest::Kind::Other,
+        };
+        let modified = (self.modified != 0)
+            .then(|| {
+                let seconds = self.modified.div_euclid(1_000_000_000);
+                let nanos = self.modified.rem_euclid(1_000_000_000);
+                DateTime::from_timestamp(i64::try_from(seconds).ok()?, nanos as u32)
+            })
+            .flatten()
+            .map(|time| time.naive_utc());
+
+        manifest::Entry::new(&path.to_string_lossy(), kind, self.size, modified)
+    }
+
+    /// Looks up what a snapshot needs to know about the file at this path,
+    /// without following symlinks.
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::symlink_metadata(path).ok()?;
+        let file_type = metadata.file_type();
+
+        if file_type.is_dir() {
+            return Some(Self {
+                kind: 'd',
+                size: 0,
+                modified: 0,
+                hash: None,
+            });
+        }
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos() as i128);
+
+        let (kind, hash) = if file_type.is_symlink() {
+            let target = fs::read_link(path).ok();
+            (
+                'l',
+                target.map(|t| hash_bytes(t.to_string_lossy().as_bytes())),
+            )
+        } else if file_type.is_file() {
+            ('f', hash_file(path).ok())
+        } else {
+            ('o', None)
+        };
+
+        Some(Self {
+            kind,
+            size: metadata.len(),
+            modified,
+            hash,
+        })
+    }
+}
+
+/// The name of a path that a snapshot was taken of, which the paths of the
+/// entries beneath it start with: the last part of its full path, so that
+/// it’s the same whichever directory it was given from.
+fn root_name(path: &Path) -> PathBuf {
+    let full = if path.is_symlink() {
+        path.to_path_buf()
+    } else {
+        fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    };
+    full.file_name().map_or_else(|| full.clone(), PathBuf::from)
+}
+
+/// Parses one entry line of a snapshot file.
+fn parse_line(line: &str) -> Option<(PathBuf, Entry)> {
+    let mut fields = line.splitn(5, '\t');
+
+    let mut kind = fields.next()?.chars();
+    let entry = Entry {
+        kind: kind.next().filter(|_| kind.next().is_none())?,
+        size: fields.next()?.parse().ok()?,
+        modified: fields.next()?.parse().ok()?,
+        hash: match fields.next()? {
+            "-" => None,
+            hex => Some(u64::from_str_radix(hex, 16).ok()?),
+        },
+    };
+
+    Some((decode_path(fields.next()?), entry))
+}
+
+#[cfg(unix)]
+fn encode_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    percent_encode(path.as_os_str().as_bytes(), PATH).to_string()
+}
+
+#[cfg(not(unix))]
+fn encode_path(path: &Path) -> String {
+    percent_encode(path.to_string_lossy().as_bytes(), PATH).to_string()
+}
+
+#[cfg(unix)]
+fn decode_path(field: &str) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(OsString::from_vec(percent_decode_str(field).collect()))
+}
+
+#[cfg(not(unix))]
+fn decode_path(field: &str) -> PathBuf {
+    PathBuf::from(OsString::from(
+        percent_decode_str(field).decode_utf8_lossy().into_owned(),
+    ))
+}
+
+/// Hashes the contents of a file, a chunk at a time.
+fn hash_file(path: &Path) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = [0; 64 * 1024];
+    let mut hash = Fnv::default();
+
+    loop {
+        let count = file.read(&mut buffer)?;
+        if count == 0 {
+            return Ok(hash.0);
+        }
+        hash.write(&buffer[..count]);
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv::default();
+    hash.write(bytes);
+    hash.0
+}
+
+/// The 64-bit FNV-1a hash, which is used because its output is the same
+/// on every platform and with every version of Rust, so snapshots can be
+/// compared wherever they were saved.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::{Options, OptionsResult};
+    use std::ffi::OsStr;
+
+    fn entry(size: u64, hash: u64) -> Entry {
+        Entry {
+            kind: 'f',
+            size,
+            modified: 1_700_000_000_000_000_000,
+            hash: Some(hash),
+        }
+    }
+
+    fn snapshot(entries: &[(&str, Entry)]) -> Snapshot {
+        Snapshot {
+            entries: entries
+                .iter()
+                .map(|(path, entry)| (PathBuf::from(path), *entry))
+                .collect(),
+            ..Snapshot::default()
+        }
+    }
+
+    #[test]
+    fn changes() {
+        let before = snapshot(&[("a", entry(1, 1)), ("b", entry(2, 2)), ("c", entry(3, 3))]);
+        let after = snapshot(&[("a", entry(1, 1)), ("b", entry(2, 9)), ("d", entry(4, 4))]);
+
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                (Change::Modified, Path::new("b"), &entry(2, 9)),
+                (Change::Removed, Path::new("c"), &entry(3, 3)),
+                (Change::Added, Path::new("d"), &entry(4, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_to_each_root() {
+        let dir = std::env::temp_dir().join(format!("eza-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("config/sub")).unwrap();
+        fs::write(dir.join("config/sub/file"), "text").unwrap();
+        fs::write(dir.join("notes"), "text").unwrap();
+
+        let OptionsResult::Ok(options, _) = Options::parse(Vec::<&OsStr>::new(), &None) else {
+            panic!("Default options didn't parse");
+        };
+        let filter = options.filter;
+        let one = Snapshot::take(&[dir.join("config")], &filter);
+        let again = Snapshot::take(&[dir.join("config/sub/..")], &filter);
+        let two = Snapshot::take(&[dir.join("config/sub/.."), dir.join("notes")], &filter);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(one.changes_since(&again), vec![]);
+        assert_eq!(
+            one.entries.keys().collect::<Vec<_>>(),
+            vec![Path::new("sub"), Path::new("sub/file")]
+        );
+        assert_eq!(
+            two.entries.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new("config/sub"),
+                Path::new("config/sub/file"),
+                Path::new("notes")
+            ]
+        );
+        assert_eq!(
+            two.location(Path::new("config/gone")),
+            Some(dir.join("config/sub/../gone"))
+        );
+        assert_eq!(two.location(Path::new("notes")), Some(dir.join("notes")));
+    }
+
+    #[test]
+    fn removed_entry() {
+        let removed = entry(5, 1).to_manifest(Path::new("sub/file"));
+        assert_eq!(removed.name, "sub/file");
+        assert_eq!(removed.kind, manifest::Kind::File);
+        assert_eq!(removed.size, 5);
+        assert_eq!(
+            removed.modified,
+            DateTime::from_timestamp(1_700_000_000, 0).map(|time| time.naive_utc())
+        );
+    }
+
+    #[test]
+    fn line_round_trip() {
+        let path = Path::new("dir/tab\there%");
+        let line = format!(
+            "f\t1\t1700000000000000000\t00000000000000ff\t{}",
+            encode_path(path)
+        );
+
+        assert_eq!(
+            parse_line(&line),
+            Some((path.to_path_buf(), entry(1, 0xff)))
+        );
+    }
+
+    #[test]
+    fn unhashed_line() {
+        let (_, entry) = parse_line("o\t0\t0\t-\tfifo").unwrap();
+        assert_eq!(entry.hash, None);
+    }
+
+    #[test]
+    fn bad_lines() {
+        assert_eq!(parse_line("f\t1\t2"), None);
+        assert_eq!(parse_line("file\t1\t2\t-\tpath"), None);
+        assert_eq!(parse_line("f\tone\t2\t-\tpath"), None);
+    }
+
+    #[test]
+    fn fnv() {
+        assert_eq!(hash_bytes(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_bytes(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out whether anything is listening on a Unix domain socket, by
+//! looking it up in the kernel’s socket table.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// What the socket table says about the socket bound to a path.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct SocketState {
+    /// The inode of the socket a process is accepting connections on, if
+    /// one is, which is how to find the process.
+    pub listener: Option<u64>,
+
+    /// The number of connections that have been accepted on it.
+    pub connections: usize,
+}
+
+/// The `__SO_ACCEPTCON` flag, which the kernel sets on listening sockets.
+const ACCEPTING_CONNECTIONS: u32 = 0x0001_0000;
+
+/// The `SS_CONNECTED` socket state.
+const CONNECTED: u32 = 3;
+
+// A lazily initialised static map of every Unix domain socket bound to a
+// path, read from `/proc/net/unix`. This is `None` when the table can’t be
+// read, such as when not running on Linux, in which case nothing is known
+// about any socket.
+pub(super) fn all_sockets() -> Option<&'static HashMap<PathBuf, SocketState>> {
+    static ALL_SOCKETS: OnceLock<Option<HashMap<PathBuf, SocketState>>> = OnceLock::new();
+
+    ALL_SOCKETS
+        .get_or_init(|| {
+            if cfg!(target_os = "linux") {
+                std::fs::read_to_string("/proc/net/unix")
+                    .ok()
+                    .map(|table| parse(&table))
+            } else {
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Parses the socket table. Each line after the header describes one
+/// socket, with its path, if it has one, in the last column.
+fn parse(table: &str) -> HashMap<PathBuf, SocketState> {
+    let mut sockets: HashMap<PathBuf, SocketState> = HashMap::new();
+
+    for line in table.lines().skip(1) {
+        let (_num, rest) = split_field(line);
+        let (_ref_count, rest) = split_field(rest);
+        let (_protocol, rest) = split_field(rest);
+        let (flags, rest) = split_field(rest);
+        let (_type, rest) = split_field(rest);
+        let (state, rest) = split_field(rest);
+        let (inode, rest) = split_field(rest);
+
+        // The path is whatever is left, and can contain spaces itself.
+        let path = rest.trim();
+
+        // Abstract sockets have no presence on the filesystem.
+        if !path.starts_with('/') {
+            continue;
+        }
+
+        let (Ok(flags), Ok(state), Ok(inode)) = (
+            u32::from_str_radix(flags, 16),
+            u32::from_str_radix(state, 16),
+            inode.parse(),
+        ) else {
+            continue;
+        };
+
+        let entry = sockets.entry(PathBuf::from(path)).or_default();
+        if flags & ACCEPTING_CONNECTIONS != 0 {
+            entry.listener = Some(inode);
+        } else if state == CONNECTED {
+            entry.connections += 1;
+        }
+    }
+
+    sockets
+}
+
+/// Splits the first whitespace-separated field off the front of a line.
+fn split_field(line: &str) -> (&str, &str) {
+    let line = line.trim_start();
+    line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static TABLE: &str = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+00000000f1ede099: 00000003 00000000 00000000 0001 03   933
+00000000901ef094: 00000002 00000000 00010000 0001 01  1193 /run/daemon.sock
+0000000017f512c4: 00000003 00000000 00000000 0001 03  1200 /run/daemon.sock
+00000000a2a88efd: 00000003 00000000 00000000 0001 03  1201 /run/daemon.sock
+00000000a2a88eff: 00000002 00000000 00010000 0001 01  1300 @abstract
+00000000a2a88f00: 00000002 00000000 00000000 0002 01  1400 /run/my socket
+";
+
+    #[test]
+    fn listening_with_connections() {
+        let sockets = parse(TABLE);
+        let state = sockets[&PathBuf::from("/run/daemon.sock")];
+        assert_eq!(
+            state,
+            SocketState {
+                listener: Some(1193),
+                connections: 2
+            }
+        );
+    }
+
+    #[test]
+    fn bound_but_not_listening() {
+        let sockets = parse(TABLE);
+        let state = sockets[&PathBuf::from("/run/my socket")];
+        assert_eq!(state, SocketState::default());
+    }
+
+    #[test]
+    fn abstract_and_unbound_sockets() {
+        assert_eq!(parse(TABLE).len(), 2);
+    }
+}
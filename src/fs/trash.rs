@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Reading where the files in a trash directory were deleted from, and
+//! when.
+//!
+//! A freedesktop.org trash directory, such as `~/.local/share/Trash`, keeps
+//! deleted files in `files`, and a `.trashinfo` file for each of them in
+//! `info`, which gives its original path and deletion date. The Windows
+//! Recycle Bin keeps a deleted file as `$R` followed by some random
+//! characters, next to an `$I` file with the same characters that gives the
+//! same details in a binary format.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use percent_encoding::percent_decode_str;
+
+/// Where a file in a trash directory was deleted from, and when.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TrashInfo {
+    pub original_path: PathBuf,
+
+    /// When the file was deleted, in UTC.
+    pub deleted: Option<NaiveDateTime>,
+}
+
+/// Whether the directory at this path is one that trashed files get kept
+/// in, so a listing of it gets the columns for their details.
+pub fn is_trash_dir(path: &Path) -> bool {
+    let path = canonical(path);
+    freedesktop_top_dir(&path).is_some() || is_recycle_bin(&path)
+}
+
+/// Reads where the file at this path, in a trash directory, was deleted
+/// from, and when. Files that aren’t in one have nothing to read.
+pub fn info(path: &Path) -> io::Result<Option<TrashInfo>> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(None);
+    };
+    let dir = &canonical(dir);
+
+    if let Some(top) = freedesktop_top_dir(dir) {
+        let mut info_name = name.to_os_string();
+        info_name.push(".trashinfo");
+        let info_path = dir.with_file_name("info").join(info_name);
+        return Ok(parse_trashinfo(&fs::read_to_string(info_path)?, &top));
+    }
+
+    if is_recycle_bin(dir) {
+        let Some(rest) = name.to_str().and_then(|n| n.strip_prefix("$R")) else {
+            return Ok(None);
+        };
+        return Ok(parse_recycle_bin_info(&fs::read(
+            dir.join(format!("$I{rest}")),
+        )?));
+    }
+
+    Ok(None)
+}
+
+/// The directory at this path with any symlinks, `.`, or `..` in it
+/// resolved, so that a relative path such as `files` still has the trash
+/// directory above it. A path that can’t be resolved is left as it is.
+fn canonical(dir: &Path) -> PathBuf {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// For the `files` directory of a freedesktop.org trash directory, the
+/// directory that relative original paths are relative to, which is the
+/// top of the filesystem the trash directory is on. The trash directory
+/// in a user’s home only gets absolute paths, so `/` does for it.
+fn freedesktop_top_dir(files: &Path) -> Option<PathBuf> {
+    if files.file_name()? != "files" {
+        return None;
+    }
+
+    let trash = files.parent()?;
+    let trash_name = trash.file_name()?.to_str()?;
+    if trash_name == "Trash" {
+        Some(PathBuf::from("/"))
+    } else if trash_name.starts_with(".Trash-") {
+        // `$topdir/.Trash-$uid`
+        trash.parent().map(Path::to_path_buf)
+    } else if trash.parent()?.file_name()? == ".Trash" {
+        // `$topdir/.Trash/$uid`
+        trash.parent()?.parent().map(Path::to_path_buf)
+    } else {
+        None
+    }
+}
+
+/// Whether this is one user’s directory in a Recycle Bin, which are named
+/// after their security identifiers.
+fn is_recycle_bin(dir: &Path) -> bool {
+    dir.parent()
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.eq_ignore_ascii_case("$Recycle.Bin"))
+}
+
+/// Reads the original path and deletion date out of a `.trashinfo` file.
+/// The path is percent-encoded, and the date is in local time.
+fn parse_trashinfo(text: &str, top: &Path) -> Option<TrashInfo> {
+    let mut lines = text.lines().map(str::trim);
+    if lines.next()? != "[Trash Info]" {
+        return None;
+    }
+
+    let mut original_path = None;
+    let mut deleted = None;
+    for line in lines.take_while(|line| !line.starts_with('[')) {
+        if let Some(path) = line.strip_prefix("Path=") {
+            let path = percent_decode_str(path).decode_utf8_lossy();
+            original_path = Some(top.join(&*path));
+        } else if let Some(date) = line.strip_prefix("DeletionDate=") {
+            deleted = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+                .ok()
+                .and_then(|date| Local.from_local_datetime(&date).earliest())
+                .map(|date| date.naive_utc());
+        }
+    }
+
+    Some(TrashInfo {
+        original_path: original_path?,
+        deleted,
+    })
+}
+
+/// Reads the original path and deletion date out of a Recycle Bin `$I`
+/// file. The first version has the path in a fixed 260 characters, and the
+/// second has its length first; both have a 64-bit version, the file’s
+/// size, and a `FILETIME` of when it was deleted before it.
+fn parse_recycle_bin_info(bytes: &[u8]) -> Option<TrashInfo> {
+    /// The seconds between 1601, when `FILETIME`s count from, and 1970.
+    const FILETIME_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+    let u64_at = |at: usize| Some(u64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?));
+    let version = u64_at(0)?;
+    let filetime = u64_at(16)?;
+
+    let path_bytes = match version {
+        1 => bytes.get(24..24 + 260 * 2)?,
+        2 => {
+            let length = u32::from_le_bytes(bytes.get(24..28)?.try_into().ok()?);
+            let length = usize::try_from(length).ok()?.checked_mul(2)?;
+            bytes.get(28..28_usize.checked_add(length)?)?
+        }
+        _ => return None,
+    };
+
+    let units: Vec<u16> = path_bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+
+    let seconds = i64::try_from(filetime / 10_000_000).ok()? - FILETIME_EPOCH_OFFSET;
+    let nanos = u32::try_from(filetime % 10_000_000).ok()? * 100;
+    let deleted = DateTime::from_timestamp(seconds, nanos).map(|date| date.naive_utc());
+
+    Some(TrashInfo {
+        original_path: PathBuf::from(String::from_utf16_lossy(&units)),
+        deleted,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn home_trash() {
+        assert!(is_trash_dir(Path::new("/home/me/.local/share/Trash/files")));
+        assert_eq!(
+            freedesktop_top_dir(Path::new("/home/me/.local/share/Trash/files")),
+            Some(PathBuf::from("/"))
+        );
+    }
+
+    #[test]
+    fn top_dir_trashes() {
+        assert_eq!(
+            freedesktop_top_dir(Path::new("/mnt/usb/.Trash-1000/files")),
+            Some(PathBuf::from("/mnt/usb"))
+        );
+        assert_eq!(
+            freedesktop_top_dir(Path::new("/mnt/usb/.Trash/1000/files")),
+            Some(PathBuf::from("/mnt/usb"))
+        );
+    }
+
+    #[test]
+    fn not_trash() {
+        assert!(!is_trash_dir(Path::new("/home/me/files")));
+        assert!(!is_trash_dir(Path::new("/home/me/.local/share/Trash/info")));
+    }
+
+    #[test]
+    fn relative_dirs() {
+        let here = fs::canonicalize(".").unwrap();
+        assert_eq!(canonical(Path::new("")), here);
+        assert_eq!(canonical(Path::new("src/..")), here);
+    }
+
+    #[test]
+    fn recycle_bin() {
+        assert!(is_trash_dir(Path::new(
+            "/mnt/c/$RECYCLE.BIN/S-1-5-21-1004336348-1177238915-682003330-1001"
+        )));
+    }
+
+    #[test]
+    fn trashinfo() {
+        let text = "[Trash Info]\nPath=/home/me/My%20Notes.txt\nDeletionDate=2024-03-01T12:30:00\n";
+        let info = parse_trashinfo(text, Path::new("/")).unwrap();
+        assert_eq!(info.original_path, PathBuf::from("/home/me/My Notes.txt"));
+
+        let expected = Local
+            .with_ymd_and_hms(2024, 3, 1, 12, 30, 0)
+            .earliest()
+            .map(|date| date.naive_utc());
+        assert_eq!(info.deleted, expected);
+    }
+
+    #[test]
+    fn relative_trashinfo() {
+        let text = "[Trash Info]\nPath=photos/cat.jpg\nDeletionDate=2024-03-01T12:30:00\n";
+        let info = parse_trashinfo(text, Path::new("/mnt/usb")).unwrap();
+        assert_eq!(info.original_path, PathBuf::from("/mnt/usb/photos/cat.jpg"));
+    }
+
+    #[test]
+    fn trashinfo_without_path() {
+        assert_eq!(
+            parse_trashinfo(
+                "[Trash Info]\nDeletionDate=2024-03-01T12:30:00\n",
+                Path::new("/")
+            ),
+            None
+        );
+        assert_eq!(
+            parse_trashinfo("Path=/home/me/notes.txt\n", Path::new("/")),
+            None
+        );
+    }
+
+    fn recycle_bin_info(version: u64, path: &str) -> Vec<u8> {
+        let units: Vec<u16> = path.encode_utf16().chain([0]).collect();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        bytes.extend_from_slice(&1234_u64.to_le_bytes());
+        // 2024-03-01 12:30:00 UTC
+        bytes.extend_from_slice(&133_537_698_000_000_000_u64.to_le_bytes());
+        if version == 2 {
+            bytes.extend_from_slice(&u32::try_from(units.len()).unwrap().to_le_bytes());
+        }
+        for unit in &units {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        if version == 1 {
+            bytes.resize(24 + 260 * 2, 0);
+        }
+        bytes
+    }
+
+    #[test]
+    fn recycle_bin_versions() {
+        let expected = TrashInfo {
+            original_path: PathBuf::from(r"C:\Users\me\notes.txt"),
+            deleted: DateTime::from_timestamp(1_709_296_200, 0).map(|date| date.naive_utc()),
+        };
+        for version in [1, 2] {
+            let bytes = recycle_bin_info(version, r"C:\Users\me\notes.txt");
+            assert_eq!(parse_recycle_bin_info(&bytes).as_ref(), Some(&expected));
+        }
+    }
+
+    #[test]
+    fn truncated_recycle_bin_info() {
+        let mut bytes = recycle_bin_info(2, r"C:\Users\me\notes.txt");
+        bytes.truncate(30);
+        assert_eq!(parse_recycle_bin_info(&bytes), None);
+    }
+}
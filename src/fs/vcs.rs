@@ -0,0 +1,395 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Getting the status of files in working copies of any of the version
+//! control systems eza knows about, for `--vcs-status`.
+//!
+//! Git repositories get looked up in a Git cache, the same as for `--git`.
+//! Mercurial and Jujutsu keep their working copy state in formats only they
+//! can read, so they get asked for it instead, by running `hg status` or
+//! `jj diff --summary` in the root of the working copy the first time a file
+//! in it gets looked up. Both print each changed file’s path from the root
+//! after a letter saying what happened to it. Jujutsu working copies that
+//! are colocated with a Git repository get asked through `jj`, as it’s what
+//! the people using them will be committing with.
+//!
+//! Listing a directory shouldn’t change or run anything in it, so `hg` gets
+//! told to skip the repository’s own `.hg/hgrc`, which can set up hooks and
+//! extensions, and `jj` to leave the working copy alone rather than taking
+//! a snapshot of it. Jujutsu statuses are as of its last snapshot, then.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use log::*;
+
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields as f;
+
+/// Something that knows the status of the files in some working copies.
+pub trait VcsStatus: Send + Sync {
+    /// Whether the file at the given path is in one of the working copies.
+    fn has_path(&self, path: &Path) -> bool;
+
+    /// The status of the file at the given path, or of anything inside it
+    /// if it’s a directory and `prefix_lookup` is set.
+    fn status(&self, path: &Path, prefix_lookup: bool) -> f::Git;
+}
+
+impl VcsStatus for GitCache {
+    fn has_path(&self, path: &Path) -> bool {
+        self.has_anything_for(path)
+    }
+
+    fn status(&self, path: &Path, prefix_lookup: bool) -> f::Git {
+        self.get(path, prefix_lookup)
+    }
+}
+
+/// The version control systems that get run to find out what’s changed.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Kind {
+    Mercurial,
+    Jujutsu,
+}
+
+impl Kind {
+    /// The command that lists the changed files in a working copy, without
+    /// using any of the working copy’s own configuration or changing it.
+    fn command(self) -> Command {
+        match self {
+            Self::Mercurial => {
+                let mut command = Command::new("hg");
+                command
+                    .args(["status", "--modified", "--added", "--removed"])
+                    .args(["--deleted", "--unknown", "--print0"])
+                    .env("HGPLAIN", "1")
+                    .env("HGRCSKIPREPO", "1");
+                command
+            }
+            Self::Jujutsu => {
+                let mut command = Command::new("jj");
+                command
+                    .args(["diff", "--summary", "--no-pager", "--color=never"])
+                    .arg("--ignore-working-copy");
+                command
+            }
+        }
+    }
+
+    /// Reads the paths and statuses out of what the command printed.
+    fn parse(self, output: &str) -> Vec<(PathBuf, f::GitStatus)> {
+        match self {
+            Self::Mercurial => output.split('\0').filter_map(parse_hg_line).collect(),
+            Self::Jujutsu => output.lines().filter_map(parse_jj_line).collect(),
+        }
+    }
+}
+
+/// Reads one entry of `hg status`, where `!` marks files that are missing
+/// without having been removed, and `?` ones that aren’t tracked.
+fn parse_hg_line(line: &str) -> Option<(PathBuf, f::GitStatus)> {
+    let (code, path) = line.split_once(' ')?;
+    let status = match code {
+        "M" => f::GitStatus::Modified,
+        "A" | "?" => f::GitStatus::New,
+        "R" | "!" => f::GitStatus::Deleted,
+        _ => return None,
+    };
+    Some((PathBuf::from(path), status))
+}
+
+/// Reads one line of `jj diff --summary`. Jujutsu has no untracked files,
+/// as everything in the working copy gets snapshotted, so new files are
+/// always added ones.
+fn parse_jj_line(line: &str) -> Option<(PathBuf, f::GitStatus)> {
+    let (code, path) = line.split_once(' ')?;
+    let (status, path) = match code {
+        "M" => (f::GitStatus::Modified, path.to_owned()),
+        "A" => (f::GitStatus::New, path.to_owned()),
+        "D" => (f::GitStatus::Deleted, path.to_owned()),
+        "R" => (f::GitStatus::Renamed, renamed_path(path)),
+        "C" => (f::GitStatus::New, renamed_path(path)),
+        _ => return None,
+    };
+    Some((PathBuf::from(path), status))
+}
+
+/// The path a file got renamed or copied to, from the way Jujutsu shows
+/// both paths at once, with the part that changed in braces like
+/// `src/{old.rs => new.rs}`.
+fn renamed_path(path: &str) -> String {
+    let Some((before, rest)) = path.split_once('{') else {
+        return path.to_owned();
+    };
+    let Some((change, after)) = rest.split_once('}') else {
+        return path.to_owned();
+    };
+    let Some((_, new)) = change.split_once(" => ") else {
+        return path.to_owned();
+    };
+
+    // Moving a file up a directory leaves an empty part, and with it, two
+    // slashes next to each other.
+    let after = if new.is_empty() {
+        after.strip_prefix('/').unwrap_or(after)
+    } else {
+        after
+    };
+    format!("{before}{new}{after}")
+}
+
+/// A Mercurial or Jujutsu working copy, with the changed files in it once
+/// they’ve been asked for.
+struct WorkingCopy {
+    kind: Kind,
+    root: PathBuf,
+    changes: OnceLock<Vec<(PathBuf, f::GitStatus)>>,
+}
+
+impl WorkingCopy {
+    fn changes(&self) -> &[(PathBuf, f::GitStatus)] {
+        self.changes.get_or_init(|| {
+            self.read_changes().unwrap_or_else(|e| {
+                error!(
+                    "Error getting {:?} status of {:?}: {e}",
+                    self.kind, self.root
+                );
+                Vec::new()
+            })
+        })
+    }
+
+    fn read_changes(&self) -> io::Result<Vec<(PathBuf, f::GitStatus)>> {
+        info!("Getting {:?} status of {:?}", self.kind, self.root);
+        let output = self.kind.command().current_dir(&self.root).output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(stderr.trim().to_owned()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(self
+            .kind
+            .parse(&stdout)
+            .into_iter()
+            .map(|(path, status)| (self.root.join(path), status))
+            .collect())
+    }
+}
+
+impl VcsStatus for WorkingCopy {
+    fn has_path(&self, path: &Path) -> bool {
+        absolute(path).starts_with(&self.root)
+    }
+
+    fn status(&self, path: &Path, prefix_lookup: bool) -> f::Git {
+        let path = absolute(path);
+        let statuses = self
+            .changes()
+            .iter()
+            .filter(|(changed, _)| {
+                if prefix_lookup {
+                    changed.starts_with(&path)
+                } else {
+                    *changed == path
+                }
+            })
+            .map(|(_, status)| *status)
+            .collect::<Vec<_>>();
+
+        // A directory with several kinds of change inside it shows the one
+        // that’s most worth knowing about, the same as for Git.
+        let unstaged = [
+            f::GitStatus::New,
+            f::GitStatus::Modified,
+            f::GitStatus::Deleted,
+            f::GitStatus::Renamed,
+        ]
+        .into_iter()
+        .find(|status| statuses.contains(status))
+        .unwrap_or(f::GitStatus::NotModified);
+
+        f::Git {
+            unstaged,
+            ..f::Git::default()
+        }
+    }
+}
+
+/// A **VCS cache** holds the working copies the user’s input arguments
+/// are in, whichever version control system they belong to.
+pub struct VcsCache {
+    working_copies: Vec<Box<dyn VcsStatus>>,
+}
+
+impl VcsCache {
+    pub fn has_anything_for(&self, path: &Path) -> bool {
+        self.working_copies.iter().any(|wc| wc.has_path(path))
+    }
+
+    pub fn get(&self, path: &Path, prefix_lookup: bool) -> f::Git {
+        self.working_copies
+            .iter()
+            .find(|wc| wc.has_path(path))
+            .map(|wc| wc.status(path, prefix_lookup))
+            .unwrap_or_default()
+    }
+}
+
+impl FromIterator<PathBuf> for VcsCache {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let mut working_copies = Vec::<Box<dyn VcsStatus>>::new();
+        let mut roots = Vec::new();
+        let mut git_paths = Vec::new();
+
+        for path in iter {
+            let path = absolute(&path);
+            let Some((kind, root)) = path.ancestors().find_map(|dir| {
+                if dir.join(".jj").is_dir() {
+                    Some((Some(Kind::Jujutsu), dir))
+                } else if dir.join(".hg").is_dir() {
+                    Some((Some(Kind::Mercurial), dir))
+                } else if dir.join(".git").exists() {
+                    Some((None, dir))
+                } else {
+                    None
+                }
+            }) else {
+                debug!("No working copy found for {path:?}");
+                continue;
+            };
+
+            match kind {
+                Some(kind) if !roots.iter().any(|r| r == root) => {
+                    roots.push(root.to_path_buf());
+                    working_copies.push(Box::new(WorkingCopy {
+                        kind,
+                        root: root.to_path_buf(),
+                        changes: OnceLock::new(),
+                    }));
+                }
+                Some(_) => {}
+                None => git_paths.push(path.clone()),
+            }
+        }
+
+        if !git_paths.is_empty() {
+            working_copies.push(Box::new(git_paths.into_iter().collect::<GitCache>()));
+        }
+
+        Self { working_copies }
+    }
+}
+
+/// Converts a path to an absolute one without any symlinks in it, so it
+/// can be compared with the paths from the root of a working copy.
+fn absolute(path: &Path) -> PathBuf {
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    path.canonicalize().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn commands_skip_working_copy_config() {
+        let hg = Kind::Mercurial.command();
+        assert!(hg
+            .get_envs()
+            .any(|env| env == (OsStr::new("HGRCSKIPREPO"), Some(OsStr::new("1")))));
+
+        let jj = Kind::Jujutsu.command();
+        assert!(jj.get_args().any(|arg| arg == "--ignore-working-copy"));
+    }
+
+    #[test]
+    fn hg_status() {
+        let output = "M src/main.rs\0A NEWS\0R old.txt\0! gone.txt\0? notes.txt\0";
+        assert_eq!(
+            Kind::Mercurial.parse(output),
+            vec![
+                (PathBuf::from("src/main.rs"), f::GitStatus::Modified),
+                (PathBuf::from("NEWS"), f::GitStatus::New),
+                (PathBuf::from("old.txt"), f::GitStatus::Deleted),
+                (PathBuf::from("gone.txt"), f::GitStatus::Deleted),
+                (PathBuf::from("notes.txt"), f::GitStatus::New),
+            ]
+        );
+    }
+
+    #[test]
+    fn hg_status_with_spaces() {
+        assert_eq!(
+            Kind::Mercurial.parse("M file with spaces.txt\0"),
+            vec![(
+                PathBuf::from("file with spaces.txt"),
+                f::GitStatus::Modified
+            )]
+        );
+    }
+
+    #[test]
+    fn jj_diff_summary() {
+        let output = "M src/main.rs\nA NEWS\nD old.txt\n";
+        assert_eq!(
+            Kind::Jujutsu.parse(output),
+            vec![
+                (PathBuf::from("src/main.rs"), f::GitStatus::Modified),
+                (PathBuf::from("NEWS"), f::GitStatus::New),
+                (PathBuf::from("old.txt"), f::GitStatus::Deleted),
+            ]
+        );
+    }
+
+    #[test]
+    fn jj_renames() {
+        assert_eq!(renamed_path("{a.txt => b.txt}"), "b.txt");
+        assert_eq!(renamed_path("src/{old.rs => new.rs}"), "src/new.rs");
+        assert_eq!(renamed_path("{src => lib}/main.rs"), "lib/main.rs");
+        assert_eq!(renamed_path("src/{nested => }/main.rs"), "src/main.rs");
+        assert_eq!(renamed_path("plain.txt"), "plain.txt");
+    }
+
+    #[test]
+    fn directory_shows_newest_change() {
+        let wc = WorkingCopy {
+            kind: Kind::Mercurial,
+            root: PathBuf::from("/repo"),
+            changes: OnceLock::from(vec![
+                (PathBuf::from("/repo/src/a.rs"), f::GitStatus::Modified),
+                (PathBuf::from("/repo/src/b.rs"), f::GitStatus::New),
+                (PathBuf::from("/repo/README"), f::GitStatus::Deleted),
+            ]),
+        };
+
+        assert_eq!(
+            wc.status(Path::new("/repo/src"), true).unstaged,
+            f::GitStatus::New
+        );
+        assert_eq!(
+            wc.status(Path::new("/repo/src"), false).unstaged,
+            f::GitStatus::NotModified
+        );
+        assert_eq!(
+            wc.status(Path::new("/repo/README"), false).unstaged,
+            f::GitStatus::Deleted
+        );
+        assert_eq!(
+            wc.status(Path::new("/repo/docs"), true).unstaged,
+            f::GitStatus::NotModified
+        );
+    }
+}
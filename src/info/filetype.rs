@@ -412,6 +412,31 @@ const EXTENSION_TYPES: Map<&'static str, FileType> = phf_map! {
 };
 
 impl FileType {
+    /// Returns the `LS_COLORS` patterns that pick out each file type, with
+    /// the type they pick out. Extensions come before full file names, as
+    /// later patterns take precedence over earlier ones.
+    ///
+    /// Compiled files can’t be expressed as patterns, as they depend on what
+    /// else is in their directory, and neither can READMEs, as they depend on
+    /// how the name begins rather than how it ends.
+    pub(crate) fn patterns() -> impl Iterator<Item = (String, &'static FileType)> {
+        let mut extensions: Vec<_> = EXTENSION_TYPES.entries().collect();
+        extensions.sort_by_key(|(ext, _)| **ext);
+
+        let mut filenames: Vec<_> = FILENAME_TYPES.entries().collect();
+        filenames.sort_by_key(|(name, _)| **name);
+
+        extensions
+            .into_iter()
+            .map(|(ext, file_type)| (format!("*.{ext}"), file_type))
+            .chain(std::iter::once((String::from("*~"), &FileType::Temp)))
+            .chain(
+                filenames
+                    .into_iter()
+                    .map(|(name, file_type)| (format!("*{name}"), file_type)),
+            )
+    }
+
     /// Lookup the file type based on the file's name, by the file name
     /// lowercase extension, or if the file could be compiled from related
     /// source code.
@@ -29,18 +29,29 @@
 
 use std::env;
 use std::ffi::{OsStr, OsString};
-use std::io::{self, stdin, ErrorKind, IsTerminal, Read, Write};
-use std::path::{Component, PathBuf};
+use std::io::{self, stdin, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
 use std::process::exit;
+use std::sync::atomic::Ordering;
 
 use nu_ansi_term::{AnsiStrings as ANSIStrings, Style};
 
+use crate::fs::dir_action::{DirAction, RecurseOptions};
 use crate::fs::feature::git::GitCache;
-use crate::fs::filter::{FileFilterFlags::OnlyFiles, GitIgnore};
+use crate::fs::feature::{s3, sftp};
+use crate::fs::filter::{FileFilterFlags::Dedupe, FileFilterFlags::OnlyFiles, GitIgnore};
+use crate::fs::snapshot::{Change, Snapshot};
+use crate::fs::vcs::VcsCache;
+use crate::fs::{dedupe, lfs, manifest};
 use crate::fs::{Dir, File};
 use crate::options::stdin::FilesInput;
-use crate::options::{vars, Options, OptionsResult, Vars};
-use crate::output::{details, escape, file_name, grid, grid_details, lines, Mode, View};
+use crate::options::{vars, Action, Options, OptionsResult, Vars};
+use crate::output::pager::{Pager, Writer};
+use crate::output::summary::{self, Summary};
+use crate::output::table::FIXED_ENVIRONMENT;
+use crate::output::{
+    details, escape, file_name, grid, grid_details, html, lines, template, Mode, View,
+};
 use crate::theme::Theme;
 use log::*;
 
@@ -59,12 +70,17 @@ fn main() {
 
     logger::configure(env::var_os(vars::EZA_DEBUG).or_else(|| env::var_os(vars::EXA_DEBUG)));
 
-    let stdout_istty = io::stdout().is_terminal();
-
     let mut input = String::new();
     let args: Vec<_> = env::args_os().skip(1).collect();
     match Options::parse(args.iter().map(std::convert::AsRef::as_ref), &LiveVars) {
         OptionsResult::Ok(options, mut input_paths) => {
+            if options.deterministic {
+                FIXED_ENVIRONMENT.store(true, Ordering::Relaxed);
+            }
+            if options.lfs {
+                lfs::OBJECT_SIZES.store(true, Ordering::Relaxed);
+            }
+
             // List the current directory by default.
             // (This has to be done here, otherwise git_options won’t see it.)
             if input_paths.is_empty() {
@@ -88,11 +104,47 @@ fn main() {
             }
 
             let git = git_options(&options, &input_paths);
-            let writer = io::stdout();
+            let writer = if options.pager && options.is_a_tty {
+                Writer::Paged(Pager::default())
+            } else {
+                Writer::Direct(io::stdout())
+            };
             let git_repos = git_repos(&options, &input_paths);
+            let vcs = vcs_options(&options, &input_paths);
 
             let console_width = options.view.width.actual_terminal_width();
-            let theme = options.theme.to_theme(stdout_istty);
+            // HTML output turns colours into CSS rather than escape codes, so
+            // it can be coloured automatically even when written to a file.
+            let html = matches!(options.view.mode, Mode::Html(_));
+
+            if options.action == Action::DumpTheme {
+                print!("{}", options.theme.dump_theme(options.is_a_tty));
+                exit(exits::SUCCESS);
+            }
+
+            let export_ls_colors = options.action == Action::ExportLsColors;
+            let theme = options
+                .theme
+                .to_theme(options.is_a_tty || html || export_ls_colors);
+
+            // The theme is meant for other programs, so it gets printed even
+            // when colours wouldn’t be used for a listing.
+            if export_ls_colors {
+                println!("{}", theme.to_ls_colors());
+                exit(exits::SUCCESS);
+            }
+
+            // A snapshot gets saved instead of a listing, unless what’s
+            // changed since an earlier one is getting listed too.
+            if let (Some(path), None) = (&options.save_snapshot, &options.changed_since) {
+                let snapshot = Snapshot::take(&input_paths, &options.filter);
+                if let Err(e) = save_snapshot(&snapshot, path) {
+                    eprintln!("{e}");
+                    exit(exits::RUNTIME_ERROR);
+                }
+                exit(exits::SUCCESS);
+            }
+
             let exa = Exa {
                 options,
                 writer,
@@ -100,7 +152,11 @@ fn main() {
                 theme,
                 console_width,
                 git,
+                vcs,
                 git_repos,
+                denied_dirs: Vec::new(),
+                html_base: PathBuf::new(),
+                dedupe: None,
             };
 
             info!("matching on exa.run");
@@ -148,8 +204,9 @@ pub struct Exa<'args> {
     /// List of command-line options, having been successfully parsed.
     pub options: Options,
 
-    /// The output handle that we write to.
-    pub writer: io::Stdout,
+    /// The output handle that we write to, which buffers the listing
+    /// instead if it’s going to be paged.
+    pub writer: Writer,
 
     /// List of the free command-line arguments that should correspond to file
     /// names (anything that isn’t an option).
@@ -169,7 +226,46 @@ pub struct Exa<'args> {
     /// want to list several directories in the same repository.
     pub git: Option<GitCache>,
 
+    /// A global cache of the working copies the input paths are in, of any
+    /// version control system, if `--vcs-status` was passed in.
+    pub vcs: Option<VcsCache>,
+
     pub git_repos: bool,
+
+    /// The directories that couldn’t be read because of their permissions
+    /// while recursing, which get summarised once each argument has been
+    /// listed rather than reported one at a time.
+    pub denied_dirs: Vec<(PathBuf, io::Error)>,
+
+    /// The directory the links in an HTML page are relative to: the only
+    /// directory being listed, if there is one, or else the current one.
+    pub html_base: PathBuf,
+
+    /// The arguments and the directories listed so far, with `--dedupe`.
+    pub dedupe: Option<dedupe::Dedupe>,
+}
+
+/// How deep a directory is, for comparing against the `--level` limit.
+fn depth_of(dir: &Dir) -> usize {
+    dir.path
+        .components()
+        .filter(|&c| c != Component::CurDir)
+        .count()
+        + 1
+}
+
+/// A directory waiting on the stack to be listed while recursing.
+enum PendingDir {
+    /// A directory given as an argument, which has already been read.
+    Argument(Dir),
+
+    /// A directory found beneath one of the arguments, which gets read when
+    /// it comes off the stack.
+    Subdirectory(PathBuf),
+
+    /// The point where everything beneath an argument has been listed, so
+    /// any directories that couldn’t be read can be reported.
+    EndOfArgument(PathBuf),
 }
 
 /// The “real” environment variables type.
@@ -186,6 +282,18 @@ impl Vars for LiveVars {
 /// listed before they’re actually listed, if the options demand it.
 fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
     if options.should_scan_for_git() {
+        let git: GitCache = args.iter().map(PathBuf::from).collect();
+        if options.should_load_git_statuses() {
+            git.load_statuses();
+        }
+        Some(git)
+    } else {
+        None
+    }
+}
+
+fn vcs_options(options: &Options, args: &[&OsStr]) -> Option<VcsCache> {
+    if options.should_scan_for_vcs() {
         Some(args.iter().map(PathBuf::from).collect())
     } else {
         None
@@ -194,7 +302,7 @@ fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
 
 #[cfg(not(feature = "git"))]
 fn git_repos(_options: &Options, _args: &[&OsStr]) -> bool {
-    return false;
+    false
 }
 
 #[cfg(feature = "git")]
@@ -216,6 +324,8 @@ fn get_files_in_dir(paths: &mut Vec<PathBuf>, path: PathBuf) {
 
 #[cfg(feature = "git")]
 fn git_repos(options: &Options, args: &[&OsStr]) -> bool {
+    use crate::output::table::Column;
+
     let option_enabled = match options.view.mode {
         Mode::Details(details::Options {
             table: Some(ref table),
@@ -228,7 +338,10 @@ fn git_repos(options: &Options, args: &[&OsStr]) -> bool {
                     ..
                 },
             ..
-        }) => table.columns.subdir_git_repos || table.columns.subdir_git_repos_no_stat,
+        }) => {
+            table.columns.contains(Column::SubdirGitRepo(true))
+                || table.columns.contains(Column::SubdirGitRepo(false))
+        }
         _ => false,
     };
     if option_enabled {
@@ -255,16 +368,35 @@ impl<'args> Exa<'args> {
     pub fn run(mut self) -> io::Result<i32> {
         debug!("Running with options: {:#?}", self.options);
 
+        if let Some(path) = self.options.from_manifest.clone() {
+            return self.list_manifest(&path);
+        }
+
+        if let Some(path) = self.options.changed_since.clone() {
+            return self.list_changes(&path);
+        }
+
         let mut files = Vec::new();
         let mut dirs = Vec::new();
         let mut exit_status = 0;
 
+        if self.options.filter.flags.contains(&Dedupe) {
+            self.dedupe = Some(dedupe::Dedupe::default());
+        }
+
+        let mut urls = Vec::new();
+
         for file_path in &self.input_paths {
+            if s3::is_url(file_path) || sftp::is_url(file_path) {
+                urls.push(file_path.to_string_lossy().into_owned());
+                continue;
+            }
+
             let f = File::from_args(
                 PathBuf::from(file_path),
                 None,
                 None,
-                self.options.view.deref_links,
+                self.options.view.deref_links.for_arguments(),
                 self.options.view.total_size,
                 None,
             );
@@ -273,10 +405,22 @@ impl<'args> Exa<'args> {
             // the metadata to verify.
             if let Err(e) = f.metadata() {
                 exit_status = 2;
-                writeln!(io::stderr(), "{file_path:?}: {e}")?;
+                if self.options.view.file_style.inline_errors {
+                    files.push(f);
+                } else {
+                    writeln!(io::stderr(), "{file_path:?}: {e}")?;
+                }
                 continue;
             }
 
+            // Anything reached through more than one argument only gets
+            // listed under the first, with a note of the others.
+            if let Some(dedupe) = &mut self.dedupe {
+                if !dedupe.add_argument(&f.path) {
+                    continue;
+                }
+            }
+
             if f.points_to_directory() && !self.options.dir_action.treat_dirs_as_files() {
                 trace!("matching on to_dir");
                 match f.to_dir() {
@@ -292,17 +436,284 @@ impl<'args> Exa<'args> {
             }
         }
 
+        for file in &mut files {
+            file.aliases = self.aliases(&file.path);
+        }
+
+        // Files given as arguments are no levels down, so they’re too shallow
+        // for any minimum depth. A tree starts again from each file that’s
+        // deep enough instead, with the level limit counted from there.
+        if let Some(recurse_opts) = self
+            .options
+            .dir_action
+            .recurse_options()
+            .filter(|r| r.min_depth > 0)
+        {
+            if recurse_opts.tree {
+                files = self.files_at_min_depth(files, recurse_opts)?;
+                self.options.dir_action = DirAction::Recurse(RecurseOptions {
+                    max_depth: recurse_opts
+                        .max_depth
+                        .map(|d| d.saturating_sub(recurse_opts.min_depth)),
+                    min_depth: 0,
+                    ..recurse_opts
+                });
+            } else {
+                files.clear();
+            }
+        }
+
         // We want to print a directory’s name before we list it, *except* in
         // the case where it’s the only directory, *except* if there are any
         // files to print as well. (It’s a double negative)
 
         let no_files = files.is_empty();
-        let is_only_dir = dirs.len() == 1 && no_files;
+        let no_dirs = dirs.is_empty();
+        let is_only_dir = dirs.len() + urls.len() == 1 && no_files;
+
+        // An HTML page holds the whole listing, with a section for each
+        // directory rather than a header line.
+        let html = matches!(self.options.view.mode, Mode::Html(_));
+        if html {
+            let title = match dirs.first().filter(|_| is_only_dir) {
+                Some(dir) => {
+                    self.html_base.clone_from(&dir.path);
+                    format!("Index of {}", dir.path.display())
+                }
+                None => String::from("Index"),
+            };
+            html::write_start(&mut self.writer, &title)?;
+        }
 
         self.options.filter.filter_argument_files(&mut files);
         self.print_files(None, files)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        let mut exit_status = self.print_dirs(dirs, no_files, is_only_dir, exit_status)?;
+
+        // Objects in object stores and directories on other machines get
+        // listed after everything local, like directories, with their URLs
+        // as headers.
+        let recursive = self.options.dir_action.recurse_options().is_some();
+        for (index, url) in urls.iter().enumerate() {
+            let listing = if sftp::is_url(OsStr::new(url)) {
+                sftp::list(url, recursive)
+            } else {
+                s3::list(url, recursive)
+            };
+
+            let entries = match listing {
+                Ok(entries) => entries,
+                Err(e) => {
+                    writeln!(io::stderr(), "{url}: {e}")?;
+                    exit_status = 2;
+                    continue;
+                }
+            };
+
+            if !html && (index > 0 || !no_files || !no_dirs) {
+                writeln!(&mut self.writer)?;
+            }
+            if !html && !is_only_dir {
+                writeln!(&mut self.writer, "{url}:")?;
+            }
+
+            self.list_virtual(entries, Some(url))?;
+        }
+
+        if html {
+            html::write_end(&mut self.writer)?;
+        }
+        self.writer.finish()?;
+        Ok(exit_status)
+    }
+
+    /// Lists the entries in a manifest instead of anything on the
+    /// filesystem.
+    fn list_manifest(mut self, path: &Path) -> io::Result<i32> {
+        let entries = match manifest::load(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                writeln!(io::stderr(), "{}: {e}", path.display())?;
+                return Ok(exits::RUNTIME_ERROR);
+            }
+        };
+
+        let html = matches!(self.options.view.mode, Mode::Html(_));
+        if html {
+            html::write_start(&mut self.writer, "Index")?;
+        }
+        self.list_virtual(entries, None)?;
+        if html {
+            html::write_end(&mut self.writer)?;
+        }
+        self.writer.finish()?;
+        Ok(exits::SUCCESS)
+    }
+
+    /// Lists what’s been added, modified, or removed beneath the input
+    /// paths since the snapshot given to `--changed-since` was saved, in a
+    /// section for each, then saves a new snapshot for `--save-snapshot`.
+    /// Removed entries get listed with what the snapshot knew about them.
+    fn list_changes(mut self, path: &Path) -> io::Result<i32> {
+        let earlier = match Snapshot::load(path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                writeln!(io::stderr(), "{}: {e}", path.display())?;
+                return Ok(exits::RUNTIME_ERROR);
+            }
+        };
+        let snapshot = Snapshot::take(&self.input_paths, &self.options.filter);
+        let changes = snapshot.changes_since(&earlier);
+
+        let html = matches!(self.options.view.mode, Mode::Html(_));
+        if html {
+            html::write_start(&mut self.writer, "Changes")?;
+        }
+
+        // Everything that changed gets listed, so there’s nothing to
+        // recurse into.
+        let dir_action = self.options.dir_action;
+        self.options.dir_action = DirAction::AsFile;
+        let total_size = self.options.view.total_size;
+        let git = self.theme.ui.git.unwrap_or_default();
+        let mut first = true;
+
+        #[rustfmt::skip]
+        let sections = [
+            (Change::Added,    "Added",    git.new()),
+            (Change::Modified, "Modified", git.modified()),
+            (Change::Removed,  "Removed",  git.deleted()),
+        ];
+
+        for (section, heading, style) in sections {
+            let mut files = changes
+                .iter()
+                .filter(|(change, _, _)| *change == section)
+                .map(|&(change, name, entry)| {
+                    let location = snapshot.location(name).unwrap_or_else(|| name.into());
+                    if change == Change::Removed {
+                        File::from_manifest(entry.to_manifest(&location))
+                    } else {
+                        File::from_args(location, None, None, false, total_size, None)
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if files.is_empty() {
+                continue;
+            }
+            self.options.filter.sort_files(&mut files);
+
+            if !html {
+                if !first {
+                    writeln!(&mut self.writer)?;
+                }
+                writeln!(&mut self.writer, "{}:", style.paint(heading))?;
+            }
+            first = false;
+            self.print_files(None, files)?;
+        }
+
+        self.options.dir_action = dir_action;
+        if html {
+            html::write_end(&mut self.writer)?;
+        }
+        self.writer.finish()?;
+
+        if let Some(path) = &self.options.save_snapshot {
+            if let Err(e) = save_snapshot(&snapshot, path) {
+                writeln!(io::stderr(), "{e}")?;
+                return Ok(exits::RUNTIME_ERROR);
+            }
+        }
+
+        Ok(exits::SUCCESS)
+    }
+
+    /// Lists entries that aren’t on the local filesystem, sorted into the
+    /// directories their names lead through. A tree goes into those the
+    /// same way as real ones, and recursing lists each one in turn, with
+    /// the URL they came from, if any, at the start of their headers.
+    fn list_virtual(&mut self, entries: Vec<manifest::Entry>, url: Option<&str>) -> io::Result<()> {
+        let root = Dir::from_manifest(PathBuf::new(), manifest::nest(entries));
+
+        let recurse_opts = self.options.dir_action.recurse_options();
+        let Some(recurse_opts) = recurse_opts.filter(|r| !r.tree) else {
+            let files = self.children(&root);
+            return self.print_files(None, files);
+        };
+
+        let dirs = self.virtual_dirs(root, recurse_opts);
+        if recurse_opts.flat {
+            let mut files = Vec::new();
+            for dir in dirs
+                .iter()
+                .filter(|d| !recurse_opts.is_too_shallow(depth_of(d)))
+            {
+                for mut file in self.children(dir) {
+                    file.name = file.path.to_string_lossy().into();
+                    files.push(file);
+                }
+            }
+            return self.print_files(None, files);
+        }
+
+        let quote_style = self.options.view.file_style.quote_style;
+        let html = matches!(self.options.view.mode, Mode::Html(_));
+        let mut first = true;
+        for dir in dirs
+            .iter()
+            .filter(|d| !recurse_opts.is_too_shallow(depth_of(d)))
+        {
+            if !dir.path.as_os_str().is_empty() && !html {
+                if !first {
+                    writeln!(&mut self.writer)?;
+                }
+
+                let path = match url {
+                    Some(url) => format!("{}/{}", url.trim_end_matches('/'), dir.path.display()),
+                    None => dir.path.display().to_string(),
+                };
+                let mut bits = Vec::new();
+                escape(
+                    path,
+                    &mut bits,
+                    Style::default(),
+                    Style::default(),
+                    quote_style,
+                );
+                writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
+            }
+            first = false;
+
+            let files = self.children(dir);
+            self.print_files(Some(dir), files)?;
+        }
+
+        Ok(())
+    }
+
+    /// Every directory beneath some entries that aren’t on the local
+    /// filesystem, starting with the one they’re in, as deep as the
+    /// recursion goes, in the order they get listed.
+    fn virtual_dirs(&self, root: Dir, recurse_opts: RecurseOptions) -> Vec<Dir> {
+        let mut dirs = Vec::new();
+        let mut stack = vec![root];
+
+        while let Some(dir) = stack.pop() {
+            if !recurse_opts.is_too_deep(depth_of(&dir)) {
+                let subdirs = self
+                    .children(&dir)
+                    .iter()
+                    .filter(|f| f.is_directory() && !self.options.filter.is_pruned(f))
+                    .filter_map(|f| f.to_dir().ok())
+                    .collect::<Vec<_>>();
+                stack.extend(subdirs.into_iter().rev());
+            }
+            dirs.push(dir);
+        }
+
+        dirs
     }
 
     fn print_dirs(
@@ -316,16 +727,82 @@ impl<'args> Exa<'args> {
             file_style: file_name::Options { quote_style, .. },
             ..
         } = self.options.view;
-        for dir in dir_files {
+        let html = matches!(self.options.view.mode, Mode::Html(_));
+
+        // Directories get listed depth-first from a stack, rather than by
+        // recursing. Each subdirectory only gets read once it’s its turn to
+        // be listed, so however wide or deep the tree is, there’s only ever
+        // one directory being read at a time.
+        let mut stack: Vec<PendingDir> = dir_files
+            .into_iter()
+            .rev()
+            .map(PendingDir::Argument)
+            .collect();
+
+        // How deep the argument being listed is, for counting how many
+        // levels down the directories beneath it are.
+        let mut argument_depth = 0;
+
+        while let Some(pending) = stack.pop() {
+            let (dir, is_argument) = match pending {
+                PendingDir::Argument(dir) => {
+                    stack.push(PendingDir::EndOfArgument(dir.path.clone()));
+                    argument_depth = depth_of(&dir);
+                    (dir, true)
+                }
+                PendingDir::Subdirectory(path) => match Dir::read_dir(path.clone()) {
+                    Ok(dir) => (dir, false),
+                    Err(e)
+                        if e.kind() == ErrorKind::PermissionDenied
+                            && !self.options.verbose_errors =>
+                    {
+                        self.denied_dirs.push((path, e));
+                        continue;
+                    }
+                    Err(e) => {
+                        writeln!(io::stderr(), "{}: {}", path.display(), e)?;
+                        continue;
+                    }
+                },
+                PendingDir::EndOfArgument(path) => {
+                    self.report_denied_dirs(&path)?;
+                    continue;
+                }
+            };
+
+            // Directories with files too shallow to list still get recursed
+            // into, to reach the ones beneath them.
+            let depth = depth_of(&dir) - argument_depth;
+            if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
+                if !recurse_opts.flat && recurse_opts.is_too_shallow(depth + 1) {
+                    if !recurse_opts.is_too_deep(depth_of(&dir)) {
+                        let child_dirs = self.child_dirs(&self.children(&dir));
+                        stack.extend(child_dirs.into_iter().rev().map(PendingDir::Subdirectory));
+                    }
+                    continue;
+                }
+            }
+
+            // A directory that’s already been listed through another path
+            // doesn’t get listed again.
+            if let Some(dedupe) = &self.dedupe {
+                if !dedupe.first_listing(&dir.path) {
+                    continue;
+                }
+            }
+
             // Put a gap between directories, or between the list of files and
-            // the first directory.
-            if first {
+            // the first directory. HTML pages have sections instead.
+            if first || html {
                 first = false;
             } else {
                 writeln!(&mut self.writer)?;
             }
 
-            if !is_only_dir {
+            // A lone directory still gets a header to note the other
+            // arguments it was listed once for.
+            let aliases = self.aliases(&dir.path);
+            if !(html || is_only_dir && is_argument && aliases.is_empty()) {
                 let mut bits = Vec::new();
                 escape(
                     dir.path.display().to_string(),
@@ -334,56 +811,64 @@ impl<'args> Exa<'args> {
                     Style::default(),
                     quote_style,
                 );
+
+                if !aliases.is_empty() {
+                    bits.push(Style::default().paint(" (also "));
+                    for (index, alias) in aliases.iter().enumerate() {
+                        if index > 0 {
+                            bits.push(Style::default().paint(", "));
+                        }
+                        escape(
+                            alias.display().to_string(),
+                            &mut bits,
+                            Style::default(),
+                            Style::default(),
+                            quote_style,
+                        );
+                    }
+                    bits.push(Style::default().paint(")"));
+                }
                 writeln!(&mut self.writer, "{}:", ANSIStrings(&bits))?;
             }
 
-            let mut children = Vec::new();
-            let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
-            for file in dir.files(
-                self.options.filter.dot_filter,
-                self.git.as_ref(),
-                git_ignore,
-                self.options.view.deref_links,
-                self.options.view.total_size,
-            ) {
-                children.push(file);
-            }
-            let recursing = self.options.dir_action.recurse_options().is_some();
-            self.options
-                .filter
-                .filter_child_files(recursing, &mut children);
-            self.options.filter.sort_files(&mut children);
+            let mut children = self.children(&dir);
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
-                let depth = dir
-                    .path
-                    .components()
-                    .filter(|&c| c != Component::CurDir)
-                    .count()
-                    + 1;
-                let follow_links = self.options.view.follow_links;
-                if !recurse_opts.tree && !recurse_opts.is_too_deep(depth) {
-                    let mut child_dirs = Vec::new();
-                    for child_dir in children.iter().filter(|f| {
-                        (if follow_links {
-                            f.points_to_directory()
-                        } else {
-                            f.is_directory()
-                        }) && !f.is_all_all
-                    }) {
-                        match child_dir.to_dir() {
-                            Ok(d) => child_dirs.push(d),
-                            Err(e) => {
-                                writeln!(io::stderr(), "{}: {}", child_dir.path.display(), e)?;
+                if recurse_opts.flat {
+                    let subdirs = self.read_dirs_beneath(&dir, &children, recurse_opts)?;
+                    if recurse_opts.is_too_shallow(depth + 1) {
+                        children.clear();
+                    }
+
+                    for subdir in &subdirs {
+                        if recurse_opts.is_too_shallow(depth_of(subdir) - argument_depth + 1) {
+                            continue;
+                        }
+
+                        let prefix = subdir.path.strip_prefix(&dir.path).unwrap_or(&subdir.path);
+                        for mut file in self.children(subdir) {
+                            if !file.is_all_all {
+                                file.name = prefix.join(&file.name).to_string_lossy().into();
+                                children.push(file);
                             }
                         }
                     }
 
                     self.print_files(Some(&dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
-                        Ok(_) => (),
-                        Err(e) => return Err(e),
-                    }
+                    continue;
+                }
+
+                if !recurse_opts.tree && !recurse_opts.is_too_deep(depth_of(&dir)) {
+                    // The directories left out by --max-results aren’t
+                    // recursed into either.
+                    let shown = self
+                        .options
+                        .view
+                        .max_results
+                        .map_or(children.len(), |max| max.min(children.len()));
+                    let child_dirs = self.child_dirs(&children[..shown]);
+                    self.print_files(Some(&dir), children)?;
+                    stack.extend(child_dirs.into_iter().rev().map(PendingDir::Subdirectory));
                     continue;
                 }
             }
@@ -394,12 +879,222 @@ impl<'args> Exa<'args> {
         Ok(exit_status)
     }
 
+    /// Reads the files in a directory, leaving out the ones that get
+    /// filtered out, and sorts them.
+    fn children<'dir>(&self, dir: &'dir Dir) -> Vec<File<'dir>> {
+        self.children_where(dir, |_| true)
+    }
+
+    /// Reads the files in a directory that pass a test, leaving out the
+    /// ones that get filtered out, and sorts them. Files that fail the test
+    /// don’t get looked at any further.
+    fn children_where<'dir>(
+        &self,
+        dir: &'dir Dir,
+        test: impl Fn(&File<'dir>) -> bool,
+    ) -> Vec<File<'dir>> {
+        let git_ignore = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
+        let mut children = dir
+            .files(
+                self.options.filter.dot_filter,
+                self.git.as_ref(),
+                git_ignore,
+                self.options.view.deref_links.for_contents(),
+                self.options.view.total_size,
+            )
+            .filter(|f| test(f))
+            .collect::<Vec<_>>();
+
+        let recursing = self.options.dir_action.recurse_options().is_some();
+        self.options
+            .filter
+            .filter_child_files(recursing, &mut children);
+        self.options
+            .filter
+            .filter_git_files(self.git.as_ref(), &mut children);
+        self.options.filter.sort_files(&mut children);
+
+        for child in &mut children {
+            child.aliases = self.aliases(&child.path);
+        }
+        children
+    }
+
+    /// The arguments other than this path that lead to the same file, which
+    /// `--dedupe` notes next to it.
+    fn aliases(&self, path: &Path) -> Vec<PathBuf> {
+        self.dedupe
+            .as_ref()
+            .map(|dedupe| dedupe.aliases(path))
+            .unwrap_or_default()
+    }
+
+    /// The paths of the directories among some files that get recursed
+    /// into, which are all of them apart from any that get pruned.
+    fn child_dirs(&self, files: &[File<'_>]) -> Vec<PathBuf> {
+        let follow_links = self.options.view.follow_links;
+        files
+            .iter()
+            .filter(|f| {
+                (if follow_links {
+                    f.points_to_directory()
+                } else {
+                    f.is_directory()
+                }) && !f.is_all_all
+                    && !self.options.filter.is_pruned(f)
+            })
+            .map(|f| f.path.clone())
+            .collect()
+    }
+
+    /// Finds the files that are as many levels beneath the given directories
+    /// as the minimum depth, for `--tree` to start from, leaving out any
+    /// that the level limit doesn’t reach.
+    fn files_at_min_depth<'a>(
+        &mut self,
+        files: Vec<File<'a>>,
+        recurse_opts: RecurseOptions,
+    ) -> io::Result<Vec<File<'a>>> {
+        let mut found = Vec::new();
+
+        for file in files {
+            if !file.points_to_directory() || self.options.filter.is_pruned(&file) {
+                continue;
+            }
+
+            let mut stack = vec![(file.path, 0)];
+            while let Some((path, depth)) = stack.pop() {
+                if recurse_opts.is_too_deep(depth) {
+                    continue;
+                }
+
+                let dir = match Dir::read_dir(path.clone()) {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        writeln!(io::stderr(), "{}: {}", path.display(), e)?;
+                        continue;
+                    }
+                };
+
+                let children = self.children(&dir);
+                if recurse_opts.is_too_shallow(depth + 1) {
+                    let child_dirs = self.child_dirs(&children);
+                    stack.extend(child_dirs.into_iter().rev().map(|p| (p, depth + 1)));
+                } else {
+                    found.extend(children.into_iter().map(|f| f.path));
+                }
+            }
+        }
+
+        Ok(found
+            .into_iter()
+            .map(|path| {
+                File::from_args(
+                    path,
+                    None,
+                    None,
+                    self.options.view.deref_links.for_contents(),
+                    self.options.view.total_size,
+                    None,
+                )
+            })
+            .collect())
+    }
+
+    /// Reads every directory beneath the one given, for `--flat`, as deep
+    /// as the recursion goes. Directories that can’t be read get reported
+    /// the same way as when listing them one at a time.
+    fn read_dirs_beneath(
+        &mut self,
+        dir: &Dir,
+        children: &[File<'_>],
+        recurse_opts: RecurseOptions,
+    ) -> io::Result<Vec<Dir>> {
+        let mut dirs = Vec::new();
+        if recurse_opts.is_too_deep(depth_of(dir)) {
+            return Ok(dirs);
+        }
+
+        let mut stack = self.child_dirs(children);
+        stack.reverse();
+
+        while let Some(path) = stack.pop() {
+            let subdir = match Dir::read_dir(path.clone()) {
+                Ok(subdir) => subdir,
+                Err(e)
+                    if e.kind() == ErrorKind::PermissionDenied && !self.options.verbose_errors =>
+                {
+                    self.denied_dirs.push((path, e));
+                    continue;
+                }
+                Err(e) => {
+                    writeln!(io::stderr(), "{}: {}", path.display(), e)?;
+                    continue;
+                }
+            };
+
+            if let Some(dedupe) = &self.dedupe {
+                if !dedupe.first_listing(&subdir.path) {
+                    continue;
+                }
+            }
+
+            // Only the directories get looked at here, as the rest of the
+            // files get read when the directory’s contents are listed.
+            if !recurse_opts.is_too_deep(depth_of(&subdir)) {
+                let grandchildren =
+                    self.child_dirs(&self.children_where(&subdir, File::points_to_directory));
+                stack.extend(grandchildren.into_iter().rev());
+            }
+            dirs.push(subdir);
+        }
+
+        Ok(dirs)
+    }
+
+    /// Prints the directories beneath this argument that couldn’t be read
+    /// because of their permissions. A lone directory gets its error printed
+    /// as usual, but any more than that just get counted, so that recursing
+    /// through a tree full of them doesn’t flood the terminal.
+    fn report_denied_dirs(&mut self, path: &Path) -> io::Result<()> {
+        match &self.denied_dirs[..] {
+            [] => {}
+            [(child_path, e)] => {
+                writeln!(io::stderr(), "{}: {}", child_path.display(), e)?;
+            }
+            denied => {
+                writeln!(
+                    io::stderr(),
+                    "{}: {} directories could not be read: permission denied (use --verbose-errors to list them)",
+                    path.display(),
+                    denied.len()
+                )?;
+            }
+        }
+
+        self.denied_dirs.clear();
+        Ok(())
+    }
+
     /// Prints the list of files using whichever view is selected.
     fn print_files(&mut self, dir: Option<&Dir>, mut files: Vec<File<'_>>) -> io::Result<()> {
+        // An empty directory still gets its footer, saying so.
         if files.is_empty() {
+            if let (Some(size_format), Some(_)) = (self.options.view.total, dir) {
+                if !matches!(self.options.view.mode, Mode::Html(_)) {
+                    let summary = Summary::default().render(&self.theme, size_format);
+                    writeln!(&mut self.writer, "{}", ANSIStrings(&summary))?;
+                }
+            }
             return Ok(());
         }
-        let recursing = self.options.dir_action.recurse_options().is_some();
+        // A tree needs its directories to reach the files in them, so it
+        // leaves out the ones that lead to no files itself.
+        let recursing = self
+            .options
+            .dir_action
+            .recurse_options()
+            .is_some_and(|r| !r.tree);
         let only_files = self.options.filter.flags.contains(&OnlyFiles);
         if recursing && only_files {
             files = files
@@ -411,9 +1106,43 @@ impl<'args> Exa<'args> {
         let View {
             ref mode,
             ref file_style,
+            deref_links,
+            sections,
+            max_results,
+            total,
+            progress,
             ..
         } = self.options.view;
 
+        // The footer would end up after the end of an HTML page.
+        let summary = total
+            .filter(|_| dir.is_some() && !matches!(mode, Mode::Html(_)))
+            .map(|size_format| Summary::of(&files).render(theme, size_format));
+
+        // A directory’s listing gets cut short after the most entries it can
+        // have, with a note after it saying how many were left out.
+        let hidden = match max_results {
+            Some(max) if dir.is_some() && files.len() > max => {
+                let hidden = files.len() - max;
+                files.truncate(max);
+                hidden
+            }
+            _ => 0,
+        };
+        let overflow =
+            (hidden > 0 && !matches!(mode, Mode::Html(_))).then(|| summary::overflow(hidden));
+
+        // When paging, each table’s header row stays in view while the rest
+        // of its table gets scrolled through.
+        if let Mode::Details(details::Options { header: true, .. })
+        | Mode::GridDetails(grid_details::Options {
+            details: details::Options { header: true, .. },
+            ..
+        }) = mode
+        {
+            self.writer.mark_header();
+        }
+
         match (mode, self.console_width) {
             (Mode::Grid(ref opts), Some(console_width)) => {
                 let filter = &self.options.filter;
@@ -435,6 +1164,39 @@ impl<'args> Exa<'args> {
                     theme,
                     file_style,
                     filter,
+                    sections,
+                };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Template(ref opts), _) => {
+                let filter = &self.options.filter;
+                let r = template::Render {
+                    files,
+                    theme,
+                    file_style,
+                    opts,
+                    filter,
+                };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Html(ref opts), _) => {
+                let filter = &self.options.filter;
+                let git = self.git.as_ref();
+                let vcs = self.vcs.as_ref();
+                let git_repos = self.git_repos;
+                let r = html::Render {
+                    dir,
+                    files,
+                    theme,
+                    file_style,
+                    opts,
+                    filter,
+                    git,
+                    vcs,
+                    git_repos,
+                    base: &self.html_base,
                 };
                 r.render(&mut self.writer)
             }
@@ -445,6 +1207,7 @@ impl<'args> Exa<'args> {
 
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let vcs = self.vcs.as_ref();
                 let git_repos = self.git_repos;
                 let r = details::Render {
                     dir,
@@ -456,7 +1219,14 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    vcs,
                     git_repos,
+                    deref_links: deref_links.for_contents(),
+                    max_results,
+                    sections,
+                    progress,
+                    total,
+                    dedupe: self.dedupe.as_ref(),
                 };
                 r.render(&mut self.writer)
             }
@@ -464,10 +1234,13 @@ impl<'args> Exa<'args> {
             (Mode::GridDetails(ref opts), Some(console_width)) => {
                 let details = &opts.details;
                 let row_threshold = opts.row_threshold;
+                let direction = opts.direction();
+                let shrink = &opts.shrink;
 
                 let filter = &self.options.filter;
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let vcs = self.vcs.as_ref();
                 let git_repos = self.git_repos;
 
                 let r = grid_details::Render {
@@ -476,12 +1249,16 @@ impl<'args> Exa<'args> {
                     theme,
                     file_style,
                     details,
+                    direction,
                     filter,
                     row_threshold,
                     git_ignoring,
                     git,
+                    vcs,
                     console_width,
                     git_repos,
+                    shrink,
+                    progress,
                 };
                 r.render(&mut self.writer)
             }
@@ -492,6 +1269,7 @@ impl<'args> Exa<'args> {
                 let recurse = self.options.dir_action.recurse_options();
                 let git_ignoring = self.options.filter.git_ignore == GitIgnore::CheckAndIgnore;
                 let git = self.git.as_ref();
+                let vcs = self.vcs.as_ref();
                 let git_repos = self.git_repos;
 
                 let r = details::Render {
@@ -504,14 +1282,38 @@ impl<'args> Exa<'args> {
                     filter,
                     git_ignoring,
                     git,
+                    vcs,
                     git_repos,
+                    deref_links: deref_links.for_contents(),
+                    max_results,
+                    sections,
+                    progress,
+                    total,
+                    dedupe: self.dedupe.as_ref(),
                 };
                 r.render(&mut self.writer)
             }
+        }?;
+
+        if let Some(overflow) = overflow {
+            writeln!(&mut self.writer, "{overflow}")?;
+        }
+
+        if let Some(summary) = summary {
+            writeln!(&mut self.writer, "{}", ANSIStrings(&summary))?;
         }
+
+        Ok(())
     }
 }
 
+/// Saves a snapshot for `--save-snapshot`, saying where it failed to.
+fn save_snapshot(snapshot: &Snapshot, path: &Path) -> io::Result<()> {
+    snapshot
+        .save(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+}
+
 mod exits {
 
     /// Exit code for when exa runs OK.
@@ -4,6 +4,8 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use crate::output::icons::IconOverrides;
+use crate::output::table::XattrColumn;
 use crate::theme::ThemeFileType as FileType;
 use crate::theme::*;
 use nu_ansi_term::{Color, Style};
@@ -166,10 +168,10 @@ impl FromOverride<StyleOverride> for Style {
     fn from(value: StyleOverride, default: Self) -> Self {
         let mut style = default;
         if value.foreground.is_some() {
-            style.foreground = value.foreground;
+            style.foreground = value.foreground.map(reduce_colour);
         }
         if value.background.is_some() {
-            style.background = value.background;
+            style.background = value.background.map(reduce_colour);
         }
         if let Some(bold) = value.is_bold {
             style.is_bold = bold;
@@ -214,6 +216,12 @@ impl FromOverride<char> for char {
     }
 }
 
+impl FromOverride<u64> for u64 {
+    fn from(value: u64, _default: u64) -> u64 {
+        value
+    }
+}
+
 impl FromOverride<IconStyleOverride> for IconStyle {
     fn from(value: IconStyleOverride, default: Self) -> Self {
         IconStyle {
@@ -238,6 +246,21 @@ impl FromOverride<FileNameStyleOverride> for FileNameStyle {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct ColumnStyleOverride {
+    pub header_style: Option<StyleOverride>,
+    pub style: Option<StyleOverride>,
+}
+
+impl FromOverride<ColumnStyleOverride> for ColumnStyle {
+    fn from(value: ColumnStyleOverride, default: Self) -> Self {
+        ColumnStyle {
+            header_style: FromOverride::from(value.header_style, default.header_style),
+            style: FromOverride::from(value.style, default.style),
+        }
+    }
+}
+
 impl<R, S, T> FromOverride<HashMap<R, S>> for HashMap<R, T>
 where
     T: FromOverride<S>,
@@ -270,6 +293,16 @@ pub struct FileKindsOverride {
     pub special: Option<StyleOverride>,       // sp
     pub executable: Option<StyleOverride>,    // ex
     pub mount_point: Option<StyleOverride>,   // mp
+    pub encrypted: Option<StyleOverride>,     // ec
+    pub offline: Option<StyleOverride>,       // of
+    pub multi_hard_link: Option<StyleOverride>,       // mh
+    pub door: Option<StyleOverride>,                  // do
+    pub setuid: Option<StyleOverride>,                // su
+    pub setgid: Option<StyleOverride>,                // sg
+    pub capability: Option<StyleOverride>,            // ca
+    pub sticky_other_writable: Option<StyleOverride>, // tw
+    pub other_writable: Option<StyleOverride>,        // ow
+    pub sticky: Option<StyleOverride>,                // st
 }
 
 impl FromOverride<FileKindsOverride> for FileKinds {
@@ -285,6 +318,19 @@ impl FromOverride<FileKindsOverride> for FileKinds {
             special: FromOverride::from(value.special, default.special),
             executable: FromOverride::from(value.executable, default.executable),
             mount_point: FromOverride::from(value.mount_point, default.mount_point),
+            encrypted: FromOverride::from(value.encrypted, default.encrypted),
+            offline: FromOverride::from(value.offline, default.offline),
+            multi_hard_link: FromOverride::from(value.multi_hard_link, default.multi_hard_link),
+            door: FromOverride::from(value.door, default.door),
+            setuid: FromOverride::from(value.setuid, default.setuid),
+            setgid: FromOverride::from(value.setgid, default.setgid),
+            capability: FromOverride::from(value.capability, default.capability),
+            sticky_other_writable: FromOverride::from(
+                value.sticky_other_writable,
+                default.sticky_other_writable,
+            ),
+            other_writable: FromOverride::from(value.other_writable, default.other_writable),
+            sticky: FromOverride::from(value.sticky, default.sticky),
         }
     }
 }
@@ -357,6 +403,11 @@ pub struct SizeOverride {
     pub unit_mega: Option<StyleOverride>,    // sb um
     pub unit_giga: Option<StyleOverride>,    // sb ug
     pub unit_huge: Option<StyleOverride>,    // sb ut
+
+    pub threshold_kilo: Option<u64>,
+    pub threshold_mega: Option<u64>,
+    pub threshold_giga: Option<u64>,
+    pub threshold_huge: Option<u64>,
 }
 
 impl FromOverride<SizeOverride> for Size {
@@ -374,6 +425,10 @@ impl FromOverride<SizeOverride> for Size {
             unit_mega: FromOverride::from(value.unit_mega, default.unit_mega),
             unit_giga: FromOverride::from(value.unit_giga, default.unit_giga),
             unit_huge: FromOverride::from(value.unit_huge, default.unit_huge),
+            threshold_kilo: FromOverride::from(value.threshold_kilo, default.threshold_kilo),
+            threshold_mega: FromOverride::from(value.threshold_mega, default.threshold_mega),
+            threshold_giga: FromOverride::from(value.threshold_giga, default.threshold_giga),
+            threshold_huge: FromOverride::from(value.threshold_huge, default.threshold_huge),
         }
     }
 }
@@ -552,19 +607,32 @@ pub struct UiStylesOverride {
 
     pub punctuation:  Option<StyleOverride>,          // xx
     pub date:         Option<StyleOverride>,          // da
+    pub date_recent:  Option<StyleOverride>,          // dr
+    pub date_old:     Option<StyleOverride>,          // dO
     pub inode:        Option<StyleOverride>,          // in
     pub blocks:       Option<StyleOverride>,          // bl
     pub header:       Option<StyleOverride>,          // hd
     pub octal:        Option<StyleOverride>,          // oc
     pub flags:        Option<StyleOverride>,          // ff
+    pub file_flags:   Option<HashMap<String, StyleOverride>>,
+
+    pub date_threshold_recent: Option<u64>,
+    pub date_threshold_old:    Option<u64>,
 
     pub symlink_path:         Option<StyleOverride>,  // lp
     pub control_char:         Option<StyleOverride>,  // cc
     pub broken_symlink:       Option<StyleOverride>,  // or
     pub broken_path_overlay:  Option<StyleOverride>,  // bO
+    pub tree_branch:          Option<StyleOverride>,  // tb
+    pub link_arrow:           Option<StyleOverride>,  // la
+    pub permission_dash:      Option<StyleOverride>,  // pd
+    pub grid_separator:       Option<StyleOverride>,  // gs
+    pub error_note:           Option<StyleOverride>,  // en
 
     pub filenames: Option<HashMap<String, FileNameStyleOverride>>,
     pub extensions: Option<HashMap<String, FileNameStyleOverride>>,
+    pub globs: Option<HashMap<String, FileNameStyleOverride>>,
+    pub columns: Option<HashMap<String, ColumnStyleOverride>>,
 }
 
 impl FromOverride<UiStylesOverride> for UiStyles {
@@ -584,11 +652,22 @@ impl FromOverride<UiStylesOverride> for UiStyles {
 
             punctuation: FromOverride::from(value.punctuation, default.punctuation),
             date: FromOverride::from(value.date, default.date),
+            date_recent: FromOverride::from(value.date_recent, default.date_recent),
+            date_old: FromOverride::from(value.date_old, default.date_old),
             inode: FromOverride::from(value.inode, default.inode),
             blocks: FromOverride::from(value.blocks, default.blocks),
             header: FromOverride::from(value.header, default.header),
             octal: FromOverride::from(value.octal, default.octal),
             flags: FromOverride::from(value.flags, default.flags),
+            file_flags: FromOverride::from(value.file_flags, default.file_flags),
+            date_threshold_recent: FromOverride::from(
+                value.date_threshold_recent,
+                default.date_threshold_recent,
+            ),
+            date_threshold_old: FromOverride::from(
+                value.date_threshold_old,
+                default.date_threshold_old,
+            ),
 
             symlink_path: FromOverride::from(value.symlink_path, default.symlink_path),
             control_char: FromOverride::from(value.control_char, default.control_char),
@@ -597,22 +676,134 @@ impl FromOverride<UiStylesOverride> for UiStyles {
                 value.broken_path_overlay,
                 default.broken_path_overlay,
             ),
+            tree_branch: FromOverride::from(value.tree_branch, default.tree_branch),
+            link_arrow: FromOverride::from(value.link_arrow, default.link_arrow),
+            permission_dash: FromOverride::from(value.permission_dash, default.permission_dash),
+            grid_separator: FromOverride::from(value.grid_separator, default.grid_separator),
+            error_note: FromOverride::from(value.error_note, default.error_note),
 
             filenames: FromOverride::from(value.filenames, default.filenames),
             extensions: FromOverride::from(value.extensions, default.extensions),
+            globs: FromOverride::from(value.globs, default.globs),
+            columns: FromOverride::from(value.columns, default.columns),
+        }
+    }
+}
+
+/// A column declared in the theme file, under `xattr_columns`, which shows
+/// the value of an extended attribute.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct XattrColumnOverride {
+    pub attribute: String,
+    pub header: Option<String>,
+    pub style: Option<StyleOverride>,
+}
+
+impl From<XattrColumnOverride> for XattrColumn {
+    fn from(value: XattrColumnOverride) -> Self {
+        XattrColumn {
+            attribute: value.attribute,
+            header: value.header,
+            style: value
+                .style
+                .map(|style| FromOverride::from(style, Style::default())),
+        }
+    }
+}
+
+/// The parts of the theme file that aren’t styles, which have to be read
+/// even when there aren’t any colours.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ColumnsOverride {
+    pub xattr_columns: Option<Vec<XattrColumnOverride>>,
+}
+
+impl ColumnsOverride {
+    fn into_xattr_columns(self) -> Vec<XattrColumn> {
+        self.xattr_columns
+            .unwrap_or_default()
+            .into_iter()
+            .map(XattrColumn::from)
+            .collect()
+    }
+}
+
+/// The icon glyphs in the theme file, which get used whether or not there
+/// are colours, unlike the rest of the file names’ styles.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct IconsOverride {
+    pub filenames: Option<HashMap<String, FileNameStyleOverride>>,
+    pub extensions: Option<HashMap<String, FileNameStyleOverride>>,
+    pub globs: Option<HashMap<String, FileNameStyleOverride>>,
+}
+
+impl IconsOverride {
+    fn into_icon_overrides(self) -> IconOverrides {
+        let glyphs = |styles: Option<HashMap<String, FileNameStyleOverride>>| {
+            styles
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(key, style)| Some((key, style.icon?.glyph?)))
+                .collect::<Vec<_>>()
+        };
+
+        let mut icons = IconOverrides::default();
+        for (name, glyph) in glyphs(self.filenames) {
+            icons.add_filename(&name, glyph);
+        }
+        for (ext, glyph) in glyphs(self.extensions) {
+            icons.add_extension(&ext, glyph);
         }
+        for (pattern, glyph) in glyphs(self.globs) {
+            match glob::Pattern::new(&pattern) {
+                Ok(pat) => icons.add_glob(pat, glyph),
+                Err(e) => log::warn!("Couldn't parse glob pattern {pattern:?}: {e}"),
+            }
+        }
+        icons
     }
 }
+
 impl ThemeConfig {
     pub fn from_path(path: PathBuf) -> Self {
         ThemeConfig { location: path }
     }
-    pub fn to_theme(&self) -> Option<UiStyles> {
+    /// The styles in the theme file, on top of the default ones for a light
+    /// or dark background.
+    pub fn to_theme(&self, light: bool) -> Option<UiStyles> {
         let ui_styles_override: Option<UiStylesOverride> = {
             let file = std::fs::File::open(&self.location).ok()?;
-            serde_norway::from_reader(&file).ok()
+            // A mistake anywhere in the file means none of it gets used, so
+            // it’s worth saying where the mistake is.
+            serde_norway::from_reader(&file)
+                .map_err(|e| eprintln!("eza: Ignoring theme file {:?}: {e}", self.location))
+                .ok()
+        };
+        let mut default = UiStyles::default();
+        if light {
+            default.for_light_background();
+        }
+        FromOverride::from(ui_styles_override, Some(default))
+    }
+
+    /// The extended attribute columns declared in the theme file.
+    pub fn to_xattr_columns(&self) -> Vec<XattrColumn> {
+        let Ok(file) = std::fs::File::open(&self.location) else {
+            return Vec::new();
+        };
+        serde_norway::from_reader::<_, ColumnsOverride>(&file)
+            .unwrap_or_default()
+            .into_xattr_columns()
+    }
+
+    /// The icons picked for files in the theme file.
+    pub fn to_icon_overrides(&self) -> IconOverrides {
+        let Ok(file) = std::fs::File::open(&self.location) else {
+            return IconOverrides::default();
         };
-        FromOverride::from(ui_styles_override, Some(UiStyles::default()))
+        serde_norway::from_reader::<_, IconsOverride>(&file)
+            .unwrap_or_default()
+            .into_icon_overrides()
     }
 }
 
@@ -661,4 +852,76 @@ mod tests {
             assert_eq!(color_from_str(s), Some(Color::Fixed(*c)));
         }
     }
+
+    #[test]
+    fn parse_size_thresholds() {
+        let yaml = "size:\n  threshold_mega: 100000000\n";
+        let ui_styles_override: Option<UiStylesOverride> = serde_norway::from_str(yaml).ok();
+        let ui_styles: Option<UiStyles> =
+            FromOverride::from(ui_styles_override, Some(UiStyles::default()));
+
+        assert_eq!(
+            ui_styles.unwrap().size.unwrap().thresholds(),
+            [1 << 10, 100_000_000, 1 << 30, 1 << 40]
+        );
+    }
+
+    #[test]
+    fn parse_date_thresholds() {
+        let yaml = "date_old: {foreground: DarkGray}\ndate_threshold_old: 2592000\n";
+        let ui_styles_override: Option<UiStylesOverride> = serde_norway::from_str(yaml).ok();
+        let ui_styles: UiStyles =
+            FromOverride::from(ui_styles_override, Some(UiStyles::default())).unwrap();
+
+        assert_eq!(ui_styles.date_old, Some(Color::DarkGray.normal()));
+        assert_eq!(ui_styles.date_thresholds(), [86_400, 2_592_000]);
+    }
+
+    #[test]
+    fn parse_file_flags() {
+        let yaml =
+            "flags: {foreground: Yellow}\nfile_flags:\n  uchg: {foreground: Red, is_bold: true}\n";
+        let ui_styles_override: Option<UiStylesOverride> = serde_norway::from_str(yaml).ok();
+        let ui_styles: UiStyles =
+            FromOverride::from(ui_styles_override, Some(UiStyles::default())).unwrap();
+
+        assert_eq!(ui_styles.flags, Some(Color::Yellow.normal()));
+        let file_flags = ui_styles.file_flags.unwrap();
+        assert_eq!(file_flags.len(), 1);
+        assert_eq!(file_flags.get("uchg"), Some(&Color::Red.bold()));
+    }
+
+    #[test]
+    fn parse_xattr_columns() {
+        let yaml = "filekinds:\n  directory: {foreground: Blue}\nxattr_columns:\n  - attribute: user.project\n    header: PROJECT\n    style: {foreground: Cyan, is_bold: true}\n  - attribute: user.owner\n";
+        let columns = serde_norway::from_str::<ColumnsOverride>(yaml)
+            .unwrap()
+            .into_xattr_columns();
+
+        assert_eq!(
+            columns,
+            vec![
+                XattrColumn {
+                    attribute: "user.project".into(),
+                    header: Some("PROJECT".into()),
+                    style: Some(Color::Cyan.bold()),
+                },
+                XattrColumn {
+                    attribute: "user.owner".into(),
+                    header: None,
+                    style: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_no_xattr_columns() {
+        let yaml = "filekinds:\n  directory: {foreground: Blue}\n";
+        let columns = serde_norway::from_str::<ColumnsOverride>(yaml)
+            .unwrap()
+            .into_xattr_columns();
+
+        assert!(columns.is_empty());
+    }
 }
@@ -6,7 +6,7 @@
 // SPDX-License-Identifier: MIT
 //! Parsing the options for `DirAction`.
 
-use crate::options::parser::MatchedFlags;
+use crate::options::parser::{Arg, MatchedFlags};
 use crate::options::{flags, NumberSource, OptionsError};
 
 use crate::fs::dir_action::{DirAction, RecurseOptions};
@@ -20,6 +20,7 @@ impl DirAction {
         let recurse = matches.has(&flags::RECURSE)?;
         let as_file = matches.has(&flags::LIST_DIRS)?;
         let tree = matches.has(&flags::TREE)?;
+        let flat = matches.has(&flags::FLAT)?;
 
         if matches.is_strict() {
             // Early check for --level when it wouldn’t do anything
@@ -29,10 +30,20 @@ impl DirAction {
                     &flags::RECURSE,
                     &flags::TREE,
                 ));
+            } else if !recurse && !tree && matches.count(&flags::MIN_DEPTH) > 0 {
+                return Err(OptionsError::Useless2(
+                    &flags::MIN_DEPTH,
+                    &flags::RECURSE,
+                    &flags::TREE,
+                ));
             } else if recurse && as_file {
                 return Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS));
             } else if tree && as_file {
                 return Err(OptionsError::Conflict(&flags::TREE, &flags::LIST_DIRS));
+            } else if flat && tree {
+                return Err(OptionsError::Conflict(&flags::FLAT, &flags::TREE));
+            } else if flat && !recurse {
+                return Err(OptionsError::Useless(&flags::FLAT, false, &flags::RECURSE));
             }
         }
 
@@ -52,27 +63,38 @@ impl DirAction {
 
 impl RecurseOptions {
     /// Determine which files should be recursed into, based on the `--level`
-    /// flag’s value, and whether the `--tree` flag was passed, which was
-    /// determined earlier. The maximum level should be a number, and this
-    /// will fail with an `Err` if it isn’t.
+    /// and `--min-depth` flags’ values, and whether the `--tree` flag was
+    /// passed, which was determined earlier. The levels should be numbers,
+    /// and this will fail with an `Err` if they aren’t. A tree can’t be
+    /// flat, so the `--flat` flag only counts without one.
     pub fn deduce(matches: &MatchedFlags<'_>, tree: bool) -> Result<Self, OptionsError> {
-        if let Some(level) = matches.get(&flags::LEVEL)? {
-            let arg_str = level.to_string_lossy();
-            match arg_str.parse() {
-                Ok(l) => Ok(Self {
-                    tree,
-                    max_depth: Some(l),
-                }),
-                Err(e) => {
-                    let source = NumberSource::Arg(&flags::LEVEL);
-                    Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
-                }
+        let flat = !tree && matches.has(&flags::FLAT)?;
+        let max_depth = Self::deduce_depth(matches, &flags::LEVEL)?;
+        let min_depth = Self::deduce_depth(matches, &flags::MIN_DEPTH)?.unwrap_or(0);
+
+        Ok(Self {
+            tree,
+            max_depth,
+            flat,
+            min_depth,
+        })
+    }
+
+    fn deduce_depth(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Option<usize>, OptionsError> {
+        let Some(level) = matches.get(flag)? else {
+            return Ok(None);
+        };
+
+        let arg_str = level.to_string_lossy();
+        match arg_str.parse() {
+            Ok(l) => Ok(Some(l)),
+            Err(e) => {
+                let source = NumberSource::Arg(flag);
+                Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
             }
-        } else {
-            Ok(Self {
-                tree,
-                max_depth: None,
-            })
         }
     }
 }
@@ -96,6 +118,8 @@ mod test {
                     &flags::LIST_DIRS,
                     &flags::TREE,
                     &flags::LEVEL,
+                    &flags::FLAT,
+                    &flags::MIN_DEPTH,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
                     $type::deduce(mf, true)
@@ -115,26 +139,42 @@ mod test {
 
     // Recursing
     use self::DirAction::Recurse;
-    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4) })));
-    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5) })));
-    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666) })));
-    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118) })));
-    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
-    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(rec_short:       DirAction <- ["-R"];                           Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: false, min_depth: 0 })));
+    test!(rec_long:        DirAction <- ["--recurse"];                    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: false, min_depth: 0 })));
+    test!(rec_lim_short:   DirAction <- ["-RL4"];                         Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(4), flat: false, min_depth: 0 })));
+    test!(rec_lim_short_2: DirAction <- ["-RL=5"];                        Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(5), flat: false, min_depth: 0 })));
+    test!(rec_lim_long:    DirAction <- ["--recurse", "--level", "666"];  Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(666), flat: false, min_depth: 0 })));
+    test!(rec_lim_long_2:  DirAction <- ["--recurse", "--level=0118"];    Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(118), flat: false, min_depth: 0 })));
+    test!(tree:            DirAction <- ["--tree"];                       Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, flat: false, min_depth: 0 })));
+    test!(rec_tree:        DirAction <- ["--recurse", "--tree"];          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, flat: false, min_depth: 0 })));
+    test!(rec_short_tree:  DirAction <- ["-TR"];                          Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, flat: false, min_depth: 0 })));
 
     // Overriding --list-dirs, --recurse, and --tree
-    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None })));
-    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None })));
+    test!(dirs_recurse:    DirAction <- ["--list-dirs", "--recurse"];     Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: false, min_depth: 0 })));
+    test!(dirs_tree:       DirAction <- ["--list-dirs", "--tree"];        Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, flat: false, min_depth: 0 })));
     test!(just_level:      DirAction <- ["--level=4"];                    Last => Ok(DirAction::List));
 
     test!(dirs_recurse_2:  DirAction <- ["--list-dirs", "--recurse"]; Complain => Err(OptionsError::Conflict(&flags::RECURSE, &flags::LIST_DIRS)));
     test!(dirs_tree_2:     DirAction <- ["--list-dirs", "--tree"];    Complain => Err(OptionsError::Conflict(&flags::TREE,    &flags::LIST_DIRS)));
     test!(just_level_2:    DirAction <- ["--level=4"];                Complain => Err(OptionsError::Useless2(&flags::LEVEL, &flags::RECURSE, &flags::TREE)));
 
+    // Flattening
+    test!(flat:            DirAction <- ["-R", "--flat"];                 Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: true, min_depth: 0 })));
+    test!(flat_lim:        DirAction <- ["--flat", "-RL2"];               Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(2), flat: true, min_depth: 0 })));
+    test!(flat_tree:       DirAction <- ["-RT", "--flat"];                Last => Ok(Recurse(RecurseOptions { tree: true,  max_depth: None, flat: false, min_depth: 0 })));
+    test!(just_flat:       DirAction <- ["--flat"];                       Last => Ok(DirAction::List));
+
+    test!(flat_tree_2:     DirAction <- ["-RT", "--flat"];            Complain => Err(OptionsError::Conflict(&flags::FLAT, &flags::TREE)));
+    test!(just_flat_2:     DirAction <- ["--flat"];                   Complain => Err(OptionsError::Useless(&flags::FLAT, false, &flags::RECURSE)));
+
+    // Minimum depths
+    test!(min_depth:       DirAction <- ["-R", "--min-depth=2"];          Both => Ok(Recurse(RecurseOptions { tree: false, max_depth: None, flat: false, min_depth: 2 })));
+    test!(min_depth_tree:  DirAction <- ["-TL3", "--min-depth", "1"];     Both => Ok(Recurse(RecurseOptions { tree: true,  max_depth: Some(3), flat: false, min_depth: 1 })));
+    test!(min_depth_bad:   DirAction <- ["-R", "--min-depth=two"];        Both => Err(OptionsError::FailedParse(String::from("two"), NumberSource::Arg(&flags::MIN_DEPTH), "two".parse::<usize>().unwrap_err())));
+    test!(just_min_depth:  DirAction <- ["--min-depth=2"];                Last => Ok(DirAction::List));
+    test!(just_min_depth_2: DirAction <- ["--min-depth=2"];           Complain => Err(OptionsError::Useless2(&flags::MIN_DEPTH, &flags::RECURSE, &flags::TREE)));
+
     // Overriding levels
-    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7) })));
+    test!(overriding_1:    DirAction <- ["-RL=6", "-L=7"];                Last => Ok(Recurse(RecurseOptions { tree: false, max_depth: Some(7), flat: false, min_depth: 0 })));
     test!(overriding_2:    DirAction <- ["-RL=6", "-L=7"];            Complain => Err(OptionsError::Duplicate(Flag::Short(b'L'), Flag::Short(b'L'))));
 }
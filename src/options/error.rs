@@ -45,6 +45,18 @@ pub enum OptionsError {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A regex filter was given that failed to be parsed.
+    FailedRegex(String),
+
+    /// A format template was given that failed to be parsed.
+    FailedTemplate(String),
+
+    /// A custom timestamp format was given that failed to be parsed.
+    FailedTimeFormat(String),
+
+    /// A file named by an option couldn’t be read.
+    FailedRead(&'static Arg, String),
 }
 
 /// The source of a string that failed to be parsed as a number.
@@ -63,6 +75,12 @@ impl From<glob::PatternError> for OptionsError {
     }
 }
 
+impl From<regex::Error> for OptionsError {
+    fn from(error: regex::Error) -> Self {
+        Self::FailedRegex(error.to_string())
+    }
+}
+
 impl fmt::Display for NumberSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -102,6 +120,10 @@ impl fmt::Display for OptionsError {
             Self::TreeAllAll                 => write!(f, "Option --tree is useless given --all --all"),
             Self::FailedParse(s, n, e)       => write!(f, "Value {s:?} not valid for {n}: {e}"),
             Self::FailedGlobPattern(ref e)   => write!(f, "Failed to parse glob pattern: {e}"),
+            Self::FailedRegex(ref e)         => write!(f, "Failed to parse regex: {e}"),
+            Self::FailedTemplate(ref e)      => write!(f, "Failed to parse format template: {e}"),
+            Self::FailedTimeFormat(ref e)    => write!(f, "Failed to parse timestamp format: {e}"),
+            Self::FailedRead(a, ref e)       => write!(f, "Failed to read file for option {a}: {e}"),
         };
     }
 }
@@ -7,9 +7,12 @@
 use crate::options::parser::MatchedFlags;
 use crate::options::vars::{self, Vars};
 use crate::options::{flags, NumberSource, OptionsError};
+use crate::output::color_scale::ColorScaleOptions;
+use crate::output::icons::IconSet;
 
 use crate::output::file_name::{
-    Absolute, Classify, EmbedHyperlinks, Options, QuoteStyle, ShowIcons,
+    Absolute, Classify, EmbedHyperlinks, HardLinks, Options, QuoteStyle, ShortcutTargets,
+    ShowIcons, ShowNormalized, SocketInfo,
 };
 
 impl Options {
@@ -20,18 +23,32 @@ impl Options {
     ) -> Result<Self, OptionsError> {
         let classify = Classify::deduce(matches)?;
         let show_icons = ShowIcons::deduce(matches, vars)?;
+        let icon_set = IconSet::deduce(matches)?;
 
         let quote_style = QuoteStyle::deduce(matches)?;
-        let embed_hyperlinks = EmbedHyperlinks::deduce(matches)?;
+        let embed_hyperlinks = EmbedHyperlinks::deduce(matches, is_a_tty)?;
 
         let absolute = Absolute::deduce(matches)?;
+        let shortcut_targets = ShortcutTargets::deduce(matches)?;
+        let socket_info = SocketInfo::deduce(matches)?;
+        let hard_links = HardLinks::deduce(matches)?;
+        let show_normalized = ShowNormalized::deduce(matches)?;
+        let inline_errors = matches.has(&flags::INLINE_ERRORS)?;
+        let color_scale = ColorScaleOptions::deduce(matches, vars)?;
 
         Ok(Self {
             classify,
             show_icons,
+            icon_set,
             quote_style,
             embed_hyperlinks,
             absolute,
+            shortcut_targets,
+            socket_info,
+            hard_links,
+            show_normalized,
+            inline_errors,
+            color_scale,
             is_a_tty,
         })
     }
@@ -103,6 +120,22 @@ impl ShowIcons {
     }
 }
 
+impl IconSet {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::ICON_SET)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("nerd3") => Ok(Self::Nerd3),
+            Some("nerd2") => Ok(Self::Nerd2),
+            Some("unicode") => Ok(Self::Unicode),
+            Some("ascii") => Ok(Self::Ascii),
+            _ => Err(OptionsError::BadArgument(&flags::ICON_SET, word.into())),
+        }
+    }
+}
+
 impl QuoteStyle {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         if matches.has(&flags::NO_QUOTES)? {
@@ -114,13 +147,59 @@ impl QuoteStyle {
 }
 
 impl EmbedHyperlinks {
+    fn deduce(matches: &MatchedFlags<'_>, is_a_tty: bool) -> Result<Self, OptionsError> {
+        match matches.get(&flags::HYPERLINK)? {
+            Some(word) => match word.to_str() {
+                Some("always") => Ok(Self::On),
+                Some("auto" | "automatic") if is_a_tty => Ok(Self::On),
+                Some("auto" | "automatic" | "never") => Ok(Self::Off),
+                _ => Err(OptionsError::BadArgument(&flags::HYPERLINK, word.into())),
+            },
+            // No flag given, default to plain file names
+            None => Ok(Self::Off),
+        }
+    }
+}
+
+impl ShortcutTargets {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
-        let flagged = matches.has(&flags::HYPERLINK)?;
+        if matches.has(&flags::SHORTCUTS)? {
+            Ok(Self::Show)
+        } else {
+            Ok(Self::Hide)
+        }
+    }
+}
+
+impl SocketInfo {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if matches.has(&flags::SOCKET_INFO)? {
+            Ok(Self::Show)
+        } else {
+            Ok(Self::Hide)
+        }
+    }
+}
 
-        if flagged {
-            Ok(Self::On)
+impl HardLinks {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if matches.has(&flags::FIND_HARDLINKS)? {
+            Ok(Self::Show)
         } else {
-            Ok(Self::Off)
+            Ok(Self::Hide)
+        }
+    }
+}
+
+impl ShowNormalized {
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        match matches.get(&flags::NORMALIZED)? {
+            Some(word) => match word.to_str() {
+                Some("nfc") => Ok(Self::Nfc),
+                Some("fold") => Ok(Self::CaseFolded),
+                _ => Err(OptionsError::BadArgument(&flags::NORMALIZED, word.into())),
+            },
+            None => Ok(Self::Off),
         }
     }
 }
@@ -6,13 +6,21 @@
 // SPDX-License-Identifier: MIT
 //! Parsing the options for `FileFilter`.
 
+use crate::fs::fields as f;
 use crate::fs::filter::{
-    FileFilter, FileFilterFlags, GitIgnore, IgnorePatterns, SortCase, SortField,
+    EmptyFilter, ExtensionFilter, FileFilter, FileFilterFlags, GitIgnore, GitOnly, IgnorePatterns,
+    PermFilter, PermMatching, RegexFilter, SortCase, SortField, TimeFilter, TypeFilter,
 };
 use crate::fs::DotFilter;
+use crate::output::table::{TimeType, TimeTypes};
+use chrono::prelude::*;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::options::parser::MatchedFlags;
-use crate::options::{flags, OptionsError};
+use crate::options::parser::{Arg, MatchedFlags};
+use crate::options::{flags, NumberSource, OptionsError};
 
 impl FileFilter {
     /// Determines which of all the file filter options to use.
@@ -20,14 +28,34 @@ impl FileFilter {
         use FileFilterFlags as FFF;
         let mut filter_flags: Vec<FileFilterFlags> = vec![];
 
+        if matches.is_strict() && matches.has(&flags::ONLY_BROKEN_LINKS)? {
+            if matches.has(&flags::NO_SYMLINKS)? {
+                return Err(OptionsError::Conflict(
+                    &flags::ONLY_BROKEN_LINKS,
+                    &flags::NO_SYMLINKS,
+                ));
+            } else if matches.has(&flags::ONLY_DIRS)? {
+                return Err(OptionsError::Conflict(
+                    &flags::ONLY_BROKEN_LINKS,
+                    &flags::ONLY_DIRS,
+                ));
+            }
+        }
+
         for (has, flag) in &[
             (matches.has(&flags::REVERSE)?, FFF::Reverse),
             (matches.has(&flags::ONLY_DIRS)?, FFF::OnlyDirs),
             (matches.has(&flags::ONLY_FILES)?, FFF::OnlyFiles),
             (matches.has(&flags::NO_SYMLINKS)?, FFF::NoSymlinks),
             (matches.has(&flags::SHOW_SYMLINKS)?, FFF::ShowSymlinks),
+            (
+                matches.has(&flags::ONLY_BROKEN_LINKS)?,
+                FFF::OnlyBrokenLinks,
+            ),
             (matches.has(&flags::DIRS_LAST)?, FFF::ListDirsLast),
             (matches.has(&flags::DIRS_FIRST)?, FFF::ListDirsFirst),
+            (matches.has(&flags::DETERMINISTIC)?, FFF::BreakTies),
+            (matches.has(&flags::DEDUPE)?, FFF::Dedupe),
         ] {
             if *has {
                 filter_flags.push(flag.clone());
@@ -42,7 +70,16 @@ impl FileFilter {
             sort_field:       SortField::deduce(matches)?,
             dot_filter:       DotFilter::deduce(matches)?,
             ignore_patterns:  IgnorePatterns::deduce(matches)?,
+            prune_patterns:   IgnorePatterns::deduce_from(matches, &flags::PRUNE_GLOB)?,
+            regex:            RegexFilter::deduce(matches)?,
+            time_filter:      TimeFilter::deduce(matches)?,
+            type_filter:      TypeFilter::deduce(matches)?,
+            extension_filter: ExtensionFilter::deduce(matches)?,
+            empty_filter:     EmptyFilter::deduce(matches)?,
+            perm_filter:      PermFilter::deduce(matches)?,
             git_ignore:       GitIgnore::deduce(matches)?,
+            git_only:         GitOnly::deduce(matches)?,
+            ignore_files:     matches.has(&flags::IGNORE_FILES)?,
         });
     }
 }
@@ -89,13 +126,38 @@ impl SortField {
             "inode" => Self::FileInode,
             "type" => Self::FileType,
             "none" => Self::Unsorted,
+            "random" => Self::Random(Self::deduce_seed(matches)?),
             _ => {
                 return Err(OptionsError::BadArgument(&flags::SORT, word.into()));
             }
         };
 
+        if matches.is_strict()
+            && !matches!(field, Self::Random(_))
+            && matches.get(&flags::SEED)?.is_some()
+        {
+            return Err(OptionsError::Useless(&flags::SEED, false, &flags::SORT));
+        }
+
         Ok(field)
     }
+
+    /// Determines the seed to shuffle files with, from the `--seed` argument
+    /// if one is given, or the current time and process ID otherwise, so
+    /// each run gets a different order.
+    fn deduce_seed(matches: &MatchedFlags<'_>) -> Result<u64, OptionsError> {
+        if let Some(seed) = matches.get(&flags::SEED)? {
+            let seed = seed.to_string_lossy();
+            return seed.parse().map_err(|e| {
+                OptionsError::FailedParse(seed.to_string(), NumberSource::Arg(&flags::SEED), e)
+            });
+        }
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Ok(nanos ^ u64::from(std::process::id()))
+    }
 }
 
 // I’ve gone back and forth between whether to sort case-sensitively or
@@ -151,8 +213,19 @@ impl DotFilter {
         let all_count = matches.count(&flags::ALL);
         let has_almost_all = matches.has(&flags::ALMOST_ALL)?;
 
+        let no_windows_hidden = matches.has(&flags::NO_WINDOWS_HIDDEN)?;
+
         match (all_count, has_almost_all) {
+            (0, false) if no_windows_hidden => Ok(Self::JustFilesAndWindowsHidden),
             (0, false) => Ok(Self::JustFiles),
+            _ if no_windows_hidden && matches.is_strict() => {
+                let all = if all_count > 0 {
+                    &flags::ALL
+                } else {
+                    &flags::ALMOST_ALL
+                };
+                Err(OptionsError::Useless(&flags::NO_WINDOWS_HIDDEN, true, all))
+            }
 
             // either a single --all or at least one --almost-all is given
             (1, _) | (0, true) => Ok(Self::Dotfiles),
@@ -172,18 +245,58 @@ impl DotFilter {
 
 impl IgnorePatterns {
     /// Determines the set of glob patterns to use based on the
-    /// `--ignore-glob` argument’s value. This is a list of strings
-    /// separated by pipe (`|`) characters, given in any order.
+    /// `--ignore-glob` argument’s value, which is a list of strings
+    /// separated by pipe (`|`) characters, given in any order, along with
+    /// the patterns in the file named by `--exclude-from`.
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let globs = matches
+            .get(&flags::IGNORE_GLOB)?
+            .map(OsStr::to_string_lossy);
+
+        let excludes = match matches.get(&flags::EXCLUDE_FROM)? {
+            Some(path) => fs::read_to_string(path).map_err(|e| {
+                let error = format!("{}: {e}", Path::new(path).display());
+                OptionsError::FailedRead(&flags::EXCLUDE_FROM, error)
+            })?,
+            None => String::new(),
+        };
+
+        let inputs = globs
+            .iter()
+            .flat_map(|globs| globs.split('|'))
+            .chain(Self::exclude_lines(&excludes));
+        Self::parse_inputs(inputs)
+    }
+
+    /// Picks out the patterns in an `--exclude-from` file, which has one on
+    /// each line, skipping blank lines and comments starting with `#`.
+    fn exclude_lines(contents: &str) -> impl Iterator<Item = &str> {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    }
+
+    /// Determines the set of glob patterns to use based on the value of
+    /// the given argument, such as `--prune-glob`, which is written the
+    /// same way as `--ignore-glob`.
+    pub fn deduce_from(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Self, OptionsError> {
         // If there are no inputs, we return a set of patterns that doesn’t
         // match anything, rather than, say, `None`.
-        let Some(inputs) = matches.get(&flags::IGNORE_GLOB)? else {
+        let Some(inputs) = matches.get(flag)? else {
             return Ok(Self::empty());
         };
 
+        Self::parse_inputs(inputs.to_string_lossy().split('|'))
+    }
+
+    fn parse_inputs<'a, I: IntoIterator<Item = &'a str>>(inputs: I) -> Result<Self, OptionsError> {
         // Awkwardly, though, a glob pattern can be invalid, and we need to
         // deal with invalid patterns somehow.
-        let (patterns, mut errors) = Self::parse_from_iter(inputs.to_string_lossy().split('|'));
+        let (patterns, mut errors) = Self::parse_from_iter(inputs);
 
         // It can actually return more than one glob error,
         // but we only use one. (TODO)
@@ -194,6 +307,284 @@ impl IgnorePatterns {
     }
 }
 
+impl RegexFilter {
+    /// Determines the regex to filter files with from the `--regex` or
+    /// `--iregex` argument, the latter of which ignores case, and whether
+    /// to match it against whole paths from `--regex-full-path`.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let regex = matches.get(&flags::REGEX)?;
+        let iregex = matches.get(&flags::IREGEX)?;
+        let full_path = matches.has(&flags::REGEX_FULL_PATH)?;
+
+        if matches.is_strict() {
+            if regex.is_some() && iregex.is_some() {
+                return Err(OptionsError::Conflict(&flags::REGEX, &flags::IREGEX));
+            } else if full_path && regex.is_none() && iregex.is_none() {
+                return Err(OptionsError::Useless(
+                    &flags::REGEX_FULL_PATH,
+                    false,
+                    &flags::REGEX,
+                ));
+            }
+        }
+
+        let (pattern, ignore_case) = match (regex, iregex) {
+            (_, Some(pattern)) => (pattern, true),
+            (Some(pattern), None) => (pattern, false),
+            (None, None) => return Ok(None),
+        };
+
+        let filter = Self::new(&pattern.to_string_lossy(), ignore_case, full_path)?;
+        Ok(Some(filter))
+    }
+}
+
+impl TimeFilter {
+    /// Determines the limits on files’ timestamps from the `--newer-than`
+    /// and `--older-than` arguments. The timestamp they apply to is the one
+    /// picked with `--time`, or the first of them if several are shown.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let now = Utc::now().naive_utc();
+        let newer_than = deduce_limit(matches, &flags::NEWER_THAN, now)?;
+        let older_than = deduce_limit(matches, &flags::OLDER_THAN, now)?;
+
+        if newer_than.is_none() && older_than.is_none() {
+            return Ok(None);
+        }
+
+        let time_types = TimeTypes::deduce(matches)?;
+        let time_type = if time_types.modified {
+            TimeType::Modified
+        } else if time_types.changed {
+            TimeType::Changed
+        } else if time_types.accessed {
+            TimeType::Accessed
+        } else if time_types.created {
+            TimeType::Created
+        } else {
+            TimeType::Modified
+        };
+
+        Ok(Some(Self {
+            time_type,
+            newer_than,
+            older_than,
+        }))
+    }
+}
+
+/// Parses the value of `--newer-than` or `--older-than`, if it was given.
+fn deduce_limit(
+    matches: &MatchedFlags<'_>,
+    flag: &'static Arg,
+    now: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, OptionsError> {
+    let Some(word) = matches.get(flag)? else {
+        return Ok(None);
+    };
+
+    match word.to_str().and_then(|w| parse_time(w, now)) {
+        Some(time) => Ok(Some(time)),
+        None => Err(OptionsError::BadArgument(flag, word.into())),
+    }
+}
+
+/// Parses a time given on the command line, which is either a date, with
+/// or without a time of day, in local time, or how long ago it was as a
+/// number and a unit, such as `2 days` or `3h`. Returns the time in UTC,
+/// which is what files’ timestamps are in.
+fn parse_time(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let input = input.trim();
+
+    let local = if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        date.and_hms_opt(0, 0, 0)
+    } else {
+        [
+            "%Y-%m-%d %H:%M",
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%dT%H:%M:%S",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    };
+    if let Some(local) = local {
+        let time = Local.from_local_datetime(&local).earliest()?;
+        return Some(time.naive_utc());
+    }
+
+    let unit_start = input.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = input.split_at(unit_start);
+    let number: i64 = number.parse().ok()?;
+
+    #[rustfmt::skip]
+    let seconds = match unit.trim() {
+        "s" | "sec"  | "secs"  | "second" | "seconds"  => 1,
+        "m" | "min"  | "mins"  | "minute" | "minutes"  => 60,
+        "h" | "hour" | "hours"                         => 60 * 60,
+        "d" | "day"  | "days"                          => 24 * 60 * 60,
+        "w" | "week" | "weeks"                         => 7 * 24 * 60 * 60,
+        "y" | "year" | "years"                         => 365 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    let ago = chrono::Duration::try_seconds(number.checked_mul(seconds)?)?;
+    now.checked_sub_signed(ago)
+}
+
+impl TypeFilter {
+    /// Determines the kinds of file to list from the `--type` argument’s
+    /// value, which is a comma-separated list of the letters `find -type`
+    /// uses for them.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(input) = matches.get(&flags::TYPE)? else {
+            return Ok(None);
+        };
+
+        input
+            .to_string_lossy()
+            .split(',')
+            .map(|letter| match letter {
+                "f" => Ok(f::Type::File),
+                "d" => Ok(f::Type::Directory),
+                "l" => Ok(f::Type::Link),
+                "p" => Ok(f::Type::Pipe),
+                "s" => Ok(f::Type::Socket),
+                "b" => Ok(f::Type::BlockDevice),
+                "c" => Ok(f::Type::CharDevice),
+                _ => Err(OptionsError::BadArgument(&flags::TYPE, letter.into())),
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
+impl ExtensionFilter {
+    /// Determines the extensions of the files to list from the `--ext`
+    /// argument’s value, which is a comma-separated list of them.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(input) = matches.get(&flags::EXT)? else {
+            return Ok(None);
+        };
+
+        let input = input.to_string_lossy();
+        let extensions = input
+            .split(',')
+            .filter(|ext| !ext.is_empty())
+            .collect::<Vec<_>>();
+
+        if extensions.is_empty() {
+            return Err(OptionsError::BadArgument(
+                &flags::EXT,
+                input.as_ref().into(),
+            ));
+        }
+
+        Ok(Some(extensions.into_iter().collect()))
+    }
+}
+
+impl EmptyFilter {
+    /// Determines whether to list only empty files, with `--only-empty`, or
+    /// only ones that aren’t, with `--no-empty`. Whichever of the two comes
+    /// last wins.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let flag =
+            matches.has_where(|f| f.matches(&flags::ONLY_EMPTY) || f.matches(&flags::NO_EMPTY))?;
+
+        Ok(match flag {
+            Some(f) if f.matches(&flags::ONLY_EMPTY) => Some(Self::OnlyEmpty),
+            Some(f) if f.matches(&flags::NO_EMPTY) => Some(Self::NoEmpty),
+            _ => None,
+        })
+    }
+}
+
+impl PermFilter {
+    /// Determines the permissions files need to have from the `--perm`
+    /// argument’s value, which is a mode in octal, such as `644`, or in the
+    /// symbols `chmod` uses, such as `u=rw,go=r`. As with `find -perm`, a
+    /// `-` in front means files need all of the mode’s bits, and a `/` means
+    /// they need any of them.
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(input) = matches.get(&flags::PERM)? else {
+            return Ok(None);
+        };
+
+        input
+            .to_str()
+            .and_then(Self::parse)
+            .map(Some)
+            .ok_or_else(|| OptionsError::BadArgument(&flags::PERM, input.into()))
+    }
+
+    fn parse(input: &str) -> Option<Self> {
+        let (matching, mode) = if let Some(mode) = input.strip_prefix('-') {
+            (PermMatching::All, mode)
+        } else if let Some(mode) = input.strip_prefix('/') {
+            (PermMatching::Any, mode)
+        } else {
+            (PermMatching::Exact, input)
+        };
+
+        let mode = if !mode.is_empty() && mode.bytes().all(|b| matches!(b, b'0'..=b'7')) {
+            u32::from_str_radix(mode, 8).ok().filter(|m| *m <= 0o7777)?
+        } else {
+            Self::parse_symbolic(mode)?
+        };
+
+        Some(Self { mode, matching })
+    }
+
+    /// Parses a mode written as comma-separated clauses such as `g+w`, each
+    /// with the classes of user it applies to, then `=`, `+`, or `-`, then
+    /// the permissions. Starting from no bits set, as `find` does, `=` and
+    /// `+` set the permissions, and `-` unsets them.
+    fn parse_symbolic(mode: &str) -> Option<u32> {
+        let mut bits = 0;
+
+        for clause in mode.split(',') {
+            let op_index = clause.find(['=', '+', '-'])?;
+            let (who, rest) = clause.split_at(op_index);
+            let (op, perms) = rest.split_at(1);
+
+            // Without any classes, the permissions apply to everyone.
+            let who = if who.is_empty() { "a" } else { who };
+            let mut classes = 0;
+            let mut special = 0;
+            for c in who.chars() {
+                match c {
+                    'u' => (classes, special) = (classes | 0o700, special | 0o4000),
+                    'g' => (classes, special) = (classes | 0o070, special | 0o2000),
+                    'o' => (classes, special) = (classes | 0o007, special | 0o1000),
+                    'a' => (classes, special) = (0o777, 0o7000),
+                    _ => return None,
+                }
+            }
+
+            let mut clause_bits = 0;
+            for c in perms.chars() {
+                clause_bits |= match c {
+                    'r' => classes & 0o444,
+                    'w' => classes & 0o222,
+                    'x' => classes & 0o111,
+                    's' => special & 0o6000,
+                    't' => special & 0o1000,
+                    _ => return None,
+                };
+            }
+
+            match op {
+                "=" => bits = (bits & !(classes | special)) | clause_bits,
+                "+" => bits |= clause_bits,
+                _ => bits &= !clause_bits,
+            }
+        }
+
+        Some(bits)
+    }
+}
+
 impl GitIgnore {
     pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         if matches.has(&flags::GIT_IGNORE)? {
@@ -204,6 +595,20 @@ impl GitIgnore {
     }
 }
 
+impl GitOnly {
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::GIT_ONLY)? else {
+            return Ok(None);
+        };
+
+        match word.to_str() {
+            Some("tracked") => Ok(Some(Self::Tracked)),
+            Some("dirty") => Ok(Some(Self::Dirty)),
+            _ => Err(OptionsError::BadArgument(&flags::GIT_ONLY, word.into())),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -221,11 +626,35 @@ mod test {
 
                 static TEST_ARGS: &[&Arg] = &[
                     &flags::SORT,
+                    &flags::SEED,
                     &flags::ALL,
                     &flags::ALMOST_ALL,
+                    &flags::NO_WINDOWS_HIDDEN,
                     &flags::TREE,
                     &flags::IGNORE_GLOB,
+                    &flags::EXCLUDE_FROM,
+                    &flags::PRUNE_GLOB,
                     &flags::GIT_IGNORE,
+                    &flags::GIT_ONLY,
+                    &flags::REGEX,
+                    &flags::IREGEX,
+                    &flags::REGEX_FULL_PATH,
+                    &flags::NEWER_THAN,
+                    &flags::OLDER_THAN,
+                    &flags::TIME,
+                    &flags::MODIFIED,
+                    &flags::CHANGED,
+                    &flags::ACCESSED,
+                    &flags::CREATED,
+                    &flags::NO_TIME,
+                    &flags::TYPE,
+                    &flags::EXT,
+                    &flags::ONLY_DIRS,
+                    &flags::NO_SYMLINKS,
+                    &flags::ONLY_BROKEN_LINKS,
+                    &flags::ONLY_EMPTY,
+                    &flags::NO_EMPTY,
+                    &flags::PERM,
                 ];
                 for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
                     $type::deduce(mf)
@@ -257,6 +686,12 @@ mod test {
         test!(mix_hidden_lowercase:     SortField <- ["--sort", ".name"];  Both => Ok(SortField::NameMixHidden(SortCase::AaBbCc)));
         test!(mix_hidden_uppercase:     SortField <- ["--sort", ".Name"];  Both => Ok(SortField::NameMixHidden(SortCase::ABCabc)));
 
+        // Random
+        test!(random_seed:   SortField <- ["--sort=random", "--seed=42"];  Both => Ok(SortField::Random(42)));
+        test!(bad_seed:      SortField <- ["--sort=random", "--seed=a"];   Both => Err(OptionsError::FailedParse(String::from("a"), NumberSource::Arg(&flags::SEED), "a".parse::<u64>().unwrap_err())));
+        test!(useless_seed:  SortField <- ["--sort=size", "--seed=42"];    Complain => Err(OptionsError::Useless(&flags::SEED, false, &flags::SORT)));
+        test!(ignored_seed:  SortField <- ["--sort=size", "--seed=42"];    Last => Ok(SortField::Size));
+
         // Errors
         test!(error:         SortField <- ["--sort=colour"];   Both => Err(OptionsError::BadArgument(&flags::SORT, OsString::from("colour"))));
 
@@ -290,6 +725,12 @@ mod test {
         test!(almost_all:       DotFilter <- ["--almost-all"]; Both => Ok(DotFilter::Dotfiles));
         test!(almost_all_all:   DotFilter <- ["-Aa"];          Both => Ok(DotFilter::Dotfiles));
         test!(almost_all_all_2: DotFilter <- ["-Aaa"];         Both => Ok(DotFilter::DotfilesAndDots));
+
+        // --no-windows-hidden
+        test!(windows_hidden:   DotFilter <- ["--no-windows-hidden"];          Both => Ok(DotFilter::JustFilesAndWindowsHidden));
+        test!(windows_hidden_a: DotFilter <- ["--no-windows-hidden", "-a"];    Last => Ok(DotFilter::Dotfiles));
+        test!(windows_hidden_2: DotFilter <- ["--no-windows-hidden", "-a"];    Complain => Err(OptionsError::Useless(&flags::NO_WINDOWS_HIDDEN, true, &flags::ALL)));
+        test!(windows_hidden_3: DotFilter <- ["--no-windows-hidden", "-A"];    Complain => Err(OptionsError::Useless(&flags::NO_WINDOWS_HIDDEN, true, &flags::ALMOST_ALL)));
     }
 
     mod ignore_patterns {
@@ -311,6 +752,215 @@ mod test {
         test!(overridden_2: IgnorePatterns <- ["-I", "*.OGG", "-I*.MP3"];          Last => Ok(IgnorePatterns::from_iter(vec![ pat("*.MP3") ])));
         test!(overridden_3: IgnorePatterns <- ["-I=*.ogg",    "-I", "*.mp3"];  Complain => Err(OptionsError::Duplicate(Flag::Short(b'I'), Flag::Short(b'I'))));
         test!(overridden_4: IgnorePatterns <- ["-I", "*.OGG", "-I*.MP3"];      Complain => Err(OptionsError::Duplicate(Flag::Short(b'I'), Flag::Short(b'I'))));
+
+        #[test]
+        fn exclude_lines() {
+            let contents = "# build output\ntarget\n\n  *.log  \n#*.rs\r\nnode_modules/*\r\n";
+            let lines = IgnorePatterns::exclude_lines(contents).collect::<Vec<_>>();
+            assert_eq!(lines, vec!["target", "*.log", "node_modules/*"]);
+        }
+
+        #[test]
+        fn missing_exclude_file() {
+            use crate::options::test::parse_for_test;
+            use crate::options::test::Strictnesses::Both;
+
+            static TEST_ARGS: &[&Arg] = &[&flags::IGNORE_GLOB, &flags::EXCLUDE_FROM];
+            for result in parse_for_test(
+                &["--exclude-from=/nonexistent/excludes"],
+                TEST_ARGS,
+                Both,
+                IgnorePatterns::deduce,
+            ) {
+                assert!(matches!(
+                    result,
+                    Err(OptionsError::FailedRead(flag, _)) if flag == &flags::EXCLUDE_FROM
+                ));
+            }
+        }
+
+        #[test]
+        fn prune() {
+            use crate::options::test::parse_for_test;
+            use crate::options::test::Strictnesses::Both;
+
+            static TEST_ARGS: &[&Arg] = &[&flags::IGNORE_GLOB, &flags::PRUNE_GLOB];
+            let inputs = ["--prune-glob=node_modules|.git", "-I", "*.ogg"];
+            for result in parse_for_test(&inputs, TEST_ARGS, Both, |mf| {
+                IgnorePatterns::deduce_from(mf, &flags::PRUNE_GLOB)
+            }) {
+                let expected = IgnorePatterns::from_iter(vec![pat("node_modules"), pat(".git")]);
+                assert_eq!(result, Ok(expected));
+            }
+        }
+    }
+
+    mod regexes {
+        use super::*;
+
+        fn regex(pattern: &str, ignore_case: bool, full_path: bool) -> RegexFilter {
+            RegexFilter::new(pattern, ignore_case, full_path).unwrap()
+        }
+
+        test!(none:       RegexFilter <- [];                                             Both => Ok(None));
+        test!(one:        RegexFilter <- ["--regex", "^a.*z$"];                          Both => Ok(Some(regex("^a.*z$", false, false))));
+        test!(icase:      RegexFilter <- ["--iregex=\\.jpe?g$"];                       Both => Ok(Some(regex("\\.jpe?g$", true, false))));
+        test!(full_path:  RegexFilter <- ["--regex=^src/", "--regex-full-path"];         Both => Ok(Some(regex("^src/", false, true))));
+
+        // Errors
+        test!(invalid:    RegexFilter <- ["--regex=(unclosed"];                          Both => Err(OptionsError::from(RegexFilter::new("(unclosed", false, false).unwrap_err())));
+        test!(both:       RegexFilter <- ["--regex=a", "--iregex=b"];                Complain => Err(OptionsError::Conflict(&flags::REGEX, &flags::IREGEX)));
+        test!(both_2:     RegexFilter <- ["--regex=a", "--iregex=b"];                    Last => Ok(Some(regex("b", true, false))));
+        test!(useless:    RegexFilter <- ["--regex-full-path"];                      Complain => Err(OptionsError::Useless(&flags::REGEX_FULL_PATH, false, &flags::REGEX)));
+        test!(useless_2:  RegexFilter <- ["--regex-full-path"];                          Last => Ok(None));
+
+        // Overriding
+        test!(overridden:   RegexFilter <- ["--regex=a", "--regex=b"];                   Last => Ok(Some(regex("b", false, false))));
+        test!(overridden_2: RegexFilter <- ["--regex=a", "--regex=b"];               Complain => Err(OptionsError::Duplicate(Flag::Long("regex"), Flag::Long("regex"))));
+    }
+
+    mod time_filters {
+        use super::*;
+
+        fn ago(now: NaiveDateTime, seconds: i64) -> NaiveDateTime {
+            now - chrono::Duration::seconds(seconds)
+        }
+
+        fn local(input: &str) -> NaiveDateTime {
+            let time = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S").unwrap();
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .unwrap()
+                .naive_utc()
+        }
+
+        #[test]
+        fn durations() {
+            let now = Utc::now().naive_utc();
+            assert_eq!(parse_time("2 days", now), Some(ago(now, 2 * 86400)));
+            assert_eq!(parse_time("2d", now), Some(ago(now, 2 * 86400)));
+            assert_eq!(parse_time("1 hour", now), Some(ago(now, 3600)));
+            assert_eq!(parse_time("90min", now), Some(ago(now, 90 * 60)));
+            assert_eq!(parse_time("3 weeks", now), Some(ago(now, 21 * 86400)));
+            assert_eq!(parse_time("1y", now), Some(ago(now, 365 * 86400)));
+        }
+
+        #[test]
+        fn dates() {
+            let now = Utc::now().naive_utc();
+            assert_eq!(
+                parse_time("2024-01-01", now),
+                Some(local("2024-01-01 00:00:00"))
+            );
+            assert_eq!(
+                parse_time("2024-01-01 12:30", now),
+                Some(local("2024-01-01 12:30:00"))
+            );
+            assert_eq!(
+                parse_time("2024-01-01T12:30:15", now),
+                Some(local("2024-01-01 12:30:15"))
+            );
+        }
+
+        #[test]
+        fn invalid() {
+            let now = Utc::now().naive_utc();
+            assert_eq!(parse_time("", now), None);
+            assert_eq!(parse_time("2", now), None);
+            assert_eq!(parse_time("days", now), None);
+            assert_eq!(parse_time("2 fortnights", now), None);
+            assert_eq!(parse_time("2024-13-01", now), None);
+            assert_eq!(parse_time("99999999999999999 years", now), None);
+        }
+
+        test!(none:       TimeFilter <- [];                                      Both => Ok(None));
+        test!(bad:        TimeFilter <- ["--newer-than=soon"];                   Both => Err(OptionsError::BadArgument(&flags::NEWER_THAN, OsString::from("soon"))));
+        test!(modified:   TimeFilter <- ["--older-than=2024-01-01"];             Both => Ok(Some(TimeFilter { time_type: TimeType::Modified, newer_than: None, older_than: Some(local("2024-01-01 00:00:00")) })));
+        test!(accessed:   TimeFilter <- ["--older-than=2024-01-01", "-u"];       Both => Ok(Some(TimeFilter { time_type: TimeType::Accessed, newer_than: None, older_than: Some(local("2024-01-01 00:00:00")) })));
+        test!(created:    TimeFilter <- ["--older-than=2024-01-01", "--time=created"];  Both => Ok(Some(TimeFilter { time_type: TimeType::Created, newer_than: None, older_than: Some(local("2024-01-01 00:00:00")) })));
+        test!(several:    TimeFilter <- ["--older-than=2024-01-01", "-mu"];      Both => Ok(Some(TimeFilter { time_type: TimeType::Modified, newer_than: None, older_than: Some(local("2024-01-01 00:00:00")) })));
+    }
+
+    mod type_filters {
+        use super::*;
+        use std::iter::FromIterator;
+
+        test!(none:    TypeFilter <- [];                    Both => Ok(None));
+        test!(one:     TypeFilter <- ["--type", "l"];       Both => Ok(Some(TypeFilter::from_iter(vec![ f::Type::Link ]))));
+        test!(several: TypeFilter <- ["--type=f,d,p"];      Both => Ok(Some(TypeFilter::from_iter(vec![ f::Type::File, f::Type::Directory, f::Type::Pipe ]))));
+        test!(devices: TypeFilter <- ["--type=b,c,s"];      Both => Ok(Some(TypeFilter::from_iter(vec![ f::Type::BlockDevice, f::Type::CharDevice, f::Type::Socket ]))));
+
+        // Errors
+        test!(bad:     TypeFilter <- ["--type=f,x"];        Both => Err(OptionsError::BadArgument(&flags::TYPE, OsString::from("x"))));
+
+        // Overriding
+        test!(overridden:   TypeFilter <- ["--type=f", "--type=d"];  Last => Ok(Some(TypeFilter::from_iter(vec![ f::Type::Directory ]))));
+        test!(overridden_2: TypeFilter <- ["--type=f", "--type=d"];  Complain => Err(OptionsError::Duplicate(Flag::Long("type"), Flag::Long("type"))));
+    }
+
+    mod extension_filters {
+        use super::*;
+        use std::iter::FromIterator;
+
+        test!(none:     ExtensionFilter <- [];                   Both => Ok(None));
+        test!(one:      ExtensionFilter <- ["--ext", "rs"];      Both => Ok(Some(ExtensionFilter::from_iter(["rs"]))));
+        test!(several:  ExtensionFilter <- ["--ext=rs,toml,md"]; Both => Ok(Some(ExtensionFilter::from_iter(["rs", "toml", "md"]))));
+        test!(dotted:   ExtensionFilter <- ["--ext=.RS"];        Both => Ok(Some(ExtensionFilter::from_iter(["rs"]))));
+        test!(gaps:     ExtensionFilter <- ["--ext=rs,,md,"];    Both => Ok(Some(ExtensionFilter::from_iter(["rs", "md"]))));
+
+        // Errors
+        test!(empty:    ExtensionFilter <- ["--ext=,"];          Both => Err(OptionsError::BadArgument(&flags::EXT, OsString::from(","))));
+
+        // Overriding
+        test!(overridden:   ExtensionFilter <- ["--ext=rs", "--ext=md"];  Last => Ok(Some(ExtensionFilter::from_iter(["md"]))));
+        test!(overridden_2: ExtensionFilter <- ["--ext=rs", "--ext=md"];  Complain => Err(OptionsError::Duplicate(Flag::Long("ext"), Flag::Long("ext"))));
+    }
+
+    mod broken_links {
+        use super::*;
+
+        test!(no_symlinks:  FileFilter <- ["--only-broken-links", "--no-symlinks"];  Complain => Err(OptionsError::Conflict(&flags::ONLY_BROKEN_LINKS, &flags::NO_SYMLINKS)));
+        test!(only_dirs:    FileFilter <- ["--only-broken-links", "-D"];             Complain => Err(OptionsError::Conflict(&flags::ONLY_BROKEN_LINKS, &flags::ONLY_DIRS)));
+    }
+
+    mod empty_filters {
+        use super::*;
+
+        test!(none:         EmptyFilter <- [];                               Both => Ok(None));
+        test!(only_empty:   EmptyFilter <- ["--only-empty"];                 Both => Ok(Some(EmptyFilter::OnlyEmpty)));
+        test!(no_empty:     EmptyFilter <- ["--no-empty"];                   Both => Ok(Some(EmptyFilter::NoEmpty)));
+
+        // Overriding
+        test!(overridden:   EmptyFilter <- ["--only-empty", "--no-empty"];   Last => Ok(Some(EmptyFilter::NoEmpty)));
+        test!(overridden_2: EmptyFilter <- ["--no-empty", "--only-empty"];   Last => Ok(Some(EmptyFilter::OnlyEmpty)));
+        test!(overridden_3: EmptyFilter <- ["--only-empty", "--no-empty"];   Complain => Err(OptionsError::Duplicate(Flag::Long("only-empty"), Flag::Long("no-empty"))));
+    }
+
+    mod perm_filters {
+        use super::*;
+
+        fn perm(mode: u32, matching: PermMatching) -> PermFilter {
+            PermFilter { mode, matching }
+        }
+
+        test!(none:         PermFilter <- [];                       Both => Ok(None));
+        test!(exact:        PermFilter <- ["--perm=644"];           Both => Ok(Some(perm(0o644, PermMatching::Exact))));
+        test!(all:          PermFilter <- ["--perm=-002"];          Both => Ok(Some(perm(0o002, PermMatching::All))));
+        test!(any:          PermFilter <- ["--perm", "/4000"];      Both => Ok(Some(perm(0o4000, PermMatching::Any))));
+        test!(symbolic:     PermFilter <- ["--perm=u=rw,go=r"];     Both => Ok(Some(perm(0o644, PermMatching::Exact))));
+        test!(symbolic_all: PermFilter <- ["--perm=-o+w"];          Both => Ok(Some(perm(0o002, PermMatching::All))));
+        test!(everyone:     PermFilter <- ["--perm=/+x"];           Both => Ok(Some(perm(0o111, PermMatching::Any))));
+        test!(setuid:       PermFilter <- ["--perm=-u+s"];          Both => Ok(Some(perm(0o4000, PermMatching::All))));
+        test!(sticky:       PermFilter <- ["--perm=a=rwxt"];        Both => Ok(Some(perm(0o1777, PermMatching::Exact))));
+        test!(taken_away:   PermFilter <- ["--perm=a=rw,o-w"];      Both => Ok(Some(perm(0o664, PermMatching::Exact))));
+
+        // Errors
+        test!(too_big:      PermFilter <- ["--perm=17777"];         Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("17777"))));
+        test!(bad_class:    PermFilter <- ["--perm=z+w"];           Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("z+w"))));
+        test!(bad_perm:     PermFilter <- ["--perm=u+q"];           Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("u+q"))));
+        test!(no_op:        PermFilter <- ["--perm=rw"];            Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("rw"))));
+        test!(empty:        PermFilter <- ["--perm=-"];             Both => Err(OptionsError::BadArgument(&flags::PERM, OsString::from("-"))));
     }
 
     mod git_ignores {
@@ -319,4 +969,14 @@ mod test {
         test!(off:  GitIgnore <- [];                Both => Ok(GitIgnore::Off));
         test!(on:   GitIgnore <- ["--git-ignore"];  Both => Ok(GitIgnore::CheckAndIgnore));
     }
+
+    mod git_onlys {
+        use super::*;
+
+        test!(off:      GitOnly <- [];                       Both => Ok(None));
+        test!(tracked:  GitOnly <- ["--git-only"];           Both => Ok(Some(GitOnly::Tracked)));
+        test!(explicit: GitOnly <- ["--git-only=tracked"];   Both => Ok(Some(GitOnly::Tracked)));
+        test!(dirty:    GitOnly <- ["--git-only=dirty"];     Both => Ok(Some(GitOnly::Dirty)));
+        test!(unknown:  GitOnly <- ["--git-only=staged"];    Both => Err(OptionsError::BadArgument(&flags::GIT_ONLY, OsString::from("staged"))));
+    }
 }
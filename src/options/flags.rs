@@ -16,15 +16,42 @@ pub static ONE_LINE:     Arg = Arg { short: Some(b'1'), long: "oneline",
 pub static LONG:         Arg = Arg { short: Some(b'l'), long: "long",            takes_value: TakesValue::Forbidden };
 pub static GRID:         Arg = Arg { short: Some(b'G'), long: "grid",            takes_value: TakesValue::Forbidden };
 pub static ACROSS:       Arg = Arg { short: Some(b'x'), long: "across",          takes_value: TakesValue::Forbidden };
+pub static DOWN:         Arg = Arg { short: None,       long: "down",            takes_value: TakesValue::Forbidden };
+pub static GRID_GUTTER:      Arg = Arg { short: None, long: "grid-gutter",      takes_value: TakesValue::Necessary(None) };
+pub static GRID_MIN_COLUMNS: Arg = Arg { short: None, long: "grid-min-columns", takes_value: TakesValue::Necessary(None) };
+pub static GRID_MAX_COLUMNS: Arg = Arg { short: None, long: "grid-max-columns", takes_value: TakesValue::Necessary(None) };
+pub static GRID_SPREAD:      Arg = Arg { short: None, long: "grid-spread",      takes_value: TakesValue::Forbidden };
+pub static GRID_SHRINK:      Arg = Arg { short: None, long: "grid-shrink",      takes_value: TakesValue::Necessary(None) };
+pub static GRID_DETAILS_THRESHOLD: Arg = Arg { short: None, long: "grid-details-threshold", takes_value: TakesValue::Necessary(None) };
+pub static FORMAT:       Arg = Arg { short: None,       long: "format",          takes_value: TakesValue::Necessary(None) };
+pub static HTML:         Arg = Arg { short: None,       long: "html",            takes_value: TakesValue::Forbidden };
+pub static SECTIONS:     Arg = Arg { short: None,       long: "sections",        takes_value: TakesValue::Necessary(None) };
+pub static MAX_RESULTS:  Arg = Arg { short: None,       long: "max-results",     takes_value: TakesValue::Necessary(None) };
 pub static RECURSE:      Arg = Arg { short: Some(b'R'), long: "recurse",         takes_value: TakesValue::Forbidden };
 pub static TREE:         Arg = Arg { short: Some(b'T'), long: "tree",            takes_value: TakesValue::Forbidden };
+pub static FLAT:         Arg = Arg { short: None,       long: "flat",            takes_value: TakesValue::Forbidden };
+pub static TREE_STYLE:   Arg = Arg { short: None,       long: "tree-style",      takes_value: TakesValue::Necessary(Some(TREE_STYLES)) };
+pub static VERBOSE_ERRORS: Arg = Arg { short: None,     long: "verbose-errors",  takes_value: TakesValue::Forbidden };
+pub static INLINE_ERRORS: Arg = Arg { short: None,      long: "inline-errors",   takes_value: TakesValue::Forbidden };
+pub static PAGER:        Arg = Arg { short: None,       long: "pager",           takes_value: TakesValue::Forbidden };
+pub static DETERMINISTIC: Arg = Arg { short: None,      long: "deterministic",   takes_value: TakesValue::Forbidden };
+pub static CHANGED_SINCE: Arg = Arg { short: None,      long: "changed-since",   takes_value: TakesValue::Necessary(None) };
+pub static SAVE_SNAPSHOT: Arg = Arg { short: None,      long: "save-snapshot",   takes_value: TakesValue::Necessary(None) };
+pub static FROM_MANIFEST: Arg = Arg { short: None,      long: "from-manifest",   takes_value: TakesValue::Necessary(None) };
 pub static CLASSIFY:     Arg = Arg { short: Some(b'F'), long: "classify",        takes_value: TakesValue::Optional(Some(WHEN), "auto") };
 pub static DEREF_LINKS:  Arg = Arg { short: Some(b'X'), long: "dereference",     takes_value: TakesValue::Forbidden };
 pub static WIDTH:        Arg = Arg { short: Some(b'w'), long: "width",           takes_value: TakesValue::Necessary(None) };
 pub static NO_QUOTES:    Arg = Arg { short: None,       long: "no-quotes",       takes_value: TakesValue::Forbidden };
 pub static ABSOLUTE:     Arg = Arg { short: None,       long: "absolute",        takes_value: TakesValue::Optional(Some(ABSOLUTE_MODES), "on") };
+pub static SHORTCUTS:    Arg = Arg { short: None,       long: "shortcuts",       takes_value: TakesValue::Forbidden };
+pub static SOCKET_INFO:  Arg = Arg { short: None,       long: "socket-info",     takes_value: TakesValue::Forbidden };
+pub static FIND_HARDLINKS: Arg = Arg { short: None,     long: "find-hardlinks",  takes_value: TakesValue::Forbidden };
+pub static NORMALIZED:   Arg = Arg { short: None,       long: "normalized",      takes_value: TakesValue::Optional(Some(NORMAL_FORMS), "nfc") };
+pub static DEREF_ARGS:   Arg = Arg { short: None,       long: "dereference-command-line", takes_value: TakesValue::Forbidden };
 pub static FOLLOW_LINKS: Arg = Arg { short: None,       long: "follow-symlinks", takes_value: TakesValue::Forbidden };
 const ABSOLUTE_MODES: &[&str] = &["on", "follow", "off"];
+const NORMAL_FORMS: &[&str] = &["nfc", "fold"];
+const TREE_STYLES: &[&str] = &["unicode", "rounded", "ascii"];
 
 pub static COLOR:  Arg = Arg { short: None, long: "color",  takes_value: TakesValue::Optional(Some(WHEN), "auto") };
 pub static COLOUR: Arg = Arg { short: None, long: "colour", takes_value: TakesValue::Optional(Some(WHEN), "auto") };
@@ -34,82 +61,140 @@ pub static COLOR_SCALE:  Arg = Arg { short: None, long: "color-scale",  takes_va
 pub static COLOUR_SCALE: Arg = Arg { short: None, long: "colour-scale", takes_value: TakesValue::Optional(Some(SCALES), "all") };
 pub static COLOR_SCALE_MODE:  Arg = Arg { short: None, long: "color-scale-mode",  takes_value: TakesValue::Necessary(Some(COLOR_SCALE_MODES))};
 pub static COLOUR_SCALE_MODE: Arg = Arg { short: None, long: "colour-scale-mode", takes_value: TakesValue::Necessary(Some(COLOR_SCALE_MODES))};
+pub static EXPORT_LS_COLORS:  Arg = Arg { short: None, long: "export-ls-colors",  takes_value: TakesValue::Forbidden };
+pub static DUMP_THEME:        Arg = Arg { short: None, long: "dump-theme",        takes_value: TakesValue::Forbidden };
+pub static THEME:             Arg = Arg { short: None, long: "theme",             takes_value: TakesValue::Necessary(Some(THEMES)) };
 const SCALES: Values = &["all", "size", "age"];
 const COLOR_SCALE_MODES: Values = &["fixed", "gradient"];
+const THEMES: Values = &["light", "dark", "auto"];
 
 // filtering and sorting options
 pub static ALL:         Arg = Arg { short: Some(b'a'), long: "all",         takes_value: TakesValue::Forbidden };
 pub static ALMOST_ALL:  Arg = Arg { short: Some(b'A'), long: "almost-all",  takes_value: TakesValue::Forbidden };
+pub static NO_WINDOWS_HIDDEN: Arg = Arg { short: None, long: "no-windows-hidden", takes_value: TakesValue::Forbidden };
 pub static LIST_DIRS:   Arg = Arg { short: Some(b'd'), long: "list-dirs",   takes_value: TakesValue::Forbidden };
 pub static LEVEL:       Arg = Arg { short: Some(b'L'), long: "level",       takes_value: TakesValue::Necessary(None) };
+pub static MIN_DEPTH:   Arg = Arg { short: None,       long: "min-depth",   takes_value: TakesValue::Necessary(None) };
 pub static REVERSE:     Arg = Arg { short: Some(b'r'), long: "reverse",     takes_value: TakesValue::Forbidden };
 pub static SORT:        Arg = Arg { short: Some(b's'), long: "sort",        takes_value: TakesValue::Necessary(Some(SORTS)) };
+pub static SEED:        Arg = Arg { short: None,       long: "seed",        takes_value: TakesValue::Necessary(None) };
 pub static IGNORE_GLOB: Arg = Arg { short: Some(b'I'), long: "ignore-glob", takes_value: TakesValue::Necessary(None) };
+pub static EXCLUDE_FROM: Arg = Arg { short: None,      long: "exclude-from", takes_value: TakesValue::Necessary(None) };
+pub static PRUNE_GLOB:  Arg = Arg { short: None,       long: "prune-glob",  takes_value: TakesValue::Necessary(None) };
 pub static GIT_IGNORE:  Arg = Arg { short: None, long: "git-ignore",           takes_value: TakesValue::Forbidden };
+pub static GIT_ONLY:    Arg = Arg { short: None, long: "git-only",             takes_value: TakesValue::Optional(Some(GIT_ONLY_FILES), "tracked") };
+const GIT_ONLY_FILES: Values = &["tracked", "dirty"];
+pub static IGNORE_FILES: Arg = Arg { short: None, long: "ignore-files",        takes_value: TakesValue::Forbidden };
+pub static REGEX:       Arg = Arg { short: None, long: "regex",       takes_value: TakesValue::Necessary(None) };
+pub static IREGEX:      Arg = Arg { short: None, long: "iregex",      takes_value: TakesValue::Necessary(None) };
+pub static REGEX_FULL_PATH: Arg = Arg { short: None, long: "regex-full-path", takes_value: TakesValue::Forbidden };
+pub static NEWER_THAN:  Arg = Arg { short: None, long: "newer-than",  takes_value: TakesValue::Necessary(None) };
+pub static OLDER_THAN:  Arg = Arg { short: None, long: "older-than",  takes_value: TakesValue::Necessary(None) };
+pub static TYPE:        Arg = Arg { short: None, long: "type",        takes_value: TakesValue::Necessary(Some(TYPES)) };
+const TYPES: Values = &["f", "d", "l", "p", "s", "b", "c"];
+pub static EXT:         Arg = Arg { short: None, long: "ext",         takes_value: TakesValue::Necessary(None) };
 pub static DIRS_FIRST:  Arg = Arg { short: None, long: "group-directories-first",  takes_value: TakesValue::Forbidden };
 pub static DIRS_LAST:   Arg = Arg { short: None, long: "group-directories-last",  takes_value: TakesValue::Forbidden };
 pub static ONLY_DIRS:   Arg = Arg { short: Some(b'D'), long: "only-dirs", takes_value: TakesValue::Forbidden };
 pub static ONLY_FILES:  Arg = Arg { short: Some(b'f'), long: "only-files", takes_value: TakesValue::Forbidden };
 pub static NO_SYMLINKS: Arg = Arg { short: None,       long: "no-symlinks", takes_value: TakesValue::Forbidden };
 pub static SHOW_SYMLINKS: Arg = Arg { short: None,     long: "show-symlinks", takes_value: TakesValue::Forbidden };
+pub static ONLY_BROKEN_LINKS: Arg = Arg { short: None, long: "only-broken-links", takes_value: TakesValue::Forbidden };
+pub static ONLY_EMPTY:  Arg = Arg { short: None,       long: "only-empty",  takes_value: TakesValue::Forbidden };
+pub static NO_EMPTY:    Arg = Arg { short: None,       long: "no-empty",    takes_value: TakesValue::Forbidden };
+pub static PERM:        Arg = Arg { short: None,       long: "perm",        takes_value: TakesValue::Necessary(None) };
+pub static DEDUPE:      Arg = Arg { short: None,       long: "dedupe",      takes_value: TakesValue::Forbidden };
 const SORTS: Values = &[ "name", "Name", "size", "extension",
                          "Extension", "modified", "changed", "accessed",
-                         "created", "inode", "type", "none" ];
+                         "created", "inode", "type", "random", "none" ];
 
 // display options
 pub static BINARY:      Arg = Arg { short: Some(b'b'), long: "binary",      takes_value: TakesValue::Forbidden };
 pub static BYTES:       Arg = Arg { short: Some(b'B'), long: "bytes",       takes_value: TakesValue::Forbidden };
+pub static SIZE:        Arg = Arg { short: None,       long: "size",        takes_value: TakesValue::Necessary(Some(SIZES)) };
+pub static SIZE_UNIT:   Arg = Arg { short: None,       long: "size-unit",   takes_value: TakesValue::Necessary(None) };
 pub static GROUP:       Arg = Arg { short: Some(b'g'), long: "group",       takes_value: TakesValue::Forbidden };
 pub static NUMERIC:     Arg = Arg { short: Some(b'n'), long: "numeric",     takes_value: TakesValue::Forbidden };
 pub static HEADER:      Arg = Arg { short: Some(b'h'), long: "header",      takes_value: TakesValue::Forbidden };
+pub static HEADER_NAMES: Arg = Arg { short: None,       long: "header-names", takes_value: TakesValue::Necessary(None) };
 pub static ICONS:       Arg = Arg { short: None,       long: "icons",       takes_value: TakesValue::Optional(Some(WHEN), "auto")};
+pub static ICON_SET:    Arg = Arg { short: None,       long: "icon-set",    takes_value: TakesValue::Necessary(Some(ICON_SETS)) };
 pub static INODE:       Arg = Arg { short: Some(b'i'), long: "inode",       takes_value: TakesValue::Forbidden };
 pub static LINKS:       Arg = Arg { short: Some(b'H'), long: "links",       takes_value: TakesValue::Forbidden };
 pub static MODIFIED:    Arg = Arg { short: Some(b'm'), long: "modified",    takes_value: TakesValue::Forbidden };
 pub static CHANGED:     Arg = Arg { short: None,       long: "changed",     takes_value: TakesValue::Forbidden };
 pub static BLOCKSIZE:   Arg = Arg { short: Some(b'S'), long: "blocksize",   takes_value: TakesValue::Forbidden };
-pub static TOTAL_SIZE:  Arg = Arg { short: None,       long: "total-size",  takes_value: TakesValue::Forbidden };
+pub static TOTAL_SIZE:  Arg = Arg { short: None,       long: "total-size",  takes_value: TakesValue::Optional(Some(TOTAL_SIZES), "apparent") };
+pub static TOTAL_SIZE_MAX_DEPTH: Arg = Arg { short: None, long: "total-size-max-depth", takes_value: TakesValue::Necessary(None) };
+pub static TOTAL:       Arg = Arg { short: None,       long: "total",       takes_value: TakesValue::Forbidden };
+pub static LFS:         Arg = Arg { short: None,       long: "lfs",         takes_value: TakesValue::Forbidden };
 pub static TIME:        Arg = Arg { short: Some(b't'), long: "time",        takes_value: TakesValue::Necessary(Some(TIMES)) };
 pub static ACCESSED:    Arg = Arg { short: Some(b'u'), long: "accessed",    takes_value: TakesValue::Forbidden };
 pub static CREATED:     Arg = Arg { short: Some(b'U'), long: "created",     takes_value: TakesValue::Forbidden };
 pub static TIME_STYLE:  Arg = Arg { short: None,       long: "time-style",  takes_value: TakesValue::Necessary(Some(TIME_STYLES)) };
-pub static HYPERLINK:   Arg = Arg { short: None,       long: "hyperlink",   takes_value: TakesValue::Forbidden };
+pub static HYPERLINK:   Arg = Arg { short: None,       long: "hyperlink",   takes_value: TakesValue::Optional(Some(WHEN), "always") };
 pub static MOUNTS:      Arg = Arg { short: Some(b'M'), long: "mounts",      takes_value: TakesValue::Forbidden };
 pub static SMART_GROUP: Arg = Arg { short: None,       long: "smart-group", takes_value: TakesValue::Forbidden };
-const TIMES: Values = &["modified", "changed", "accessed", "created"];
+const TIMES: Values = &["modified", "changed", "accessed", "created", "git"];
 const TIME_STYLES: Values = &["default", "long-iso", "full-iso", "iso", "relative"];
+const SIZES: Values = &["apparent", "allocated", "both"];
+const ICON_SETS: Values = &["nerd3", "nerd2", "unicode", "ascii"];
+const TOTAL_SIZES: Values = &["apparent", "allocated"];
 
 // suppressing columns
 pub static NO_PERMISSIONS: Arg = Arg { short: None, long: "no-permissions", takes_value: TakesValue::Forbidden };
 pub static NO_FILESIZE: Arg = Arg { short: None, long: "no-filesize", takes_value: TakesValue::Forbidden };
 pub static NO_USER: Arg = Arg { short: None, long: "no-user", takes_value: TakesValue::Forbidden };
 pub static NO_TIME: Arg = Arg { short: None, long: "no-time", takes_value: TakesValue::Forbidden };
+pub static FIELDS: Arg = Arg { short: None, long: "fields", takes_value: TakesValue::Necessary(None) };
 
 // optional feature options
 pub static GIT:               Arg = Arg { short: None,       long: "git",                  takes_value: TakesValue::Forbidden };
+pub static GIT_LOG:           Arg = Arg { short: None,       long: "git-log",              takes_value: TakesValue::Forbidden };
+pub static GIT_AUTHOR:        Arg = Arg { short: None,       long: "git-author",           takes_value: TakesValue::Forbidden };
+pub static GIT_OWNER:         Arg = Arg { short: None,       long: "git-owner",            takes_value: TakesValue::Forbidden };
+pub static GIT_SINCE_TAG:     Arg = Arg { short: None,       long: "git-since-tag",        takes_value: TakesValue::Forbidden };
 pub static NO_GIT:            Arg = Arg { short: None,       long: "no-git",               takes_value: TakesValue::Forbidden };
 pub static GIT_REPOS:         Arg = Arg { short: None,       long: "git-repos",            takes_value: TakesValue::Forbidden };
 pub static GIT_REPOS_NO_STAT: Arg = Arg { short: None,       long: "git-repos-no-status",  takes_value: TakesValue::Forbidden };
+pub static VCS_STATUS:        Arg = Arg { short: None,       long: "vcs-status",           takes_value: TakesValue::Forbidden };
 pub static EXTENDED:          Arg = Arg { short: Some(b'@'), long: "extended",             takes_value: TakesValue::Forbidden };
 pub static OCTAL:             Arg = Arg { short: Some(b'o'), long: "octal-permissions",    takes_value: TakesValue::Forbidden };
 pub static SECURITY_CONTEXT:  Arg = Arg { short: Some(b'Z'), long: "context",              takes_value: TakesValue::Forbidden };
+pub static XATTRS:            Arg = Arg { short: None,       long: "xattrs",               takes_value: TakesValue::Necessary(Some(XATTR_STYLES)) };
+pub static ACL:               Arg = Arg { short: None,       long: "acl",                  takes_value: TakesValue::Forbidden };
+pub static STREAMS:           Arg = Arg { short: None,       long: "streams",              takes_value: TakesValue::Forbidden };
 pub static STDIN:             Arg = Arg { short: None,       long: "stdin",                takes_value: TakesValue::Forbidden };
 pub static FILE_FLAGS:        Arg = Arg { short: Some(b'O'), long: "flags",                takes_value: TakesValue::Forbidden };
+pub static FILE_FLAGS_ALIAS:  Arg = Arg { short: None,       long: "file-flags",           takes_value: TakesValue::Forbidden };
+pub static FILE_VERSION:      Arg = Arg { short: None,       long: "file-version",         takes_value: TakesValue::Forbidden };
+pub static ACCESS_RIGHTS:     Arg = Arg { short: None,       long: "access-rights",        takes_value: TakesValue::Forbidden };
+pub static COW:               Arg = Arg { short: None,       long: "cow",                  takes_value: TakesValue::Forbidden };
+pub static PROJECT_ID:        Arg = Arg { short: None,       long: "project-id",           takes_value: TakesValue::Forbidden };
+pub static STORAGE_CLASS:     Arg = Arg { short: None,       long: "storage-class",        takes_value: TakesValue::Forbidden };
+pub static MIME:              Arg = Arg { short: None,       long: "mime",                 takes_value: TakesValue::Forbidden };
+pub static HASH:              Arg = Arg { short: None,       long: "hash",                 takes_value: TakesValue::Forbidden };
+pub static IMAGE_INFO:        Arg = Arg { short: None,       long: "image-info",           takes_value: TakesValue::Forbidden };
+pub static BINARY_INFO:       Arg = Arg { short: None,       long: "binary-info",          takes_value: TakesValue::Forbidden };
+pub static PREVIEW:           Arg = Arg { short: None,       long: "preview",              takes_value: TakesValue::Optional(None, "40") };
+pub static LINK_TARGET_DETAILS: Arg = Arg { short: None,     long: "link-target-details",  takes_value: TakesValue::Forbidden };
+const XATTR_STYLES: Values = &["names", "values"];
 
 pub static ALL_ARGS: Args = Args(&[
     &VERSION, &HELP,
 
-    &ONE_LINE, &LONG, &GRID, &ACROSS, &RECURSE, &TREE, &CLASSIFY, &DEREF_LINKS, &FOLLOW_LINKS,
-    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &COLOR_SCALE_MODE, &COLOUR_SCALE_MODE,
-    &WIDTH, &NO_QUOTES, &ABSOLUTE,
+    &ONE_LINE, &LONG, &GRID, &ACROSS, &DOWN, &RECURSE, &TREE, &TREE_STYLE, &FLAT, &VERBOSE_ERRORS, &INLINE_ERRORS, &PAGER, &DETERMINISTIC, &CHANGED_SINCE, &SAVE_SNAPSHOT, &FROM_MANIFEST, &CLASSIFY, &DEREF_LINKS, &DEREF_ARGS, &FOLLOW_LINKS,
+    &COLOR, &COLOUR, &COLOR_SCALE, &COLOUR_SCALE, &COLOR_SCALE_MODE, &COLOUR_SCALE_MODE, &EXPORT_LS_COLORS, &DUMP_THEME, &THEME,
+    &WIDTH, &NO_QUOTES, &ABSOLUTE, &SHORTCUTS, &SOCKET_INFO, &FIND_HARDLINKS, &NORMALIZED, &GRID_GUTTER, &GRID_MIN_COLUMNS, &GRID_MAX_COLUMNS, &GRID_SPREAD, &GRID_SHRINK, &GRID_DETAILS_THRESHOLD, &SECTIONS, &MAX_RESULTS,
+    &FORMAT, &HTML,
 
-    &ALL, &ALMOST_ALL, &LIST_DIRS, &LEVEL, &REVERSE, &SORT, &DIRS_FIRST, &DIRS_LAST,
-    &IGNORE_GLOB, &GIT_IGNORE, &ONLY_DIRS, &ONLY_FILES,
+    &ALL, &ALMOST_ALL, &NO_WINDOWS_HIDDEN, &LIST_DIRS, &LEVEL, &MIN_DEPTH, &REVERSE, &SORT, &SEED, &DIRS_FIRST, &DIRS_LAST,
+    &IGNORE_GLOB, &EXCLUDE_FROM, &PRUNE_GLOB, &GIT_IGNORE, &GIT_ONLY, &IGNORE_FILES, &REGEX, &IREGEX, &REGEX_FULL_PATH, &NEWER_THAN, &OLDER_THAN, &TYPE, &EXT, &ONLY_DIRS, &ONLY_FILES, &ONLY_BROKEN_LINKS, &ONLY_EMPTY, &NO_EMPTY, &PERM, &DEDUPE,
 
-    &BINARY, &BYTES, &GROUP, &NUMERIC, &HEADER, &ICONS, &INODE, &LINKS, &MODIFIED, &CHANGED,
-    &BLOCKSIZE, &TOTAL_SIZE, &TIME, &ACCESSED, &CREATED, &TIME_STYLE, &HYPERLINK, &MOUNTS,
-    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &SMART_GROUP, &NO_SYMLINKS, &SHOW_SYMLINKS,
+    &BINARY, &BYTES, &SIZE, &SIZE_UNIT, &GROUP, &NUMERIC, &HEADER, &HEADER_NAMES, &ICONS, &ICON_SET, &INODE, &LINKS, &MODIFIED, &CHANGED,
+    &BLOCKSIZE, &TOTAL_SIZE, &TOTAL_SIZE_MAX_DEPTH, &TOTAL, &LFS, &TIME, &ACCESSED, &CREATED, &TIME_STYLE, &HYPERLINK, &MOUNTS,
+    &NO_PERMISSIONS, &NO_FILESIZE, &NO_USER, &NO_TIME, &FIELDS, &SMART_GROUP, &NO_SYMLINKS, &SHOW_SYMLINKS,
 
-    &GIT, &NO_GIT, &GIT_REPOS, &GIT_REPOS_NO_STAT,
-    &EXTENDED, &OCTAL, &SECURITY_CONTEXT, &STDIN, &FILE_FLAGS
+    &GIT, &GIT_LOG, &GIT_AUTHOR, &GIT_OWNER, &GIT_SINCE_TAG, &NO_GIT, &GIT_REPOS, &GIT_REPOS_NO_STAT, &VCS_STATUS,
+    &EXTENDED, &XATTRS, &OCTAL, &SECURITY_CONTEXT, &ACL, &STREAMS, &STDIN, &FILE_FLAGS, &FILE_FLAGS_ALIAS, &FILE_VERSION, &ACCESS_RIGHTS, &COW, &PROJECT_ID, &STORAGE_CLASS, &MIME, &HASH, &IMAGE_INFO, &BINARY_INFO, &PREVIEW, &LINK_TARGET_DETAILS
 ]);
@@ -6,7 +6,7 @@
 // SPDX-License-Identifier: MIT
 use std::fmt;
 
-use crate::fs::feature::xattr;
+use crate::fs::feature::{acl, streams, xattr};
 use crate::options::flags;
 use crate::options::parser::MatchedFlags;
 
@@ -22,17 +22,48 @@ DISPLAY OPTIONS
   -l, --long                 display extended file metadata as a table
   -G, --grid                 display entries as a grid (default)
   -x, --across               sort the grid across, rather than downwards
+  --down                     sort the grid downwards (default)
+  --grid-gutter COLS         set the number of spaces between grid columns
+  --grid-min-columns COUNT   lay the grid out in at least this many columns
+  --grid-max-columns COUNT   lay the grid out in at most this many columns
+  --grid-spread              spread leftover width across the grid's gutters
+  --grid-shrink FIELDS       columns to drop from a long grid so more fit across
+  --grid-details-threshold ROWS
+                             only use a long grid if it has at least ROWS rows
+  --format TEMPLATE          display each entry using a custom template
+  --html                     display entries as a self-contained HTML table
+  --sections COUNT           split listings of more than COUNT entries by letter
+  --total                    print the number and size of entries after each directory
+  --max-results COUNT        list at most COUNT entries from each directory
   -R, --recurse              recurse into directories
   -T, --tree                 recurse into directories as a tree
+  --tree-style STYLE         which characters to draw trees with (unicode, rounded, ascii)
+  --flat                     list everything beneath a directory together when recursing
+  --verbose-errors           list every unreadable directory when recursing
+  --inline-errors            show entries that can't be read with an error note
+  --pager                    page long listings, keeping the header row in view
+  --deterministic            give the same output on every computer and terminal
+  --changed-since SNAPSHOT   list only what's changed since a saved snapshot
+  --save-snapshot FILE       save a snapshot of the files to compare against later
+  --from-manifest FILE       list the entries in a JSON or CSV manifest instead
   -X, --dereference          dereference symbolic links when displaying information
+  --dereference-command-line dereference only symbolic links given as arguments
   -F, --classify=WHEN        display type indicator by file names (always, auto, never)
   --colo[u]r=WHEN            when to use terminal colours (always, auto, never)
   --colo[u]r-scale           highlight levels of 'field' distinctly(all, age, size)
   --colo[u]r-scale-mode      use gradient or fixed colors in --color-scale (fixed, gradient)
+  --export-ls-colors         print the colour theme as an LS_COLORS string
+  --dump-theme               print every style in the theme and where it came from
+  --theme=BACKGROUND         pick colours for the terminal's background (light, dark, auto)
   --icons=WHEN               when to display icons (always, auto, never)
+  --icon-set=SET             which icons to use (nerd3, nerd2, unicode, ascii)
   --no-quotes                don't quote file names with spaces
-  --hyperlink                display entries as hyperlinks
+  --hyperlink=WHEN           display entries as hyperlinks (always, auto, never)
   --absolute                 display entries with their absolute path (on, follow, off)
+  --shortcuts                show the targets of desktop entries and shortcuts
+  --socket-info              show what has sockets and named pipes open
+  --find-hardlinks           show which earlier file each hard link is the same as
+  --normalized=FORM          show how names would look normalized (nfc, fold)
   --follow-symlinks          drill down into symbolic links that point to directories
   -w, --width COLS           set screen width in columns
 
@@ -41,39 +72,74 @@ FILTERING AND SORTING OPTIONS
   -a, --all                  show hidden and 'dot' files. Use this twice to also
                              show the '.' and '..' directories
   -A, --almost-all           equivalent to --all; included for compatibility with `ls -A`
+  --no-windows-hidden        show files with the hidden attribute without --all (Windows only)
   -d, --list-dirs            list directories as files; don't list their contents
   -D, --only-dirs            list only directories
   -f, --only-files           list only files
   --show-symlinks            explicitly show symbolic links (for use with --only-dirs | --only-files)
   --no-symlinks              do not show symbolic links
+  --only-broken-links        list only symbolic links whose targets don't exist
+  --only-empty               list only empty files and directories
+  --no-empty                 list only files and directories that aren't empty
+  --dedupe                   list what several arguments lead to only once
   -L, --level DEPTH          limit the depth of recursion
+  --min-depth DEPTH          only list files at least this deep when recursing
   -r, --reverse              reverse the sort order
   -s, --sort SORT_FIELD      which field to sort by
+  --seed NUMBER              seed to shuffle files with for '--sort=random'
   --group-directories-first  list directories before other files
   --group-directories-last   list directories after other files
-  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore";
+  -I, --ignore-glob GLOBS    glob patterns (pipe-separated) of files to ignore
+  --exclude-from FILE        ignore files matching the glob patterns in FILE, one per line
+  --prune-glob GLOBS         glob patterns (pipe-separated) of directories not to recurse into
+  --ignore-files             ignore files mentioned in '.ignore' and '.fdignore'
+  --regex PATTERN            list only files whose names match the regex
+  --iregex PATTERN           like --regex, ignoring case
+  --regex-full-path          match --regex against paths rather than names
+  --newer-than WHEN          list only files newer than a date or age
+  --older-than WHEN          list only files older than a date or age
+  --type TYPES               list only these kinds of file (f, d, l, p, s, b, c)
+  --ext EXTENSIONS           list only files with these extensions (comma-separated)
+  --perm MODE                list only files with these permissions (e.g. 644, -o+w, /022)";
 
 static GIT_FILTER_HELP: &str = "  \
-  --git-ignore               ignore files mentioned in '.gitignore'";
+  --git-ignore               ignore files mentioned in '.gitignore'
+  --git-only[=WHICH]         list only files Git tracks, or only changed ones (dirty)";
 
 static USAGE_PART2: &str = "  \
   Valid sort fields:         name, Name, extension, Extension, size, type,
-                             created, modified, accessed, changed, inode, and none.
+                             created, modified, accessed, changed, inode, random,
+                             and none.
                              date, time, old, and new all refer to modified.
 
 LONG VIEW OPTIONS
   -b, --binary               list file sizes with binary prefixes
   -B, --bytes                list file sizes in bytes, without any prefixes
+  --size WHICH               which sizes to list (apparent, allocated, or both)
+  --size-unit UNIT           list file sizes in units (bytes, sectors, pages, blocks:N)
   -g, --group                list each file's group
   --smart-group              only show group if it has a different name from owner
   -h, --header               add a header row to each column
+  --header-names LIST        rename header columns (e.g. size:SZ,mtime:MODIFIED)
   -H, --links                list each file's number of hard links
   -i, --inode                list each file's inode number
   -M, --mounts               show mount details (Linux and Mac only)
   -n, --numeric              list numeric user and group IDs
-  -O, --flags                list file flags (Mac, BSD, and Windows only)
+  -O, --flags, --file-flags  list file flags (Mac, BSD, Linux, and Windows only)
+  --file-version             list the version of executables and libraries (Windows only)
+  --access-rights            list what you may do with each file, from its DACL (Windows only)
+  --cow                      list btrfs subvolumes and compression, and ZFS datasets (Linux only)
+  --project-id               list the project ID used for project quotas (Linux only)
+  --storage-class            list the storage class of objects in object stores
+  --mime                     list each file's MIME type, sniffed from its contents
+  --hash                     list the SHA-256 digest of each file's contents
+  --image-info               list the width and height of PNG, JPEG, GIF, and WebP images
+  --binary-info              list the architecture and linking of executables and libraries
+  --preview[=N]              list the first N characters of text files (default 40)
   -S, --blocksize            show size of allocated file system blocks
-  -t, --time FIELD           which timestamp field to list (modified, accessed, created)
+  --link-target-details      list the permissions and size of symlinks' targets
+  --vcs-status               list each file's Git, Mercurial, or Jujutsu status
+  -t, --time FIELD           which timestamp field to list (modified, accessed, created, git)
   -m, --modified             use the modified timestamp field
   -u, --accessed             use the accessed timestamp field
   -U, --created              use the created timestamp field
@@ -81,27 +147,41 @@ LONG VIEW OPTIONS
   --time-style               how to format timestamps (default, iso, long-iso,
                              full-iso, relative, or a custom style '+<FORMAT>'
                              like '+%Y-%m-%d %H:%M')
-  --total-size               show the size of a directory as the size of all
-                             files and directories inside (unix only)
+  --total-size[=WHICH]       show the size of a directory as the size of all
+                             files and directories inside, adding up their
+                             apparent or allocated sizes (unix only)
+  --total-size-max-depth N   only count what's N levels down with --total-size
+  --lfs                      list Git LFS pointer files with the size of the
+                             file they point to
   -o, --octal-permissions    list each file's permission in octal format
   --no-permissions           suppress the permissions field
   --no-filesize              suppress the filesize field
   --no-user                  suppress the user field
   --no-time                  suppress the time field
+  --fields LIST              choose which columns to list, and in what order
   --stdin                    read file names from stdin, one per line or other separator 
                              specified in environment";
 
 static GIT_VIEW_HELP: &str = "  \
   --git                      list each file's Git status, if tracked or ignored
+  --git-log                  list the hash and subject of each file's last commit
+  --git-author               list the author of each file's last commit
+  --git-owner                list who wrote the most lines of each file
+  --git-since-tag            list the first tag to include each file's last change
   --no-git                   suppress Git status (always overrides --git,
                              --git-repos, --git-repos-no-status)
   --git-repos                list root of git-tree status
   --git-repos-no-status      list each git-repos branch name (much faster)
     ";
 static EXTENDED_HELP: &str = "  \
-  -@, --extended             list each file's extended attributes and sizes";
+  -@, --extended             list each file's extended attributes and sizes
+  --xattrs=WHICH             list each file's extended attributes' names or values";
 static SECATTR_HELP: &str = "  \
   -Z, --context              list each file's security context";
+static ACL_HELP: &str = "  \
+  --acl                      list each file's access control list entries";
+static STREAMS_HELP: &str = "  \
+  --streams                  list each file's alternate data streams";
 
 /// All the information needed to display the help text, which depends
 /// on which features are enabled and whether the user only wants to
@@ -147,6 +227,14 @@ impl fmt::Display for HelpString {
             write!(f, "\n{SECATTR_HELP}")?;
         }
 
+        if acl::ENABLED {
+            write!(f, "\n{ACL_HELP}")?;
+        }
+
+        if streams::ENABLED {
+            write!(f, "\n{STREAMS_HELP}")?;
+        }
+
         writeln!(f)
     }
 }
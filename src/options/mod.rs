@@ -75,10 +75,12 @@
 //! it’s clear what the user wants.
 
 use std::ffi::OsStr;
+use std::path::PathBuf;
 
 use crate::fs::dir_action::DirAction;
-use crate::fs::filter::{FileFilter, GitIgnore};
+use crate::fs::filter::{FileFilter, GitIgnore, GitOnly};
 use crate::options::stdin::FilesInput;
+use crate::output::table::{Column, TimeType};
 use crate::output::{details, grid_details, Mode, View};
 use crate::theme::Options as ThemeOptions;
 
@@ -89,6 +91,7 @@ mod filter;
 mod error;
 #[rustfmt::skip]
 mod flags;
+mod terminal;
 mod theme;
 mod view;
 
@@ -111,6 +114,7 @@ use self::version::VersionString;
 /// These **options** represent a parsed, error-checked versions of the
 /// user’s command-line options.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Options {
     /// The action to perform when encountering a directory rather than a
     /// regular file.
@@ -130,6 +134,40 @@ pub struct Options {
 
     /// Whether to read file names from stdin instead of the command-line
     pub stdin: FilesInput,
+
+    /// Whether the output is going to a terminal.
+    pub is_a_tty: bool,
+
+    /// Whether to report every directory that couldn’t be read because of
+    /// its permissions while recursing, rather than a count of them.
+    pub verbose_errors: bool,
+
+    /// Whether to page listings too long to fit on the screen, when the
+    /// output is a terminal.
+    pub pager: bool,
+
+    /// Whether to list anything at all, or to print something about the
+    /// theme instead.
+    pub action: Action,
+
+    /// Whether to produce the same output on every computer, ignoring the
+    /// terminal, time zone, and locale.
+    pub deterministic: bool,
+
+    /// Whether to list Git LFS pointer files with the size of the object
+    /// they point to.
+    pub lfs: bool,
+
+    /// The snapshot to list what’s changed since, rather than listing
+    /// anything else.
+    pub changed_since: Option<PathBuf>,
+
+    /// Where to save a snapshot of the files beneath the input paths.
+    pub save_snapshot: Option<PathBuf>,
+
+    /// The manifest to list the entries of, rather than anything on the
+    /// filesystem.
+    pub from_manifest: Option<PathBuf>,
 }
 
 impl Options {
@@ -174,7 +212,7 @@ impl Options {
     /// status column. It’s only worth trying to discover a repository if the
     /// results will end up being displayed.
     pub fn should_scan_for_git(&self) -> bool {
-        if self.filter.git_ignore == GitIgnore::CheckAndIgnore {
+        if self.filter.git_ignore == GitIgnore::CheckAndIgnore || self.filter.git_only.is_some() {
             return true;
         }
 
@@ -190,7 +228,64 @@ impl Options {
                         ..
                     },
                 ..
-            }) => table.columns.git,
+            }) => [
+                Column::GitStatus,
+                Column::GitCommit,
+                Column::GitSubject,
+                Column::GitAuthor,
+                Column::GitOwner,
+                Column::GitSinceTag,
+                Column::Timestamp(TimeType::Git),
+            ]
+            .into_iter()
+            .any(|column| table.columns.contains(column)),
+            _ => false,
+        }
+    }
+
+    /// Whether the Git statuses of files are needed, either to show them or
+    /// to leave out ignored or unchanged files, rather than just their
+    /// commits.
+    pub fn should_load_git_statuses(&self) -> bool {
+        if self.filter.git_ignore == GitIgnore::CheckAndIgnore
+            || self.filter.git_only == Some(GitOnly::Dirty)
+        {
+            return true;
+        }
+
+        match self.view.mode {
+            Mode::Details(details::Options {
+                table: Some(ref table),
+                ..
+            })
+            | Mode::GridDetails(grid_details::Options {
+                details:
+                    details::Options {
+                        table: Some(ref table),
+                        ..
+                    },
+                ..
+            }) => table.columns.contains(Column::GitStatus),
+            _ => false,
+        }
+    }
+
+    /// Whether the View specified in this set of options includes a column
+    /// for the status of files in any version control system.
+    pub fn should_scan_for_vcs(&self) -> bool {
+        match self.view.mode {
+            Mode::Details(details::Options {
+                table: Some(ref table),
+                ..
+            })
+            | Mode::GridDetails(grid_details::Options {
+                details:
+                    details::Options {
+                        table: Some(ref table),
+                        ..
+                    },
+                ..
+            }) => table.columns.contains(Column::VcsStatus),
             _ => false,
         }
     }
@@ -200,18 +295,35 @@ impl Options {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         if cfg!(not(feature = "git"))
             && matches
-                .has_where_any(|f| f.matches(&flags::GIT) || f.matches(&flags::GIT_IGNORE))
+                .has_where_any(|f| {
+                    f.matches(&flags::GIT)
+                        || f.matches(&flags::GIT_LOG)
+                        || f.matches(&flags::GIT_AUTHOR)
+                        || f.matches(&flags::GIT_OWNER)
+                        || f.matches(&flags::GIT_SINCE_TAG)
+                        || f.matches(&flags::GIT_IGNORE)
+                        || f.matches(&flags::GIT_ONLY)
+                })
                 .is_some()
         {
             return Err(OptionsError::Unsupported(String::from(
-                "Options --git and --git-ignore can't be used because `git` feature was disabled in this build of exa"
+                "Options --git, --git-log, --git-author, and --git-ignore can't be used because `git` feature was disabled in this build of exa"
             )));
         }
-        let view = View::deduce(matches, vars)?;
+        let is_a_tty = terminal::is_a_tty(matches)?;
+        let view = View::deduce(matches, vars, is_a_tty)?;
         let dir_action = DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)))?;
         let filter = FileFilter::deduce(matches)?;
         let theme = ThemeOptions::deduce(matches, vars)?;
         let stdin = FilesInput::deduce(matches, vars)?;
+        let verbose_errors = matches.has(&flags::VERBOSE_ERRORS)?;
+        let pager = matches.has(&flags::PAGER)?;
+        let action = Action::deduce(matches)?;
+        let deterministic = matches.has(&flags::DETERMINISTIC)?;
+        let lfs = matches.has(&flags::LFS)?;
+        let changed_since = matches.get(&flags::CHANGED_SINCE)?.map(PathBuf::from);
+        let save_snapshot = matches.get(&flags::SAVE_SNAPSHOT)?.map(PathBuf::from);
+        let from_manifest = matches.get(&flags::FROM_MANIFEST)?.map(PathBuf::from);
 
         Ok(Self {
             dir_action,
@@ -219,10 +331,48 @@ impl Options {
             view,
             theme,
             stdin,
+            is_a_tty,
+            verbose_errors,
+            pager,
+            action,
+            deterministic,
+            lfs,
+            changed_since,
+            save_snapshot,
+            from_manifest,
         })
     }
 }
 
+/// What eza should do once the options have been parsed.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Action {
+    /// List the input paths, which is what eza normally does.
+    List,
+
+    /// Print the theme as an `LS_COLORS` string rather than listing
+    /// anything.
+    ExportLsColors,
+
+    /// Print every style in the theme and where it came from rather than
+    /// listing anything.
+    DumpTheme,
+}
+
+impl Action {
+    /// Determine the action from the command-line arguments. Dumping the
+    /// theme wins if both it and exporting `LS_COLORS` are asked for.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if matches.has(&flags::DUMP_THEME)? {
+            Ok(Self::DumpTheme)
+        } else if matches.has(&flags::EXPORT_LS_COLORS)? {
+            Ok(Self::ExportLsColors)
+        } else {
+            Ok(Self::List)
+        }
+    }
+}
+
 /// The result of the `Options::parse` function.
 ///
 /// NOTE: We disallow the `large_enum_variant` lint here, because we're not
@@ -0,0 +1,251 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Deciding whether the output is going to a terminal, and whether to use
+//! colours, in one place, so that the theme, icons, hyperlinks, and file
+//! type indicators all come to the same answer.
+//!
+//! Whether to use colours is decided by the first of these that applies:
+//!
+//! 1. `--color` or `--colour`, given on the command line;
+//! 2. `NO_COLOR`, which turns colours off when it’s set to anything;
+//! 3. `CLICOLOR_FORCE`, which turns them on when it’s set to anything
+//!    but `0`;
+//! 4. `CLICOLOR`, which turns them off when it’s set to `0`;
+//! 5. whether the output is a terminal.
+
+use std::io::{self, IsTerminal};
+
+use crate::options::parser::MatchedFlags;
+use crate::options::{flags, vars, OptionsError, Vars};
+use crate::theme::UseColours;
+
+/// Whether standard output is a terminal, which decides whether anything
+/// set to `auto` gets turned on. `--deterministic` acts as though it
+/// never is.
+pub fn is_a_tty(matches: &MatchedFlags<'_>) -> Result<bool, OptionsError> {
+    Ok(!matches.has(&flags::DETERMINISTIC)? && io::stdout().is_terminal())
+}
+
+impl UseColours {
+    pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        let Some(word) =
+            matches.get_where(|f| f.matches(&flags::COLOR) || f.matches(&flags::COLOUR))?
+        else {
+            return Ok(Self::from_vars(vars));
+        };
+
+        if word == "always" {
+            Ok(Self::Always)
+        } else if word == "auto" || word == "automatic" {
+            Ok(Self::Automatic)
+        } else if word == "never" {
+            Ok(Self::Never)
+        } else {
+            Err(OptionsError::BadArgument(&flags::COLOR, word.into()))
+        }
+    }
+
+    /// Decides from the environment, when there’s no `--color` argument.
+    fn from_vars<V: Vars>(vars: &V) -> Self {
+        let set_to = |name| {
+            vars.get(name)
+                .map(|value| value.to_string_lossy().into_owned())
+        };
+
+        if vars.get(vars::NO_COLOR).is_some() {
+            Self::Never
+        } else if set_to(vars::CLICOLOR_FORCE).is_some_and(|v| !v.is_empty() && v != "0") {
+            Self::Always
+        } else if set_to(vars::CLICOLOR).is_some_and(|v| v == "0") {
+            Self::Never
+        } else {
+            Self::Automatic
+        }
+    }
+
+    /// Whether colours should be used, given whether the output can show
+    /// them. Everything that gets coloured asks this, by way of the theme.
+    pub fn resolve(self, is_a_tty: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Automatic => is_a_tty,
+            Self::Never => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::flags;
+    use crate::options::parser::{Arg, Flag};
+    use std::ffi::OsString;
+
+    use crate::options::test::parse_for_test;
+    use crate::options::test::Strictnesses::*;
+
+    static TEST_ARGS: &[&Arg] = &[
+        &flags::COLOR,
+        &flags::COLOUR,
+        &flags::COLOR_SCALE,
+        &flags::COLOUR_SCALE,
+    ];
+
+    #[allow(unused_macro_rules)]
+    macro_rules! test {
+        ($name:ident:  $type:ident <- $inputs:expr;  $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+
+        ($name:ident:  $type:ident <- $inputs:expr, $env:expr;  $stricts:expr => $result:expr) => {
+            #[test]
+            fn $name() {
+                let env = $env;
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf, &env)
+                }) {
+                    assert_eq!(result, $result);
+                }
+            }
+        };
+
+        ($name:ident:  $type:ident <- $inputs:expr;  $stricts:expr => err $result:expr) => {
+            #[test]
+            fn $name() {
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf)
+                }) {
+                    assert_eq!(result.unwrap_err(), $result);
+                }
+            }
+        };
+
+        ($name:ident:  $type:ident <- $inputs:expr, $env:expr;  $stricts:expr => err $result:expr) => {
+            #[test]
+            fn $name() {
+                let env = $env;
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
+                    $type::deduce(mf, &env)
+                }) {
+                    assert_eq!(result.unwrap_err(), $result);
+                }
+            }
+        };
+    }
+
+    struct MockVars {
+        ls: &'static str,
+        exa: &'static str,
+        no_color: &'static str,
+        clicolor: &'static str,
+        clicolor_force: &'static str,
+    }
+
+    impl MockVars {
+        fn empty() -> MockVars {
+            MockVars {
+                ls: "",
+                exa: "",
+                no_color: "",
+                clicolor: "",
+                clicolor_force: "",
+            }
+        }
+        fn with_no_color() -> MockVars {
+            MockVars {
+                no_color: "true",
+                ..MockVars::empty()
+            }
+        }
+        fn with_clicolor(clicolor: &'static str) -> MockVars {
+            MockVars {
+                clicolor,
+                ..MockVars::empty()
+            }
+        }
+        fn with_clicolor_force(clicolor_force: &'static str) -> MockVars {
+            MockVars {
+                clicolor_force,
+                ..MockVars::empty()
+            }
+        }
+    }
+
+    // Test impl that just returns the value it has.
+    impl Vars for MockVars {
+        fn get(&self, name: &'static str) -> Option<OsString> {
+            if name == vars::LS_COLORS && !self.ls.is_empty() {
+                Some(OsString::from(self.ls))
+            } else if (name == vars::EZA_COLORS || name == vars::EXA_COLORS) && !self.exa.is_empty()
+            {
+                Some(OsString::from(self.exa))
+            } else if name == vars::NO_COLOR && !self.no_color.is_empty() {
+                Some(OsString::from(self.no_color))
+            } else if name == vars::CLICOLOR && !self.clicolor.is_empty() {
+                Some(OsString::from(self.clicolor))
+            } else if name == vars::CLICOLOR_FORCE && !self.clicolor_force.is_empty() {
+                Some(OsString::from(self.clicolor_force))
+            } else {
+                None
+            }
+        }
+    }
+
+    // Default
+    test!(empty:         UseColours <- [], MockVars::empty();                     Both => Ok(UseColours::Automatic));
+    test!(empty_with_no_color: UseColours <- [], MockVars::with_no_color();             Both => Ok(UseColours::Never));
+
+    // CLICOLOR and CLICOLOR_FORCE
+    test!(clicolor_off:        UseColours <- [], MockVars::with_clicolor("0");          Both => Ok(UseColours::Never));
+    test!(clicolor_on:         UseColours <- [], MockVars::with_clicolor("1");          Both => Ok(UseColours::Automatic));
+    test!(clicolor_force:      UseColours <- [], MockVars::with_clicolor_force("1");    Both => Ok(UseColours::Always));
+    test!(clicolor_force_off:  UseColours <- [], MockVars::with_clicolor_force("0");    Both => Ok(UseColours::Automatic));
+    test!(no_color_over_force: UseColours <- [], MockVars { no_color: "1", clicolor_force: "1", ..MockVars::empty() };  Both => Ok(UseColours::Never));
+    test!(force_over_clicolor: UseColours <- [], MockVars { clicolor: "0", clicolor_force: "1", ..MockVars::empty() };  Both => Ok(UseColours::Always));
+    test!(flag_over_no_color:  UseColours <- ["--color=always"], MockVars::with_no_color();       Both => Ok(UseColours::Always));
+    test!(flag_over_force:     UseColours <- ["--color=never"], MockVars::with_clicolor_force("1"); Both => Ok(UseColours::Never));
+
+    // --colour
+    test!(u_always:      UseColours <- ["--colour=always"], MockVars::empty();    Both => Ok(UseColours::Always));
+    test!(u_auto:        UseColours <- ["--colour", "auto"], MockVars::empty();   Both => Ok(UseColours::Automatic));
+    test!(u_never:       UseColours <- ["--colour=never"], MockVars::empty();     Both => Ok(UseColours::Never));
+
+    // --color
+    test!(no_u_always:   UseColours <- ["--color", "always"], MockVars::empty();  Both => Ok(UseColours::Always));
+    test!(no_u_auto:     UseColours <- ["--color=auto"], MockVars::empty();       Both => Ok(UseColours::Automatic));
+    test!(no_u_never:    UseColours <- ["--color", "never"], MockVars::empty();   Both => Ok(UseColours::Never));
+
+    // Errors
+    test!(no_u_error:    UseColours <- ["--color=upstream"], MockVars::empty();   Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("upstream"))); // the error is for --color
+    test!(u_error:       UseColours <- ["--colour=lovers"], MockVars::empty();    Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("lovers"))); // and so is this one!
+
+    // Overriding
+    test!(overridden_1:  UseColours <- ["--colour=auto", "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
+    test!(overridden_2:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
+    test!(overridden_3:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Last => Ok(UseColours::Never));
+    test!(overridden_4:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Last => Ok(UseColours::Never));
+
+    test!(overridden_5:  UseColours <- ["--colour=auto", "--colour=never"], MockVars::empty();  Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("colour")));
+    test!(overridden_6:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("colour")));
+    test!(overridden_7:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("color")));
+    test!(overridden_8:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("color")));
+
+    #[test]
+    fn resolve() {
+        assert!(UseColours::Always.resolve(false));
+        assert!(UseColours::Automatic.resolve(true));
+        assert!(!UseColours::Automatic.resolve(false));
+        assert!(!UseColours::Never.resolve(true));
+    }
+}
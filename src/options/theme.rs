@@ -7,8 +7,11 @@
 use crate::options::parser::MatchedFlags;
 use crate::options::{flags, vars, OptionsError, Vars};
 use crate::output::color_scale::ColorScaleOptions;
-use crate::theme::{Definitions, Options, UseColours};
-use std::path::PathBuf;
+use crate::output::icons::IconOverrides;
+use crate::theme::{
+    from_colorfgbg, Background, ColourDepth, Definitions, LSColors, Options, UseColours,
+};
+use std::path::{Path, PathBuf};
 
 use super::config::ThemeConfig;
 
@@ -16,7 +19,10 @@ impl Options {
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let use_colours = UseColours::deduce(matches, vars)?;
         let colour_scale = ColorScaleOptions::deduce(matches, vars)?;
+        let background = Background::deduce(matches, vars)?;
+        let colour_depth = ColourDepth::deduce(matches, vars)?;
         let theme_config = ThemeConfig::deduce(vars);
+        let icons = IconOverrides::deduce(vars, theme_config.as_ref());
 
         let definitions = if use_colours == UseColours::Never {
             Definitions::default()
@@ -28,65 +34,150 @@ impl Options {
             use_colours,
             colour_scale,
             definitions,
+            background,
+            colour_depth,
             theme_config,
+            icons,
         })
     }
 }
 
-impl ThemeConfig {
-    fn deduce<V: Vars>(vars: &V) -> Option<Self> {
-        if let Some(path) = vars.get("EZA_CONFIG_DIR") {
-            let path = PathBuf::from(path);
-            let theme = path.join("theme.yml");
-            if theme.exists() {
-                return Some(ThemeConfig::from_path(theme));
-            }
-            let theme = path.join("theme.yaml");
-            if theme.exists() {
-                return Some(ThemeConfig::from_path(theme));
-            }
-            None
-        } else {
-            let path = dirs::config_dir().unwrap_or_default();
-            let path = path.join("eza");
-            let theme = path.join("theme.yml");
-            if theme.exists() {
-                return Some(ThemeConfig::default());
-            }
-            let theme = path.join("theme.yaml");
-            if theme.exists() {
-                return Some(ThemeConfig::from_path(theme));
-            }
-            None
+impl Background {
+    /// Picks the background from `--theme`, or from `COLORFGBG` when that’s
+    /// `auto` or not given. The terminal only gets asked when `--theme=auto`
+    /// is given explicitly and `COLORFGBG` doesn’t say; otherwise, output
+    /// that has to be the same everywhere, and dumb terminals that wouldn’t
+    /// answer, get the dark background.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        let ask = match matches.get(&flags::THEME)? {
+            None => false,
+            Some(word) => match word.to_str() {
+                Some("light") => return Ok(Self::Light),
+                Some("dark") => return Ok(Self::Dark),
+                Some("auto" | "automatic") => true,
+                _ => return Err(OptionsError::BadArgument(&flags::THEME, word.into())),
+            },
+        };
+
+        if matches.has(&flags::DETERMINISTIC)? || vars.get(vars::TERM).is_some_and(|t| t == "dumb")
+        {
+            return Ok(Self::Dark);
         }
+
+        let fallback = if ask { Self::Automatic } else { Self::Dark };
+        Ok(vars
+            .get(vars::COLORFGBG)
+            .and_then(|value| from_colorfgbg(&value.to_string_lossy()))
+            .unwrap_or(fallback))
     }
 }
 
-impl UseColours {
+impl ColourDepth {
+    /// Keeps 24-bit colours when `COLORTERM` says the terminal can show
+    /// them, and otherwise reduces them on terminals whose `TERM` is known to
+    /// have 256 or 16 colours. Other terminals get the colours as they are,
+    /// as does output that has to be the same everywhere.
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let default_value = match vars.get(vars::NO_COLOR) {
-            Some(_) => Self::Never,
-            None => Self::Automatic,
-        };
+        if matches.has(&flags::DETERMINISTIC)? {
+            return Ok(Self::TrueColour);
+        }
 
-        let Some(word) =
-            matches.get_where(|f| f.matches(&flags::COLOR) || f.matches(&flags::COLOUR))?
-        else {
-            return Ok(default_value);
-        };
+        if let Some(colorterm) = vars.get(vars::COLORTERM) {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return Ok(Self::TrueColour);
+            }
+        }
 
-        if word == "always" {
-            Ok(Self::Always)
-        } else if word == "auto" || word == "automatic" {
-            Ok(Self::Automatic)
-        } else if word == "never" {
-            Ok(Self::Never)
+        let Some(term) = vars.get(vars::TERM) else {
+            return Ok(Self::TrueColour);
+        };
+        let term = term.to_string_lossy();
+        if term.contains("256color") {
+            Ok(Self::Colours256)
+        } else if BASIC_TERMS.contains(&&*term)
+            || term.ends_with("-color")
+            || term.ends_with("-16color")
+        {
+            Ok(Self::Colours16)
         } else {
-            Err(OptionsError::BadArgument(&flags::COLOR, word.into()))
+            Ok(Self::TrueColour)
         }
     }
 }
 
+/// Values of `TERM` for terminals that only have the 16 basic colours.
+const BASIC_TERMS: &[&str] = &[
+    "ansi", "cygwin", "linux", "rxvt", "screen", "tmux", "vt100", "vt220", "xterm",
+];
+
+/// The names of the directories in the user’s configuration directory that
+/// the theme file gets looked for in, in order.
+const CONFIG_DIR_NAMES: &[&str] = &["zetta", "eza"];
+
+impl ThemeConfig {
+    /// Finds the theme file, which is in the directory `EZA_CONFIG_DIR`
+    /// names if it’s set, and otherwise in a `zetta` or `eza` directory in
+    /// `XDG_CONFIG_HOME`, or in the platform’s configuration directory.
+    pub(super) fn deduce<V: Vars>(vars: &V) -> Option<Self> {
+        if let Some(path) = vars.get(vars::EZA_CONFIG_DIR) {
+            return Self::find_in(&PathBuf::from(path));
+        }
+
+        // The spec says to ignore relative paths, which would change meaning
+        // with the current directory.
+        let xdg_config_home = vars
+            .get(vars::XDG_CONFIG_HOME)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute());
+
+        xdg_config_home
+            .into_iter()
+            .chain(dirs::config_dir())
+            .flat_map(|dir| CONFIG_DIR_NAMES.iter().map(move |name| dir.join(name)))
+            .find_map(|dir| Self::find_in(&dir))
+    }
+
+    /// The theme file in the given directory, if there is one.
+    fn find_in(dir: &Path) -> Option<Self> {
+        ["theme.yml", "theme.yaml"]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+            .map(Self::from_path)
+    }
+}
+
+impl IconOverrides {
+    /// Reads the icons picked in the theme file, then the ones in the
+    /// `ZETTA_ICONS` variable, which take precedence over them.
+    fn deduce<V: Vars>(vars: &V, theme_config: Option<&ThemeConfig>) -> Self {
+        use log::*;
+
+        let mut icons = theme_config
+            .map(ThemeConfig::to_icon_overrides)
+            .unwrap_or_default();
+
+        let Some(var) = vars.get(vars::ZETTA_ICONS) else {
+            return icons;
+        };
+        let var = var.to_string_lossy();
+        LSColors(&var).each_pair(|pair| {
+            let mut chars = pair.value.chars();
+            let (Some(glyph), None) = (chars.next(), chars.next()) else {
+                warn!(
+                    "Icon for {:?} isn't one character: {:?}",
+                    pair.key, pair.value
+                );
+                return;
+            };
+            if let Err(e) = icons.add(pair.key, glyph) {
+                warn!("Couldn't parse glob pattern {:?}: {}", pair.key, e);
+            }
+        });
+        icons
+    }
+}
+
 impl Definitions {
     fn deduce<V: Vars>(vars: &V) -> Self {
         let ls = vars
@@ -100,136 +191,56 @@ impl Definitions {
 }
 
 #[cfg(test)]
-mod terminal_test {
+mod test {
     use super::*;
     use crate::options::flags;
-    use crate::options::parser::{Arg, Flag};
+    use crate::options::parser::Arg;
     use std::ffi::OsString;
 
     use crate::options::test::parse_for_test;
     use crate::options::test::Strictnesses::*;
 
-    static TEST_ARGS: &[&Arg] = &[
-        &flags::COLOR,
-        &flags::COLOUR,
-        &flags::COLOR_SCALE,
-        &flags::COLOUR_SCALE,
-    ];
+    static TEST_ARGS: &[&Arg] = &[&flags::THEME, &flags::DETERMINISTIC];
 
-    #[allow(unused_macro_rules)]
-    macro_rules! test {
-        ($name:ident:  $type:ident <- $inputs:expr;  $stricts:expr => $result:expr) => {
-            #[test]
-            fn $name() {
-                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
-                    $type::deduce(mf)
-                }) {
-                    assert_eq!(result, $result);
-                }
-            }
-        };
-
-        ($name:ident:  $type:ident <- $inputs:expr, $env:expr;  $stricts:expr => $result:expr) => {
-            #[test]
-            fn $name() {
-                let env = $env;
-                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
-                    $type::deduce(mf, &env)
-                }) {
-                    assert_eq!(result, $result);
-                }
-            }
-        };
+    struct MockVars {
+        term: Option<&'static str>,
+        colorfgbg: Option<&'static str>,
+    }
 
-        ($name:ident:  $type:ident <- $inputs:expr;  $stricts:expr => err $result:expr) => {
-            #[test]
-            fn $name() {
-                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
-                    $type::deduce(mf)
-                }) {
-                    assert_eq!(result.unwrap_err(), $result);
-                }
+    impl Vars for MockVars {
+        fn get(&self, name: &'static str) -> Option<OsString> {
+            match name {
+                "TERM" => self.term.map(OsString::from),
+                "COLORFGBG" => self.colorfgbg.map(OsString::from),
+                _ => None,
             }
-        };
+        }
+    }
 
-        ($name:ident:  $type:ident <- $inputs:expr, $env:expr;  $stricts:expr => err $result:expr) => {
+    macro_rules! test {
+        ($name:ident: $inputs:expr, $term:expr, $colorfgbg:expr => $result:expr) => {
             #[test]
             fn $name() {
-                let env = $env;
-                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, $stricts, |mf| {
-                    $type::deduce(mf, &env)
+                let vars = MockVars {
+                    term: $term,
+                    colorfgbg: $colorfgbg,
+                };
+                for result in parse_for_test($inputs.as_ref(), TEST_ARGS, Both, |mf| {
+                    Background::deduce(mf, &vars)
                 }) {
-                    assert_eq!(result.unwrap_err(), $result);
+                    assert_eq!(result, $result);
                 }
             }
         };
     }
 
-    struct MockVars {
-        ls: &'static str,
-        exa: &'static str,
-        no_color: &'static str,
-    }
-
-    impl MockVars {
-        fn empty() -> MockVars {
-            MockVars {
-                ls: "",
-                exa: "",
-                no_color: "",
-            }
-        }
-        fn with_no_color() -> MockVars {
-            MockVars {
-                ls: "",
-                exa: "",
-                no_color: "true",
-            }
-        }
-    }
-
-    // Test impl that just returns the value it has.
-    impl Vars for MockVars {
-        fn get(&self, name: &'static str) -> Option<OsString> {
-            if name == vars::LS_COLORS && !self.ls.is_empty() {
-                Some(OsString::from(self.ls))
-            } else if (name == vars::EZA_COLORS || name == vars::EXA_COLORS) && !self.exa.is_empty()
-            {
-                Some(OsString::from(self.exa))
-            } else if name == vars::NO_COLOR && !self.no_color.is_empty() {
-                Some(OsString::from(self.no_color))
-            } else {
-                None
-            }
-        }
-    }
-
-    // Default
-    test!(empty:         UseColours <- [], MockVars::empty();                     Both => Ok(UseColours::Automatic));
-    test!(empty_with_no_color: UseColours <- [], MockVars::with_no_color();             Both => Ok(UseColours::Never));
-
-    // --colour
-    test!(u_always:      UseColours <- ["--colour=always"], MockVars::empty();    Both => Ok(UseColours::Always));
-    test!(u_auto:        UseColours <- ["--colour", "auto"], MockVars::empty();   Both => Ok(UseColours::Automatic));
-    test!(u_never:       UseColours <- ["--colour=never"], MockVars::empty();     Both => Ok(UseColours::Never));
-
-    // --color
-    test!(no_u_always:   UseColours <- ["--color", "always"], MockVars::empty();  Both => Ok(UseColours::Always));
-    test!(no_u_auto:     UseColours <- ["--color=auto"], MockVars::empty();       Both => Ok(UseColours::Automatic));
-    test!(no_u_never:    UseColours <- ["--color", "never"], MockVars::empty();   Both => Ok(UseColours::Never));
-
-    // Errors
-    test!(no_u_error:    UseColours <- ["--color=upstream"], MockVars::empty();   Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("upstream"))); // the error is for --color
-    test!(u_error:       UseColours <- ["--colour=lovers"], MockVars::empty();    Both => err OptionsError::BadArgument(&flags::COLOR, OsString::from("lovers"))); // and so is this one!
-
-    // Overriding
-    test!(overridden_1:  UseColours <- ["--colour=auto", "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
-    test!(overridden_2:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Last => Ok(UseColours::Never));
-    test!(overridden_3:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Last => Ok(UseColours::Never));
-    test!(overridden_4:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Last => Ok(UseColours::Never));
-
-    test!(overridden_5:  UseColours <- ["--colour=auto", "--colour=never"], MockVars::empty();  Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("colour")));
-    test!(overridden_6:  UseColours <- ["--color=auto",  "--colour=never"], MockVars::empty();  Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("colour")));
-    test!(overridden_7:  UseColours <- ["--colour=auto", "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("colour"), Flag::Long("color")));
-    test!(overridden_8:  UseColours <- ["--color=auto",  "--color=never"], MockVars::empty();   Complain => err OptionsError::Duplicate(Flag::Long("color"),  Flag::Long("color")));
+    test!(default_dark:         [],                 None,          None           => Ok(Background::Dark));
+    test!(default_colorfgbg:    [],                 None,          Some("0;15")   => Ok(Background::Light));
+    test!(default_bad_colorfgbg: [],                None,          Some("x")      => Ok(Background::Dark));
+    test!(auto_asks:            ["--theme=auto"],   None,          None           => Ok(Background::Automatic));
+    test!(auto_colorfgbg:       ["--theme=auto"],   None,          Some("15;0")   => Ok(Background::Dark));
+    test!(auto_dumb:            ["--theme=auto"],   Some("dumb"),  None           => Ok(Background::Dark));
+    test!(auto_deterministic:   ["--theme=auto", "--deterministic"], None, None   => Ok(Background::Dark));
+    test!(light:                ["--theme=light"],  None,          Some("15;0")   => Ok(Background::Light));
+    test!(bad:                  ["--theme=sepia"],  None,          None           => Err(OptionsError::BadArgument(&flags::THEME, OsString::from("sepia"))));
 }
@@ -24,6 +24,24 @@ pub static TIME_STYLE: &str = "TIME_STYLE";
 /// See: <https://no-color.org/>
 pub static NO_COLOR: &str = "NO_COLOR";
 
+/// Environment variables used to turn colours off when set to `0`, or to
+/// force them on even when the output isn’t a terminal.
+/// See: <https://bixense.com/clicolors/>
+pub static CLICOLOR: &str = "CLICOLOR";
+pub static CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
+
+/// Environment variable some terminals set to the numbers of their
+/// foreground and background colours, separated by a semicolon.
+pub static COLORFGBG: &str = "COLORFGBG";
+
+/// Environment variable naming the type of terminal, which is `dumb` for
+/// ones that can’t be asked anything.
+pub static TERM: &str = "TERM";
+
+/// Environment variable that terminals set to `truecolor` or `24bit` when
+/// they can show any 24-bit colour.
+pub static COLORTERM: &str = "COLORTERM";
+
 // exa-specific variables
 
 /// Environment variable used to colour exa’s interface when colours are
@@ -72,6 +90,19 @@ pub static EZA_ICONS_AUTO: &str = "EZA_ICONS_AUTO";
 
 pub static EZA_STDIN_SEPARATOR: &str = "EZA_STDIN_SEPARATOR";
 
+/// Environment variable used to pick the icons for files, as a list of
+/// `pattern=glyph` pairs separated by colons, like `LS_COLORS`.
+pub static ZETTA_ICONS: &str = "ZETTA_ICONS";
+
+/// Environment variable used to name the directory to look for the theme
+/// file in, rather than the user’s configuration directory.
+pub static EZA_CONFIG_DIR: &str = "EZA_CONFIG_DIR";
+
+/// Environment variable naming the user’s configuration directory, as
+/// the XDG base directory spec has it. This gets used over the platform’s
+/// own configuration directory, so the same place works everywhere.
+pub static XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+
 /// Environment variable used to choose how windows attributes are displayed.
 /// Short will display a single character for each set attribute, long will
 /// display a comma separated list of descriptions.
@@ -4,28 +4,46 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
+use std::num::NonZeroUsize;
 
-use crate::fs::feature::xattr;
-use crate::options::parser::MatchedFlags;
+use crate::fs::feature::{acl, chattr, cow, security, streams, version_info, xattr};
+use crate::fs::recursive_size::{SizeMeasure, TotalSize};
+use crate::options::config::ThemeConfig;
+use crate::options::parser::{Arg, MatchedFlags};
 use crate::options::{flags, vars, NumberSource, OptionsError, Vars};
 use crate::output::color_scale::{ColorScaleMode, ColorScaleOptions};
+use crate::output::details::XattrStyle;
 use crate::output::file_name::Options as FileStyle;
-use crate::output::grid_details::{self, RowThreshold};
+use crate::output::grid_details::{self, RowThreshold, Shrink};
 use crate::output::table::{
-    Columns, FlagsFormat, GroupFormat, Options as TableOptions, SizeFormat, TimeTypes, UserFormat,
+    Column, Columns, FlagsFormat, GroupFormat, HeaderNames, Options as TableOptions, SizeFormat,
+    TimeType, TimeTypes, UserFormat, XattrColumn,
 };
 use crate::output::time::TimeFormat;
-use crate::output::{details, grid, Mode, TerminalWidth, View};
+use crate::output::tree::TreeStyle;
+use crate::output::{details, grid, html, template, DerefLinks, Mode, TerminalWidth, View};
 
 impl View {
-    pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+    pub fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+        is_a_tty: bool,
+    ) -> Result<Self, OptionsError> {
         let mode = Mode::deduce(matches, vars)?;
-        let deref_links = matches.has(&flags::DEREF_LINKS)?;
+        let deref_links = DerefLinks::deduce(matches)?;
         let follow_links = matches.has(&flags::FOLLOW_LINKS)?;
-        let total_size = matches.has(&flags::TOTAL_SIZE)?;
+        let total_size = TotalSize::deduce(matches)?;
+        let sections = grid::Options::deduce_number(matches, &flags::SECTIONS)?;
+        let max_results = grid::Options::deduce_number(matches, &flags::MAX_RESULTS)?;
+        let total = if matches.has(&flags::TOTAL)? {
+            Some(SizeFormat::deduce(matches)?)
+        } else {
+            None
+        };
         let width = TerminalWidth::deduce(matches, vars)?;
-        let file_style = FileStyle::deduce(matches, vars, width.actual_terminal_width().is_some())?;
+        let file_style = FileStyle::deduce(matches, vars, is_a_tty)?;
+        let progress = details::Progress::deduce(matches, is_a_tty)?;
         Ok(Self {
             mode,
             width,
@@ -33,6 +51,10 @@ impl View {
             deref_links,
             follow_links,
             total_size,
+            sections,
+            max_results,
+            total,
+            progress,
         })
     }
 }
@@ -47,6 +69,16 @@ impl Mode {
     /// This is complicated a little by the fact that `--grid` and `--tree`
     /// can also combine with `--long`, so care has to be taken to use the
     pub fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if matches.has(&flags::HTML)? {
+            let table = TableOptions::deduce(matches, vars)?;
+            return Ok(Self::Html(html::Options { table }));
+        }
+
+        if let Some(format) = matches.get(&flags::FORMAT)? {
+            let template = template::Options::deduce(format, matches, vars)?;
+            return Ok(Self::Template(template));
+        }
+
         let flag = matches.has_where_any(|f| {
             f.matches(&flags::LONG)
                 || f.matches(&flags::ONE_LINE)
@@ -72,10 +104,12 @@ impl Mode {
 
             if flag.is_some() && flag.unwrap().matches(&flags::GRID) {
                 let _ = matches.has(&flags::GRID)?;
-                let row_threshold = RowThreshold::deduce(vars)?;
+                let row_threshold = RowThreshold::deduce(matches, vars)?;
                 let grid_details = grid_details::Options {
                     details,
                     row_threshold,
+                    across: grid::Options::deduce_across(matches)?,
+                    shrink: grid_details::Options::deduce_shrink(matches)?,
                 };
                 return Ok(Self::GridDetails(grid_details));
             }
@@ -105,17 +139,42 @@ impl Mode {
         // If --long hasn’t been passed, then check if we need to warn the
         // user about flags that won’t have any effect.
         if matches.is_strict() {
+            // The size options also apply to the footer from --total.
+            if !matches.has(&flags::TOTAL)? {
+                for option in &[&flags::BINARY, &flags::BYTES, &flags::SIZE_UNIT] {
+                    if matches.has(option)? {
+                        return Err(OptionsError::Useless(option, false, &flags::LONG));
+                    }
+                }
+            }
+
             for option in &[
-                &flags::BINARY,
-                &flags::BYTES,
                 &flags::INODE,
                 &flags::LINKS,
                 &flags::HEADER,
                 &flags::BLOCKSIZE,
+                &flags::SIZE,
                 &flags::TIME,
                 &flags::GROUP,
                 &flags::NUMERIC,
                 &flags::MOUNTS,
+                &flags::STORAGE_CLASS,
+                &flags::ACCESS_RIGHTS,
+                &flags::COW,
+                &flags::PROJECT_ID,
+                &flags::MIME,
+                &flags::HASH,
+                &flags::IMAGE_INFO,
+                &flags::BINARY_INFO,
+                &flags::PREVIEW,
+                &flags::LINK_TARGET_DETAILS,
+                &flags::VCS_STATUS,
+                &flags::GIT_LOG,
+                &flags::GIT_AUTHOR,
+                &flags::GIT_OWNER,
+                &flags::GIT_SINCE_TAG,
+                &flags::FIELDS,
+                &flags::HEADER_NAMES,
             ] {
                 if matches.has(option)? {
                     return Err(OptionsError::Useless(option, false, &flags::LONG));
@@ -133,6 +192,12 @@ impl Mode {
                     &flags::RECURSE,
                     &flags::TREE,
                 ));
+            } else if matches.has(&flags::TREE_STYLE)? && !matches.has(&flags::TREE)? {
+                return Err(OptionsError::Useless(
+                    &flags::TREE_STYLE,
+                    false,
+                    &flags::TREE,
+                ));
             }
         }
 
@@ -142,12 +207,191 @@ impl Mode {
 
 impl grid::Options {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let min_columns = Self::deduce_number(matches, &flags::GRID_MIN_COLUMNS)?;
+        let max_columns = Self::deduce_number(matches, &flags::GRID_MAX_COLUMNS)?;
+
+        if max_columns == Some(0) {
+            return Err(OptionsError::BadArgument(
+                &flags::GRID_MAX_COLUMNS,
+                "0".into(),
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (min_columns, max_columns) {
+            if min > max {
+                return Err(OptionsError::Conflict(
+                    &flags::GRID_MIN_COLUMNS,
+                    &flags::GRID_MAX_COLUMNS,
+                ));
+            }
+        }
+
         let grid = grid::Options {
-            across: matches.has(&flags::ACROSS)?,
+            across: Self::deduce_across(matches)?,
+            gutter: Self::deduce_number(matches, &flags::GRID_GUTTER)?.unwrap_or(2),
+            min_columns,
+            max_columns,
+            spread: matches.has(&flags::GRID_SPREAD)?,
         };
 
         Ok(grid)
     }
+
+    /// Determine whether the grid should be filled row by row, with
+    /// `--across`, or column by column, with `--down`, which is the default.
+    /// Whichever of the two comes last wins.
+    fn deduce_across(matches: &MatchedFlags<'_>) -> Result<bool, OptionsError> {
+        let flag = matches.has_where(|f| f.matches(&flags::ACROSS) || f.matches(&flags::DOWN))?;
+        Ok(flag.is_some_and(|f| f.matches(&flags::ACROSS)))
+    }
+
+    fn deduce_number(
+        matches: &MatchedFlags<'_>,
+        flag: &'static Arg,
+    ) -> Result<Option<usize>, OptionsError> {
+        if let Some(number) = matches.get(flag)? {
+            let arg_str = number.to_string_lossy();
+            match arg_str.parse() {
+                Ok(n) => Ok(Some(n)),
+                Err(e) => {
+                    let source = NumberSource::Arg(flag);
+                    Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+                }
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl DerefLinks {
+    /// Works out which symbolic links to dereference. `--dereference` does
+    /// them all, so it takes over from `--dereference-command-line`.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let all = matches.has(&flags::DEREF_LINKS)?;
+        let arguments = matches.has(&flags::DEREF_ARGS)?;
+
+        if all && arguments && matches.is_strict() {
+            return Err(OptionsError::Useless(
+                &flags::DEREF_ARGS,
+                true,
+                &flags::DEREF_LINKS,
+            ));
+        }
+
+        Ok(if all {
+            Self::Always
+        } else if arguments {
+            Self::Arguments
+        } else {
+            Self::Never
+        })
+    }
+}
+
+impl TotalSize {
+    /// Works out whether to show the sizes of directories as the sizes of
+    /// everything in them, adding up the files’ apparent sizes by default or
+    /// the space allocated to them, and how deep to look.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        let Some(word) = matches.get(&flags::TOTAL_SIZE)? else {
+            if matches.get(&flags::TOTAL_SIZE_MAX_DEPTH)?.is_some() {
+                return Err(OptionsError::Useless(
+                    &flags::TOTAL_SIZE_MAX_DEPTH,
+                    false,
+                    &flags::TOTAL_SIZE,
+                ));
+            }
+            return Ok(None);
+        };
+
+        let measure = match word.to_str() {
+            Some("apparent") => SizeMeasure::Apparent,
+            Some("allocated") => SizeMeasure::Allocated,
+            _ => return Err(OptionsError::BadArgument(&flags::TOTAL_SIZE, word.into())),
+        };
+
+        let max_depth = match matches.get(&flags::TOTAL_SIZE_MAX_DEPTH)? {
+            Some(depth) => {
+                let arg_str = depth.to_string_lossy();
+                match arg_str.parse::<NonZeroUsize>() {
+                    Ok(depth) => Some(depth.get()),
+                    Err(e) => {
+                        let source = NumberSource::Arg(&flags::TOTAL_SIZE_MAX_DEPTH);
+                        return Err(OptionsError::FailedParse(arg_str.to_string(), source, e));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        Ok(Some(Self { measure, max_depth }))
+    }
+}
+
+impl grid_details::Options {
+    /// What to take away to make room for the grid, in order, from the
+    /// comma-separated list of fields given to `--grid-shrink`, which can
+    /// also include `icons`. Without it, the columns that take up the most
+    /// room for the least information go first, and icons go last.
+    fn deduce_shrink(matches: &MatchedFlags<'_>) -> Result<Vec<Shrink>, OptionsError> {
+        let Some(fields) = matches.get(&flags::GRID_SHRINK)? else {
+            return Ok(Self::default_shrink());
+        };
+
+        fields
+            .to_string_lossy()
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(|field| match field {
+                "icons" => Ok(Shrink::Icons),
+                _ => Column::from_field_name(field)
+                    .map(Shrink::Column)
+                    .ok_or_else(|| OptionsError::BadArgument(&flags::GRID_SHRINK, field.into())),
+            })
+            .collect()
+    }
+
+    #[cfg(unix)]
+    fn default_shrink() -> Vec<Shrink> {
+        vec![
+            Shrink::Column(Column::Group),
+            Shrink::Column(Column::User),
+            Shrink::Column(Column::HardLinks),
+            Shrink::Column(Column::Blocksize),
+            Shrink::Column(Column::Inode),
+            Shrink::Column(Column::Octal),
+            Shrink::Icons,
+        ]
+    }
+
+    #[cfg(windows)]
+    fn default_shrink() -> Vec<Shrink> {
+        vec![
+            Shrink::Column(Column::Group),
+            Shrink::Column(Column::User),
+            Shrink::Icons,
+        ]
+    }
+}
+
+impl template::Options {
+    fn deduce<V: Vars>(
+        format: &OsStr,
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+    ) -> Result<Self, OptionsError> {
+        let template = template::Template::parse(&format.to_string_lossy())
+            .map_err(OptionsError::FailedTemplate)?;
+
+        Ok(Self {
+            template,
+            size_format: SizeFormat::deduce(matches)?,
+            time_format: TimeFormat::deduce(matches, vars)?,
+            user_format: UserFormat::deduce(matches)?,
+        })
+    }
 }
 
 impl details::Options {
@@ -155,11 +399,14 @@ impl details::Options {
         let details = details::Options {
             table: None,
             header: false,
-            xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
+            xattr: XattrStyle::deduce(matches)?,
             secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
+            acl: acl::ENABLED && matches.has(&flags::ACL)?,
+            streams: streams::ENABLED && matches.has(&flags::STREAMS)?,
             mounts: matches.has(&flags::MOUNTS)?,
             color_scale: ColorScaleOptions::deduce(matches, vars)?,
             follow_links: matches.has(&flags::FOLLOW_LINKS)?,
+            tree_style: TreeStyle::deduce(matches)?,
         };
 
         Ok(details)
@@ -169,23 +416,54 @@ impl details::Options {
         if matches.is_strict() {
             if matches.has(&flags::ACROSS)? && !matches.has(&flags::GRID)? {
                 return Err(OptionsError::Useless(&flags::ACROSS, true, &flags::LONG));
+            } else if matches.has(&flags::DOWN)? && !matches.has(&flags::GRID)? {
+                return Err(OptionsError::Useless(&flags::DOWN, true, &flags::LONG));
             } else if matches.has(&flags::ONE_LINE)? {
                 return Err(OptionsError::Useless(&flags::ONE_LINE, true, &flags::LONG));
             }
         }
 
+        let table = TableOptions::deduce(matches, vars)?;
+
+        // Mount details get a column of their own in a table, so the file
+        // names only need them when that column has been taken away.
+        let mounts = matches.has(&flags::MOUNTS)? && !table.columns.contains(Column::Mount);
+
+        #[cfg(unix)]
+        let secattr = table.columns.contains(Column::SecurityContext);
+        #[cfg(windows)]
+        let secattr = false;
+
         Ok(details::Options {
-            table: Some(TableOptions::deduce(matches, vars)?),
-            header: matches.has(&flags::HEADER)?,
-            xattr: xattr::ENABLED && matches.has(&flags::EXTENDED)?,
-            secattr: xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?,
-            mounts: matches.has(&flags::MOUNTS)?,
+            secattr,
+            table: Some(table),
+            header: matches.has(&flags::HEADER)? || matches.get(&flags::HEADER_NAMES)?.is_some(),
+            xattr: XattrStyle::deduce(matches)?,
+            acl: acl::ENABLED && matches.has(&flags::ACL)?,
+            streams: streams::ENABLED && matches.has(&flags::STREAMS)?,
+            mounts,
             color_scale: ColorScaleOptions::deduce(matches, vars)?,
             follow_links: matches.has(&flags::FOLLOW_LINKS)?,
+            tree_style: TreeStyle::deduce(matches)?,
         })
     }
 }
 
+impl details::Progress {
+    /// Rows can only be rewritten on a terminal, and not through the
+    /// pager, which needs whole lines; `--deterministic` keeps them in
+    /// order, as does `--tree`, whose rows have to follow their parents.
+    fn deduce(matches: &MatchedFlags<'_>, is_a_tty: bool) -> Result<Self, OptionsError> {
+        if matches.has(&flags::DETERMINISTIC)? || matches.has(&flags::TREE)? {
+            Ok(Self::Ordered)
+        } else if is_a_tty && !matches.has(&flags::PAGER)? {
+            Ok(Self::Live)
+        } else {
+            Ok(Self::Stream)
+        }
+    }
+}
+
 impl TerminalWidth {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         if let Some(width) = matches.get(&flags::WIDTH)? {
@@ -203,6 +481,8 @@ impl TerminalWidth {
                     Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
                 }
             }
+        } else if matches.has(&flags::DETERMINISTIC)? {
+            Ok(Self::Detached)
         } else if let Some(columns) = vars.get(vars::COLUMNS).and_then(|s| s.into_string().ok()) {
             match columns.parse() {
                 Ok(width) => Ok(Self::Set(width)),
@@ -218,8 +498,13 @@ impl TerminalWidth {
 }
 
 impl RowThreshold {
-    fn deduce<V: Vars>(vars: &V) -> Result<Self, OptionsError> {
-        if let Some(columns) = vars
+    /// Determine the minimum number of rows for the grid-details view from
+    /// `--grid-details-threshold`, falling back to the `EZA_GRID_ROWS`
+    /// environment variable.
+    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
+        if let Some(rows) = grid::Options::deduce_number(matches, &flags::GRID_DETAILS_THRESHOLD)? {
+            Ok(Self::MinimumRows(rows))
+        } else if let Some(columns) = vars
             .get_with_fallback(vars::EZA_GRID_ROWS, vars::EXA_GRID_ROWS)
             .and_then(|s| s.into_string().ok())
         {
@@ -246,7 +531,11 @@ impl TableOptions {
         let user_format = UserFormat::deduce(matches)?;
         let group_format = GroupFormat::deduce(matches)?;
         let flags_format = FlagsFormat::deduce(vars);
-        let columns = Columns::deduce(matches, vars)?;
+        let xattr_columns = ThemeConfig::deduce(vars)
+            .map(|config| config.to_xattr_columns())
+            .unwrap_or_default();
+        let columns = Columns::deduce(matches, vars, &xattr_columns)?;
+        let header_names = HeaderNames::deduce(matches)?;
         Ok(Self {
             size_format,
             time_format,
@@ -254,54 +543,338 @@ impl TableOptions {
             group_format,
             flags_format,
             columns,
+            header_names,
+            xattr_columns,
         })
     }
 }
 
 impl Columns {
-    fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
-        let time_types = TimeTypes::deduce(matches)?;
+    fn deduce<V: Vars>(
+        matches: &MatchedFlags<'_>,
+        vars: &V,
+        xattr_columns: &[XattrColumn],
+    ) -> Result<Self, OptionsError> {
+        let mut columns = Self::deduce_flags(matches)?;
+
+        // Columns declared in the theme file go after all the others.
+        columns.extend((0..xattr_columns.len()).map(Column::Xattr));
+
+        if let Some(fields) = matches.get(&flags::FIELDS)? {
+            Self::apply_fields(&mut columns, &fields.to_string_lossy(), xattr_columns)?;
+        }
 
         let no_git_env = vars
             .get_with_fallback(vars::EXA_OVERRIDE_GIT, vars::EZA_OVERRIDE_GIT)
             .is_some();
+        let no_git = matches.has(&flags::NO_GIT)? || no_git_env;
+
+        columns.retain(|column| match column {
+            Column::GitStatus
+            | Column::GitCommit
+            | Column::GitSubject
+            | Column::GitAuthor
+            | Column::GitOwner
+            | Column::GitSinceTag
+            | Column::Timestamp(TimeType::Git)
+            | Column::SubdirGitRepo(_) => !no_git,
+            #[cfg(unix)]
+            Column::SecurityContext => xattr::ENABLED,
+            Column::FileVersion => version_info::ENABLED,
+            Column::Access => security::ENABLED,
+            Column::Subvolume | Column::Compression | Column::Dataset => cow::ENABLED,
+            Column::Project => chattr::ENABLED,
+            Column::Xattr(_) => xattr::ENABLED,
+            _ => true,
+        });
 
-        let git = matches.has(&flags::GIT)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
-        let subdir_git_repos =
-            matches.has(&flags::GIT_REPOS)? && !matches.has(&flags::NO_GIT)? && !no_git_env;
-        let subdir_git_repos_no_stat = !subdir_git_repos
-            && matches.has(&flags::GIT_REPOS_NO_STAT)?
-            && !matches.has(&flags::NO_GIT)?
-            && !no_git_env;
-
-        let blocksize = matches.has(&flags::BLOCKSIZE)?;
-        let group = matches.has(&flags::GROUP)?;
-        let inode = matches.has(&flags::INODE)?;
-        let links = matches.has(&flags::LINKS)?;
-        let octal = matches.has(&flags::OCTAL)?;
-        let security_context = xattr::ENABLED && matches.has(&flags::SECURITY_CONTEXT)?;
-        let file_flags = matches.has(&flags::FILE_FLAGS)?;
-
-        let permissions = !matches.has(&flags::NO_PERMISSIONS)?;
-        let filesize = !matches.has(&flags::NO_FILESIZE)?;
-        let user = !matches.has(&flags::NO_USER)?;
+        Ok(Self::new(columns))
+    }
 
-        Ok(Self {
-            time_types,
-            inode,
-            links,
-            blocksize,
-            group,
-            git,
-            subdir_git_repos,
-            subdir_git_repos_no_stat,
-            octal,
-            security_context,
-            file_flags,
-            permissions,
-            filesize,
-            user,
-        })
+    /// Picks the columns to show from the individual flags for each one.
+    fn deduce_flags(matches: &MatchedFlags<'_>) -> Result<Vec<Column>, OptionsError> {
+        let time_types = TimeTypes::deduce(matches)?;
+        let mut columns = Vec::with_capacity(4);
+
+        #[cfg(unix)]
+        if matches.has(&flags::INODE)? {
+            columns.push(Column::Inode);
+        }
+
+        #[cfg(unix)]
+        if matches.has(&flags::OCTAL)? {
+            columns.push(Column::Octal);
+        }
+
+        if !matches.has(&flags::NO_PERMISSIONS)? {
+            columns.push(Column::Permissions);
+        }
+
+        #[cfg(unix)]
+        if matches.has(&flags::LINKS)? {
+            columns.push(Column::HardLinks);
+        }
+
+        // Allocated sizes are only known on Unix, where they’re the same
+        // as what the blocksize column lists.
+        #[cfg_attr(windows, allow(unused_variables))]
+        let (apparent, allocated) = Self::deduce_sizes(matches)?;
+
+        if apparent && !matches.has(&flags::NO_FILESIZE)? {
+            columns.push(Column::FileSize);
+        }
+
+        // With links dereferenced, the other columns already describe the
+        // targets, so these would only be repeating them.
+        if matches.has(&flags::LINK_TARGET_DETAILS)? && !matches.has(&flags::DEREF_LINKS)? {
+            columns.push(Column::TargetPermissions);
+            columns.push(Column::TargetSize);
+        }
+
+        #[cfg(unix)]
+        if allocated || matches.has(&flags::BLOCKSIZE)? {
+            columns.push(Column::Blocksize);
+        }
+
+        if !matches.has(&flags::NO_USER)? {
+            columns.push(Column::User);
+        }
+
+        if matches.has(&flags::GROUP)? {
+            columns.push(Column::Group);
+        }
+
+        if matches.has(&flags::FILE_FLAGS)? || matches.has(&flags::FILE_FLAGS_ALIAS)? {
+            columns.push(Column::FileFlags);
+        }
+
+        #[cfg(unix)]
+        if matches.has(&flags::SECURITY_CONTEXT)? {
+            columns.push(Column::SecurityContext);
+        }
+
+        if matches.has(&flags::FILE_VERSION)? {
+            columns.push(Column::FileVersion);
+        }
+
+        if matches.has(&flags::ACCESS_RIGHTS)? {
+            columns.push(Column::Access);
+        }
+
+        if matches.has(&flags::COW)? {
+            columns.push(Column::Subvolume);
+            columns.push(Column::Compression);
+            columns.push(Column::Dataset);
+        }
+
+        if matches.has(&flags::PROJECT_ID)? {
+            columns.push(Column::Project);
+        }
+
+        if matches.has(&flags::STORAGE_CLASS)? {
+            columns.push(Column::StorageClass);
+        }
+
+        if matches.has(&flags::MIME)? {
+            columns.push(Column::Mime);
+        }
+
+        if matches.has(&flags::HASH)? {
+            columns.push(Column::Hash);
+        }
+
+        if matches.has(&flags::IMAGE_INFO)? {
+            columns.push(Column::Dimensions);
+        }
+
+        if matches.has(&flags::BINARY_INFO)? {
+            columns.push(Column::Binary);
+        }
+
+        if time_types.modified {
+            columns.push(Column::Timestamp(TimeType::Modified));
+        }
+
+        if time_types.changed {
+            columns.push(Column::Timestamp(TimeType::Changed));
+        }
+
+        if time_types.created {
+            columns.push(Column::Timestamp(TimeType::Created));
+        }
+
+        if time_types.accessed {
+            columns.push(Column::Timestamp(TimeType::Accessed));
+        }
+
+        if time_types.git {
+            columns.push(Column::Timestamp(TimeType::Git));
+        }
+
+        if matches.has(&flags::GIT)? {
+            columns.push(Column::GitStatus);
+        }
+
+        if matches.has(&flags::VCS_STATUS)? {
+            columns.push(Column::VcsStatus);
+        }
+
+        if matches.has(&flags::GIT_LOG)? {
+            columns.push(Column::GitCommit);
+            columns.push(Column::GitSubject);
+        }
+
+        if matches.has(&flags::GIT_AUTHOR)? {
+            columns.push(Column::GitAuthor);
+        }
+
+        if matches.has(&flags::GIT_OWNER)? {
+            columns.push(Column::GitOwner);
+        }
+
+        if matches.has(&flags::GIT_SINCE_TAG)? {
+            columns.push(Column::GitSinceTag);
+        }
+
+        if matches.has(&flags::GIT_REPOS)? {
+            columns.push(Column::SubdirGitRepo(true));
+        } else if matches.has(&flags::GIT_REPOS_NO_STAT)? {
+            columns.push(Column::SubdirGitRepo(false));
+        }
+
+        if matches.has(&flags::MOUNTS)? {
+            columns.push(Column::Mount);
+        }
+
+        if let Some(length) = matches.get(&flags::PREVIEW)? {
+            columns.push(Column::Preview(Self::deduce_preview_length(length)?));
+        }
+
+        Ok(columns)
+    }
+
+    /// Parses the number of characters `--preview` shows, which has to be
+    /// at least one.
+    fn deduce_preview_length(length: &OsStr) -> Result<usize, OptionsError> {
+        let arg_str = length.to_string_lossy();
+        match arg_str.parse::<NonZeroUsize>() {
+            Ok(length) => Ok(length.get()),
+            Err(e) => {
+                let source = NumberSource::Arg(&flags::PREVIEW);
+                Err(OptionsError::FailedParse(arg_str.to_string(), source, e))
+            }
+        }
+    }
+
+    /// Picks which of a file’s sizes to list from `--size`: its apparent
+    /// size, which is the default, the size of the blocks allocated to it,
+    /// or both, which tells sparse files apart from dense ones.
+    fn deduce_sizes(matches: &MatchedFlags<'_>) -> Result<(bool, bool), OptionsError> {
+        let Some(word) = matches.get(&flags::SIZE)? else {
+            return Ok((true, false));
+        };
+
+        match word.to_str() {
+            Some("apparent") => Ok((true, false)),
+            Some("allocated") => Ok((false, true)),
+            Some("both") => Ok((true, true)),
+            _ => Err(OptionsError::BadArgument(&flags::SIZE, word.into())),
+        }
+    }
+
+    /// Applies the comma-separated list of fields given to `--fields`. A
+    /// list that starts with a plain field name replaces the columns picked
+    /// by the other flags, while `+field` and `-field` add a column to the
+    /// end or take one away.
+    fn apply_fields(
+        columns: &mut Vec<Column>,
+        fields: &str,
+        xattr_columns: &[XattrColumn],
+    ) -> Result<(), OptionsError> {
+        let mut fields = fields.split(',').map(str::trim).peekable();
+        if fields.peek().is_some_and(|f| !f.starts_with(['+', '-'])) {
+            columns.clear();
+        }
+
+        let mut seen_name = false;
+        for field in fields {
+            let (remove, name) = match field.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, field.strip_prefix('+').unwrap_or(field)),
+            };
+
+            // The file name always goes at the end of each row, so it can
+            // only come last, and can’t be taken away.
+            if seen_name {
+                return Err(OptionsError::Unsupported(
+                    "The name field must come last in --fields".into(),
+                ));
+            } else if name == "name" {
+                if remove {
+                    return Err(OptionsError::Unsupported(
+                        "The name field cannot be removed with --fields".into(),
+                    ));
+                }
+
+                seen_name = true;
+                continue;
+            }
+
+            // Columns from the theme file go by their attributes’ names.
+            let column = Column::from_field_name(name).or_else(|| {
+                xattr_columns
+                    .iter()
+                    .position(|c| c.attribute == name)
+                    .map(Column::Xattr)
+            });
+
+            let Some(column) = column else {
+                return Err(OptionsError::BadArgument(&flags::FIELDS, name.into()));
+            };
+
+            if remove {
+                columns.retain(|c| *c != column);
+            } else if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HeaderNames {
+    /// Reads the comma-separated list of `field:heading` pairs given to
+    /// `--header-names`. An empty heading leaves that column’s heading blank.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(list) = matches.get(&flags::HEADER_NAMES)? else {
+            return Ok(Self::default());
+        };
+
+        let mut columns = Vec::new();
+        let mut name = None;
+
+        for entry in list.to_string_lossy().split(',') {
+            let Some((field, heading)) = entry.split_once(':') else {
+                return Err(OptionsError::BadArgument(
+                    &flags::HEADER_NAMES,
+                    entry.into(),
+                ));
+            };
+
+            if field == "name" {
+                name = Some(heading.to_owned());
+            } else if let Some(column) = Column::from_field_name(field) {
+                columns.push((column, heading.to_owned()));
+            } else {
+                return Err(OptionsError::BadArgument(
+                    &flags::HEADER_NAMES,
+                    field.into(),
+                ));
+            }
+        }
+
+        Ok(Self::new(columns, name))
     }
 }
 
@@ -313,8 +886,15 @@ impl SizeFormat {
     /// most commonly-understood, and don’t involve trying to parse large
     /// strings of digits in your head. Changing the format to anything else
     /// involves the `--binary` or `--bytes` flags, and these conflict with
-    /// each other.
+    /// each other, or the `--size-unit` flag, which overrides both.
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        if let Some(unit) = matches.get(&flags::SIZE_UNIT)? {
+            return unit
+                .to_str()
+                .and_then(Self::from_unit)
+                .ok_or_else(|| OptionsError::BadArgument(&flags::SIZE_UNIT, unit.into()));
+        }
+
         let flag = matches.has_where(|f| f.matches(&flags::BINARY) || f.matches(&flags::BYTES))?;
 
         Ok(match flag {
@@ -330,6 +910,9 @@ impl TimeFormat {
     fn deduce<V: Vars>(matches: &MatchedFlags<'_>, vars: &V) -> Result<Self, OptionsError> {
         let word = if let Some(w) = matches.get(&flags::TIME_STYLE)? {
             w.to_os_string()
+        } else if matches.has(&flags::DETERMINISTIC)? {
+            // The default style depends on the current year.
+            return Ok(Self::LongISO);
         } else {
             match vars.get(vars::TIME_STYLE) {
                 Some(ref t) if !t.is_empty() => t.clone(),
@@ -344,43 +927,50 @@ impl TimeFormat {
             "long-iso" => Ok(Self::LongISO),
             "full-iso" => Ok(Self::FullISO),
             fmt if fmt.starts_with('+') => {
-                let mut lines = fmt[1..].lines();
-
-                // line 1 will be None when:
-                //   - there is nothing after `+`
-                // line 1 will be empty when:
-                //   - `+` is followed immediately by `\n`
-                let empty_non_recent_format_msg = "Custom timestamp format is empty, \
-                    please supply a chrono format string after the plus sign.";
-                let non_recent = lines.next().expect(empty_non_recent_format_msg);
-                let non_recent = if non_recent.is_empty() {
-                    panic!("{}", empty_non_recent_format_msg)
-                } else {
-                    non_recent.to_owned()
-                };
-
-                // line 2 will be None when:
-                //   - there is not a single `\n`
-                //   - there is nothing after the first `\n`
-                // line 2 will be empty when:
-                //   - there exist at least 2 `\n`, and no content between the 1st and 2nd `\n`
-                let empty_recent_format_msg = "Custom timestamp format for recent files is empty, \
-                    please supply a chrono format string at the second line.";
-                let recent = lines.next().map(|rec| {
-                    if rec.is_empty() {
-                        panic!("{}", empty_recent_format_msg)
-                    } else {
-                        rec.to_owned()
-                    }
-                });
-
-                Ok(Self::Custom { non_recent, recent })
+                Self::custom(&fmt[1..]).map_err(OptionsError::FailedTimeFormat)
             }
             _ => Err(OptionsError::BadArgument(&flags::TIME_STYLE, word)),
         }
     }
 }
 
+impl XattrStyle {
+    /// Determine how to list each file’s extended attributes: the way
+    /// `--xattrs` says to, or in full with `--extended`.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Option<Self>, OptionsError> {
+        if !xattr::ENABLED {
+            return Ok(None);
+        }
+
+        let Some(word) = matches.get(&flags::XATTRS)? else {
+            return Ok(matches.has(&flags::EXTENDED)?.then_some(Self::Extended));
+        };
+
+        match word.to_str() {
+            Some("names") => Ok(Some(Self::Names)),
+            Some("values") => Ok(Some(Self::Values)),
+            _ => Err(OptionsError::BadArgument(&flags::XATTRS, word.into())),
+        }
+    }
+}
+
+impl TreeStyle {
+    /// Determine which characters to draw trees with, from the argument
+    /// to `--tree-style`, which defaults to the Unicode box drawing ones.
+    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+        let Some(word) = matches.get(&flags::TREE_STYLE)? else {
+            return Ok(Self::default());
+        };
+
+        match word.to_str() {
+            Some("unicode") => Ok(Self::Unicode),
+            Some("rounded") => Ok(Self::Rounded),
+            Some("ascii") => Ok(Self::Ascii),
+            _ => Err(OptionsError::BadArgument(&flags::TREE_STYLE, word.into())),
+        }
+    }
+}
+
 impl UserFormat {
     fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let flag = matches.has(&flags::NUMERIC)?;
@@ -406,7 +996,7 @@ impl TimeTypes {
     /// It’s valid to show more than one column by passing in more than one
     /// option, but passing *no* options means that the user just wants to
     /// see the default set.
-    fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
+    pub fn deduce(matches: &MatchedFlags<'_>) -> Result<Self, OptionsError> {
         let possible_word = matches.get(&flags::TIME)?;
         let modified = matches.has(&flags::MODIFIED)?;
         let changed = matches.has(&flags::CHANGED)?;
@@ -422,6 +1012,7 @@ impl TimeTypes {
                 changed: false,
                 accessed: false,
                 created: false,
+                git: false,
             }
         } else if let Some(word) = possible_word {
             if modified {
@@ -433,13 +1024,15 @@ impl TimeTypes {
             } else if created {
                 return Err(OptionsError::Useless(&flags::CREATED, true, &flags::TIME));
             } else if word == "mod" || word == "modified" {
-                Self { modified: true,  changed: false, accessed: false, created: false }
+                Self { modified: true,  changed: false, accessed: false, created: false, git: false }
             } else if word == "ch" || word == "changed" {
-                Self { modified: false, changed: true,  accessed: false, created: false }
+                Self { modified: false, changed: true,  accessed: false, created: false, git: false }
             } else if word == "acc" || word == "accessed" {
-                Self { modified: false, changed: false, accessed: true,  created: false }
+                Self { modified: false, changed: false, accessed: true,  created: false, git: false }
             } else if word == "cr" || word == "created" {
-                Self { modified: false, changed: false, accessed: false, created: true  }
+                Self { modified: false, changed: false, accessed: false, created: true,  git: false }
+            } else if word == "git" {
+                Self { modified: false, changed: false, accessed: false, created: false, git: true  }
             } else {
                 return Err(OptionsError::BadArgument(&flags::TIME, word.into()));
             }
@@ -449,6 +1042,7 @@ impl TimeTypes {
                 changed,
                 accessed,
                 created,
+                git: false,
             }
         } else {
             Self::default()
@@ -533,6 +1127,9 @@ mod test {
     static TEST_ARGS: &[&Arg] = &[
         &flags::BINARY,
         &flags::BYTES,
+        &flags::TOTAL_SIZE,
+        &flags::TOTAL_SIZE_MAX_DEPTH,
+        &flags::SIZE_UNIT,
         &flags::TIME_STYLE,
         &flags::TIME,
         &flags::MODIFIED,
@@ -543,15 +1140,56 @@ mod test {
         &flags::GROUP,
         &flags::INODE,
         &flags::GIT,
+        &flags::GIT_LOG,
+        &flags::GIT_AUTHOR,
+        &flags::GIT_OWNER,
+        &flags::GIT_SINCE_TAG,
+        &flags::NO_GIT,
+        &flags::VCS_STATUS,
         &flags::LINKS,
         &flags::BLOCKSIZE,
+        &flags::SIZE,
         &flags::LONG,
         &flags::LEVEL,
         &flags::GRID,
         &flags::ACROSS,
+        &flags::DOWN,
         &flags::ONE_LINE,
         &flags::TREE,
+        &flags::TREE_STYLE,
         &flags::NUMERIC,
+        &flags::GRID_GUTTER,
+        &flags::GRID_MIN_COLUMNS,
+        &flags::GRID_MAX_COLUMNS,
+        &flags::GRID_SPREAD,
+        &flags::GRID_SHRINK,
+        &flags::GRID_DETAILS_THRESHOLD,
+        &flags::FORMAT,
+        &flags::HTML,
+        &flags::FIELDS,
+        &flags::NO_USER,
+        &flags::HEADER_NAMES,
+        &flags::INLINE_ERRORS,
+        &flags::DETERMINISTIC,
+        &flags::MOUNTS,
+        &flags::STORAGE_CLASS,
+        &flags::ACCESS_RIGHTS,
+        &flags::COW,
+        &flags::PROJECT_ID,
+        &flags::MIME,
+        &flags::HASH,
+        &flags::IMAGE_INFO,
+        &flags::BINARY_INFO,
+        &flags::PREVIEW,
+        &flags::ACL,
+        &flags::FILE_FLAGS,
+        &flags::FILE_FLAGS_ALIAS,
+        &flags::EXTENDED,
+        &flags::XATTRS,
+        &flags::STREAMS,
+        &flags::LINK_TARGET_DETAILS,
+        &flags::DEREF_LINKS,
+        &flags::DEREF_ARGS,
     ];
 
     #[allow(unused_macro_rules)]
@@ -648,6 +1286,15 @@ mod test {
         test!(both_6:  SizeFormat <- ["--bytes",  "--binary"];  Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("binary")));
         test!(both_7:  SizeFormat <- ["--binary", "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("binary"), Flag::Long("bytes")));
         test!(both_8:  SizeFormat <- ["--bytes",  "--bytes"];   Complain => err OptionsError::Duplicate(Flag::Long("bytes"),  Flag::Long("bytes")));
+
+        // Units
+        test!(unit_bytes:    SizeFormat <- ["--size-unit=bytes"];            Both => Ok(SizeFormat::JustBytes));
+        test!(unit_sectors:  SizeFormat <- ["--size-unit=sectors"];          Both => Ok(SizeFormat::Units(512)));
+        test!(unit_pages:    SizeFormat <- ["--size-unit", "pages"];         Both => Ok(SizeFormat::Units(4096)));
+        test!(unit_blocks:   SizeFormat <- ["--size-unit=blocks:1024"];      Both => Ok(SizeFormat::Units(1024)));
+        test!(unit_override: SizeFormat <- ["--binary", "--size-unit=pages"]; Both => Ok(SizeFormat::Units(4096)));
+        test!(unit_zero:     SizeFormat <- ["--size-unit=blocks:0"];         Both => Err(OptionsError::BadArgument(&flags::SIZE_UNIT, OsString::from("blocks:0"))));
+        test!(unit_bad:      SizeFormat <- ["--size-unit=furlongs"];         Both => Err(OptionsError::BadArgument(&flags::SIZE_UNIT, OsString::from("furlongs"))));
     }
 
     mod time_formats {
@@ -668,6 +1315,10 @@ mod test {
         test!(custom_style:           TimeFormat <- ["--time-style", "+%Y/%m/%d"], None;          Both => like Ok(TimeFormat::Custom { recent: None, .. }));
         test!(custom_style_multiline: TimeFormat <- ["--time-style", "+%Y/%m/%d\n--%m-%d"], None; Both => like Ok(TimeFormat::Custom { recent: Some(_), .. }));
         test!(bad_custom_style:       TimeFormat <- ["--time-style", "%Y/%m/%d"], None;           Both => err OptionsError::BadArgument(&flags::TIME_STYLE, OsString::from("%Y/%m/%d")));
+        test!(custom_style_offset:    TimeFormat <- ["--time-style", "+%Y-%m-%dT%H:%M:%S%z"], None; Both => like Ok(TimeFormat::Custom { recent: None, .. }));
+        test!(empty_custom_style:     TimeFormat <- ["--time-style", "+"], None;                  Both => like Err(OptionsError::FailedTimeFormat(_)));
+        test!(empty_recent_style:     TimeFormat <- ["--time-style", "+%Y\n\n%H"], None;        Both => like Err(OptionsError::FailedTimeFormat(_)));
+        test!(invalid_custom_style:   TimeFormat <- ["--time-style", "+%Y-%Q"], None;             Both => like Err(OptionsError::FailedTimeFormat(_)));
 
         // Overriding
         test!(actually:  TimeFormat <- ["--time-style=default", "--time-style", "iso"], None;  Last => like Ok(TimeFormat::ISOFormat));
@@ -685,6 +1336,10 @@ mod test {
 
         // If the time-style argument is given, `TIME_STYLE` is overriding.
         test!(override_env:     TimeFormat <- ["--time-style=full-iso"], Some("long-iso".into());  Both => like Ok(TimeFormat::FullISO));
+
+        // `--deterministic` ignores `TIME_STYLE`, but not the argument.
+        test!(deterministic:        TimeFormat <- ["--deterministic"], Some("iso".into());            Both => like Ok(TimeFormat::LongISO));
+        test!(deterministic_style:  TimeFormat <- ["--deterministic", "--time-style=iso"], None;      Both => like Ok(TimeFormat::ISOFormat));
     }
 
     mod time_types {
@@ -694,40 +1349,43 @@ mod test {
         test!(empty:     TimeTypes <- [];                      Both => Ok(TimeTypes::default()));
 
         // Modified
-        test!(modified:  TimeTypes <- ["--modified"];          Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false }));
-        test!(m:         TimeTypes <- ["-m"];                  Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false }));
-        test!(time_mod:  TimeTypes <- ["--time=modified"];     Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false }));
-        test!(t_m:       TimeTypes <- ["-tmod"];               Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false }));
+        test!(modified:  TimeTypes <- ["--modified"];          Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false, git: false }));
+        test!(m:         TimeTypes <- ["-m"];                  Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false, git: false }));
+        test!(time_mod:  TimeTypes <- ["--time=modified"];     Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false, git: false }));
+        test!(t_m:       TimeTypes <- ["-tmod"];               Both => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false, git: false }));
 
         // Changed
         #[cfg(target_family = "unix")]
-        test!(changed:   TimeTypes <- ["--changed"];           Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false }));
+        test!(changed:   TimeTypes <- ["--changed"];           Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false, git: false }));
         #[cfg(target_family = "unix")]
-        test!(time_ch:   TimeTypes <- ["--time=changed"];      Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false }));
+        test!(time_ch:   TimeTypes <- ["--time=changed"];      Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false, git: false }));
         #[cfg(target_family = "unix")]
-        test!(t_ch:    TimeTypes <- ["-t", "ch"];              Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false }));
+        test!(t_ch:    TimeTypes <- ["-t", "ch"];              Both => Ok(TimeTypes { modified: false, changed: true,  accessed: false, created: false, git: false }));
 
         // Accessed
-        test!(acc:       TimeTypes <- ["--accessed"];          Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false }));
-        test!(a:         TimeTypes <- ["-u"];                  Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false }));
-        test!(time_acc:  TimeTypes <- ["--time", "accessed"];  Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false }));
-        test!(time_a:    TimeTypes <- ["-t", "acc"];           Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false }));
+        test!(acc:       TimeTypes <- ["--accessed"];          Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false, git: false }));
+        test!(a:         TimeTypes <- ["-u"];                  Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false, git: false }));
+        test!(time_acc:  TimeTypes <- ["--time", "accessed"];  Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false, git: false }));
+        test!(time_a:    TimeTypes <- ["-t", "acc"];           Both => Ok(TimeTypes { modified: false, changed: false, accessed: true,  created: false, git: false }));
 
         // Created
-        test!(cr:        TimeTypes <- ["--created"];           Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true  }));
-        test!(c:         TimeTypes <- ["-U"];                  Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true  }));
-        test!(time_cr:   TimeTypes <- ["--time=created"];      Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true  }));
-        test!(t_cr:      TimeTypes <- ["-tcr"];                Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true  }));
+        test!(cr:        TimeTypes <- ["--created"];           Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true,  git: false }));
+        test!(c:         TimeTypes <- ["-U"];                  Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true,  git: false }));
+        test!(time_cr:   TimeTypes <- ["--time=created"];      Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true,  git: false }));
+        test!(t_cr:      TimeTypes <- ["-tcr"];                Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: true,  git: false }));
+
+        // Git
+        test!(time_git:  TimeTypes <- ["--time=git"];          Both => Ok(TimeTypes { modified: false, changed: false, accessed: false, created: false, git: true  }));
 
         // Multiples
-        test!(time_uu:   TimeTypes <- ["-u", "--modified"];    Both => Ok(TimeTypes { modified: true,  changed: false, accessed: true,  created: false }));
+        test!(time_uu:   TimeTypes <- ["-u", "--modified"];    Both => Ok(TimeTypes { modified: true,  changed: false, accessed: true,  created: false, git: false }));
 
         // Errors
         test!(time_tea:  TimeTypes <- ["--time=tea"];          Both => err OptionsError::BadArgument(&flags::TIME, OsString::from("tea")));
         test!(t_ea:      TimeTypes <- ["-tea"];                Both => err OptionsError::BadArgument(&flags::TIME, OsString::from("ea")));
 
         // Overriding
-        test!(overridden:   TimeTypes <- ["-tcr", "-tmod"];    Last => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false }));
+        test!(overridden:   TimeTypes <- ["-tcr", "-tmod"];    Last => Ok(TimeTypes { modified: true,  changed: false, accessed: false, created: false, git: false }));
         test!(overridden_2: TimeTypes <- ["-tcr", "-tmod"];    Complain => err OptionsError::Duplicate(Flag::Short(b't'), Flag::Short(b't')));
     }
 
@@ -744,18 +1402,62 @@ mod test {
         test!(grid:          Mode <- ["--grid"], None;    Both => like Ok(Mode::Grid(GridOptions { across: false, .. })));
         test!(across:        Mode <- ["--across"], None;  Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
         test!(gracross:      Mode <- ["-xG"], None;       Both => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
+        test!(down:          Mode <- ["--down"], None;    Both => like Ok(Mode::Grid(GridOptions { across: false, .. })));
+        test!(across_down:   Mode <- ["--across", "--down"], None;  Last => like Ok(Mode::Grid(GridOptions { across: false, .. })));
+        test!(down_across:   Mode <- ["--down", "--across"], None;  Last => like Ok(Mode::Grid(GridOptions { across: true,  .. })));
+        test!(down_across_2: Mode <- ["--down", "--across"], None;  Complain => err OptionsError::Duplicate(Flag::Long("down"), Flag::Long("across")));
+        test!(gutter:        Mode <- ["--grid-gutter=4"], None;     Both => like Ok(Mode::Grid(GridOptions { gutter: 4, .. })));
+        test!(no_gutter:     Mode <- [], None;                      Both => like Ok(Mode::Grid(GridOptions { gutter: 2, .. })));
+        test!(min_columns:   Mode <- ["--grid-min-columns", "3"], None;  Both => like Ok(Mode::Grid(GridOptions { min_columns: Some(3), max_columns: None, .. })));
+        test!(max_columns:   Mode <- ["--grid-max-columns=5"], None;     Both => like Ok(Mode::Grid(GridOptions { min_columns: None, max_columns: Some(5), .. })));
+        test!(spread:        Mode <- ["--grid-spread"], None;       Both => like Ok(Mode::Grid(GridOptions { spread: true, .. })));
+        test!(no_columns:    Mode <- ["--grid-max-columns=0"], None;     Both => err OptionsError::BadArgument(&flags::GRID_MAX_COLUMNS, OsString::from("0")));
+        test!(columns_clash: Mode <- ["--grid-min-columns=6", "--grid-max-columns=2"], None;  Both => err OptionsError::Conflict(&flags::GRID_MIN_COLUMNS, &flags::GRID_MAX_COLUMNS));
 
         // Lines views
         test!(lines:         Mode <- ["--oneline"], None;     Both => like Ok(Mode::Lines));
         test!(prima:         Mode <- ["-1"], None;            Both => like Ok(Mode::Lines));
 
+        // Template views
+        test!(format:        Mode <- ["--format={name}"], None;            Both => like Ok(Mode::Template(_)));
+        test!(format_long:   Mode <- ["--long", "--format", "{size}"], None;  Both => like Ok(Mode::Template(_)));
+        test!(format_bad:    Mode <- ["--format={colour}"], None;          Both => like Err(OptionsError::FailedTemplate(_)));
+
+        // HTML views
+        test!(html:          Mode <- ["--html"], None;            Both => like Ok(Mode::Html(_)));
+        test!(html_long:     Mode <- ["--long", "--html"], None;  Both => like Ok(Mode::Html(_)));
+
         // Details views
         test!(long:          Mode <- ["--long"], None;    Both => like Ok(Mode::Details(_)));
         test!(ell:           Mode <- ["-l"], None;        Both => like Ok(Mode::Details(_)));
 
+        #[cfg(target_os = "linux")]
+        test!(acl:           Mode <- ["--long", "--acl"], None;   Both => like Ok(Mode::Details(details::Options { acl: true, .. })));
+        #[cfg(target_os = "linux")]
+        test!(tree_acl:      Mode <- ["--tree", "--acl"], None;   Both => like Ok(Mode::Details(details::Options { acl: true, table: None, .. })));
+        test!(no_acl:        Mode <- ["--long"], None;            Both => like Ok(Mode::Details(details::Options { acl: false, .. })));
+
+        // Extended attributes
+        #[cfg(target_os = "linux")]
+        test!(extended:      Mode <- ["--long", "--extended"], None;    Both => like Ok(Mode::Details(details::Options { xattr: Some(XattrStyle::Extended), .. })));
+        #[cfg(target_os = "linux")]
+        test!(xattr_names:   Mode <- ["--long", "--xattrs=names"], None; Both => like Ok(Mode::Details(details::Options { xattr: Some(XattrStyle::Names), .. })));
+        #[cfg(target_os = "linux")]
+        test!(xattr_values:  Mode <- ["--tree", "--xattrs", "values"], None; Both => like Ok(Mode::Details(details::Options { xattr: Some(XattrStyle::Values), table: None, .. })));
+        #[cfg(target_os = "linux")]
+        test!(xattr_sizes:   Mode <- ["--long", "--xattrs=sizes"], None; Both => err OptionsError::BadArgument(&flags::XATTRS, OsString::from("sizes")));
+        test!(no_xattrs:     Mode <- ["--long"], None;            Both => like Ok(Mode::Details(details::Options { xattr: None, .. })));
+        #[cfg(windows)]
+        test!(streams:       Mode <- ["--long", "--streams"], None;  Both => like Ok(Mode::Details(details::Options { streams: true, .. })));
+        #[cfg(unix)]
+        test!(no_streams:    Mode <- ["--long", "--streams"], None;  Both => like Ok(Mode::Details(details::Options { streams: false, .. })));
+
         // Grid-details views
         test!(lid:           Mode <- ["--long", "--grid"], None;  Both => like Ok(Mode::GridDetails(_)));
         test!(leg:           Mode <- ["-lG"], None;               Both => like Ok(Mode::GridDetails(_)));
+        test!(threshold:     Mode <- ["-lG", "--grid-details-threshold=4"], None;  Both => like Ok(Mode::GridDetails(grid_details::Options { row_threshold: RowThreshold::MinimumRows(4), .. })));
+        test!(no_threshold:  Mode <- ["-lG"], None;               Both => like Ok(Mode::GridDetails(grid_details::Options { row_threshold: RowThreshold::AlwaysGrid, .. })));
+        test!(bad_threshold: Mode <- ["-lG", "--grid-details-threshold=lots"], None;  Both => like Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _)));
 
         // Options that do nothing with --long
         test!(long_across:   Mode <- ["--long", "--across"],   None;  Last => like Ok(Mode::Details(_)));
@@ -769,6 +1471,7 @@ mod test {
         test!(just_binary:   Mode <- ["--binary"],    None;  Last => like Ok(Mode::Grid(_)));
         test!(just_bytes:    Mode <- ["--bytes"],     None;  Last => like Ok(Mode::Grid(_)));
         test!(just_numeric:  Mode <- ["--numeric"],   None;  Last => like Ok(Mode::Grid(_)));
+        test!(just_vcs:      Mode <- ["--vcs-status"], None; Last => like Ok(Mode::Grid(_)));
 
         #[cfg(feature = "git")]
         test!(just_git:      Mode <- ["--git"],       None;  Last => like Ok(Mode::Grid(_)));
@@ -781,6 +1484,7 @@ mod test {
         test!(just_binary_2: Mode <- ["--binary"],    None;  Complain => err OptionsError::Useless(&flags::BINARY,  false, &flags::LONG));
         test!(just_bytes_2:  Mode <- ["--bytes"],     None;  Complain => err OptionsError::Useless(&flags::BYTES,   false, &flags::LONG));
         test!(just_numeric2: Mode <- ["--numeric"],   None;  Complain => err OptionsError::Useless(&flags::NUMERIC, false, &flags::LONG));
+        test!(just_vcs_2:    Mode <- ["--vcs-status"], None; Complain => err OptionsError::Useless(&flags::VCS_STATUS, false, &flags::LONG));
 
         #[cfg(feature = "git")]
         test!(just_git_2:    Mode <- ["--git"],    None;  Complain => err OptionsError::Useless(&flags::GIT,    false, &flags::LONG));
@@ -794,4 +1498,596 @@ mod test {
         test!(og:            Mode <- ["--oneline", "--grid"],           None;  Both => like Ok(Mode::Grid(_)));
         test!(tg:            Mode <- ["--tree", "--grid"],              None;  Both => like Ok(Mode::Grid(_)));
     }
+
+    mod total_sizes {
+        use super::*;
+
+        test!(empty:      TotalSize <- [];                                   Both => Ok(None));
+        test!(apparent:   TotalSize <- ["--total-size"];                     Both => Ok(Some(TotalSize { measure: SizeMeasure::Apparent, max_depth: None })));
+        test!(allocated:  TotalSize <- ["--total-size=allocated"];           Both => Ok(Some(TotalSize { measure: SizeMeasure::Allocated, max_depth: None })));
+        test!(max_depth:  TotalSize <- ["--total-size", "--total-size-max-depth", "2"];  Both => Ok(Some(TotalSize { measure: SizeMeasure::Apparent, max_depth: Some(2) })));
+        test!(unknown:    TotalSize <- ["--total-size=both"];                Both => err OptionsError::BadArgument(&flags::TOTAL_SIZE, "both".into()));
+        test!(zero_depth: TotalSize <- ["--total-size", "--total-size-max-depth=0"];  Both => like Err(OptionsError::FailedParse(..)));
+        test!(no_total:   TotalSize <- ["--total-size-max-depth=2"];         Both => err OptionsError::Useless(&flags::TOTAL_SIZE_MAX_DEPTH, false, &flags::TOTAL_SIZE));
+    }
+
+    mod deref_links {
+        use super::*;
+
+        test!(empty:      DerefLinks <- [];                                         Both => Ok(DerefLinks::Never));
+        test!(all:        DerefLinks <- ["--dereference"];                          Both => Ok(DerefLinks::Always));
+        test!(arguments:  DerefLinks <- ["--dereference-command-line"];             Both => Ok(DerefLinks::Arguments));
+        test!(both:       DerefLinks <- ["-X", "--dereference-command-line"];       Last => Ok(DerefLinks::Always));
+        test!(both_2:     DerefLinks <- ["-X", "--dereference-command-line"];       Complain => err OptionsError::Useless(&flags::DEREF_ARGS, true, &flags::DEREF_LINKS));
+    }
+
+    mod tree_styles {
+        use super::*;
+
+        test!(default:  TreeStyle <- [];                        Both => Ok(TreeStyle::Unicode));
+        test!(ascii:    TreeStyle <- ["--tree-style=ascii"];    Both => Ok(TreeStyle::Ascii));
+        test!(rounded:  TreeStyle <- ["--tree-style", "rounded"];  Both => Ok(TreeStyle::Rounded));
+        test!(unknown:  TreeStyle <- ["--tree-style=curly"];    Both => err OptionsError::BadArgument(&flags::TREE_STYLE, "curly".into()));
+    }
+
+    mod header_names {
+        use super::*;
+
+        test!(empty:    HeaderNames <- [];  Both => Ok(HeaderNames::default()));
+        test!(renamed:  HeaderNames <- ["--header-names=size:SZ,name:File"];  Both => Ok(HeaderNames::new(vec![(Column::FileSize, "SZ".into())], Some("File".into()))));
+        test!(blank:    HeaderNames <- ["--header-names=mtime:"];  Both => Ok(HeaderNames::new(vec![(Column::Timestamp(TimeType::Modified), String::new())], None)));
+        test!(no_colon: HeaderNames <- ["--header-names=size"];  Both => err OptionsError::BadArgument(&flags::HEADER_NAMES, "size".into()));
+        test!(unknown:  HeaderNames <- ["--header-names=colour:C"];  Both => err OptionsError::BadArgument(&flags::HEADER_NAMES, "colour".into()));
+    }
+
+    mod inline_errors {
+        use super::*;
+
+        fn inline_errors(inputs: &[&'static str]) -> Vec<bool> {
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                FileStyle::deduce(mf, &None, false).unwrap().inline_errors
+            })
+        }
+
+        #[test]
+        fn given() {
+            for result in inline_errors(&["--long", "--inline-errors"]) {
+                assert!(result);
+            }
+        }
+
+        #[test]
+        fn not_given() {
+            for result in inline_errors(&["--long"]) {
+                assert!(!result);
+            }
+        }
+    }
+
+    mod progress {
+        use super::*;
+
+        fn progress(
+            inputs: &[&'static str],
+            is_a_tty: bool,
+        ) -> Vec<Result<details::Progress, OptionsError>> {
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                details::Progress::deduce(mf, is_a_tty)
+            })
+        }
+
+        #[test]
+        fn terminal() {
+            for result in progress(&["--hash"], true) {
+                assert_eq!(result, Ok(details::Progress::Live));
+            }
+        }
+
+        #[test]
+        fn redirected() {
+            for result in progress(&["--hash"], false) {
+                assert_eq!(result, Ok(details::Progress::Stream));
+            }
+        }
+
+        #[test]
+        fn tree_stays_in_order() {
+            for result in progress(&["--hash", "--tree"], false) {
+                assert_eq!(result, Ok(details::Progress::Ordered));
+            }
+        }
+
+        #[test]
+        fn deterministic() {
+            for result in progress(&["--hash", "--deterministic"], true) {
+                assert_eq!(result, Ok(details::Progress::Ordered));
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    mod grid_shrink {
+        use super::*;
+
+        fn shrink(inputs: &[&'static str]) -> Vec<Result<Vec<Shrink>, OptionsError>> {
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                grid_details::Options::deduce_shrink(mf)
+            })
+        }
+
+        #[test]
+        fn default() {
+            for result in shrink(&[]) {
+                let shrink = result.unwrap();
+                assert_eq!(shrink.first(), Some(&Shrink::Column(Column::Group)));
+                assert_eq!(shrink.last(), Some(&Shrink::Icons));
+            }
+        }
+
+        #[test]
+        fn given() {
+            for result in shrink(&["--grid-shrink=user,inode"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Shrink::Column(Column::User),
+                        Shrink::Column(Column::Inode)
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn icons() {
+            for result in shrink(&["--grid-shrink=icons,blocksize"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Shrink::Icons, Shrink::Column(Column::Blocksize)])
+                );
+            }
+        }
+
+        #[test]
+        fn nothing() {
+            for result in shrink(&["--grid-shrink", ""]) {
+                assert_eq!(result, Ok(vec![]));
+            }
+        }
+
+        #[test]
+        fn unknown_field() {
+            for result in shrink(&["--grid-shrink=owner"]) {
+                assert_eq!(
+                    result,
+                    Err(OptionsError::BadArgument(
+                        &flags::GRID_SHRINK,
+                        "owner".into()
+                    ))
+                );
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    mod columns {
+        use super::*;
+
+        fn columns(inputs: &[&'static str]) -> Vec<Result<Vec<Column>, OptionsError>> {
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                Columns::deduce(mf, &None, &[]).map(|c| c.collect(true, true))
+            })
+        }
+
+        const MODIFIED: Column = Column::Timestamp(TimeType::Modified);
+
+        #[test]
+        fn default() {
+            for result in columns(&[]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::User,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn both_sizes() {
+            for result in columns(&["--size=both", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::Blocksize,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn allocated_size() {
+            for result in columns(&["--size", "allocated", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Column::Permissions, Column::Blocksize, MODIFIED])
+                );
+            }
+        }
+
+        #[test]
+        fn bad_size() {
+            for result in columns(&["--size=sparse"]) {
+                assert_eq!(
+                    result,
+                    Err(OptionsError::BadArgument(&flags::SIZE, "sparse".into()))
+                );
+            }
+        }
+
+        #[test]
+        fn storage_class() {
+            for result in columns(&["--storage-class", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::StorageClass,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn mime() {
+            for result in columns(&["--mime", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::Mime,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn hash() {
+            for result in columns(&["--hash", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::Hash,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn git_log() {
+            for result in columns(&["--git-log", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::GitCommit,
+                        Column::GitSubject
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn git_author() {
+            for result in columns(&["--git-author", "--git-log", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::GitCommit,
+                        Column::GitSubject,
+                        Column::GitAuthor
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn no_git_log() {
+            for result in columns(&["--git-log", "--no-git", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Column::Permissions, Column::FileSize, MODIFIED])
+                );
+            }
+        }
+
+        #[test]
+        fn image_info() {
+            for result in columns(&["--image-info", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::Dimensions,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn binary_info() {
+            for result in columns(&["--binary-info", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::Binary,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn preview() {
+            for result in columns(&["--preview", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::Preview(40)
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn preview_length() {
+            for result in columns(&["--preview=12", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::Preview(12)
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn bad_preview_length() {
+            for result in columns(&["--preview=0"]) {
+                assert!(matches!(
+                    result,
+                    Err(OptionsError::FailedParse(_, NumberSource::Arg(_), _))
+                ));
+            }
+        }
+
+        #[test]
+        fn access_rights() {
+            for result in columns(&["--access-rights", "--no-user"]) {
+                let mut expected = vec![Column::Permissions, Column::FileSize];
+                if security::ENABLED {
+                    expected.push(Column::Access);
+                }
+                expected.push(MODIFIED);
+                assert_eq!(result, Ok(expected));
+            }
+        }
+
+        #[test]
+        fn cow() {
+            for result in columns(&["--cow", "--no-user"]) {
+                let mut expected = vec![Column::Permissions, Column::FileSize];
+                if cow::ENABLED {
+                    expected.extend([Column::Subvolume, Column::Compression, Column::Dataset]);
+                }
+                expected.push(MODIFIED);
+                assert_eq!(result, Ok(expected));
+            }
+        }
+
+        #[test]
+        fn project_id() {
+            for result in columns(&["--project-id", "--no-user"]) {
+                let mut expected = vec![Column::Permissions, Column::FileSize];
+                if chattr::ENABLED {
+                    expected.push(Column::Project);
+                }
+                expected.push(MODIFIED);
+                assert_eq!(result, Ok(expected));
+            }
+        }
+
+        #[test]
+        fn from_flags() {
+            for result in columns(&["--inode", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Inode,
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn replaced() {
+            for result in columns(&["--fields=size,mtime,perms,name"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Column::FileSize, MODIFIED, Column::Permissions])
+                );
+            }
+        }
+
+        #[test]
+        fn modified() {
+            for result in columns(&["--fields=-user,+inode,+size"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::Inode
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn replaced_then_modified() {
+            for result in columns(&["--inode", "--fields=size,+links"]) {
+                assert_eq!(result, Ok(vec![Column::FileSize, Column::HardLinks]));
+            }
+        }
+
+        #[test]
+        fn mounts() {
+            for result in columns(&["--mounts", "--fields=+mount"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::User,
+                        MODIFIED,
+                        Column::Mount
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn link_targets() {
+            for result in columns(&["--link-target-details", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        Column::TargetPermissions,
+                        Column::TargetSize,
+                        MODIFIED
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        fn link_targets_dereferenced() {
+            for result in columns(&["--link-target-details", "--dereference", "--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Column::Permissions, Column::FileSize, MODIFIED])
+                );
+            }
+        }
+
+        fn with_xattr_columns(inputs: &[&'static str]) -> Vec<Result<Vec<Column>, OptionsError>> {
+            let declared = [XattrColumn {
+                attribute: "user.project".into(),
+                header: Some("PROJECT".into()),
+                style: None,
+            }];
+            parse_for_test(inputs, TEST_ARGS, Both, |mf| {
+                Columns::deduce(mf, &None, &declared).map(|c| c.collect(true, true))
+            })
+        }
+
+        #[test]
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        fn xattr_columns() {
+            for result in with_xattr_columns(&["--no-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![
+                        Column::Permissions,
+                        Column::FileSize,
+                        MODIFIED,
+                        Column::Xattr(0)
+                    ])
+                );
+            }
+        }
+
+        #[test]
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        fn xattr_column_fields() {
+            for result in with_xattr_columns(&["--fields=user.project,size"]) {
+                assert_eq!(result, Ok(vec![Column::Xattr(0), Column::FileSize]));
+            }
+
+            for result in with_xattr_columns(&["--fields=-user.project,-user"]) {
+                assert_eq!(
+                    result,
+                    Ok(vec![Column::Permissions, Column::FileSize, MODIFIED])
+                );
+            }
+        }
+
+        #[test]
+        fn unknown_field() {
+            for result in columns(&["--fields=size,colour"]) {
+                assert_eq!(
+                    result,
+                    Err(OptionsError::BadArgument(&flags::FIELDS, "colour".into()))
+                );
+            }
+        }
+
+        #[test]
+        fn name_not_last() {
+            for result in columns(&["--fields=name,size"]) {
+                assert!(matches!(result, Err(OptionsError::Unsupported(_))));
+            }
+        }
+
+        #[test]
+        fn file_flags_alias() {
+            let flags = columns(&["--flags"]);
+            assert!(flags.iter().all(|result| result
+                .as_ref()
+                .is_ok_and(|c| c.contains(&Column::FileFlags))));
+            assert_eq!(columns(&["--file-flags"]), flags);
+        }
+
+        #[test]
+        fn name_removed() {
+            for result in columns(&["--fields=-name"]) {
+                assert!(matches!(result, Err(OptionsError::Unsupported(_))));
+            }
+        }
+    }
 }
@@ -97,6 +97,25 @@ impl TextCell {
         (*self.width) += *other.width;
         self.contents.0.extend(other.contents.0);
     }
+
+    /// Dims every string in this cell, to set it apart from the cells
+    /// around it.
+    pub fn dimmed(mut self) -> Self {
+        for string in &mut self.contents.0 {
+            let style = string.style_ref_mut();
+            *style = style.dimmed();
+        }
+        self
+    }
+
+    /// Paints every string in this cell in the given style, in place of
+    /// the styles they had.
+    pub fn restyled(mut self, style: Style) -> Self {
+        for string in &mut self.contents.0 {
+            *string.style_ref_mut() = style;
+        }
+        self
+    }
 }
 
 // I’d like to eventually abstract cells so that instead of *every* cell
@@ -200,6 +219,50 @@ impl<'a> From<&'a str> for DisplayWidth {
     }
 }
 
+impl DisplayWidth {
+    /// Returns the width of a string that has already been painted, skipping
+    /// over any escape sequences in it, such as colours or hyperlinks.
+    pub fn of_styled(input: &str) -> Self {
+        let mut text = String::with_capacity(input.len());
+        let mut chars = input.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                text.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                // Control Sequence Introducer: runs until a final byte.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+
+                // Operating System Command: runs until a bell or a string
+                // terminator.
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        } else if c == '\x1b' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Self::from(text.as_str())
+    }
+}
+
 impl From<usize> for DisplayWidth {
     fn from(width: usize) -> Self {
         Self(width)
@@ -273,4 +336,10 @@ mod width_unit_test {
         let cell = DisplayWidth::from("/usr/bin/");
         assert_eq!(*(cell + 8), 17);
     }
+
+    #[test]
+    fn styled() {
+        let text = "\x1b[1;34m漢字\x1b[0m \x1b]8;;file:///tmp\x1b\\👩\u{200D}💻\x1b]8;;\x1b\\";
+        assert_eq!(*DisplayWidth::of_styled(text), 7);
+    }
 }
@@ -11,6 +11,7 @@ use palette::{FromColor, LinSrgb, Oklab, Srgb};
 use crate::{
     fs::{dir_action::RecurseOptions, feature::git::GitCache, fields::Size, DotFilter, File},
     output::{table::TimeType, tree::TreeDepth},
+    theme::{fixed_to_rgb, reduce_colour},
 };
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -92,11 +93,13 @@ impl ColorScaleInformation {
                 ratio = 1.0;
             }
 
-            style.foreground = Some(adjust_luminance(
+            // The shades get worked out in true colour, then reduced to
+            // whatever the terminal can show, like the theme’s own colours.
+            style.foreground = Some(reduce_colour(adjust_luminance(
                 fg,
                 ratio,
                 self.options.min_luminance as f32 / 100.0,
-            ));
+            )));
         }
 
         style
@@ -108,6 +111,8 @@ impl ColorScaleInformation {
             TimeType::Changed => self.changed,
             TimeType::Accessed => self.accessed,
             TimeType::Created => self.created,
+            // Commit times aren’t scanned for, so they stay unshaded.
+            TimeType::Git => return style,
         };
 
         if let Some(file_time) = time_type.get_corresponding_time(file) {
@@ -153,7 +158,7 @@ fn update_information_recursively(
 
         if information.options.size {
             let size = match file.size() {
-                Size::Some(size) => Some(size as f32),
+                Size::Some(size) | Size::Lfs(size) => Some(size as f32),
                 _ => None,
             };
             Extremes::update(size, &mut information.size);
@@ -169,7 +174,7 @@ fn update_information_recursively(
             match file.to_dir() {
                 Ok(dir) => {
                     let files: Vec<File<'_>> = dir
-                        .files(dot_filter, git, git_ignoring, false, false)
+                        .files(dot_filter, git, git_ignoring, false, None)
                         .collect();
 
                     update_information_recursively(
@@ -225,6 +230,17 @@ fn adjust_luminance(color: Colour, x: f32, min_l: f32) -> Colour {
             f32::from(b) / 255.0,
         ),
 
+        // Theme colours reduced to the 256-colour palette get shaded from
+        // the colour they stand for.
+        Colour::Fixed(number) => {
+            let (red, green, blue) = fixed_to_rgb(number);
+            LinSrgb::new(
+                f32::from(red) / 255.0,
+                f32::from(green) / 255.0,
+                f32::from(blue) / 255.0,
+            )
+        }
+
         Colour::Black => LinSrgb::new(0.0, 0.0, 0.0),
 
         Colour::Green | Colour::LightGreen => LinSrgb::new(0.0, 1.0, 0.0),
@@ -247,7 +263,7 @@ fn adjust_luminance(color: Colour, x: f32, min_l: f32) -> Colour {
 
         Colour::LightPurple | Colour::Purple => LinSrgb::new(0.5, 0.0, 0.5),
 
-        _ => LinSrgb::new(1.0, 1.0, 1.0),
+        Colour::Default => LinSrgb::new(1.0, 1.0, 1.0),
     };
 
     let mut lab: Oklab = Oklab::from_color(rgb_color);
@@ -260,3 +276,16 @@ fn adjust_luminance(color: Colour, x: f32, min_l: f32) -> Colour {
         (adjusted_rgb.blue * 255.0).round() as u8,
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_colour_keeps_its_hue() {
+        let Colour::Rgb(r, g, b) = adjust_luminance(Colour::Fixed(196), 1.0, 0.4) else {
+            panic!("shades are worked out in true colour");
+        };
+        assert!(r > g && r > b);
+    }
+}
@@ -67,24 +67,34 @@
 
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
 use std::vec::IntoIter as VecIntoIter;
 
-use nu_ansi_term::Style;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use nu_ansi_term::{AnsiStrings as ANSIStrings, Style};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 use log::*;
 
+use crate::fs::dedupe::Dedupe;
+use crate::fs::digest;
 use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::acl;
 use crate::fs::feature::git::GitCache;
+use crate::fs::feature::streams::Stream;
 use crate::fs::feature::xattr::Attribute;
-use crate::fs::fields::SecurityContextType;
-use crate::fs::filter::FileFilter;
+use crate::fs::fields::{self as f, SecurityContextType};
+use crate::fs::filter::{FileFilter, FileFilterFlags};
+use crate::fs::trash;
+use crate::fs::vcs::VcsCache;
 use crate::fs::{Dir, File};
 use crate::output::cell::TextCell;
 use crate::output::color_scale::{ColorScaleInformation, ColorScaleOptions};
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
-use crate::output::tree::{TreeDepth, TreeParams, TreeTrunk};
+use crate::output::sections::{Section, Sections};
+use crate::output::summary::{self, Summary};
+use crate::output::table::{Options as TableOptions, Row as TableRow, SizeFormat, Table};
+use crate::output::tree::{TreeDepth, TreeParams, TreeStyle, TreeTrunk};
 use crate::theme::Theme;
 
 /// With the **Details** view, the output gets formatted into columns, with
@@ -111,12 +121,18 @@ pub struct Options {
     /// Whether to show a header line or not.
     pub header: bool,
 
-    /// Whether to show each file’s extended attributes.
-    pub xattr: bool,
+    /// How to show each file’s extended attributes, if at all.
+    pub xattr: Option<XattrStyle>,
 
     /// Whether to show each file's security attribute.
     pub secattr: bool,
 
+    /// Whether to show the entries in each file’s access control lists.
+    pub acl: bool,
+
+    /// Whether to show each file’s alternate data streams.
+    pub streams: bool,
+
     /// Whether to show a directory's mounted filesystem details
     pub mounts: bool,
 
@@ -124,6 +140,42 @@ pub struct Options {
 
     /// Whether to drill down into symbolic links that point to directories
     pub follow_links: bool,
+
+    /// Which characters to draw the tree with, when there is one.
+    pub tree_style: TreeStyle,
+}
+
+/// How to list each file’s extended attributes under it.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum XattrStyle {
+    /// The way `--extended` lists them: text values in full, and binary
+    /// values in hex when they’re short enough.
+    Extended,
+
+    /// Just their names and sizes.
+    Names,
+
+    /// Their values, with text quoted, binary in hex, and long values of
+    /// either kind cut short.
+    Values,
+}
+
+/// How to write a listing while its files’ hashes are still being worked
+/// out, so that hashing a lot of files doesn’t hold the listing up.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Progress {
+    /// Write every row straight away with a placeholder for its hash, then
+    /// rewrite each row in place once its hash is known. This only works
+    /// on a terminal, and only when the listing fits on the screen; longer
+    /// listings get streamed instead.
+    Live,
+
+    /// Write each row as soon as it’s complete, in whichever order that
+    /// happens in.
+    Stream,
+
+    /// Hash every file before writing anything, so the rows stay in order.
+    Ordered,
 }
 
 pub struct Render<'a> {
@@ -146,13 +198,39 @@ pub struct Render<'a> {
 
     pub git: Option<&'a GitCache>,
 
+    pub vcs: Option<&'a VcsCache>,
+
     pub git_repos: bool,
+
+    /// Whether to dereference symbolic links inside the directories the
+    /// tree goes into.
+    pub deref_links: bool,
+
+    /// At most this many entries get listed from each directory the tree
+    /// goes into.
+    pub max_results: Option<usize>,
+
+    /// Listings longer than this get split into alphabetical sections.
+    /// Only the top level of a tree gets split up.
+    pub sections: Option<usize>,
+
+    /// How to write the listing while hashes are being worked out.
+    pub progress: Progress,
+
+    /// How to write the size in the footer after a tree, if there is one.
+    /// Directories listed on their own get their footer from elsewhere.
+    pub total: Option<SizeFormat>,
+
+    /// The arguments and the directories listed so far, with `--dedupe`,
+    /// so a tree doesn’t go into the same directory twice.
+    pub dedupe: Option<&'a Dedupe>,
 }
 
 #[rustfmt::skip]
 struct Egg<'a> {
     table_row: Option<TableRow>,
     xattrs:    &'a [Attribute],
+    lines:     Vec<String>,
     errors:    Vec<(io::Error, Option<PathBuf>)>,
     dir:       Option<Dir>,
     file:      &'a File<'a>,
@@ -167,6 +245,7 @@ impl<'a> AsRef<File<'a>> for Egg<'a> {
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
         let mut rows = Vec::new();
+        let mut summary = Summary::default();
 
         let color_scale_info = ColorScaleInformation::from_color_scale(
             self.opts.color_scale,
@@ -192,12 +271,21 @@ impl<'a> Render<'a> {
                 (None, _) => { /* Keep Git how it is */ }
             }
 
-            let mut table = Table::new(table, self.git, self.theme, self.git_repos);
+            let mut table = Table::new(table, self.git, self.vcs, self.theme, self.git_repos);
+            if self.file_style.inline_errors {
+                table.show_errors_inline();
+            }
+            if self.dir.is_some_and(|dir| trash::is_trash_dir(&dir.path)) {
+                table.add_trash_columns();
+            }
+            if self.progress != Progress::Ordered {
+                table.defer_hashes();
+            }
 
             if self.opts.header {
                 let header = table.header_row();
                 table.add_widths(&header);
-                rows.push(self.render_header(header));
+                rows.push(self.render_header(header, table.name_header()));
             }
 
             // This is weird, but I can’t find a way around it:
@@ -206,18 +294,26 @@ impl<'a> Render<'a> {
             self.add_files_to_table(
                 &mut table,
                 &mut rows,
+                &mut summary,
                 &self.files,
                 TreeDepth::root(),
                 color_scale_info,
             );
 
-            for row in self.iterate_with_table(table.unwrap(), rows) {
+            let table = table.unwrap();
+            if table.defers_hashes() {
+                self.render_hashing(table, rows, w)?;
+                return self.render_total(&summary, w);
+            }
+
+            for row in self.iterate_with_table(table, rows) {
                 writeln!(w, "{}", row.strings())?;
             }
         } else {
             self.add_files_to_table(
                 &mut None,
                 &mut rows,
+                &mut summary,
                 &self.files,
                 TreeDepth::root(),
                 color_scale_info,
@@ -228,20 +324,194 @@ impl<'a> Render<'a> {
             }
         }
 
-        Ok(())
+        self.render_total(&summary, w)
+    }
+
+    /// Writes the footer for `--total` after a tree, counting everything
+    /// in it apart from the directories it starts from, as they don’t get a
+    /// listing of their own to put one after.
+    fn render_total<W: Write>(&self, summary: &Summary, w: &mut W) -> io::Result<()> {
+        match self.total {
+            Some(size_format) if self.dir.is_none() && self.recurse.is_some_and(|r| r.tree) => {
+                writeln!(
+                    w,
+                    "{}",
+                    ANSIStrings(&summary.render(self.theme, size_format))
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes a table whose rows are still waiting for their files’ hashes,
+    /// hashing the files in a pool of threads and writing each row as soon
+    /// as it can be.
+    fn render_hashing<W: Write>(
+        &'a self,
+        table: Table<'a>,
+        rows: Vec<Row>,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let pending: Vec<_> = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row)| Some((index, row.hash.clone()?)))
+            .collect();
+        let waiting: Vec<_> = rows.iter().map(|row| row.hash.is_some()).collect();
+        let nested = rows.iter().any(|row| row.tree.is_nested());
+
+        let mut iter = self.iterate_with_table(table, rows);
+        let mut lines = Vec::with_capacity(waiting.len());
+        while let Some(parts) = iter.next_parts() {
+            lines.push(parts);
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                // Sending fails once nothing’s listening, such as after the
+                // output has been closed, which stops the hashing early.
+                pending
+                    .into_par_iter()
+                    .try_for_each_with(sender, |sender, (index, path)| {
+                        let hash = match digest::of_file(&path) {
+                            Ok(digest) => f::Hash::Digest(digest),
+                            Err(e) => {
+                                debug!("Failed to hash {:?}: {e}", path);
+                                f::Hash::None
+                            }
+                        };
+                        sender.send((index, hash))
+                    })
+            });
+
+            if self.progress == Progress::Live {
+                let written: Vec<_> = lines
+                    .iter()
+                    .map(|(cells, tail)| iter.join(cells.clone(), tail.clone()))
+                    .collect();
+
+                if fits_on_screen(&written) {
+                    for line in &written {
+                        writeln!(w, "{}", line.strings())?;
+                    }
+                    w.flush()?;
+
+                    // Each row gets rewritten by moving the cursor up to it,
+                    // then back down below the listing.
+                    for (index, hash) in receiver {
+                        let (cells, tail) = &mut lines[index];
+                        if let Some(cells) = cells {
+                            iter.table.fill_hash(cells, hash);
+                        }
+                        let line = iter.join(cells.clone(), tail.clone());
+                        let up = written.len() - index;
+                        write!(w, "\x1B[{up}A\r{}\x1B[{up}B\r", line.strings())?;
+                        w.flush()?;
+                    }
+                    return Ok(());
+                }
+            }
+
+            // Rows beneath a file, such as its attributes or errors, have to
+            // stay under it, so those listings wait for every hash instead.
+            if nested {
+                for (index, hash) in receiver {
+                    if let Some(cells) = &mut lines[index].0 {
+                        iter.table.fill_hash(cells, hash);
+                    }
+                }
+                for (cells, tail) in lines {
+                    writeln!(w, "{}", iter.join(cells, tail).strings())?;
+                }
+                return Ok(());
+            }
+
+            let mut lines: Vec<_> = lines.into_iter().map(Some).collect();
+            for (line, waiting) in lines.iter_mut().zip(waiting) {
+                if !waiting {
+                    let (cells, tail) = line.take().unwrap();
+                    writeln!(w, "{}", iter.join(cells, tail).strings())?;
+                }
+            }
+
+            for (index, hash) in receiver {
+                let Some((mut cells, tail)) = lines[index].take() else {
+                    continue;
+                };
+                if let Some(cells) = &mut cells {
+                    iter.table.fill_hash(cells, hash);
+                }
+                writeln!(w, "{}", iter.join(cells, tail).strings())?;
+            }
+
+            Ok(())
+        })
     }
 
     /// Whether to show the extended attribute hint
     pub fn show_xattr_hint(&self, file: &File<'_>) -> bool {
         // Do not show the hint '@' if the only extended attribute is the security
-        // attribute and the security attribute column is active.
-        let xattr_count = file.extended_attributes().len();
-        let selinux_ctx_shown = self.opts.secattr
-            && match file.security_context().context {
-                SecurityContextType::SELinux(_) => true,
-                SecurityContextType::None => false,
-            };
-        xattr_count > 1 || (xattr_count == 1 && !selinux_ctx_shown)
+        // label and the security context column is active.
+        // ACLs get a `+` of their own instead.
+        let xattr_count = file
+            .extended_attributes()
+            .iter()
+            .filter(|a| !acl::is_acl_attribute(&a.name))
+            .count();
+        let label_shown = self.opts.secattr
+            && !matches!(file.security_context().context, SecurityContextType::None);
+        xattr_count > 1 || (xattr_count == 1 && !label_shown)
+    }
+
+    /// Whether the given file is a directory, or a link to one when links
+    /// are being followed.
+    fn is_dir_to_recurse(&self, file: &File<'_>) -> bool {
+        if self.opts.follow_links {
+            file.points_to_directory()
+        } else {
+            file.is_directory()
+        }
+    }
+
+    /// Whether the tree carries on into the given file at the given depth.
+    fn recurses_into(&self, file: &File<'_>, depth: TreeDepth) -> bool {
+        self.recurse.is_some_and(|r| {
+            r.tree
+                && self.is_dir_to_recurse(file)
+                && !r.is_too_deep(depth.0)
+                && !self.filter.is_pruned(file)
+        })
+    }
+
+    /// Whether the tree under the given directory has any files in it, for
+    /// `--only-files` and `--only-broken-links` to leave out the directories
+    /// that lead to none.
+    /// Directories that can’t be read are kept, so the error gets shown.
+    fn leads_to_files(&self, file: &File<'_>, depth: TreeDepth) -> bool {
+        if !self.recurses_into(file, depth) {
+            return false;
+        }
+
+        let Ok(dir) = file.to_dir() else {
+            return true;
+        };
+
+        let mut files = dir
+            .files(
+                self.filter.dot_filter,
+                self.git,
+                self.git_ignoring,
+                self.deref_links,
+                file.total_size(),
+            )
+            .collect::<Vec<_>>();
+        self.filter.filter_child_files(true, &mut files);
+        self.filter.filter_git_files(self.git, &mut files);
+
+        files
+            .iter()
+            .any(|f| !self.is_dir_to_recurse(f) || self.leads_to_files(f, depth.deeper()))
     }
 
     /// Adds files to the table, possibly recursively. This is easily
@@ -250,6 +520,7 @@ impl<'a> Render<'a> {
         &self,
         table: &mut Option<Table<'a>>,
         rows: &mut Vec<Row>,
+        summary: &mut Summary,
         src: &[File<'dir>],
         depth: TreeDepth,
         color_scale_info: Option<ColorScaleInformation>,
@@ -283,41 +554,52 @@ impl<'a> Render<'a> {
                 // printed unless the user passes --extended to signify
                 // that they want to see them.
 
-                let xattrs: &[Attribute] = if xattr::ENABLED && self.opts.xattr {
+                let xattrs: &[Attribute] = if xattr::ENABLED && self.opts.xattr.is_some() {
                     file.extended_attributes()
                 } else {
                     &[]
                 };
 
+                // ACL entries and alternate data streams get listed under
+                // the file in the same way as its attributes.
+                let mut lines = Vec::new();
+                if self.opts.acl {
+                    lines.extend(
+                        file.acl()
+                            .map(|acl| acl.lines(acl::name_of))
+                            .into_iter()
+                            .flatten(),
+                    );
+                }
+
+                if self.opts.streams {
+                    match file.alternate_streams() {
+                        Ok(streams) => lines.extend(streams.iter().map(Stream::to_string)),
+                        Err(e) => errors.push((e, None)),
+                    }
+                }
+
                 let table_row = table
                     .as_ref()
                     .map(|t| t.row_for_file(file, self.show_xattr_hint(file), color_scale_info));
 
                 let mut dir = None;
-                let follow_links = self.opts.follow_links;
-                if let Some(r) = self.recurse {
-                    if (if follow_links {
-                        file.points_to_directory()
-                    } else {
-                        file.is_directory()
-                    }) && r.tree
-                        && !r.is_too_deep(depth.0)
-                    {
-                        trace!("matching on to_dir");
-                        match file.to_dir() {
-                            Ok(d) => {
-                                dir = Some(d);
-                            }
-                            Err(e) => {
-                                errors.push((e, None));
-                            }
+                if self.recurses_into(file, depth) {
+                    trace!("matching on to_dir");
+                    match file.to_dir() {
+                        Ok(d) => {
+                            dir = Some(d);
+                        }
+                        Err(e) => {
+                            errors.push((e, None));
                         }
                     }
-                };
+                }
 
                 Egg {
                     table_row,
                     xattrs,
+                    lines,
                     errors,
                     dir,
                     file,
@@ -328,10 +610,66 @@ impl<'a> Render<'a> {
         // this is safe because all entries have been initialized above
         self.filter.sort_files(&mut file_eggs);
 
-        for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
+        // Every entry in the tree counts towards its total, even the ones
+        // that get left out below, but not the directories it starts from.
+        for egg in &file_eggs {
+            if depth.0 > 0 || egg.dir.is_none() {
+                summary.add(egg.file);
+            }
+        }
+
+        // The top level has already been cut short before getting here, if
+        // it’s a directory’s listing rather than the arguments.
+        let hidden = match self.max_results {
+            Some(max) if depth.0 > 0 && file_eggs.len() > max => {
+                let hidden = file_eggs.len() - max;
+                file_eggs.truncate(max);
+                hidden
+            }
+            _ => 0,
+        };
+
+        let mut sections = if depth.0 == 0 {
+            Sections::new(self.sections, self.filter.sort_field, file_eggs.len())
+        } else {
+            None
+        };
+
+        let name_gradient =
+            color_scale_info.filter(|_| !table.as_ref().is_some_and(Table::shows_timestamps));
+
+        for (tree_params, mut egg) in depth.iterate_over(file_eggs.into_iter()) {
+            // A directory that’s already been gone into through another path
+            // doesn’t get gone into again, and an argument that it’s been
+            // gone into as doesn’t get listed at all.
+            if egg.dir.is_some()
+                && !egg.file.is_virtual()
+                && self
+                    .dedupe
+                    .is_some_and(|d| !d.first_listing(&egg.file.path))
+            {
+                if depth.0 == 0 {
+                    continue;
+                }
+                egg.dir = None;
+            }
+
+            // The note about the entries left out comes last instead.
+            let tree_params = if hidden > 0 {
+                TreeParams::new(depth, false)
+            } else {
+                tree_params
+            };
             let mut files = Vec::new();
             let errors = egg.errors;
 
+            if let Some(section) = sections
+                .as_mut()
+                .and_then(|s| s.heading_for(&egg.file.name))
+            {
+                rows.push(self.render_section(section));
+            }
+
             if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
                 t.add_widths(row);
             }
@@ -341,15 +679,22 @@ impl<'a> Render<'a> {
                 .for_file(egg.file, self.theme)
                 .with_link_paths()
                 .with_mount_details(self.opts.mounts)
+                .with_age_gradient(name_gradient)
                 .paint()
                 .promote();
 
             debug!("file_name {:?}", file_name);
 
+            let hash = table
+                .as_ref()
+                .filter(|t| t.defers_hashes() && egg.file.is_hashable())
+                .map(|_| egg.file.path.clone());
+
             let row = Row {
                 tree: tree_params,
                 cells: egg.table_row,
                 name: file_name,
+                hash,
             };
 
             rows.push(row);
@@ -359,20 +704,42 @@ impl<'a> Render<'a> {
                     self.filter.dot_filter,
                     self.git,
                     self.git_ignoring,
-                    egg.file.deref_links,
-                    egg.file.is_recursive_size(),
+                    self.deref_links,
+                    egg.file.total_size(),
                 ) {
                     files.push(file_to_add);
                 }
 
                 self.filter
                     .filter_child_files(self.recurse.is_some(), &mut files);
+                self.filter.filter_git_files(self.git, &mut files);
+
+                if let Some(dedupe) = self.dedupe {
+                    for file in &mut files {
+                        file.aliases = dedupe.aliases(&file.path);
+                    }
+                }
+
+                if self.filter.flags.contains(&FileFilterFlags::OnlyFiles)
+                    || self
+                        .filter
+                        .flags
+                        .contains(&FileFilterFlags::OnlyBrokenLinks)
+                {
+                    files.retain(|f| {
+                        !self.is_dir_to_recurse(f) || self.leads_to_files(f, depth.deeper())
+                    });
+                }
 
                 if !files.is_empty() {
                     for xattr in egg.xattrs {
                         rows.push(self.render_xattr(xattr, TreeParams::new(depth.deeper(), false)));
                     }
 
+                    for line in egg.lines {
+                        rows.push(self.render_line(line, TreeParams::new(depth.deeper(), false)));
+                    }
+
                     for (error, path) in errors {
                         rows.push(self.render_error(
                             &error,
@@ -381,16 +748,33 @@ impl<'a> Render<'a> {
                         ));
                     }
 
-                    self.add_files_to_table(table, rows, &files, depth.deeper(), color_scale_info);
+                    self.add_files_to_table(
+                        table,
+                        rows,
+                        summary,
+                        &files,
+                        depth.deeper(),
+                        color_scale_info,
+                    );
                     continue;
                 }
             }
 
             let count = egg.xattrs.len();
             for (index, xattr) in egg.xattrs.iter().enumerate() {
+                let params = TreeParams::new(
+                    depth.deeper(),
+                    egg.lines.is_empty() && errors.is_empty() && index == count - 1,
+                );
+                let r = self.render_xattr(xattr, params);
+                rows.push(r);
+            }
+
+            let count = egg.lines.len();
+            for (index, line) in egg.lines.into_iter().enumerate() {
                 let params =
                     TreeParams::new(depth.deeper(), errors.is_empty() && index == count - 1);
-                let r = self.render_xattr(xattr, params);
+                let r = self.render_line(line, params);
                 rows.push(r);
             }
 
@@ -401,13 +785,39 @@ impl<'a> Render<'a> {
                 rows.push(r);
             }
         }
+
+        if hidden > 0 {
+            rows.push(self.render_overflow(hidden, TreeParams::new(depth, true)));
+        }
     }
 
-    pub fn render_header(&self, header: TableRow) -> Row {
+    pub fn render_header(&self, header: TableRow, name: &str) -> Row {
         Row {
             tree: TreeParams::new(TreeDepth::root(), false),
             cells: Some(header),
-            name: TextCell::paint_str(self.theme.ui.header.unwrap_or_default(), "Name"),
+            name: TextCell::paint(self.theme.ui.header.unwrap_or_default(), name.to_owned()),
+            hash: None,
+        }
+    }
+
+    fn render_section(&self, section: Section) -> Row {
+        Row {
+            tree: TreeParams::new(TreeDepth::root(), false),
+            cells: None,
+            name: TextCell::paint(
+                self.theme.ui.header.unwrap_or_default(),
+                section.to_string(),
+            ),
+            hash: None,
+        }
+    }
+
+    fn render_overflow(&self, hidden: usize, tree: TreeParams) -> Row {
+        Row {
+            cells: None,
+            name: TextCell::paint(Style::default(), summary::overflow(hidden)),
+            tree,
+            hash: None,
         }
     }
 
@@ -427,18 +837,32 @@ impl<'a> Render<'a> {
             cells: None,
             name,
             tree,
+            hash: None,
         }
     }
 
     fn render_xattr(&self, xattr: &Attribute, tree: TreeParams) -> Row {
-        let name = TextCell::paint(
-            self.theme.ui.perms.unwrap_or_default().attribute(),
-            format!("{xattr}"),
-        );
+        let text = match self.opts.xattr {
+            Some(XattrStyle::Names) => xattr.size_display(),
+            Some(XattrStyle::Values) => xattr.inline_display(),
+            Some(XattrStyle::Extended) | None => format!("{xattr}"),
+        };
+        let name = TextCell::paint(self.theme.ui.perms.unwrap_or_default().attribute(), text);
         Row {
             cells: None,
             name,
             tree,
+            hash: None,
+        }
+    }
+
+    fn render_line(&self, line: String, tree: TreeParams) -> Row {
+        let name = TextCell::paint(self.theme.ui.perms.unwrap_or_default().attribute(), line);
+        Row {
+            cells: None,
+            name,
+            tree,
+            hash: None,
         }
     }
 
@@ -448,7 +872,8 @@ impl<'a> Render<'a> {
             total_width: table.widths().total(),
             table,
             inner: rows.into_iter(),
-            tree_style: self.theme.ui.punctuation.unwrap_or_default(),
+            tree_style: self.theme.tree_branch(),
+            tree_chars: self.opts.tree_style,
         }
     }
 
@@ -456,7 +881,8 @@ impl<'a> Render<'a> {
         Iter {
             tree_trunk: TreeTrunk::default(),
             inner: rows.into_iter(),
-            tree_style: self.theme.ui.punctuation.unwrap_or_default(),
+            tree_style: self.theme.tree_branch(),
+            tree_chars: self.opts.tree_style,
         }
     }
 }
@@ -476,6 +902,10 @@ pub struct Row {
 
     /// Information used to determine which symbols to display in a tree.
     pub tree: TreeParams,
+
+    /// The file to hash to fill in this row’s placeholder, for a row that
+    /// has one.
+    pub hash: Option<PathBuf>,
 }
 
 #[rustfmt::skip]
@@ -485,35 +915,69 @@ pub struct TableIter<'a> {
 
     total_width: usize,
     tree_style:  Style,
+    tree_chars:  TreeStyle,
     tree_trunk:  TreeTrunk,
 }
 
-impl<'a> Iterator for TableIter<'a> {
-    type Item = TextCell;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'a> TableIter<'a> {
+    /// Takes the next row, rendering its tree and name but keeping its
+    /// cells as they are, so they can still be changed before the row
+    /// gets rendered with `join`.
+    fn next_parts(&mut self) -> Option<(Option<TableRow>, TextCell)> {
         self.inner.next().map(|row| {
-            let mut cell = if let Some(cells) = row.cells {
-                self.table.render(cells)
-            } else {
-                let mut cell = TextCell::default();
-                cell.add_spaces(self.total_width);
-                cell
-            };
+            let mut tail = TextCell::default();
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+                tail.push(
+                    self.tree_style.paint(tree_part.ascii_art(self.tree_chars)),
+                    4,
+                );
             }
 
-            cell.append(row.name);
-            cell
+            tail.append(row.name);
+            (row.cells, tail)
         })
     }
+
+    /// Renders a row’s cells, followed by its tree and name.
+    fn join(&self, cells: Option<TableRow>, tail: TextCell) -> TextCell {
+        let mut cell = if let Some(cells) = cells {
+            self.table.render(cells)
+        } else {
+            let mut cell = TextCell::default();
+            cell.add_spaces(self.total_width);
+            cell
+        };
+
+        cell.append(tail);
+        cell
+    }
+}
+
+impl<'a> Iterator for TableIter<'a> {
+    type Item = TextCell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cells, tail) = self.next_parts()?;
+        Some(self.join(cells, tail))
+    }
+}
+
+/// Whether every line fits on the terminal without wrapping, with room
+/// below them for the cursor, so that any of them can be rewritten.
+fn fits_on_screen(lines: &[TextCell]) -> bool {
+    let Some((width, height)) = terminal_size::terminal_size() else {
+        return false;
+    };
+
+    lines.len() < usize::from(height.0)
+        && lines.iter().all(|line| *line.width < usize::from(width.0))
 }
 
 pub struct Iter {
     tree_trunk: TreeTrunk,
     tree_style: Style,
+    tree_chars: TreeStyle,
     inner: VecIntoIter<Row>,
 }
 
@@ -525,7 +989,10 @@ impl Iterator for Iter {
             let mut cell = TextCell::default();
 
             for tree_part in self.tree_trunk.new_row(row.tree) {
-                cell.push(self.tree_style.paint(tree_part.ascii_art()), 4);
+                cell.push(
+                    self.tree_style.paint(tree_part.ascii_art(self.tree_chars)),
+                    4,
+                );
             }
 
             cell.append(row.name);
@@ -533,3 +1000,59 @@ impl Iterator for Iter {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::{Options as CliOptions, OptionsResult};
+    use crate::output::Mode;
+    use std::ffi::OsStr;
+    use std::fs;
+
+    #[test]
+    fn tree_total() {
+        let dir = std::env::temp_dir().join(format!("eza-tree-total-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a"), "abc").unwrap();
+        fs::write(dir.join("sub/b"), "abcdef").unwrap();
+
+        let args = ["--tree", "--total", "--bytes", "--color=never"].map(OsStr::new);
+        let OptionsResult::Ok(options, _) = CliOptions::parse(args, &None) else {
+            panic!("Tree options didn't parse");
+        };
+        let Mode::Details(ref opts) = options.view.mode else {
+            panic!("Tree isn't a details view");
+        };
+        let theme = options.theme.to_theme(false);
+
+        let render = Render {
+            dir: None,
+            files: vec![File::from_args(dir.clone(), None, None, false, None, None)],
+            theme: &theme,
+            file_style: &options.view.file_style,
+            opts,
+            recurse: options.dir_action.recurse_options(),
+            filter: &options.filter,
+            git_ignoring: false,
+            git: None,
+            vcs: None,
+            git_repos: false,
+            deref_links: false,
+            max_results: None,
+            sections: None,
+            progress: Progress::Ordered,
+            total: options.view.total,
+            dedupe: None,
+        };
+        let mut output = Vec::new();
+        render.render(&mut output).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output.lines().last(),
+            Some("3 entries: 1 directory, 2 files (9 in total)")
+        );
+    }
+}
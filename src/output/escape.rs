@@ -4,8 +4,18 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use std::path::Path;
+
 use super::file_name::QuoteStyle;
 use nu_ansi_term::{AnsiString as ANSIString, Style};
+use percent_encoding::{utf8_percent_encode, CONTROLS};
+
+/// The OSC 8 sequence that starts a hyperlink, which is followed by the
+/// link’s URL and then the string terminator.
+const HYPERLINK_START: &str = "\x1B]8;;";
+
+/// The string terminator that ends an OSC 8 sequence.
+const HYPERLINK_END: &str = "\x1B\x5C";
 
 pub fn escape(
     string: String,
@@ -43,3 +53,51 @@ pub fn escape(
         bits.push(quote_bit);
     }
 }
+
+/// Turns an absolute path into a `file://` URL, escaping any control
+/// characters so they can’t end the surrounding escape sequence early.
+pub fn file_url(path: &Path) -> Option<String> {
+    let path = utf8_percent_encode(path.to_str()?, CONTROLS).to_string();
+
+    // On Windows, `std::fs::canonicalize` adds the Win32 File prefix, which we need to remove
+    #[cfg(target_os = "windows")]
+    let path = path.strip_prefix("\\\\?\\").unwrap_or(&path);
+
+    Some(format!("file://{path}"))
+}
+
+/// The escape sequence that makes the following text link to the given URL.
+pub fn hyperlink_start(url: &str) -> String {
+    format!("{HYPERLINK_START}{url}{HYPERLINK_END}")
+}
+
+/// The escape sequence that ends a hyperlink, which is an OSC 8 sequence
+/// with an empty URL.
+pub fn hyperlink_end() -> String {
+    format!("{HYPERLINK_START}{HYPERLINK_END}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn plain_url() {
+        let url = file_url(Path::new("/home/eza/file.txt"));
+        assert_eq!(url.as_deref(), Some("file:///home/eza/file.txt"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn control_characters_are_encoded() {
+        let url = file_url(Path::new("/tmp/a\x1Bb"));
+        assert_eq!(url.as_deref(), Some("file:///tmp/a%1Bb"));
+    }
+
+    #[test]
+    fn hyperlink_sequences() {
+        assert_eq!(hyperlink_start("file:///x"), "\x1B]8;;file:///x\x1B\\");
+        assert_eq!(hyperlink_end(), "\x1B]8;;\x1B\\");
+    }
+}
@@ -5,17 +5,22 @@
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
 use std::fmt::Debug;
+use std::io;
 use std::path::Path;
 
 use nu_ansi_term::{AnsiString as ANSIString, Style};
 use path_clean;
-use unicode_width::UnicodeWidthStr;
+use unicode_normalization::UnicodeNormalization;
 
+use crate::fs::processes::{PipeAccess, Process};
 use crate::fs::{File, FileTarget};
-use crate::output::cell::TextCellContents;
+use crate::output::cell::{DisplayWidth, TextCellContents};
+use crate::output::color_scale::{ColorScaleInformation, ColorScaleOptions};
 use crate::output::escape;
-use crate::output::icons::{icon_for_file, iconify_style};
+use crate::output::escape::{file_url, hyperlink_end, hyperlink_start};
+use crate::output::icons::{icon_for_file, iconify_style, IconSet};
 use crate::output::render::FiletypeColours;
+use crate::output::table::TimeType;
 use crate::theme::FileNameStyle;
 
 /// Basically a file name factory.
@@ -27,6 +32,9 @@ pub struct Options {
     /// Whether to prepend icon characters before file names.
     pub show_icons: ShowIcons,
 
+    /// Which icons to show, for fonts with different glyphs in them.
+    pub icon_set: IconSet,
+
     /// How to display file names with spaces (with or without quotes).
     pub quote_style: QuoteStyle,
 
@@ -36,6 +44,25 @@ pub struct Options {
     /// Whether to display files with their absolute path.
     pub absolute: Absolute,
 
+    /// Whether to show what desktop entries and shortcuts point to.
+    pub shortcut_targets: ShortcutTargets,
+
+    /// Whether to show what has sockets and named pipes open.
+    pub socket_info: SocketInfo,
+
+    /// Whether to show which earlier file each hard link is the same as.
+    pub hard_links: HardLinks,
+
+    /// Whether to show the normalized form of names that aren’t in it.
+    pub show_normalized: ShowNormalized,
+
+    /// Whether to note why a file’s metadata couldn’t be read after its name.
+    pub inline_errors: bool,
+
+    /// How to shade file names by their age, in views that don’t show any
+    /// of their timestamps.
+    pub color_scale: ColorScaleOptions,
+
     /// Whether we are in a console or redirecting the output
     pub is_a_tty: bool,
 }
@@ -59,6 +86,7 @@ impl Options {
                 None
             },
             mount_style: MountStyle::JustDirectoryNames,
+            age_gradient: None,
         }
     }
 }
@@ -125,6 +153,78 @@ pub enum EmbedHyperlinks {
     On,
 }
 
+/// Whether to follow desktop entries and shortcuts with an arrow and their
+/// target, the same way as symlinks.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ShortcutTargets {
+    Hide,
+    Show,
+}
+
+/// Whether to follow sockets with whether anything is listening on them,
+/// according to the kernel’s socket table, and which processes are, and
+/// named pipes with which processes are reading and writing them.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SocketInfo {
+    Hide,
+    Show,
+}
+
+/// Whether to follow files that are hard links to the same inode as a file
+/// listed before them with that file’s path.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum HardLinks {
+    Hide,
+    Show,
+}
+
+/// Whether to follow file names that aren’t in a normalized form with what
+/// they would be in it, to help track down names that look the same but
+/// aren’t, such as ones written on different platforms.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ShowNormalized {
+    Off,
+
+    /// Unicode Normalization Form C, where characters are composed.
+    Nfc,
+
+    /// NFC, with the case folded too, as when sorting case-insensitively.
+    CaseFolded,
+}
+
+impl ShowNormalized {
+    /// Returns the normalized form of a name, or nothing if it’s already
+    /// normalized.
+    fn normalize(self, name: &str) -> Option<String> {
+        let normalized: String = match self {
+            Self::Off => return None,
+            Self::Nfc => name.nfc().collect(),
+            Self::CaseFolded => name.nfc().collect::<String>().to_lowercase(),
+        };
+
+        (normalized != name).then_some(normalized)
+    }
+}
+
+/// Returns a short description of why a file’s metadata couldn’t be read,
+/// to go after its name in place of the full error message.
+fn error_note(error: &io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::NotFound => String::from("no such file"),
+        kind => kind.to_string(),
+    }
+}
+
+/// Lists processes by name and process ID, for showing what has a socket
+/// or named pipe open. Anyone can name their own processes, so the names
+/// need escaping before they get shown.
+fn process_list<'p>(processes: impl Iterator<Item = &'p Process>) -> String {
+    processes
+        .map(|process| format!("{} ({})", process.name, process.pid))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Whether to show absolute paths
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Absolute {
@@ -163,6 +263,10 @@ pub struct FileName<'a, 'dir, C> {
 
     /// How to handle displaying a mounted filesystem.
     mount_style: MountStyle,
+
+    /// The range of ages across the listing to shade the name within, if
+    /// it’s getting shaded by age.
+    age_gradient: Option<ColorScaleInformation>,
 }
 
 impl<'a, 'dir, C> FileName<'a, 'dir, C> {
@@ -185,6 +289,13 @@ impl<'a, 'dir, C> FileName<'a, 'dir, C> {
         };
         self
     }
+
+    /// Sets the range of ages to shade this file name within, from newest to
+    /// oldest, if the colour scale covers ages.
+    pub fn with_age_gradient(mut self, info: Option<ColorScaleInformation>) -> Self {
+        self.age_gradient = info.filter(|info| info.options.age);
+        self
+    }
 }
 
 impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
@@ -215,23 +326,15 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         };
 
         if let Some(spaces_count) = spaces_count_opt {
-            let (style, icon) = match icon_override {
-                Some(icon_override) => (
-                    if let Some(style_override) = icon_override.style {
-                        style_override
-                    } else {
-                        iconify_style(self.style())
-                    },
-                    icon_override
-                        .glyph
-                        .unwrap_or_else(|| icon_for_file(self.file))
-                        .to_string(),
-                ),
-                None => (
-                    iconify_style(self.style()),
-                    icon_for_file(self.file).to_string(),
-                ),
-            };
+            let style = icon_override
+                .and_then(|icon_override| icon_override.style)
+                .unwrap_or_else(|| iconify_style(self.shaded(self.style())));
+            let icon = self
+                .colours
+                .icon_override(self.file)
+                .or_else(|| icon_override.and_then(|icon_override| icon_override.glyph))
+                .unwrap_or_else(|| icon_for_file(self.file, self.options.icon_set))
+                .to_string();
 
             bits.push(style.paint(icon));
             bits.push(style.paint(" ".repeat(spaces_count as usize)));
@@ -271,9 +374,16 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             classify: Classify::JustFilenames,
                             quote_style: QuoteStyle::QuoteSpaces,
                             show_icons: ShowIcons::Never,
+                            icon_set: self.options.icon_set,
                             embed_hyperlinks: EmbedHyperlinks::Off,
                             is_a_tty: self.options.is_a_tty,
                             absolute: Absolute::Off,
+                            shortcut_targets: ShortcutTargets::Hide,
+                            socket_info: SocketInfo::Hide,
+                            hard_links: HardLinks::Hide,
+                            show_normalized: ShowNormalized::Off,
+                            inline_errors: false,
+                            color_scale: self.options.color_scale,
                         };
 
                         let target_name = FileName {
@@ -283,6 +393,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
                             link_style: LinkStyle::FullLinkPaths,
                             options: target_options,
                             mount_style: MountStyle::JustDirectoryNames,
+                            age_gradient: None,
                         };
 
                         for bit in target_name.escaped_file_name(filename_style_override) {
@@ -321,6 +432,174 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             }
         }
 
+        if self.link_style == LinkStyle::FullLinkPaths
+            && self.target.is_none()
+            && self.options.shortcut_targets == ShortcutTargets::Show
+        {
+            if let Some(target) = self.file.shortcut_target() {
+                bits.push(Style::default().paint(" "));
+                bits.push(self.colours.normal_arrow().paint("->"));
+                bits.push(Style::default().paint(" "));
+
+                escape(
+                    target,
+                    &mut bits,
+                    self.colours.symlink_path(),
+                    self.colours.control_char(),
+                    self.options.quote_style,
+                );
+            }
+        }
+
+        // Encrypted and offline files get an icon after their names when
+        // icons are shown and the icon set has one, and a word otherwise.
+        let indicators = [
+            (
+                self.file.is_encrypted(),
+                self.colours.encrypted(),
+                self.options.icon_set.encrypted(),
+                "encrypted",
+            ),
+            (
+                self.file.is_offline(),
+                self.colours.offline(),
+                self.options.icon_set.offline(),
+                "offline",
+            ),
+        ];
+        for (_, style, icon, word) in indicators.into_iter().filter(|(is, ..)| *is) {
+            bits.push(Style::default().paint(" "));
+            if let (Some(_), Some(icon)) = (spaces_count_opt, icon) {
+                bits.push(style.paint(icon.to_string()));
+            } else {
+                bits.push(style.paint(format!("[{word}]")));
+            }
+        }
+
+        if let Some(normalized) = self.options.show_normalized.normalize(&self.file.name) {
+            let style = self.colours.normalized_name();
+            bits.push(Style::default().paint(" "));
+            bits.push(style.paint("("));
+            escape(
+                normalized,
+                &mut bits,
+                style,
+                self.colours.control_char(),
+                self.options.quote_style,
+            );
+            bits.push(style.paint(")"));
+        }
+
+        if self.options.inline_errors && !self.file.is_virtual() {
+            if let Err(e) = self.file.metadata() {
+                bits.push(Style::default().paint(" "));
+                bits.push(
+                    self.colours
+                        .error_note()
+                        .paint(format!("({})", error_note(e))),
+                );
+            }
+        }
+
+        #[cfg(unix)]
+        if self.options.socket_info == SocketInfo::Show {
+            if let Some(state) = self.file.socket_state() {
+                let mut details = match (state.listener.is_some(), state.connections) {
+                    (false, _) => String::from("no listener"),
+                    (true, 0) => String::from("listening"),
+                    (true, 1) => String::from("listening, 1 connection"),
+                    (true, n) => format!("listening, {n} connections"),
+                };
+
+                if let Some(listeners) = self.file.socket_listeners() {
+                    if !listeners.is_empty() {
+                        details.push_str(", by ");
+                        details.push_str(&process_list(listeners.iter()));
+                    }
+                }
+
+                bits.push(Style::default().paint(" ["));
+                escape(
+                    details,
+                    &mut bits,
+                    Style::default(),
+                    self.colours.control_char(),
+                    QuoteStyle::NoQuotes,
+                );
+                bits.push(Style::default().paint("]"));
+            }
+
+            if let Some(holders) = self.file.pipe_holders() {
+                let by_access = |access| {
+                    holders
+                        .iter()
+                        .filter(move |(_, a)| *a == access)
+                        .map(|(process, _)| process)
+                };
+
+                let details = [
+                    (PipeAccess::Read, "read by "),
+                    (PipeAccess::Write, "written by "),
+                    (PipeAccess::ReadWrite, "read and written by "),
+                ]
+                .into_iter()
+                .filter(|(access, _)| by_access(*access).next().is_some())
+                .map(|(access, prefix)| format!("{prefix}{}", process_list(by_access(access))))
+                .collect::<Vec<_>>();
+
+                let details = if details.is_empty() {
+                    String::from("not open")
+                } else {
+                    details.join(", ")
+                };
+
+                bits.push(Style::default().paint(" ["));
+                escape(
+                    details,
+                    &mut bits,
+                    Style::default(),
+                    self.colours.control_char(),
+                    QuoteStyle::NoQuotes,
+                );
+                bits.push(Style::default().paint("]"));
+            }
+        }
+
+        #[cfg(unix)]
+        if self.options.hard_links == HardLinks::Show {
+            if let Some(first) = self.file.first_hard_link() {
+                bits.push(Style::default().paint(" "));
+                bits.push(self.colours.normal_arrow().paint("=>"));
+                bits.push(Style::default().paint(" same as "));
+
+                escape(
+                    first.display().to_string(),
+                    &mut bits,
+                    self.colours.symlink_path(),
+                    self.colours.control_char(),
+                    self.options.quote_style,
+                );
+            }
+        }
+
+        // The other arguments that `--dedupe` listed this file once for.
+        if !self.file.aliases.is_empty() {
+            bits.push(Style::default().paint(" (also "));
+            for (index, alias) in self.file.aliases.iter().enumerate() {
+                if index > 0 {
+                    bits.push(Style::default().paint(", "));
+                }
+                escape(
+                    alias.display().to_string(),
+                    &mut bits,
+                    self.colours.symlink_path(),
+                    self.colours.control_char(),
+                    self.options.quote_style,
+                );
+            }
+            bits.push(Style::default().paint(")"));
+        }
+
         if self.mount_style == MountStyle::MountInfo {
             if let Some(mount_details) = self.file.mount_point_info() {
                 // This is a filesystem mounted on the directory, output its details
@@ -408,31 +687,13 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         &self,
         style_override: Option<Style>,
     ) -> Vec<ANSIString<'unused>> {
-        use percent_encoding::{utf8_percent_encode, CONTROLS};
-
-        const HYPERLINK_START: &str = "\x1B]8;;";
-        const HYPERLINK_END: &str = "\x1B\x5C";
-
-        let file_style = style_override.unwrap_or(self.style());
+        let file_style = self.shaded(style_override.unwrap_or(self.style()));
         let mut bits = Vec::new();
 
         let mut display_hyperlink = false;
         if self.options.embed_hyperlinks == EmbedHyperlinks::On {
-            if let Some(abs_path) = self
-                .file
-                .absolute_path()
-                .and_then(|p| p.as_os_str().to_str())
-            {
-                let abs_path = utf8_percent_encode(abs_path, CONTROLS).to_string();
-
-                // On Windows, `std::fs::canonicalize` adds the Win32 File prefix, which we need to remove
-                #[cfg(target_os = "windows")]
-                let abs_path = abs_path.strip_prefix("\\\\?\\").unwrap_or(&abs_path);
-
-                bits.push(ANSIString::from(format!(
-                    "{HYPERLINK_START}file://{abs_path}{HYPERLINK_END}"
-                )));
-
+            if let Some(url) = self.file.absolute_path().and_then(|p| file_url(p)) {
+                bits.push(ANSIString::from(hyperlink_start(&url)));
                 display_hyperlink = true;
             }
         }
@@ -446,9 +707,7 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         );
 
         if display_hyperlink {
-            bits.push(ANSIString::from(format!(
-                "{HYPERLINK_START}{HYPERLINK_END}"
-            )));
+            bits.push(ANSIString::from(hyperlink_end()));
         }
 
         bits
@@ -488,9 +747,12 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
         #[rustfmt::skip]
         return match self.file {
             f if f.is_mount_point()      => self.colours.mount_point(),
+            #[cfg(unix)]
+            f if f.is_directory()        => self.directory_style(),
+            #[cfg(not(unix))]
             f if f.is_directory()        => self.colours.directory(),
             #[cfg(unix)]
-            f if f.is_executable_file()  => self.colours.executable_file(),
+            f if f.is_file()             => self.regular_file_style(),
             f if f.is_link()             => self.colours.symlink(),
             #[cfg(unix)]
             f if f.is_pipe()             => self.colours.pipe(),
@@ -500,14 +762,78 @@ impl<'a, 'dir, C: Colours> FileName<'a, 'dir, C> {
             f if f.is_char_device()      => self.colours.char_device(),
             #[cfg(unix)]
             f if f.is_socket()           => self.colours.socket(),
+            #[cfg(unix)]
+            f if f.is_door()             => self.colours.door().unwrap_or_else(|| self.colours.special()),
             f if ! f.is_file()           => self.colours.special(),
             _                            => self.colours.colour_file(self.file),
         };
     }
 
+    /// Shades the given style by how recently the file was modified, if the
+    /// name is getting shaded by age. Names without a colour of their own get
+    /// shaded in the colour of dates.
+    fn shaded(&self, mut style: Style) -> Style {
+        let Some(info) = self.age_gradient else {
+            return style;
+        };
+
+        if style.foreground.is_none() {
+            style.foreground = self.colours.date().foreground;
+        }
+        info.apply_time_gradient(style, self.file, TimeType::Modified)
+    }
+
+    /// The style for a directory, unless it’s sticky or anyone can write to
+    /// it and there’s a style set for that, which goes over it as in ls.
+    #[cfg(unix)]
+    fn directory_style(&self) -> Style {
+        let perms = self.file.permissions().unwrap_or_default();
+
+        let sticky_other_writable = if perms.sticky && perms.other_write {
+            self.colours.sticky_other_writable()
+        } else {
+            None
+        };
+
+        sticky_other_writable
+            .or_else(|| self.colours.other_writable().filter(|_| perms.other_write))
+            .or_else(|| self.colours.sticky().filter(|_| perms.sticky))
+            .unwrap_or_else(|| self.colours.directory())
+    }
+
+    /// The style for a regular file, which is the setuid, setgid, or
+    /// capability style if one’s set and it applies, then the executable
+    /// style, then the style for files with many hard links, and otherwise
+    /// the style for its name, in the same order as ls picks them.
+    #[cfg(unix)]
+    fn regular_file_style(&self) -> Style {
+        let file = self.file;
+        let perms = file.permissions().unwrap_or_default();
+
+        self.colours
+            .setuid()
+            .filter(|_| perms.setuid)
+            .or_else(|| self.colours.setgid().filter(|_| perms.setgid))
+            .or_else(|| {
+                self.colours
+                    .capability()
+                    .filter(|_| file.has_capabilities())
+            })
+            .or_else(|| {
+                file.is_executable_file()
+                    .then(|| self.colours.executable_file())
+            })
+            .or_else(|| {
+                self.colours
+                    .multi_hard_link()
+                    .filter(|_| file.links().multiple)
+            })
+            .unwrap_or_else(|| self.colours.colour_file(file))
+    }
+
     /// For grid's use, to cover the case of hyperlink escape sequences
     pub fn bare_utf8_width(&self) -> usize {
-        UnicodeWidthStr::width(self.file.name.as_str())
+        *DisplayWidth::from(self.file.name.as_str())
     }
 }
 
@@ -535,13 +861,91 @@ pub trait Colours: FiletypeColours {
     /// when the filename is being displayed as a broken link target.
     fn broken_control_char(&self) -> Style;
 
+    /// The style to paint the normalized form of a file’s name, when it’s
+    /// shown after the name itself.
+    fn normalized_name(&self) -> Style;
+
+    /// The style to paint the note after a file’s name saying why its
+    /// metadata couldn’t be read.
+    fn error_note(&self) -> Style;
+
     /// The style to paint a file that has its executable bit set.
     fn executable_file(&self) -> Style;
 
     /// The style to paint a directory that has a filesystem mounted on it.
     fn mount_point(&self) -> Style;
 
+    /// The style to paint the indicator after an encrypted file’s name.
+    fn encrypted(&self) -> Style;
+
+    /// The style to paint the indicator after the name of a file whose
+    /// contents are stored somewhere else.
+    fn offline(&self) -> Style;
+
+    /// The style whose colour gets shaded by age for file names that don’t
+    /// have a colour of their own.
+    fn date(&self) -> Style;
+
+    /// The styles that `LS_COLORS` can give files for having many hard
+    /// links, for being doors, for their setuid, setgid, sticky, and
+    /// writable-by-anyone bits, or for having capabilities. These go over
+    /// the style for the file’s kind, but only when they’re set.
+    fn multi_hard_link(&self) -> Option<Style>;
+    fn door(&self) -> Option<Style>;
+    fn setuid(&self) -> Option<Style>;
+    fn setgid(&self) -> Option<Style>;
+    fn capability(&self) -> Option<Style>;
+    fn sticky_other_writable(&self) -> Option<Style>;
+    fn other_writable(&self) -> Option<Style>;
+    fn sticky(&self) -> Option<Style>;
+
     fn colour_file(&self, file: &File<'_>) -> Style;
 
     fn style_override(&self, file: &File<'_>) -> Option<FileNameStyle>;
+
+    /// The icon the user picked for the given file, which gets used over
+    /// the glyph from `style_override` and the built-in icon.
+    fn icon_override(&self, file: &File<'_>) -> Option<char>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decomposed_name() {
+        let name = "cafe\u{301}.txt";
+        assert_eq!(
+            ShowNormalized::Nfc.normalize(name),
+            Some("caf\u{e9}.txt".into())
+        );
+    }
+
+    #[test]
+    fn composed_name() {
+        assert_eq!(ShowNormalized::Nfc.normalize("caf\u{e9}.txt"), None);
+        assert_eq!(ShowNormalized::Nfc.normalize("README"), None);
+    }
+
+    #[test]
+    fn case_folded_name() {
+        assert_eq!(
+            ShowNormalized::CaseFolded.normalize("README"),
+            Some("readme".into())
+        );
+        assert_eq!(ShowNormalized::CaseFolded.normalize("readme"), None);
+    }
+
+    #[test]
+    fn off() {
+        assert_eq!(ShowNormalized::Off.normalize("cafe\u{301}"), None);
+    }
+
+    #[test]
+    fn error_notes() {
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(error_note(&missing), "no such file");
+        assert_eq!(error_note(&denied), "permission denied");
+    }
 }
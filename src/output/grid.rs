@@ -4,18 +4,37 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use std::fmt;
 use std::io::{self, Write};
 
-use term_grid::{Direction, Filling, Grid, GridOptions};
+use nu_ansi_term::Style;
+use term_grid::Direction;
 
 use crate::fs::filter::FileFilter;
 use crate::fs::File;
+use crate::output::cell::DisplayWidth;
+use crate::output::color_scale::ColorScaleInformation;
 use crate::output::file_name::Options as FileStyle;
 use crate::theme::Theme;
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct Options {
+    /// Whether to fill the grid row by row, rather than column by column.
     pub across: bool,
+
+    /// The number of spaces to put between two columns.
+    pub gutter: usize,
+
+    /// The fewest columns to lay the grid out in, even if this means going
+    /// past the width of the terminal.
+    pub min_columns: Option<usize>,
+
+    /// The most columns to lay the grid out in, even if more would fit.
+    pub max_columns: Option<usize>,
+
+    /// Whether to spread any width left over at the end of each line across
+    /// the gutters, rather than leaving it empty.
+    pub spread: bool,
 }
 
 impl Options {
@@ -26,6 +45,235 @@ impl Options {
             Direction::TopToBottom
         }
     }
+
+    /// Lays the cells out into a grid that fits in the given width, then
+    /// adjusts the width the grid is allowed to take up until the number of
+    /// columns falls within the configured bounds. The gutters get painted
+    /// in the given separator style.
+    pub fn fit(self, cells: Vec<String>, console_width: usize, separator: Style) -> Grid {
+        let widths = cells.iter().map(|c| *DisplayWidth::of_styled(c)).collect();
+        let mut grid = Grid::new(cells, widths, self.direction(), self.gutter, separator);
+        let columns = |grid: &Grid, width| grid.dimensions(width).widths.len();
+        let count = columns(&grid, console_width);
+
+        let width = match (self.min_columns, self.max_columns) {
+            (_, Some(most)) if count > most => {
+                // Find the widest grid that still has few enough columns.
+                let (mut low, mut high) = (0, console_width);
+                while low < high {
+                    let mid = (low + high).div_ceil(2);
+                    if columns(&grid, mid) <= most {
+                        low = mid;
+                    } else {
+                        high = mid - 1;
+                    }
+                }
+                low
+            }
+            (Some(fewest), _) if count < fewest && count < grid.cells.len() => {
+                // Find the narrowest grid that has enough columns. At the
+                // very most, this puts every cell on the same line.
+                let (mut low, mut high) = (console_width, grid.one_line_width());
+                while low < high {
+                    let mid = (low + high) / 2;
+                    if columns(&grid, mid) >= fewest {
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+                low
+            }
+            _ => console_width,
+        };
+
+        grid.lay_out(width);
+        let count = grid.column_widths().len();
+        if !self.spread || count < 2 {
+            return grid;
+        }
+
+        let used = grid.column_widths().iter().sum::<usize>() + self.gutter * (count - 1);
+        let extra = width.saturating_sub(used) / (count - 1);
+        if extra > 0 {
+            grid.gutter += extra;
+            grid.lay_out(width);
+        }
+        grid
+    }
+}
+
+/// Cells laid out in columns, in as few lines as fit in a given width.
+///
+/// This does the job of `term_grid::Grid`, except that it gets told how
+/// wide each cell is: that crate adds up the widths of the characters one
+/// by one, which gets emoji sequences such as “👩‍💻” wrong, and throws
+/// every column after them out of line.
+pub struct Grid {
+    cells: Vec<String>,
+    widths: Vec<usize>,
+    direction: Direction,
+    gutter: usize,
+    separator: Style,
+    dimensions: Dimensions,
+}
+
+/// The number of lines a grid takes up, and the width of each column.
+#[derive(PartialEq, Eq, Debug, Default)]
+struct Dimensions {
+    lines: usize,
+    widths: Vec<usize>,
+}
+
+impl Grid {
+    /// Creates a grid of the given cells, each with the width it takes up
+    /// on screen, with gutters of the given width painted in the given
+    /// separator style. It needs to be laid out before it gets displayed.
+    pub fn new(
+        cells: Vec<String>,
+        widths: Vec<usize>,
+        direction: Direction,
+        gutter: usize,
+        separator: Style,
+    ) -> Self {
+        Self {
+            cells,
+            widths,
+            direction,
+            gutter,
+            separator,
+            dimensions: Dimensions::default(),
+        }
+    }
+
+    /// Lays the cells out to fit in the given width. If even the widest
+    /// cell doesn’t fit, they all go in one column.
+    pub fn lay_out(&mut self, width: usize) {
+        self.dimensions = self.dimensions(width);
+    }
+
+    /// The width of each column.
+    pub fn column_widths(&self) -> &[usize] {
+        &self.dimensions.widths
+    }
+
+    /// The number of lines the grid takes up.
+    pub fn row_count(&self) -> usize {
+        self.dimensions.lines
+    }
+
+    /// Works out the fewest lines the cells fit in with the given width.
+    fn dimensions(&self, width: usize) -> Dimensions {
+        let widest = self.widths.iter().copied().max().unwrap_or(0);
+        if self.cells.len() <= 1 || widest > width {
+            return Dimensions {
+                lines: self.cells.len(),
+                widths: self.widths.iter().copied().max().into_iter().collect(),
+            };
+        }
+
+        if self.one_line_width() <= width {
+            return Dimensions {
+                lines: 1,
+                widths: self.widths.clone(),
+            };
+        }
+
+        // Try fewer and fewer lines, starting from the most that could be
+        // needed, until the columns stop fitting.
+        let mut fitted = None;
+        for lines in (1..=self.most_lines(width)).rev() {
+            let columns = self.cells.len().div_ceil(lines);
+            let Some(room) = width.checked_sub((columns - 1) * self.gutter) else {
+                continue;
+            };
+
+            let dimensions = self.column_dimensions(lines, columns);
+            if dimensions.widths.iter().sum::<usize>() > room {
+                break;
+            }
+            fitted = Some(dimensions);
+        }
+
+        fitted.unwrap_or(Dimensions {
+            lines: self.cells.len(),
+            widths: vec![widest],
+        })
+    }
+
+    /// The most lines the cells could need: however many it takes if the
+    /// widest cells all end up next to each other.
+    fn most_lines(&self, width: usize) -> usize {
+        let mut widths = self.widths.clone();
+        widths.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut used = 0;
+        for (index, &cell_width) in widths.iter().enumerate() {
+            used += if index == 0 {
+                cell_width
+            } else {
+                cell_width + self.gutter
+            };
+            if used > width {
+                return self.cells.len().div_ceil(index);
+            }
+        }
+        1
+    }
+
+    fn column_dimensions(&self, lines: usize, columns: usize) -> Dimensions {
+        let mut widths = vec![0; columns];
+        for (index, &cell_width) in self.widths.iter().enumerate() {
+            let column = match self.direction {
+                Direction::LeftToRight => index % columns,
+                Direction::TopToBottom => index / lines,
+            };
+            widths[column] = widths[column].max(cell_width);
+        }
+        Dimensions { lines, widths }
+    }
+
+    /// The width needed to fit every cell on a single line.
+    fn one_line_width(&self) -> usize {
+        self.widths.iter().sum::<usize>() + self.gutter * self.cells.len().saturating_sub(1)
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.gutter);
+        let separator = if self.separator == Style::default() {
+            gutter
+        } else {
+            self.separator.paint(gutter).to_string()
+        };
+
+        let columns = self.dimensions.widths.len();
+        for line in 0..self.dimensions.lines {
+            for (column, column_width) in self.dimensions.widths.iter().enumerate() {
+                let index = match self.direction {
+                    Direction::LeftToRight => line * columns + column,
+                    Direction::TopToBottom => line + self.dimensions.lines * column,
+                };
+
+                // Lines can end early, when there aren’t enough cells to
+                // fill the last column.
+                let Some(cell) = self.cells.get(index) else {
+                    continue;
+                };
+
+                // The final column doesn’t need any trailing spaces.
+                f.write_str(cell)?;
+                if column + 1 < columns {
+                    let padding = column_width.saturating_sub(self.widths[index]);
+                    write!(f, "{:padding$}{separator}", "")?;
+                }
+            }
+            f.write_str("\n")?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Render<'a> {
@@ -41,27 +289,141 @@ impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
         self.filter.sort_files(&mut self.files);
 
+        let color_scale_info = ColorScaleInformation::from_color_scale(
+            self.file_style.color_scale,
+            &self.files,
+            self.filter.dot_filter,
+            None,
+            false,
+            None,
+        );
+
         let cells = self
             .files
             .iter()
             .map(|file| {
                 self.file_style
                     .for_file(file, self.theme)
+                    .with_age_gradient(color_scale_info)
                     .paint()
                     .strings()
                     .to_string()
             })
             .collect();
 
-        let grid = Grid::new(
-            cells,
-            GridOptions {
-                filling: Filling::Spaces(2),
-                direction: self.opts.direction(),
-                width: self.console_width,
-            },
-        );
+        let grid = self
+            .opts
+            .fit(cells, self.console_width, self.theme.grid_separator());
 
         write!(w, "{grid}")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn down(gutter: usize) -> Options {
+        Options {
+            across: false,
+            gutter,
+            min_columns: None,
+            max_columns: None,
+            spread: false,
+        }
+    }
+
+    fn fit(options: Options, cells: &[&str], width: usize) -> String {
+        let cells = cells.iter().map(ToString::to_string).collect();
+        options.fit(cells, width, Style::default()).to_string()
+    }
+
+    const LETTERS: [&str; 6] = ["a", "b", "c", "d", "e", "f"];
+
+    #[test]
+    fn fits_the_width() {
+        assert_eq!(fit(down(2), &LETTERS, 80), "a  b  c  d  e  f\n");
+        assert_eq!(fit(down(2), &LETTERS, 4), "a  d\nb  e\nc  f\n");
+    }
+
+    #[test]
+    fn max_columns() {
+        let options = Options {
+            max_columns: Some(3),
+            ..down(2)
+        };
+        assert_eq!(fit(options, &LETTERS, 80), "a  c  e\nb  d  f\n");
+    }
+
+    #[test]
+    fn min_columns() {
+        let options = Options {
+            min_columns: Some(3),
+            ..down(2)
+        };
+        assert_eq!(fit(options, &LETTERS, 4), "a  c  e\nb  d  f\n");
+    }
+
+    #[test]
+    fn min_columns_past_every_cell() {
+        let options = Options {
+            min_columns: Some(10),
+            ..down(2)
+        };
+        assert_eq!(fit(options, &LETTERS, 4), "a  b  c  d  e  f\n");
+    }
+
+    #[test]
+    fn spread() {
+        let options = Options {
+            spread: true,
+            ..down(2)
+        };
+        assert_eq!(fit(options, &LETTERS, 12), "a    c    e\nb    d    f\n");
+    }
+
+    #[test]
+    fn spread_one_column() {
+        let options = Options {
+            spread: true,
+            ..down(2)
+        };
+        assert_eq!(fit(options, &["abcdef", "g"], 8), "abcdef\ng\n");
+    }
+
+    #[test]
+    fn emoji_zwj_sequence() {
+        let cells = ["👨\u{200D}👩\u{200D}👧", "ab", "c", "d"];
+        assert_eq!(
+            fit(down(2), &cells, 7),
+            "👨\u{200D}👩\u{200D}👧  c\nab  d\n"
+        );
+    }
+
+    #[test]
+    fn emoji_presentation() {
+        let cells = ["❤\u{FE0F}", "ab", "c", "d"];
+        assert_eq!(fit(down(2), &cells, 6), "❤\u{FE0F}  c\nab  d\n");
+    }
+
+    #[test]
+    fn emoji_modifier() {
+        let cells = ["👍🏽", "ab", "c", "d"];
+        assert_eq!(fit(down(2), &cells, 6), "👍🏽  c\nab  d\n");
+    }
+
+    #[test]
+    fn cjk() {
+        let cells = ["漢字", "a", "한국어", "b"];
+        assert_eq!(fit(down(2), &cells, 12), "漢字  한국어\na     b\n");
+    }
+
+    #[test]
+    fn styled() {
+        let cells = ["\x1b[1;34m👩\u{200D}💻\x1b[0m", "ab", "c", "d"];
+        assert_eq!(
+            fit(down(2), &cells, 6),
+            "\x1b[1;34m👩\u{200D}💻\x1b[0m  c\nab  d\n"
+        );
+    }
+}
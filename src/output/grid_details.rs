@@ -8,34 +8,61 @@
 
 use std::io::{self, Write};
 
-use ansi_width;
-use grid::{Direction, Filling, Grid, GridOptions};
-use term_grid as grid;
+use term_grid::Direction;
 
 use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
+use crate::fs::vcs::VcsCache;
 use crate::fs::{Dir, File};
-use crate::output::cell::TextCell;
+use crate::output::cell::DisplayWidth;
 use crate::output::color_scale::ColorScaleInformation;
-use crate::output::details::{Options as DetailsOptions, Render as DetailsRender};
-use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Table};
+use crate::output::details::{Options as DetailsOptions, Progress, Render as DetailsRender};
+use crate::output::file_name::{Options as FileStyle, ShowIcons};
+use crate::output::grid::Grid;
+use crate::output::table::{Column, Options as TableOptions, Table};
 use crate::theme::Theme;
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub details: DetailsOptions,
     pub row_threshold: RowThreshold,
+
+    /// Whether to fill the grid row by row, rather than column by column.
+    pub across: bool,
+
+    /// What to take away, in this order, when the details are too wide for
+    /// more than one of them to fit across the screen.
+    pub shrink: Vec<Shrink>,
+}
+
+/// Something that can be taken away from the grid-details view to make room
+/// for more columns of files.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Shrink {
+    /// One of the table’s columns.
+    Column(Column),
+
+    /// The icons before file names.
+    Icons,
 }
 
 impl Options {
     pub fn to_details_options(&self) -> &DetailsOptions {
         &self.details
     }
+
+    pub fn direction(&self) -> Direction {
+        if self.across {
+            Direction::LeftToRight
+        } else {
+            Direction::TopToBottom
+        }
+    }
 }
 
 /// The grid-details view can be configured to revert to just a details view
-/// (with one column) if it wouldn’t produce enough rows of output.
+/// (with one column) if it wouldn’t produce enough rows of output, with
+/// `--grid-details-threshold` or the `EZA_GRID_ROWS` environment variable.
 ///
 /// Doing this makes the resulting output look a bit better: when listing a
 /// small directory of four files in four columns, the files just look spaced
@@ -69,6 +96,9 @@ pub struct Render<'a> {
     /// The details part of the grid-details view.
     pub details: &'a DetailsOptions,
 
+    /// Which way to fill the grid.
+    pub direction: Direction,
+
     /// How to filter files after listing a directory. The files in this
     /// render will already have been filtered and sorted, but any directories
     /// that we recurse into will have to have this applied.
@@ -84,9 +114,18 @@ pub struct Render<'a> {
 
     pub git: Option<&'a GitCache>,
 
+    pub vcs: Option<&'a VcsCache>,
+
     pub console_width: usize,
 
     pub git_repos: bool,
+
+    /// What can be taken away to make room for the grid.
+    pub shrink: &'a [Shrink],
+
+    /// How to write the listing while hashes are being worked out, when
+    /// it falls back to a details view.
+    pub progress: Progress,
 }
 
 impl<'a> Render<'a> {
@@ -108,7 +147,14 @@ impl<'a> Render<'a> {
             filter:        self.filter,
             git_ignoring:  self.git_ignoring,
             git:           self.git,
+            vcs:           self.vcs,
             git_repos:     self.git_repos,
+            deref_links:   false,
+            max_results:   None,
+            sections:      None,
+            progress:      Progress::Ordered,
+            total:         None,
+            dedupe:        None,
         };
     }
 
@@ -122,7 +168,7 @@ impl<'a> Render<'a> {
             .as_ref()
             .expect("Details table options not given!");
 
-        let drender = self.details_for_column();
+        self.drop_unused_git();
 
         let color_scale_info = ColorScaleInformation::from_color_scale(
             self.details.color_scale,
@@ -133,6 +179,140 @@ impl<'a> Render<'a> {
             None,
         );
 
+        // If the details are too wide for more than one of them to fit
+        // across the screen, try again without some of the less important
+        // columns, and give up on the grid if even that doesn’t fit.
+        let shrunk;
+        let mut file_style = *self.file_style;
+        let (mut table, mut grid) = self.lay_out(options, &file_style, color_scale_info);
+        if self.files.len() > 1 && grid.column_widths().len() == 1 {
+            let Some((fitted, fitted_style)) = self.shrink_to_fit(options, color_scale_info) else {
+                return self.render_details(w);
+            };
+
+            shrunk = fitted;
+            file_style = fitted_style;
+            (table, grid) = self.lay_out(&shrunk, &file_style, color_scale_info);
+        }
+
+        // If a minimum grid rows threshold has been set
+        // and the grid is going to get rendered with fewer rows,
+        // then render a details list view instead.
+        if let RowThreshold::MinimumRows(minimum_rows) = self.row_threshold {
+            if grid.row_count() < minimum_rows {
+                return self.render_details(w);
+            }
+        }
+
+        if self.details.header {
+            let row = table.header_row();
+            let name = self
+                .theme
+                .ui
+                .header
+                .unwrap_or_default()
+                .paint(table.name_header())
+                .to_string();
+            let s = table.render(row).strings().to_string();
+            let combined_header = format!("{s} {name}");
+            let header_width = *DisplayWidth::of_styled(&combined_header);
+            for column_width in grid.column_widths() {
+                let padding = " ".repeat((column_width + 4).saturating_sub(header_width));
+                write!(w, "{combined_header}{padding}")?;
+            }
+            writeln!(w)?;
+        }
+
+        write!(w, "{grid}")?;
+
+        Ok(())
+    }
+
+    /// Renders the files as a plain details view instead, for when the
+    /// grid isn’t worth it.
+    fn render_details<W: Write>(self, w: &mut W) -> io::Result<()> {
+        let Self {
+            dir,
+            files,
+            theme,
+            file_style,
+            details: opts,
+            filter,
+            git_ignoring,
+            git,
+            vcs,
+            git_repos,
+            progress,
+            ..
+        } = self;
+
+        let r = DetailsRender {
+            dir,
+            files,
+            theme,
+            file_style,
+            opts,
+            recurse: None,
+            filter,
+            git_ignoring,
+            git,
+            vcs,
+            git_repos,
+            deref_links: false,
+            max_results: None,
+            sections: None,
+            progress,
+            total: None,
+            dedupe: None,
+        };
+        r.render(w)
+    }
+
+    /// Takes the things in the list of those to shrink away from the
+    /// table, one at a time, until the grid has room for more than one
+    /// column. Returns the options for the table and file names that fit,
+    /// or nothing if they don’t fit even with all of them gone.
+    fn shrink_to_fit(
+        &self,
+        options: &TableOptions,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> Option<(TableOptions, FileStyle)> {
+        let mut options = options.clone();
+        let mut file_style = *self.file_style;
+
+        for &shrink in self.shrink {
+            match shrink {
+                Shrink::Column(column) if options.columns.contains(column) => {
+                    options.columns = options.columns.without(column);
+                }
+                Shrink::Icons if file_style.show_icons != ShowIcons::Never => {
+                    file_style.show_icons = ShowIcons::Never;
+                }
+                Shrink::Column(_) | Shrink::Icons => continue,
+            }
+
+            let (_, grid) = self.lay_out(&options, &file_style, color_scale_info);
+            if grid.column_widths().len() > 1 {
+                return Some((options, file_style));
+            }
+        }
+
+        None
+    }
+
+    /// Turns every file into a cell holding its details and name, and lays
+    /// the cells out in a grid, returning the table used to render them
+    /// alongside it.
+    fn lay_out<'t>(
+        &self,
+        options: &'t TableOptions,
+        file_style: &FileStyle,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> (Table<'t>, Grid)
+    where
+        'a: 't,
+    {
+        let drender = self.details_for_column();
         let mut table = self.make_table(options);
 
         // It is important to collect all these rows _before_ turning them into
@@ -148,13 +328,15 @@ impl<'a> Render<'a> {
             })
             .collect();
 
+        let name_width = *DisplayWidth::from(table.name_header());
+        let name_gradient = color_scale_info.filter(|_| !table.shows_timestamps());
         let cells = rows
             .into_iter()
             .zip(&self.files)
             .map(|(row, file)| {
-                let filename = self
-                    .file_style
+                let filename = file_style
                     .for_file(file, self.theme)
+                    .with_age_gradient(name_gradient)
                     .paint()
                     .strings()
                     .to_string();
@@ -162,85 +344,36 @@ impl<'a> Render<'a> {
 
                 // This bit fixes a strange corner case. If there is a header,
                 // then "Name" will be added to the header row. That means that
-                // the filename column, should be at least 4 characters wide.
+                // the filename column, should be at least as wide as that.
                 // Therefore we pad the filenames with some spaces. We have to
-                // use ansi_width here, because the filename might contain some
-                // styling.
+                // measure the styled width here, because the filename might
+                // contain some styling.
                 let padding = " ".repeat(if self.details.header {
-                    4usize.saturating_sub(ansi_width::ansi_width(&filename))
+                    name_width.saturating_sub(*DisplayWidth::of_styled(&filename))
                 } else {
                     0
                 });
 
                 format!("{details} {filename}{padding}")
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let widths = cells.iter().map(|c| *DisplayWidth::of_styled(c)).collect();
 
-        let grid = Grid::new(
+        let mut grid = Grid::new(
             cells,
-            GridOptions {
-                filling: Filling::Spaces(4),
-                direction: Direction::TopToBottom,
-                width: self.console_width,
-            },
+            widths,
+            self.direction,
+            4,
+            self.theme.grid_separator(),
         );
+        grid.lay_out(self.console_width);
 
-        // If a minimum grid rows threshold has been set
-        // via the `EZA_GRID_ROWS` environment variable
-        // and the grid is going to get rendered with fewer rows,
-        // then render a details list view instead.
-        if let RowThreshold::MinimumRows(minimum_rows) = self.row_threshold {
-            if grid.row_count() < minimum_rows {
-                let Self {
-                    dir,
-                    files,
-                    theme,
-                    file_style,
-                    details: opts,
-                    filter,
-                    git_ignoring,
-                    git,
-                    git_repos,
-                    ..
-                } = self;
-
-                let r = DetailsRender {
-                    dir,
-                    files,
-                    theme,
-                    file_style,
-                    opts,
-                    recurse: None,
-                    filter,
-                    git_ignoring,
-                    git,
-                    git_repos,
-                };
-                return r.render(w);
-            }
-        }
-
-        if self.details.header {
-            let row = table.header_row();
-            let name = TextCell::paint_str(self.theme.ui.header.unwrap_or_default(), "Name")
-                .strings()
-                .to_string();
-            let s = table.render(row).strings().to_string();
-            let combined_header = format!("{s} {name}");
-            let header_width = ansi_width::ansi_width(&combined_header);
-            for column_width in grid.column_widths() {
-                let padding = " ".repeat((column_width + 4).saturating_sub(header_width));
-                write!(w, "{combined_header}{padding}")?;
-            }
-            writeln!(w)?;
-        }
-
-        write!(w, "{grid}")?;
-
-        Ok(())
+        (table, grid)
     }
 
-    fn make_table(&mut self, options: &'a TableOptions) -> Table<'a> {
+    /// Stops looking up Git statuses if there aren’t any for these files.
+    fn drop_unused_git(&mut self) {
         match (self.git, self.dir) {
             (Some(g), Some(d)) => {
                 if !g.has_anything_for(&d.path) {
@@ -254,8 +387,16 @@ impl<'a> Render<'a> {
             }
             (None, _) => { /* Keep Git how it is */ }
         }
+    }
 
-        let mut table = Table::new(options, self.git, self.theme, self.git_repos);
+    fn make_table<'t>(&self, options: &'t TableOptions) -> Table<'t>
+    where
+        'a: 't,
+    {
+        let mut table = Table::new(options, self.git, self.vcs, self.theme, self.git_repos);
+        if self.file_style.inline_errors {
+            table.show_errors_inline();
+        }
 
         // The header row will be printed separately, but it should be
         // considered for the width calculations.
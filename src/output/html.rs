@@ -0,0 +1,374 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The **HTML** output view writes a self-contained HTML page holding a
+//! table of the listed files, for use as a directory index. Each directory
+//! gets a section of its own on the same page.
+//!
+//! It uses the same columns as the details view, but rather than padding
+//! cells and colouring them with ANSI escape codes, each cell goes in its
+//! own table cell, and each style gets turned into inline CSS.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+
+use nu_ansi_term::{AnsiString as ANSIString, Color, Style};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::fs::feature::git::GitCache;
+use crate::fs::filter::FileFilter;
+use crate::fs::vcs::VcsCache;
+use crate::fs::{Dir, File};
+use crate::output::file_name::{EmbedHyperlinks, Options as FileStyle};
+use crate::output::table::{Alignment, Options as TableOptions, Table};
+use crate::theme::Theme;
+
+/// The characters that need encoding when a file name is used as a
+/// relative link.
+const HREF: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`');
+
+/// The stylesheet embedded in every page. Colours for the listing itself
+/// come from the theme, as inline styles on each cell.
+const STYLESHEET: &str = "\
+body { background: #1d1f21; color: #c5c8c6; font-family: monospace; }
+h2 { font-size: inherit; margin: 1em 1ch 0.5em; }
+table { border-collapse: collapse; }
+th { text-align: left; font-weight: normal; }
+th, td { padding: 0 1ch; white-space: pre; }
+.right { text-align: right; }
+.icon { font-family: 'Symbols Nerd Font', 'Symbols Nerd Font Mono', monospace; }
+a { color: inherit; text-decoration: none; }
+a:hover { text-decoration: underline; }";
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct Options {
+    /// The columns to show alongside each file name.
+    pub table: TableOptions,
+}
+
+pub struct Render<'a> {
+    pub dir: Option<&'a Dir>,
+    pub files: Vec<File<'a>>,
+    pub theme: &'a Theme,
+    pub file_style: &'a FileStyle,
+    pub opts: &'a Options,
+    pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
+    pub vcs: Option<&'a VcsCache>,
+    pub git_repos: bool,
+
+    /// The directory the page’s links are relative to.
+    pub base: &'a Path,
+}
+
+/// Writes the start of the page, up to where the first section goes.
+pub fn write_start<W: Write>(w: &mut W, title: &str) -> io::Result<()> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>{}</title>", escape_html(title))?;
+    writeln!(w, "<style>\n{STYLESHEET}\n</style>")?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")
+}
+
+/// Writes the end of the page, after the last section.
+pub fn write_end<W: Write>(w: &mut W) -> io::Result<()> {
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files);
+
+        // Terminal hyperlinks would just be noise in a web page, where the
+        // names get turned into proper links anyway.
+        let file_style = FileStyle {
+            embed_hyperlinks: EmbedHyperlinks::Off,
+            ..*self.file_style
+        };
+
+        let mut table = Table::new(
+            &self.opts.table,
+            self.git,
+            self.vcs,
+            self.theme,
+            self.git_repos,
+        );
+        if file_style.inline_errors {
+            table.show_errors_inline();
+        }
+
+        writeln!(w, "<section>")?;
+        if let Some(dir) = self.dir {
+            let heading = dir.path.display().to_string();
+            writeln!(w, "<h2>{}</h2>", escape_html(&heading))?;
+        }
+        writeln!(w, "<table>")?;
+
+        write!(w, "<thead><tr>")?;
+        for (column, cell) in table.columns().iter().zip(table.header_row().cells()) {
+            write!(w, "<th{}>", class(column.alignment()))?;
+            write!(w, "{}", to_html(cell))?;
+            write!(w, "</th>")?;
+        }
+        writeln!(
+            w,
+            "<th>{}</th></tr></thead>",
+            escape_html(table.name_header())
+        )?;
+
+        writeln!(w, "<tbody>")?;
+        for file in &self.files {
+            let row = table.row_for_file(file, false, None);
+
+            write!(w, "<tr>")?;
+            for (column, cell) in table.columns().iter().zip(row.cells()) {
+                write!(w, "<td{}>", class(column.alignment()))?;
+                write!(w, "{}", to_html(cell))?;
+                write!(w, "</td>")?;
+            }
+
+            let path = file.path.strip_prefix(self.base).unwrap_or(&file.path);
+            let href = utf8_percent_encode(&path.to_string_lossy(), HREF).to_string();
+            let slash = if file.is_directory() { "/" } else { "" };
+            let name = file_style.for_file(file, self.theme).paint();
+            write!(
+                w,
+                "<td><a href=\"{href}{slash}\">{}</a></td>",
+                to_html(&name)
+            )?;
+            writeln!(w, "</tr>")?;
+        }
+        writeln!(w, "</tbody>")?;
+
+        writeln!(w, "</table>")?;
+        writeln!(w, "</section>")?;
+
+        Ok(())
+    }
+}
+
+fn class(alignment: Alignment) -> &'static str {
+    match alignment {
+        Alignment::Left => "",
+        Alignment::Right => " class=\"right\"",
+    }
+}
+
+/// Converts some styled strings into HTML, with a `span` for each string
+/// that has a style.
+fn to_html(strings: &[ANSIString<'_>]) -> String {
+    let mut html = String::new();
+
+    for string in strings {
+        let text = escape_html(string.as_str());
+        let css = css(*string.style_ref());
+
+        if css.is_empty() {
+            html.push_str(&text);
+        } else {
+            let _ = write!(html, "<span style=\"{css}\">{text}</span>");
+        }
+    }
+
+    html
+}
+
+/// Escapes text for use in HTML. Icons live in the Private Use Area, so
+/// they get written as numeric character references instead, wrapped in
+/// a span that picks a font that has them.
+fn escape_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\u{E000}'..='\u{F8FF}' | '\u{F0000}'..='\u{10FFFF}' => {
+                let _ = write!(html, "<span class=\"icon\">&#x{:x};</span>", c as u32);
+            }
+            c => html.push(c),
+        }
+    }
+
+    html
+}
+
+/// Converts a terminal style into the equivalent CSS declarations.
+fn css(style: Style) -> String {
+    let mut css = Vec::new();
+
+    let (foreground, background) = if style.is_reverse {
+        (style.background, style.foreground)
+    } else {
+        (style.foreground, style.background)
+    };
+
+    if let Some(colour) = foreground.and_then(css_colour) {
+        css.push(format!("color: {colour}"));
+    }
+    if let Some(colour) = background.and_then(css_colour) {
+        css.push(format!("background-color: {colour}"));
+    }
+    if style.is_bold {
+        css.push(String::from("font-weight: bold"));
+    }
+    if style.is_dimmed {
+        css.push(String::from("opacity: 0.7"));
+    }
+    if style.is_italic {
+        css.push(String::from("font-style: italic"));
+    }
+    match (style.is_underline, style.is_strikethrough) {
+        (true, true) => css.push(String::from("text-decoration: underline line-through")),
+        (true, false) => css.push(String::from("text-decoration: underline")),
+        (false, true) => css.push(String::from("text-decoration: line-through")),
+        (false, false) => {}
+    }
+    if style.is_hidden {
+        css.push(String::from("visibility: hidden"));
+    }
+
+    css.join("; ")
+}
+
+/// The 16 standard terminal colours, using the xterm palette.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Converts a terminal colour into a CSS colour, or nothing for the
+/// terminal’s default colour.
+fn css_colour(colour: Color) -> Option<String> {
+    #[rustfmt::skip]
+    let index = match colour {
+        Color::Black                            => 0,
+        Color::Red                              => 1,
+        Color::Green                            => 2,
+        Color::Yellow                           => 3,
+        Color::Blue                             => 4,
+        Color::Purple | Color::Magenta          => 5,
+        Color::Cyan                             => 6,
+        Color::White                            => 7,
+        Color::DarkGray                         => 8,
+        Color::LightRed                         => 9,
+        Color::LightGreen                       => 10,
+        Color::LightYellow                      => 11,
+        Color::LightBlue                        => 12,
+        Color::LightPurple | Color::LightMagenta => 13,
+        Color::LightCyan                        => 14,
+        Color::LightGray                        => 15,
+        Color::Fixed(n)                         => n,
+        Color::Rgb(r, g, b)                     => return Some(hex(r, g, b)),
+        Color::Default                          => return None,
+    };
+
+    let (r, g, b) = match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        232..=255 => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    };
+
+    Some(hex(r, g, b))
+}
+
+fn hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(css(Style::default()), "");
+        assert_eq!(to_html(&[Style::default().paint("a<b")]), "a&lt;b");
+    }
+
+    #[test]
+    fn bold_colour() {
+        assert_eq!(css(Color::Blue.bold()), "color: #0000ee; font-weight: bold");
+    }
+
+    #[test]
+    fn reversed() {
+        let style = Color::Red.on(Color::Fixed(16)).reverse();
+        assert_eq!(css(style), "color: #000000; background-color: #cd0000");
+    }
+
+    #[test]
+    fn fixed_colours() {
+        assert_eq!(css_colour(Color::Fixed(9)), Some("#ff0000".into()));
+        assert_eq!(css_colour(Color::Fixed(208)), Some("#ff8700".into()));
+        assert_eq!(css_colour(Color::Fixed(244)), Some("#808080".into()));
+        assert_eq!(css_colour(Color::Default), None);
+    }
+
+    #[test]
+    fn styled_span() {
+        let html = to_html(&[Color::Yellow.underline().paint("x")]);
+        assert_eq!(
+            html,
+            "<span style=\"color: #cdcd00; text-decoration: underline\">x</span>"
+        );
+    }
+
+    #[test]
+    fn one_page() {
+        let mut page = Vec::new();
+        write_start(&mut page, "Index of <dir>").unwrap();
+        write_end(&mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert_eq!(page.matches("<!DOCTYPE html>").count(), 1);
+        assert!(page.contains("<title>Index of &lt;dir&gt;</title>"));
+        assert!(page.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn icon_codepoint() {
+        assert_eq!(
+            escape_html("\u{f115} dir"),
+            "<span class=\"icon\">&#xf115;</span> dir"
+        );
+    }
+}
@@ -4,10 +4,13 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use std::collections::HashMap;
+
 use nu_ansi_term::Style;
 use phf::{phf_map, Map};
 
 use crate::fs::File;
+use crate::info::filetype::FileType;
 
 #[non_exhaustive]
 struct Icons;
@@ -21,6 +24,7 @@ impl Icons {
     const CACHE: char           = '\u{f49b}';  // 
     const CAD: char             = '\u{f0eeb}'; // 󰻫
     const CLOCK: char           = '\u{f43a}';  // 
+    const CLOUD: char           = '\u{f0c2}';  // 
     const COMPRESSED: char      = '\u{f410}';  // 
     const CONFIG: char          = '\u{e615}';  // 
     const CSS3: char            = '\u{e749}';  // 
@@ -1070,22 +1074,249 @@ pub fn iconify_style(style: Style) -> Style {
         .unwrap_or_default()
 }
 
+/// Which icons to use, as not every terminal font has the same glyphs in it.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum IconSet {
+    /// The icons from Nerd Fonts 3, which is what the tables above use.
+    #[default]
+    Nerd3,
+
+    /// The icons from Nerd Fonts 2, which lack the Material Design icons
+    /// that Nerd Fonts 3 moved out of the Basic Multilingual Plane.
+    Nerd2,
+
+    /// Symbols that are in the Unicode standard, for fonts without any
+    /// icons patched into them.
+    Unicode,
+
+    /// Plain ASCII characters, for when nothing else will do.
+    Ascii,
+}
+
+impl IconSet {
+    /// The icon shown after the name of a file that’s encrypted, if this
+    /// set has one.
+    pub fn encrypted(self) -> Option<char> {
+        match self {
+            Self::Nerd3 | Self::Nerd2 => Some(Icons::LOCK), // 
+            Self::Unicode => Some('\u{26bf}'),              // ⚿
+            Self::Ascii => None,
+        }
+    }
+
+    /// The icon shown after the name of a file that’s stored somewhere
+    /// else, if this set has one.
+    pub fn offline(self) -> Option<char> {
+        match self {
+            Self::Nerd3 | Self::Nerd2 => Some(Icons::CLOUD), // 
+            Self::Unicode => Some('\u{2601}'),               // ☁
+            Self::Ascii => None,
+        }
+    }
+
+    /// Whether the given Nerd Fonts 3 icon is in this set. The Material
+    /// Design icons were the only ones Nerd Fonts 3 moved, so everything
+    /// else is in Nerd Fonts 2 as well.
+    fn has_nerd_icon(self, icon: char) -> bool {
+        match self {
+            Self::Nerd3 => true,
+            Self::Nerd2 => icon < '\u{f0000}',
+            Self::Unicode | Self::Ascii => false,
+        }
+    }
+}
+
 /// Lookup the icon for a file based on the file's name, if the entry is a
 /// directory, or by the lowercase file extension.
-pub fn icon_for_file(file: &File<'_>) -> char {
+///
+/// When an icon isn’t in the given Nerd Fonts set, this tries the next,
+/// less specific one instead, such as the icon for the file’s extension
+/// when the one for its name is missing.
+pub fn icon_for_file(file: &File<'_>, set: IconSet) -> char {
+    if matches!(set, IconSet::Unicode | IconSet::Ascii) {
+        return symbol_for_file(file, set);
+    }
+
+    let has = |icon: &&char| set.has_nerd_icon(**icon);
     if file.points_to_directory() {
-        *DIRECTORY_ICONS.get(file.name.as_str()).unwrap_or_else(|| {
-            if file.is_empty_dir() {
-                &Icons::FOLDER_OPEN // 
-            } else {
-                &Icons::FOLDER // 
-            }
-        })
-    } else if let Some(icon) = FILENAME_ICONS.get(file.name.as_str()) {
+        *DIRECTORY_ICONS
+            .get(file.name.as_str())
+            .filter(has)
+            .unwrap_or_else(|| {
+                if file.is_empty_dir() {
+                    &Icons::FOLDER_OPEN // 
+                } else {
+                    &Icons::FOLDER // 
+                }
+            })
+    } else if let Some(icon) = FILENAME_ICONS.get(file.name.as_str()).filter(has) {
         *icon
     } else if let Some(ext) = file.ext.as_ref() {
-        *EXTENSION_ICONS.get(ext.as_str()).unwrap_or(&Icons::FILE) // 
+        *EXTENSION_ICONS
+            .get(ext.as_str())
+            .filter(has)
+            .unwrap_or(&Icons::FILE) // 
     } else {
         Icons::FILE_OUTLINE // 
     }
 }
+
+/// Picks a symbol for a file from the Unicode or ASCII sets, which only
+/// have one for each kind of file, rather than for each name or extension.
+#[rustfmt::skip]
+fn symbol_for_file(file: &File<'_>, set: IconSet) -> char {
+    let pick = |symbol, ascii| if set == IconSet::Unicode { symbol } else { ascii };
+
+    if file.points_to_directory() {
+        return if file.is_empty_dir() { pick('▹', '/') } else { pick('▸', '/') };
+    }
+
+    match FileType::get_file_type(file) {
+        Some(FileType::Image)      => pick('▣', 'i'),
+        Some(FileType::Video)      => pick('►', 'v'),
+        Some(FileType::Music)      => pick('♪', 'm'),
+        Some(FileType::Lossless)   => pick('♫', 'm'),
+        Some(FileType::Crypto)     => pick('⚿', 'k'),
+        Some(FileType::Document)   => pick('¶', 'd'),
+        Some(FileType::Compressed) => pick('▤', 'z'),
+        Some(FileType::Temp)       => pick('~', '~'),
+        Some(FileType::Compiled)   => pick('◆', 'o'),
+        Some(FileType::Build)      => pick('◈', 'b'),
+        Some(FileType::Source)     => pick('§', 's'),
+        None if file.is_executable_file() => pick('✱', '*'),
+        None                       => pick('▫', '-'),
+    }
+}
+
+/// Icons the user has picked for files, from the theme file and the
+/// `ZETTA_ICONS` environment variable, which get used over the built-in
+/// ones above.
+///
+/// As with the built-in icons, exact file names take precedence over
+/// extensions. Glob patterns come last, and where several of them match a
+/// file, the longest one wins, as it’s likely to be the most specific.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct IconOverrides {
+    filenames: HashMap<String, char>,
+    extensions: HashMap<String, char>,
+    globs: Vec<(glob::Pattern, char)>,
+}
+
+impl IconOverrides {
+    pub fn add_filename(&mut self, name: &str, glyph: char) {
+        self.filenames.insert(name.to_owned(), glyph);
+    }
+
+    /// Adds an icon for an extension, which gets matched against files’
+    /// extensions in lowercase.
+    pub fn add_extension(&mut self, ext: &str, glyph: char) {
+        self.extensions.insert(ext.to_ascii_lowercase(), glyph);
+    }
+
+    pub fn add_glob(&mut self, pattern: glob::Pattern, glyph: char) {
+        self.globs.push((pattern, glyph));
+    }
+
+    /// Adds an icon for a key from `ZETTA_ICONS`, which is a file name if
+    /// it has no wildcards in it, an extension if it’s `*.` followed by
+    /// one, and a glob pattern otherwise.
+    pub fn add(&mut self, key: &str, glyph: char) -> Result<(), glob::PatternError> {
+        let is_literal = |s: &str| !s.contains(['*', '?', '[', ']']);
+
+        match key.strip_prefix("*.") {
+            _ if is_literal(key) => self.add_filename(key, glyph),
+            Some(ext) if is_literal(ext) && !ext.contains('.') => self.add_extension(ext, glyph),
+            _ => self.add_glob(glob::Pattern::new(key)?, glyph),
+        }
+        Ok(())
+    }
+
+    /// The icon the user picked for the given file, if there is one.
+    pub fn get(&self, file: &File<'_>) -> Option<char> {
+        self.get_for(&file.name, file.ext.as_deref())
+    }
+
+    fn get_for(&self, name: &str, ext: Option<&str>) -> Option<char> {
+        if let Some(glyph) = self.filenames.get(name) {
+            return Some(*glyph);
+        }
+
+        if let Some(glyph) = ext.and_then(|ext| self.extensions.get(ext)) {
+            return Some(*glyph);
+        }
+
+        self.globs
+            .iter()
+            .filter(|(pattern, _)| pattern.matches(name))
+            .max_by_key(|(pattern, _)| pattern.as_str().len())
+            .map(|(_, glyph)| *glyph)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, char)]) -> IconOverrides {
+        let mut overrides = IconOverrides::default();
+        for (key, glyph) in pairs {
+            overrides.add(key, *glyph).unwrap();
+        }
+        overrides
+    }
+
+    #[test]
+    fn file_names() {
+        let icons = overrides(&[("Makefile", 'M')]);
+        assert_eq!(icons.get_for("Makefile", None), Some('M'));
+        assert_eq!(icons.get_for("makefile", None), None);
+    }
+
+    #[test]
+    fn extensions() {
+        let icons = overrides(&[("*.RS", 'r')]);
+        assert_eq!(icons.get_for("main.rs", Some("rs")), Some('r'));
+        assert_eq!(icons.get_for("main.rs.bak", Some("bak")), None);
+    }
+
+    #[test]
+    fn names_before_extensions() {
+        let icons = overrides(&[("build.rs", 'b'), ("*.rs", 'r')]);
+        assert_eq!(icons.get_for("build.rs", Some("rs")), Some('b'));
+        assert_eq!(icons.get_for("main.rs", Some("rs")), Some('r'));
+    }
+
+    #[test]
+    fn longest_glob_wins() {
+        let icons = overrides(&[("*.test.js", 't'), ("*.js*", 'j')]);
+        assert_eq!(icons.get_for("app.test.js", Some("js")), Some('t'));
+        assert_eq!(icons.get_for("app.json", Some("json")), Some('j'));
+        assert_eq!(icons.get_for("app.ts", Some("ts")), None);
+    }
+
+    #[test]
+    fn extensions_before_globs() {
+        let icons = overrides(&[("*.js", 'j'), ("app*", 'a')]);
+        assert_eq!(icons.get_for("app.js", Some("js")), Some('j'));
+        assert_eq!(icons.get_for("app.ts", Some("ts")), Some('a'));
+    }
+
+    #[test]
+    fn nerd2_lacks_material_design() {
+        assert!(IconSet::Nerd2.has_nerd_icon(Icons::FOLDER));
+        assert!(!IconSet::Nerd2.has_nerd_icon(Icons::FOLDER_KEY));
+        assert!(IconSet::Nerd3.has_nerd_icon(Icons::FOLDER_KEY));
+    }
+
+    #[test]
+    fn ascii_indicators() {
+        assert_eq!(IconSet::Ascii.encrypted(), None);
+        assert_eq!(IconSet::Ascii.offline(), None);
+        assert_eq!(IconSet::Unicode.offline(), Some('☁'));
+    }
+
+    #[test]
+    fn bad_glob() {
+        assert!(IconOverrides::default().add("[*.js", 'x').is_err());
+    }
+}
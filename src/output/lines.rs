@@ -11,7 +11,9 @@ use nu_ansi_term::AnsiStrings as ANSIStrings;
 use crate::fs::filter::FileFilter;
 use crate::fs::File;
 use crate::output::cell::TextCellContents;
+use crate::output::color_scale::ColorScaleInformation;
 use crate::output::file_name::Options as FileStyle;
+use crate::output::sections::Sections;
 use crate::theme::Theme;
 
 /// The lines view literally just displays each file, line-by-line.
@@ -20,24 +22,48 @@ pub struct Render<'a> {
     pub theme: &'a Theme,
     pub file_style: &'a FileStyle,
     pub filter: &'a FileFilter,
+
+    /// Listings longer than this get split into alphabetical sections.
+    pub sections: Option<usize>,
 }
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
         self.filter.sort_files(&mut self.files);
+
+        let mut sections = Sections::new(self.sections, self.filter.sort_field, self.files.len());
+        let heading_style = self.theme.ui.header.unwrap_or_default();
+        let color_scale_info = ColorScaleInformation::from_color_scale(
+            self.file_style.color_scale,
+            &self.files,
+            self.filter.dot_filter,
+            None,
+            false,
+            None,
+        );
+
         for file in &self.files {
-            let name_cell = self.render_file(file);
+            if let Some(section) = sections.as_mut().and_then(|s| s.heading_for(&file.name)) {
+                writeln!(w, "{}", heading_style.paint(section.to_string()))?;
+            }
+
+            let name_cell = self.render_file(file, color_scale_info);
             writeln!(w, "{}", ANSIStrings(&name_cell))?;
         }
 
         Ok(())
     }
 
-    fn render_file<'f>(&self, file: &'f File<'a>) -> TextCellContents {
+    fn render_file<'f>(
+        &self,
+        file: &'f File<'a>,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> TextCellContents {
         self.file_style
             .for_file(file, self.theme)
             .with_link_paths()
             .with_mount_details(false)
+            .with_age_gradient(color_scale_info)
             .paint()
     }
 }
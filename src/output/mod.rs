@@ -8,20 +8,28 @@
 pub use self::cell::TextCell;
 pub use self::escape::escape;
 
+use self::table::SizeFormat;
+use crate::fs::recursive_size::TotalSize;
+
 pub mod color_scale;
 pub mod details;
 pub mod file_name;
 pub mod grid;
 pub mod grid_details;
+pub mod html;
 pub mod icons;
 pub mod lines;
+pub mod pager;
 pub mod render;
+pub mod sections;
+pub mod summary;
 pub mod table;
+pub mod template;
 pub mod time;
+pub mod tree;
 
 mod cell;
 mod escape;
-mod tree;
 
 /// The **view** contains all information about how to format output.
 #[derive(Debug)]
@@ -29,9 +37,49 @@ pub struct View {
     pub mode: Mode,
     pub width: TerminalWidth,
     pub file_style: file_name::Options,
-    pub deref_links: bool,
+    pub deref_links: DerefLinks,
     pub follow_links: bool,
-    pub total_size: bool,
+    pub total_size: Option<TotalSize>,
+
+    /// Listings longer than this get split into alphabetical sections.
+    pub sections: Option<usize>,
+
+    /// At most this many entries get listed from each directory, with a
+    /// note saying how many more there are.
+    pub max_results: Option<usize>,
+
+    /// How to format the size in the footer after each directory’s listing,
+    /// if there should be one.
+    pub total: Option<SizeFormat>,
+
+    /// How to write listings while their files’ hashes are being worked out.
+    pub progress: details::Progress,
+}
+
+/// Which symbolic links to dereference when querying for information.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DerefLinks {
+    /// Describe the links themselves.
+    Never,
+
+    /// Dereference only the links given as arguments, as with `ls -H`.
+    Arguments,
+
+    /// Dereference every link, including those in the directories being
+    /// listed, as with `ls -L`.
+    Always,
+}
+
+impl DerefLinks {
+    /// Whether to dereference the links given as arguments.
+    pub fn for_arguments(self) -> bool {
+        self != Self::Never
+    }
+
+    /// Whether to dereference the links inside directories.
+    pub fn for_contents(self) -> bool {
+        self == Self::Always
+    }
 }
 
 /// The **mode** is the “type” of output.
@@ -42,6 +90,8 @@ pub enum Mode {
     Details(details::Options),
     GridDetails(grid_details::Options),
     Lines,
+    Template(template::Options),
+    Html(html::Options),
 }
 
 /// The width of the terminal requested by the user.
@@ -52,6 +102,9 @@ pub enum TerminalWidth {
 
     /// Look up the terminal size at runtime.
     Automatic,
+
+    /// Act as though the output isn’t a terminal, whether or not it is.
+    Detached,
 }
 
 impl TerminalWidth {
@@ -77,6 +130,7 @@ impl TerminalWidth {
         return match self {
             Self::Set(width)  => Some(width),
             Self::Automatic   => stdout_term_width,
+            Self::Detached    => None,
         };
     }
 }
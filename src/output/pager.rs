@@ -0,0 +1,336 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! A simple built-in pager for `--pager`, which shows listings too long to
+//! fit on the screen on the terminal’s alternate screen, keeping the header
+//! row of the table being scrolled through pinned to the top.
+//!
+//! The listing gets rendered into a buffer first, with the line of each
+//! table’s header noted down, and only gets paged once it’s all there.
+
+use std::io::{self, Write};
+
+/// Where a listing gets written to.
+pub enum Writer {
+    /// Straight to standard output.
+    Direct(io::Stdout),
+
+    /// Into a buffer, to be paged once the listing is complete.
+    Paged(Pager),
+}
+
+impl Writer {
+    /// Notes that the next line to be written is a table’s header row.
+    pub fn mark_header(&mut self) {
+        if let Self::Paged(pager) = self {
+            pager.mark_header();
+        }
+    }
+
+    /// Shows the buffered listing, if there is one.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Direct(mut stdout) => stdout.flush(),
+            Self::Paged(pager) => pager.finish(),
+        }
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Direct(stdout) => stdout.write(buf),
+            Self::Paged(pager) => pager.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Direct(stdout) => stdout.flush(),
+            Self::Paged(_) => Ok(()),
+        }
+    }
+}
+
+/// A listing waiting to be paged.
+#[derive(Default)]
+pub struct Pager {
+    buffer: Vec<u8>,
+
+    /// How many complete lines have been written to the buffer so far.
+    lines: usize,
+
+    /// The numbers of the lines that are table header rows, in order.
+    headers: Vec<usize>,
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lines += buf
+            .iter()
+            .map(|&byte| usize::from(byte == b'\n'))
+            .sum::<usize>();
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Pager {
+    fn mark_header(&mut self) {
+        if self.headers.last() != Some(&self.lines) {
+            self.headers.push(self.lines);
+        }
+    }
+
+    /// Returns the header to pin to the top of the screen when the body
+    /// starts at the line `top`: the last one that has scrolled off it.
+    fn sticky_header(&self, top: usize) -> Option<usize> {
+        self.headers.iter().rev().copied().find(|&line| line < top)
+    }
+
+    /// Pages the listing, or just prints it if it fits on the screen.
+    fn finish(self) -> io::Result<()> {
+        let text = String::from_utf8_lossy(&self.buffer);
+        let lines: Vec<&str> = text.lines().collect();
+        let mut stdout = io::stdout();
+
+        if lines.len() < screen_height() {
+            stdout.write_all(&self.buffer)?;
+            return stdout.flush();
+        }
+
+        #[cfg(unix)]
+        if let Ok(screen) = Screen::enter() {
+            return self.page(&lines, &screen, &mut stdout);
+        }
+
+        stdout.write_all(&self.buffer)?;
+        stdout.flush()
+    }
+
+    #[cfg(unix)]
+    fn page(&self, lines: &[&str], screen: &Screen, w: &mut impl Write) -> io::Result<()> {
+        let mut top = 0;
+
+        loop {
+            // One line goes on the status line, and one more on the pinned
+            // header, if there are any headers to pin.
+            let reserved = if self.headers.is_empty() { 1 } else { 2 };
+            let body = screen_height().saturating_sub(reserved).max(1);
+            let max_top = lines.len().saturating_sub(body);
+            top = top.min(max_top);
+
+            self.draw(w, lines, top, body)?;
+
+            let input = screen.read_key()?;
+            match Key::parse(&input) {
+                _ if input.is_empty() => break,
+                Some(Key::Quit) => break,
+                Some(key) => top = key.scroll(top, body, max_top),
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&self, w: &mut impl Write, lines: &[&str], top: usize, body: usize) -> io::Result<()> {
+        write!(w, "\x1b[H")?;
+
+        let shown = match self.sticky_header(top) {
+            Some(header) => {
+                write!(w, "{}\x1b[0m\x1b[K\r\n", lines[header])?;
+                body
+            }
+            None if self.headers.is_empty() => body,
+            None => body + 1,
+        };
+
+        let end = (top + shown).min(lines.len());
+        for line in &lines[top..end] {
+            write!(w, "{line}\x1b[0m\x1b[K\r\n")?;
+        }
+        for _ in end..top + shown {
+            write!(w, "\x1b[K\r\n")?;
+        }
+
+        write!(
+            w,
+            "\x1b[7m lines {}-{} of {} (q to quit) \x1b[0m\x1b[K",
+            top + 1,
+            end,
+            lines.len()
+        )?;
+        w.flush()
+    }
+}
+
+/// The height of the terminal, falling back to a common default.
+fn screen_height() -> usize {
+    terminal_size::terminal_size().map_or(24, |(_w, h)| usize::from(h.0))
+}
+
+/// A key the pager responds to.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Key {
+    Quit,
+    LineDown,
+    LineUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+}
+
+impl Key {
+    /// Works out which key was pressed from the bytes read from the
+    /// terminal, understanding both `less`-style keys and the escape
+    /// sequences of the arrow and paging keys.
+    fn parse(input: &[u8]) -> Option<Self> {
+        #[rustfmt::skip]
+        return match input {
+            b"q" | b"Q" | b"\x1b" | b"\x03"                 => Some(Self::Quit),
+            b"j" | b"\r" | b"\n" | b"\x1b[B" | b"\x1bOB"     => Some(Self::LineDown),
+            b"k" | b"\x1b[A" | b"\x1bOA"                     => Some(Self::LineUp),
+            b" " | b"f" | b"\x1b[6~"                         => Some(Self::PageDown),
+            b"b" | b"\x1b[5~"                                => Some(Self::PageUp),
+            b"g" | b"<" | b"\x1b[H" | b"\x1bOH"              => Some(Self::Top),
+            b"G" | b">" | b"\x1b[F" | b"\x1bOF"              => Some(Self::Bottom),
+            _                                                => None,
+        };
+    }
+
+    /// Returns where the top of the body should be after this key gets
+    /// pressed.
+    fn scroll(self, top: usize, body: usize, max_top: usize) -> usize {
+        match self {
+            Self::Quit => top,
+            Self::LineDown => (top + 1).min(max_top),
+            Self::LineUp => top.saturating_sub(1),
+            Self::PageDown => (top + body).min(max_top),
+            Self::PageUp => top.saturating_sub(body),
+            Self::Top => 0,
+            Self::Bottom => max_top,
+        }
+    }
+}
+
+/// The terminal while it’s being used by the pager: in raw mode, so keys
+/// can be read as they get pressed, and on the alternate screen, so the
+/// shell’s scrollback is left as it was. Both get put back when this is
+/// dropped.
+#[cfg(unix)]
+struct Screen {
+    tty: std::fs::File,
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let tty = std::fs::File::open("/dev/tty")?;
+        let fd = tty.as_raw_fd();
+
+        let mut original = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut raw = original;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Switch to the alternate screen, and hide the cursor and turn off
+        // line wrapping while there, so long lines don’t push the rest of
+        // the screen down.
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b[?1049h\x1b[?25l\x1b[?7l")?;
+        stdout.flush()?;
+
+        Ok(Self { tty, original })
+    }
+
+    fn read_key(&self) -> io::Result<Vec<u8>> {
+        use std::io::Read;
+
+        let mut buf = [0; 16];
+        let count = (&self.tty).read(&mut buf)?;
+        Ok(buf[..count].to_vec())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Screen {
+    fn drop(&mut self) {
+        use std::os::unix::io::AsRawFd;
+
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[?7h\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+        unsafe { libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSANOW, &self.original) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pager(listing: &str, headers: &[usize]) -> Pager {
+        let mut pager = Pager::default();
+        pager.write_all(listing.as_bytes()).unwrap();
+        pager.headers = headers.to_vec();
+        pager
+    }
+
+    #[test]
+    fn header_marked_at_current_line() {
+        let mut pager = pager("a:\n", &[]);
+        pager.mark_header();
+        pager.mark_header();
+        assert_eq!(pager.headers, vec![1]);
+    }
+
+    #[test]
+    fn lines_counted_across_writes() {
+        let mut pager = pager("a", &[]);
+        pager.write_all(b"b\nc\n\nd").unwrap();
+        pager.mark_header();
+        assert_eq!(pager.headers, vec![3]);
+    }
+
+    #[test]
+    fn header_pinned_once_scrolled_past() {
+        let pager = pager("", &[0, 10]);
+        assert_eq!(pager.sticky_header(0), None);
+        assert_eq!(pager.sticky_header(1), Some(0));
+        assert_eq!(pager.sticky_header(10), Some(0));
+        assert_eq!(pager.sticky_header(11), Some(10));
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(Key::parse(b"q"), Some(Key::Quit));
+        assert_eq!(Key::parse(b"\x1b[B"), Some(Key::LineDown));
+        assert_eq!(Key::parse(b"\x1b[5~"), Some(Key::PageUp));
+        assert_eq!(Key::parse(b"x"), None);
+    }
+
+    #[test]
+    fn scrolling_stays_in_bounds() {
+        assert_eq!(Key::LineUp.scroll(0, 20, 100), 0);
+        assert_eq!(Key::PageDown.scroll(90, 20, 100), 100);
+        assert_eq!(Key::PageUp.scroll(10, 20, 100), 0);
+        assert_eq!(Key::Bottom.scroll(0, 20, 100), 100);
+    }
+}
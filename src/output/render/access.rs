@@ -0,0 +1,50 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::Access {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        let access = match self {
+            Self::Full => "full",
+            Self::ReadWrite => "read/write",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::None => "none",
+            Self::Unknown => return TextCell::blank(punctuation),
+        };
+        TextCell::paint_str(style, access)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn unknown() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Access::Unknown.render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn read_write() {
+        let expected = TextCell::paint_str(Green.normal(), "read/write");
+        assert_eq!(
+            expected,
+            f::Access::ReadWrite.render(Green.normal(), Black.italic())
+        );
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::Binary {
+    /// Renders the binary’s details in the order `file` gives them, such as
+    /// `ELF 64-bit x86-64, dynamic, stripped`.
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        let Some(info) = self.0 else {
+            return TextCell::blank(punctuation);
+        };
+
+        let format = match info.format {
+            f::BinaryFormat::Elf => "ELF",
+            f::BinaryFormat::Pe => "PE",
+        };
+        let linking = if info.dynamic { "dynamic" } else { "static" };
+        let mut text = format!(
+            "{format} {}-bit {}, {linking}",
+            info.bits, info.architecture
+        );
+        if info.stripped {
+            text.push_str(", stripped");
+        }
+        TextCell::paint(style, text)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn not_a_binary() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Binary(None).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn dynamic() {
+        let binary = f::Binary(Some(f::BinaryInfo {
+            format: f::BinaryFormat::Elf,
+            architecture: "x86-64",
+            bits: 64,
+            dynamic: true,
+            stripped: false,
+        }));
+        let expected = TextCell::paint_str(Green.normal(), "ELF 64-bit x86-64, dynamic");
+        assert_eq!(expected, binary.render(Green.normal(), Black.italic()));
+    }
+
+    #[test]
+    fn static_stripped() {
+        let binary = f::Binary(Some(f::BinaryInfo {
+            format: f::BinaryFormat::Pe,
+            architecture: "x86",
+            bits: 32,
+            dynamic: false,
+            stripped: true,
+        }));
+        let expected = TextCell::paint_str(Green.normal(), "PE 32-bit x86, static, stripped");
+        assert_eq!(expected, binary.render(Green.normal(), Black.italic()));
+    }
+}
@@ -29,17 +29,28 @@ impl f::Blocksize {
         let result = match size_format {
             SizeFormat::DecimalBytes => NumberPrefix::decimal(size as f64),
             SizeFormat::BinaryBytes => NumberPrefix::binary(size as f64),
-            SizeFormat::JustBytes => {
-                // Use the binary prefix to select a style.
-                let prefix = match NumberPrefix::binary(size as f64) {
+            SizeFormat::JustBytes | SizeFormat::Units(_) => {
+                // Use the binary prefix of the number shown to select a style.
+                let count = size_format.count(size);
+                let prefix = match NumberPrefix::binary(count as f64) {
                     NumberPrefix::Standalone(_) => None,
                     NumberPrefix::Prefixed(p, _) => Some(p),
                 };
 
                 // But format the number directly using the locale.
-                let string = numerics.format_int(size);
-
-                return TextCell::paint(colours.blocksize(prefix), string);
+                let string = numerics.format_int(count);
+
+                return match size_format.unit_suffix() {
+                    Some(suffix) => TextCell {
+                        width: DisplayWidth::from(&*string) + DisplayWidth::from(&*suffix),
+                        contents: vec![
+                            colours.blocksize(prefix).paint(string),
+                            colours.unit(prefix).paint(suffix),
+                        ]
+                        .into(),
+                    },
+                    None => TextCell::paint(colours.blocksize(prefix), string),
+                };
             }
         };
 
@@ -165,4 +176,22 @@ pub mod test {
             )
         );
     }
+
+    #[test]
+    fn file_pages() {
+        let directory = f::Blocksize::Some(8_192);
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![Fixed(66).paint("2"), Fixed(77).bold().paint("×4096")].into(),
+        };
+
+        assert_eq!(
+            expected,
+            directory.render(
+                &TestColours,
+                SizeFormat::Units(4096),
+                &NumericLocale::english()
+            )
+        );
+    }
 }
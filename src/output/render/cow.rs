@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::Subvolume {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(id) => TextCell::paint(style, id.to_string()),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+impl f::Compression {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(compression) => TextCell::paint(style, compression),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+impl f::Dataset {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(dataset) => TextCell::paint(style, dataset),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_subvolume() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Subvolume(None).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn subvolume() {
+        let expected = TextCell::paint_str(Green.normal(), "256");
+        assert_eq!(
+            expected,
+            f::Subvolume(Some(256)).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn compression() {
+        let compression = f::Compression(Some("zstd".into()));
+        let expected = TextCell::paint_str(Green.normal(), "zstd");
+        assert_eq!(expected, compression.render(Green.normal(), Black.italic()));
+    }
+
+    #[test]
+    fn dataset() {
+        let dataset = f::Dataset(Some("tank/home".into()));
+        let expected = TextCell::paint_str(Green.normal(), "tank/home");
+        assert_eq!(expected, dataset.render(Green.normal(), Black.italic()));
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::Dimensions {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some((width, height)) => TextCell::paint(style, format!("{width}x{height}")),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_dimensions() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Dimensions(None).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn dimensions() {
+        let dimensions = f::Dimensions(Some((1920, 1080)));
+        let expected = TextCell::paint_str(Green.normal(), "1920x1080");
+        assert_eq!(expected, dimensions.render(Green.normal(), Black.italic()));
+    }
+}
@@ -6,12 +6,112 @@
 // SPDX-License-Identifier: MIT
 use nu_ansi_term::Style;
 
-use crate::fs::fields as f;
-use crate::output::cell::TextCell;
-use crate::output::table::FlagsFormat;
+use crate::output::cell::{DisplayWidth, TextCell};
 
-impl f::Flags {
-    pub fn render(self, style: Style, _format: FlagsFormat) -> TextCell {
-        TextCell::paint(style, "-".to_string())
+/// Other platforms have no file flags, so the column is always a `-`.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "dragonfly",
+    target_os = "windows"
+)))]
+mod unsupported {
+    use super::{paint_names, Colours};
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+    use crate::output::table::FlagsFormat;
+
+    impl f::Flags {
+        pub fn render<C: Colours>(self, colours: &C, _format: FlagsFormat) -> TextCell {
+            paint_names(std::iter::empty(), ",", colours)
+        }
+    }
+}
+
+/// Paints each flag in the style the theme gives it, falling back to the
+/// style for the column as a whole, with a `-` for a file without any.
+pub(super) fn paint_names<'a, C: Colours>(
+    names: impl Iterator<Item = &'a str>,
+    separator: &'static str,
+    colours: &C,
+) -> TextCell {
+    let mut parts = Vec::new();
+    let mut width = 0;
+
+    for name in names {
+        if !parts.is_empty() {
+            parts.push(colours.flags().paint(separator));
+            width += separator.len();
+        }
+        parts.push(colours.flag(name).paint(name.to_owned()));
+        width += name.len();
+    }
+
+    if parts.is_empty() {
+        return TextCell::paint_str(colours.flags(), "-");
+    }
+
+    // Flag names are all ASCII, so each character is one column wide.
+    TextCell {
+        contents: parts.into(),
+        width: DisplayWidth::from(width),
+    }
+}
+
+pub trait Colours {
+    /// The style for the column, and for any flag without one of its own.
+    fn flags(&self) -> Style;
+
+    /// The style for the flag with this name, such as `uchg`.
+    fn flag(&self, name: &str) -> Style;
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::{paint_names, Colours};
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use nu_ansi_term::Color::*;
+    use nu_ansi_term::Style;
+
+    struct TestColours;
+
+    impl Colours for TestColours {
+        fn flags(&self) -> Style {
+            Fixed(244).normal()
+        }
+
+        fn flag(&self, name: &str) -> Style {
+            match name {
+                "schg" | "uchg" => Red.bold(),
+                _ => self.flags(),
+            }
+        }
+    }
+
+    #[test]
+    fn no_flags() {
+        let expected = TextCell::paint_str(Fixed(244).normal(), "-");
+        assert_eq!(expected, paint_names([].into_iter(), ",", &TestColours));
+    }
+
+    #[test]
+    fn styled_flags() {
+        let expected = TextCell {
+            contents: vec![
+                Red.bold().paint("uchg"),
+                Fixed(244).normal().paint(","),
+                Fixed(244).normal().paint("nodump"),
+            ]
+            .into(),
+            width: DisplayWidth::from(11),
+        };
+        assert_eq!(
+            expected,
+            paint_names(["uchg", "nodump"].into_iter(), ",", &TestColours)
+        );
     }
 }
@@ -4,7 +4,6 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
-use nu_ansi_term::Style;
 use std::ffi::CStr;
 
 #[cfg(target_os = "netbsd")]
@@ -12,6 +11,7 @@ use std::ffi::CString;
 
 use crate::fs::fields as f;
 use crate::output::cell::TextCell;
+use crate::output::render::flags::{paint_names, Colours};
 use crate::output::table::FlagsFormat;
 
 #[cfg(not(target_os = "netbsd"))]
@@ -24,8 +24,9 @@ extern "C" {
     fn flags_to_string(flags: libc::c_ulong, def: *const libc::c_char) -> *const libc::c_char;
 }
 
-/// Wrapper around the C library call fflagstostr or the netbsd equivalent
-/// If returned string is NULL or empty a "-" is returned
+/// Wrapper around the C library call fflagstostr or the netbsd equivalent,
+/// which gives the flags’ names separated by commas, as `ls -lO` does.
+/// If returned string is NULL an empty string is returned
 fn wrapper_flags_to_string(flags: f::flag_t) -> String {
     #[cfg(target_os = "netbsd")]
     let empty_string = CString::new("").expect("This string is always valid");
@@ -39,13 +40,10 @@ fn wrapper_flags_to_string(flags: f::flag_t) -> String {
     let flags_c_str = unsafe { flags_to_string(libc::c_ulong::from(flags), empty_string.as_ptr()) };
 
     if flags_c_str.is_null() {
-        "-".to_string()
+        String::new()
     } else {
         let flags_str = unsafe { CStr::from_ptr(flags_c_str) };
-        let flags = flags_str
-            .to_str()
-            .map_or("-", |s| if s.is_empty() { "-" } else { s })
-            .to_string();
+        let flags = flags_str.to_string_lossy().into_owned();
 
         // SAFETY: Calling external "C" function to free memory allocated by fflagstostr
         unsafe {
@@ -57,7 +55,8 @@ fn wrapper_flags_to_string(flags: f::flag_t) -> String {
 }
 
 impl f::Flags {
-    pub fn render(self, style: Style, _format: FlagsFormat) -> TextCell {
-        TextCell::paint(style, wrapper_flags_to_string(self.0))
+    pub fn render<C: Colours>(self, colours: &C, _format: FlagsFormat) -> TextCell {
+        let names = wrapper_flags_to_string(self.0);
+        paint_names(names.split(',').filter(|n| !n.is_empty()), ",", colours)
     }
 }
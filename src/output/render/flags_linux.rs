@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use crate::fs::feature::chattr;
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+use crate::output::render::flags::{paint_names, Colours};
+use crate::output::table::FlagsFormat;
+
+impl f::Flags {
+    pub fn render<C: Colours>(self, colours: &C, _format: FlagsFormat) -> TextCell {
+        paint_names(chattr::letters(self.0), "", colours)
+    }
+}
@@ -5,9 +5,9 @@
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
 use crate::fs::fields as f;
+use crate::output::render::flags::{paint_names, Colours};
 use crate::output::table::FlagsFormat;
 use crate::output::TextCell;
-use nu_ansi_term::Style;
 
 // See https://learn.microsoft.com/en-us/windows/win32/fileio/file-attribute-constants
 const FILE_ATTRIBUTE_READONLY: u32 = 0x0000_0001; // R
@@ -98,22 +98,6 @@ const ATTRIBUTES: [Attribute; 13] = [
     },
 ];
 
-fn flags_to_bsd_string(flags: f::flag_t) -> String {
-    let mut result = Vec::new();
-
-    for attribute in &ATTRIBUTES {
-        if attribute.flag & flags != 0 {
-            result.push(attribute.name);
-        }
-    }
-
-    if result.is_empty() {
-        "-".to_string()
-    } else {
-        result.join("-")
-    }
-}
-
 fn flags_to_windows_string(flags: f::flag_t) -> String {
     let mut result = String::new();
 
@@ -131,14 +115,15 @@ fn flags_to_windows_string(flags: f::flag_t) -> String {
 }
 
 impl f::Flags {
-    pub fn render(self, style: Style, format: FlagsFormat) -> TextCell {
-        TextCell::paint(
-            style,
-            if format == FlagsFormat::Short {
-                flags_to_windows_string(self.0)
-            } else {
-                flags_to_bsd_string(self.0)
-            },
-        )
+    pub fn render<C: Colours>(self, colours: &C, format: FlagsFormat) -> TextCell {
+        if format == FlagsFormat::Short {
+            return TextCell::paint(colours.flags(), flags_to_windows_string(self.0));
+        }
+
+        let names = ATTRIBUTES
+            .iter()
+            .filter(|attribute| attribute.flag & self.0 != 0)
+            .map(|attribute| attribute.name);
+        paint_names(names, "-", colours)
     }
 }
@@ -11,9 +11,27 @@ use crate::output::cell::{DisplayWidth, TextCell};
 
 impl f::Git {
     pub fn render(self, colours: &dyn Colours) -> TextCell {
+        let unstaged = match self.submodule {
+            Some(submodule) => submodule.render(self.unstaged, colours),
+            None => self.unstaged.render(colours),
+        };
+
         TextCell {
             width: DisplayWidth::from(2),
-            contents: vec![self.staged.render(colours), self.unstaged.render(colours)].into(),
+            contents: vec![self.staged.render(colours), unstaged].into(),
+        }
+    }
+}
+
+impl f::SubmoduleStatus {
+    /// Submodules show their state in place of their unstaged changes, unless
+    /// the pinned commit is checked out and there are changes in them.
+    fn render(self, unstaged: f::GitStatus, colours: &dyn Colours) -> ANSIString<'static> {
+        match self {
+            Self::Clean if unstaged != f::GitStatus::NotModified => unstaged.render(colours),
+            Self::Clean => colours.not_modified().paint("S"),
+            Self::Modified => colours.modified().paint("+"),
+            Self::Uninitialized => colours.not_modified().paint("_"),
         }
     }
 }
@@ -62,14 +80,34 @@ impl f::SubdirGitRepo {
         };
 
         if let Some(status) = self.status {
+            let mut width = DisplayWidth::from(2) + DisplayWidth::from(branch_name.as_str());
+            let mut contents = vec![
+                status.render(colours),
+                Style::default().paint(" "),
+                branch_name,
+            ];
+
+            // The commits to push and pull, and the stash, are shown after
+            // the branch name the way shell prompts show them.
+            let mut upstream = String::new();
+            if self.ahead > 0 {
+                upstream.push_str(&format!("⇡{}", self.ahead));
+            }
+            if self.behind > 0 {
+                upstream.push_str(&format!("⇣{}", self.behind));
+            }
+            if self.stashed {
+                upstream.push('$');
+            }
+            if !upstream.is_empty() {
+                width = width + 1 + DisplayWidth::from(upstream.as_str());
+                contents.push(Style::default().paint(" "));
+                contents.push(colours.git_dirty().paint(upstream));
+            }
+
             TextCell {
-                width: DisplayWidth::from(2) + DisplayWidth::from(branch_name.as_str()),
-                contents: vec![
-                    status.render(colours),
-                    Style::default().paint(" "),
-                    branch_name,
-                ]
-                .into(),
+                width,
+                contents: contents.into(),
             }
         } else {
             TextCell {
@@ -100,7 +138,7 @@ pub trait RepoColours {
 
 #[cfg(test)]
 pub mod test {
-    use super::Colours;
+    use super::{Colours, RepoColours};
     use crate::fs::fields as f;
     use crate::output::cell::{DisplayWidth, TextCell};
 
@@ -136,11 +174,30 @@ pub mod test {
         }
     }
 
+    impl RepoColours for TestColours {
+        fn branch_main(&self) -> Style {
+            Fixed(80).normal()
+        }
+        fn branch_other(&self) -> Style {
+            Fixed(81).normal()
+        }
+        fn no_repo(&self) -> Style {
+            Fixed(82).normal()
+        }
+        fn git_clean(&self) -> Style {
+            Fixed(83).normal()
+        }
+        fn git_dirty(&self) -> Style {
+            Fixed(84).normal()
+        }
+    }
+
     #[test]
     fn git_blank() {
         let stati = f::Git {
             staged: f::GitStatus::NotModified,
             unstaged: f::GitStatus::NotModified,
+            submodule: None,
         };
 
         let expected = TextCell {
@@ -156,6 +213,7 @@ pub mod test {
         let stati = f::Git {
             staged: f::GitStatus::New,
             unstaged: f::GitStatus::Modified,
+            submodule: None,
         };
 
         let expected = TextCell {
@@ -165,4 +223,97 @@ pub mod test {
 
         assert_eq!(expected, stati.render(&TestColours));
     }
+
+    #[test]
+    fn git_submodule_checked_out() {
+        let stati = f::Git {
+            staged: f::GitStatus::Modified,
+            unstaged: f::GitStatus::Modified,
+            submodule: Some(f::SubmoduleStatus::Modified),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(2),
+            contents: vec![Fixed(92).paint("M"), Fixed(92).paint("+")].into(),
+        };
+
+        assert_eq!(expected, stati.render(&TestColours));
+    }
+
+    #[test]
+    fn git_submodule_changes_inside() {
+        let stati = f::Git {
+            staged: f::GitStatus::NotModified,
+            unstaged: f::GitStatus::Modified,
+            submodule: Some(f::SubmoduleStatus::Clean),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(2),
+            contents: vec![Fixed(90).paint("-"), Fixed(92).paint("M")].into(),
+        };
+
+        assert_eq!(expected, stati.render(&TestColours));
+    }
+
+    #[test]
+    fn git_submodule_uninitialized() {
+        let stati = f::Git {
+            submodule: Some(f::SubmoduleStatus::Uninitialized),
+            ..f::Git::default()
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(2),
+            contents: vec![Fixed(90).paint("-"), Fixed(90).paint("_")].into(),
+        };
+
+        assert_eq!(expected, stati.render(&TestColours));
+    }
+
+    #[test]
+    fn repo_up_to_date() {
+        let repo = f::SubdirGitRepo {
+            status: Some(f::SubdirGitRepoStatus::GitClean),
+            branch: Some(String::from("main")),
+            ..f::SubdirGitRepo::default()
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![
+                Fixed(83).paint("|"),
+                Style::default().paint(" "),
+                Fixed(80).paint("main"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours));
+    }
+
+    #[test]
+    fn repo_ahead_behind_stashed() {
+        let repo = f::SubdirGitRepo {
+            status: Some(f::SubdirGitRepoStatus::GitDirty),
+            branch: Some(String::from("main")),
+            ahead: 2,
+            behind: 13,
+            stashed: true,
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(13),
+            contents: vec![
+                Fixed(84).paint("+"),
+                Style::default().paint(" "),
+                Fixed(80).paint("main"),
+                Style::default().paint(" "),
+                Fixed(84).paint("⇡2⇣13$"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours));
+    }
 }
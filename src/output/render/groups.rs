@@ -5,13 +5,18 @@
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
 use nu_ansi_term::Style;
+#[cfg(unix)]
 use uzers::{Groups, Users};
 
+#[cfg(unix)]
 use crate::fs::fields as f;
+#[cfg(unix)]
 use crate::fs::fields::User;
+use crate::fs::manifest::Owner;
 use crate::output::cell::TextCell;
 use crate::output::table::{GroupFormat, UserFormat};
 
+#[cfg(unix)]
 pub trait Render {
     fn render<C: Colours, U: Users + Groups>(
         self,
@@ -23,6 +28,7 @@ pub trait Render {
     ) -> TextCell;
 }
 
+#[cfg(unix)]
 impl Render for Option<f::Group> {
     fn render<C: Colours, U: Users + Groups>(
         self,
@@ -76,6 +82,39 @@ impl Render for Option<f::Group> {
     }
 }
 
+impl Owner {
+    /// Renders the group that owns a file on another system, using the name
+    /// that system gave it, alongside the user that owns the file.
+    pub fn render_group<C: Colours>(
+        &self,
+        colours: &C,
+        user_format: UserFormat,
+        group_format: GroupFormat,
+        file_user: Option<&Owner>,
+    ) -> TextCell {
+        let style = if self.yours {
+            colours.yours()
+        } else if self.id == 0 {
+            colours.root_group()
+        } else {
+            colours.not_yours()
+        };
+
+        let group_name = match (user_format, &self.name) {
+            (_, Some(name))
+                if group_format == GroupFormat::Smart
+                    && file_user.and_then(|u| u.name.as_ref()) == Some(name) =>
+            {
+                ":".to_string()
+            }
+            (UserFormat::Name, Some(name)) => name.clone(),
+            _ => self.id.to_string(),
+        };
+
+        TextCell::paint(style, group_name)
+    }
+}
+
 pub trait Colours {
     fn yours(&self) -> Style;
     fn not_yours(&self) -> Style;
@@ -84,10 +123,12 @@ pub trait Colours {
 }
 
 #[cfg(test)]
+#[cfg(unix)]
 #[allow(unused_results)]
 pub mod test {
     use super::{Colours, Render};
     use crate::fs::fields as f;
+    use crate::fs::manifest::Owner;
     use crate::output::cell::TextCell;
     use crate::output::table::{GroupFormat, UserFormat};
 
@@ -289,4 +330,40 @@ pub mod test {
             )
         );
     }
+
+    #[test]
+    fn remote() {
+        let user = Owner {
+            id: 1000,
+            name: Some("eve".into()),
+            yours: true,
+        };
+        let group = Owner {
+            id: 1000,
+            name: Some("eve".into()),
+            yours: true,
+        };
+
+        let expected = TextCell::paint_str(TestColours.yours(), "eve");
+        assert_eq!(
+            expected,
+            group.render_group(
+                &TestColours,
+                UserFormat::Name,
+                GroupFormat::Regular,
+                Some(&user)
+            )
+        );
+
+        let expected = TextCell::paint_str(TestColours.yours(), ":");
+        assert_eq!(
+            expected,
+            group.render_group(
+                &TestColours,
+                UserFormat::Name,
+                GroupFormat::Smart,
+                Some(&user)
+            )
+        );
+    }
 }
@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::digest;
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::Hash {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self {
+            Self::Digest(digest) => TextCell::paint(style, digest),
+            Self::Pending => {
+                // The placeholder is as wide as the digest that replaces it,
+                // so the column doesn’t have to get any wider.
+                let mut cell = TextCell::paint_str(punctuation, "…");
+                cell.add_spaces(digest::HEX_LENGTH - 1);
+                cell
+            }
+            Self::None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::digest;
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_hash() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Hash::None.render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn pending() {
+        let cell = f::Hash::Pending.render(Green.normal(), Black.italic());
+        assert_eq!(*cell.width, digest::HEX_LENGTH);
+    }
+
+    #[test]
+    fn digest() {
+        let hash = f::Hash::Digest("e3b0c442".into());
+        let expected = TextCell::paint_str(Green.normal(), "e3b0c442");
+        assert_eq!(expected, hash.render(Green.normal(), Black.italic()));
+    }
+}
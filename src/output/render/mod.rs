@@ -16,10 +16,13 @@ mod git;
 pub use self::git::Colours as GitColours;
 pub use self::git::RepoColours as GitRepoColours;
 
-#[cfg(unix)]
+mod access;
+// access rights use the default and punctuation colours
+
 mod groups;
+pub use self::groups::Colours as GroupColours;
 #[cfg(unix)]
-pub use self::groups::{Colours as GroupColours, Render as GroupRender};
+pub use self::groups::Render as GroupRender;
 
 #[cfg(unix)]
 mod inode;
@@ -28,6 +31,10 @@ mod inode;
 mod links;
 pub use self::links::Colours as LinksColours;
 
+mod mounts;
+pub use self::mounts::Render as MountRender;
+// mounts uses the default and punctuation colours
+
 mod permissions;
 pub use self::permissions::{Colours as PermissionsColours, PermissionsPlusRender};
 
@@ -38,9 +45,7 @@ mod times;
 pub use self::times::Render as TimeRender;
 // times does too
 
-#[cfg(unix)]
 mod users;
-#[cfg(unix)]
 pub use self::users::Colours as UserColours;
 #[cfg(unix)]
 pub use self::users::Render as UserRender;
@@ -53,6 +58,33 @@ pub use self::octal::Render as OctalPermissionsRender;
 mod securityctx;
 pub use self::securityctx::Colours as SecurityCtxColours;
 
+mod storage_class;
+// storage classes use the default and punctuation colours
+
+mod cow;
+// subvolumes, compression, and datasets do too
+
+mod project;
+// as do project IDs
+
+mod mime;
+// MIME types do too
+
+mod dimensions;
+// image dimensions do too
+
+mod binary;
+// as do binaries’ details
+
+mod hash;
+// and so do hashes
+
+mod preview;
+// previews use the control character colour for anything escaped
+
+mod version;
+// version uses the default and punctuation colours
+
 #[cfg(any(
     target_os = "macos",
     target_os = "freebsd",
@@ -65,12 +97,8 @@ mod flags_bsd;
 #[cfg(windows)]
 mod flags_windows;
 
-#[cfg(not(any(
-    target_os = "macos",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "openbsd",
-    target_os = "dragonfly",
-    target_os = "windows"
-)))]
+#[cfg(target_os = "linux")]
+mod flags_linux;
+
 mod flags;
+pub use self::flags::Colours as FlagsColours;
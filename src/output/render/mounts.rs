@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::mounts::MountedFs;
+use crate::output::cell::TextCell;
+
+pub trait Render {
+    fn render(self, style: Style, punctuation: Style) -> TextCell;
+}
+
+impl Render for Option<&MountedFs> {
+    /// Renders the type of the filesystem mounted on a directory, followed
+    /// by where it was mounted from, or a `-` for anything that isn’t a
+    /// mount point.
+    fn render(self, style: Style, punctuation: Style) -> TextCell {
+        let Some(mount) = self else {
+            return TextCell::blank(punctuation);
+        };
+
+        let mut cell = TextCell::paint(style, mount.fstype.clone());
+        cell.add_spaces(1);
+        cell.append(TextCell::paint(punctuation, mount.source.clone()));
+        cell
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Render;
+    use crate::fs::mounts::MountedFs;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use nu_ansi_term::Color::*;
+    use nu_ansi_term::Style;
+    use std::path::PathBuf;
+
+    #[test]
+    fn not_a_mount_point() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(expected, None.render(Style::default(), Black.italic()));
+    }
+
+    #[test]
+    fn mount_point() {
+        let mount = MountedFs {
+            dest: PathBuf::from("/boot"),
+            fstype: String::from("vfat"),
+            source: String::from("/dev/sda1"),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(14),
+            contents: vec![
+                Blue.paint("vfat"),
+                Style::default().paint(" "),
+                Black.italic().paint("/dev/sda1"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, Some(&mount).render(Blue.normal(), Black.italic()));
+    }
+}
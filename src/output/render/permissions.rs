@@ -8,6 +8,8 @@ use std::iter;
 
 use nu_ansi_term::{AnsiString as ANSIString, Style};
 
+#[cfg(windows)]
+use crate::fs::feature::reparse::ReparseKind;
 use crate::fs::fields as f;
 use crate::output::cell::{DisplayWidth, TextCell};
 use crate::output::render::FiletypeColours;
@@ -29,6 +31,14 @@ impl PermissionsPlusRender for Option<f::PermissionsPlus> {
                     chars.push(colours.attribute().paint("@"));
                 }
 
+                if p.acl {
+                    chars.push(colours.attribute().paint("+"));
+                }
+
+                if p.sparse {
+                    chars.push(colours.attribute().paint("S"));
+                }
+
                 // As these are all ASCII characters, we can guarantee that they’re
                 // all going to be one character wide, and don’t need to compute the
                 // cell’s display width.
@@ -159,12 +169,15 @@ impl f::Attributes {
     }
 
     pub fn render_type<C: Colours + FiletypeColours>(self, colours: &C) -> ANSIString<'static> {
-        if self.reparse_point {
-            return colours.pipe().paint("l");
-        } else if self.directory {
-            return colours.directory().paint("d");
+        match self.reparse {
+            Some(ReparseKind::Symlink) => colours.symlink().paint("l"),
+            Some(ReparseKind::Junction) => colours.symlink().paint("j"),
+            Some(ReparseKind::AppExecLink) => colours.symlink().paint("e"),
+            Some(ReparseKind::Cloud) => colours.special().paint("c"),
+            Some(ReparseKind::Other) => colours.special().paint("r"),
+            None if self.directory => colours.directory().paint("d"),
+            None => colours.dash().paint("-"),
         }
-        colours.dash().paint("-")
     }
 }
 
@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::{TextCell, TextCellContents};
+use crate::output::escape;
+use crate::output::file_name::QuoteStyle;
+
+impl f::Preview {
+    /// Renders the start of the file, with any control characters in it,
+    /// such as tabs, escaped so they can’t mess up the terminal.
+    pub fn render(self, style: Style, control_char: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(text) if !text.is_empty() => {
+                let mut bits = Vec::new();
+                escape::escape(text, &mut bits, style, control_char, QuoteStyle::NoQuotes);
+                TextCellContents::from(bits).promote()
+            }
+            _ => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_preview() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::Preview(None).render(Green.normal(), Red.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn preview() {
+        let preview = f::Preview(Some("# Notes".into()));
+        let expected = TextCell::paint_str(Green.normal(), "# Notes");
+        assert_eq!(
+            expected,
+            preview.render(Green.normal(), Red.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let preview = f::Preview(Some("a\tb".into()));
+        let expected = TextCell {
+            contents: vec![
+                Green.normal().paint("a"),
+                Red.normal().paint("\\t"),
+                Green.normal().paint("b"),
+            ]
+            .into(),
+            width: DisplayWidth::from(4),
+        };
+        assert_eq!(
+            expected,
+            preview.render(Green.normal(), Red.normal(), Black.italic())
+        );
+    }
+}
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::ProjectId {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(id) => TextCell::paint(style, id.to_string()),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_project() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::ProjectId(None).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn default_project() {
+        let expected = TextCell::paint_str(Green.normal(), "0");
+        assert_eq!(
+            expected,
+            f::ProjectId(Some(0)).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn project() {
+        let expected = TextCell::paint_str(Green.normal(), "42");
+        assert_eq!(
+            expected,
+            f::ProjectId(Some(42)).render(Green.normal(), Black.italic())
+        );
+    }
+}
@@ -34,6 +34,10 @@ impl f::SecurityContext<'_> {
                     width: DisplayWidth::from(context.len()),
                 }
             }
+            // A label that’s all one part is closest to a SELinux type.
+            f::SecurityContextType::Smack(label) | f::SecurityContextType::AppArmor(label) => {
+                TextCell::paint(colours.selinux_type(), String::from(*label))
+            }
         }
     }
 }
@@ -27,33 +27,53 @@ impl f::Size {
             Self::Some(s) => s,
             Self::None => return TextCell::blank(colours.no_size()),
             Self::DeviceIDs(ref ids) => return ids.render(colours),
+            Self::Lfs(s) => {
+                // The marker goes in front so the units still line up.
+                let mut cell = TextCell::paint_str(colours.no_size(), "LFS ");
+                cell.append(Self::Some(s).render(colours, size_format, numerics, color_scale_info));
+                return cell;
+            }
         };
 
-        let is_gradient_mode =
-            color_scale_info.is_some_and(|csi| csi.options.mode == ColorScaleMode::Gradient);
+        let gradient = color_scale_info.filter(|csi| csi.options.mode == ColorScaleMode::Gradient);
+
+        // When the size column is colour-scaled, the colour comes from the
+        // theme’s size thresholds instead of the displayed prefix, shaded by
+        // where the size falls between the smallest and largest ones.
+        let scaled = |csi: ColorScaleInformation, prefix: Option<Prefix>| {
+            let prefix = if csi.options.size {
+                colours.band(size)
+            } else {
+                prefix
+            };
+            csi.adjust_style(colours.size(prefix), size as f32, csi.size)
+        };
 
         #[rustfmt::skip]
         let result = match size_format {
             SizeFormat::DecimalBytes  => NumberPrefix::decimal(size as f64),
             SizeFormat::BinaryBytes   => NumberPrefix::binary(size as f64),
-            SizeFormat::JustBytes     => {
-                // Use the binary prefix to select a style.
-                let prefix = match NumberPrefix::binary(size as f64) {
+            SizeFormat::JustBytes | SizeFormat::Units(_) => {
+                // Use the binary prefix of the number shown to select a style.
+                let count = size_format.count(size);
+                let prefix = match NumberPrefix::binary(count as f64) {
                     NumberPrefix::Standalone(_) => None,
                     NumberPrefix::Prefixed(p, _) => Some(p),
                 };
 
                 // But format the number directly using the locale.
-                let string = numerics.format_int(size);
-
-                return if is_gradient_mode {
-                    let csi = color_scale_info.unwrap();
-                    TextCell::paint(
-                        csi.adjust_style(colours.size(prefix), size as f32, csi.size),
-                        string,
-                    )
-                } else {
-                    TextCell::paint(colours.size(prefix), string)
+                let string = numerics.format_int(count);
+                let (style, unit_style) = match gradient {
+                    Some(csi) => (scaled(csi, prefix), scaled(csi, prefix)),
+                    None      => (colours.size(prefix), colours.unit(prefix)),
+                };
+
+                return match size_format.unit_suffix() {
+                    Some(suffix) => TextCell {
+                        width: DisplayWidth::from(&*string) + DisplayWidth::from(&*suffix),
+                        contents: vec![style.paint(string), unit_style.paint(suffix)].into(),
+                    },
+                    None => TextCell::paint(style, string),
                 }
             }
         };
@@ -61,14 +81,9 @@ impl f::Size {
         #[rustfmt::skip]
         let (prefix, n) = match result {
             NumberPrefix::Standalone(b) => {
-                return if is_gradient_mode {
-                    let csi = color_scale_info.unwrap();
-                    TextCell::paint(
-                        csi.adjust_style(colours.size(None), size as f32, csi.size),
-                        numerics.format_int(b),
-                    )
-                } else {
-                    TextCell::paint(colours.size(None), numerics.format_int(b))
+                return match gradient {
+                    Some(csi) => TextCell::paint(scaled(csi, None), numerics.format_int(b)),
+                    None      => TextCell::paint(colours.size(None), numerics.format_int(b)),
                 }
             }
             NumberPrefix::Prefixed(p, n)  => (p, n),
@@ -84,14 +99,9 @@ impl f::Size {
         TextCell {
             // symbol is guaranteed to be ASCII since unit prefixes are hardcoded.
             width: DisplayWidth::from(&*number) + symbol.len(),
-            contents: if is_gradient_mode {
-                let csi = color_scale_info.unwrap();
-                vec![
-                    csi.adjust_style(colours.size(Some(prefix)), size as f32, csi.size)
-                        .paint(number),
-                    csi.adjust_style(colours.size(Some(prefix)), size as f32, csi.size)
-                        .paint(symbol),
-                ]
+            contents: if let Some(csi) = gradient {
+                let style = scaled(csi, Some(prefix));
+                vec![style.paint(number), style.paint(symbol)]
             } else {
                 vec![
                     colours.size(Some(prefix)).paint(number),
@@ -123,6 +133,12 @@ impl f::DeviceIDs {
 pub trait Colours {
     fn size(&self, prefix: Option<Prefix>) -> Style;
     fn unit(&self, prefix: Option<Prefix>) -> Style;
+
+    /// Returns the prefix whose colours a size gets when the size column
+    /// is colour-scaled, going by the theme’s thresholds rather than the
+    /// prefix the size is displayed with.
+    fn band(&self, size: u64) -> Option<Prefix>;
+
     fn no_size(&self) -> Style;
 
     fn major(&self) -> Style;
@@ -135,6 +151,7 @@ pub mod test {
     use super::Colours;
     use crate::fs::fields as f;
     use crate::output::cell::{DisplayWidth, TextCell};
+    use crate::output::color_scale::{ColorScaleInformation, ColorScaleMode, ColorScaleOptions};
     use crate::output::table::SizeFormat;
 
     use locale::Numeric as NumericLocale;
@@ -148,6 +165,7 @@ pub mod test {
     impl Colours for TestColours {
         fn size(&self, _prefix: Option<Prefix>) -> Style { Fixed(66).normal() }
         fn unit(&self, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
+        fn band(&self, _size: u64)              -> Option<Prefix> { None }
         fn no_size(&self)                       -> Style { Black.italic() }
 
         fn major(&self) -> Style { Blue.on(Red) }
@@ -189,6 +207,30 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn lfs_pointer() {
+        let pointer = f::Size::Lfs(2_100_000);
+        let expected = TextCell {
+            width: DisplayWidth::from(8),
+            contents: vec![
+                Black.italic().paint("LFS "),
+                Fixed(66).paint("2.1"),
+                Fixed(77).bold().paint("M"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(
+            expected,
+            pointer.render(
+                &TestColours,
+                SizeFormat::DecimalBytes,
+                &NumericLocale::english(),
+                None
+            )
+        );
+    }
+
     #[test]
     fn file_binary() {
         let directory = f::Size::Some(1_048_576);
@@ -227,6 +269,25 @@ pub mod test {
         );
     }
 
+    #[test]
+    fn file_sectors() {
+        let directory = f::Size::Some(1_048_577);
+        let expected = TextCell {
+            width: DisplayWidth::from(9),
+            contents: vec![Fixed(66).paint("2,049"), Fixed(77).bold().paint("×512")].into(),
+        };
+
+        assert_eq!(
+            expected,
+            directory.render(
+                &TestColours,
+                SizeFormat::Units(512),
+                &NumericLocale::english(),
+                None
+            )
+        );
+    }
+
     #[test]
     fn device_ids() {
         let directory = f::Size::DeviceIDs(f::DeviceIDs {
@@ -253,4 +314,85 @@ pub mod test {
             )
         );
     }
+
+    struct BandColours;
+
+    #[rustfmt::skip]
+    impl Colours for BandColours {
+        fn size(&self, prefix: Option<Prefix>) -> Style { if prefix.is_some() { Red.normal() } else { Green.normal() } }
+        fn unit(&self, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
+        fn band(&self, size: u64)               -> Option<Prefix> { (size >= 500).then_some(Prefix::Kibi) }
+        fn no_size(&self)                       -> Style { Black.italic() }
+
+        fn major(&self) -> Style { Blue.on(Red) }
+        fn comma(&self) -> Style { Green.italic() }
+        fn minor(&self) -> Style { Cyan.on(Yellow) }
+    }
+
+    fn size_scale() -> ColorScaleInformation {
+        ColorScaleInformation {
+            options: ColorScaleOptions {
+                mode: ColorScaleMode::Gradient,
+                min_luminance: 50,
+                size: true,
+                age: false,
+            },
+            accessed: None,
+            changed: None,
+            created: None,
+            modified: None,
+            size: None,
+        }
+    }
+
+    #[test]
+    fn scaled_by_threshold() {
+        let file = f::Size::Some(600);
+        let expected = TextCell::paint(Red.normal(), "600".into());
+
+        assert_eq!(
+            expected,
+            file.render(
+                &BandColours,
+                SizeFormat::DecimalBytes,
+                &NumericLocale::english(),
+                Some(size_scale())
+            )
+        );
+    }
+
+    #[test]
+    fn scaled_unit_matches_number() {
+        let file = f::Size::Some(1_200);
+        let expected = TextCell {
+            width: DisplayWidth::from(4),
+            contents: vec![Red.paint("1.2"), Red.paint("k")].into(),
+        };
+
+        assert_eq!(
+            expected,
+            file.render(
+                &BandColours,
+                SizeFormat::DecimalBytes,
+                &NumericLocale::english(),
+                Some(size_scale())
+            )
+        );
+    }
+
+    #[test]
+    fn scaled_below_threshold() {
+        let file = f::Size::Some(400);
+        let expected = TextCell::paint(Green.normal(), "400".into());
+
+        assert_eq!(
+            expected,
+            file.render(
+                &BandColours,
+                SizeFormat::DecimalBytes,
+                &NumericLocale::english(),
+                Some(size_scale())
+            )
+        );
+    }
 }
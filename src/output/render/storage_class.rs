@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::StorageClass {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        match self.0 {
+            Some(class) => TextCell::paint(style, class),
+            None => TextCell::blank(punctuation),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::TextCell;
+
+    use nu_ansi_term::Color::*;
+
+    #[test]
+    fn no_class() {
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            f::StorageClass(None).render(Green.normal(), Black.italic())
+        );
+    }
+
+    #[test]
+    fn class() {
+        let class = f::StorageClass(Some("GLACIER".into()));
+        let expected = TextCell::paint_str(Green.normal(), "GLACIER");
+        assert_eq!(expected, class.render(Green.normal(), Black.italic()));
+    }
+}
@@ -5,16 +5,21 @@
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
 use nu_ansi_term::Style;
+#[cfg(unix)]
 use uzers::Users;
 
+#[cfg(unix)]
 use crate::fs::fields as f;
+use crate::fs::manifest::Owner;
 use crate::output::cell::TextCell;
 use crate::output::table::UserFormat;
 
+#[cfg(unix)]
 pub trait Render {
     fn render<C: Colours, U: Users>(self, colours: &C, users: &U, format: UserFormat) -> TextCell;
 }
 
+#[cfg(unix)]
 impl Render for Option<f::User> {
     fn render<C: Colours, U: Users>(self, colours: &C, users: &U, format: UserFormat) -> TextCell {
         #[rustfmt::skip]
@@ -40,6 +45,26 @@ impl Render for Option<f::User> {
     }
 }
 
+impl Owner {
+    /// Renders the user that owns a file on another system, using the name
+    /// that system gave it.
+    pub fn render_user<C: Colours>(&self, colours: &C, format: UserFormat) -> TextCell {
+        let user_name = match (format, &self.name) {
+            (UserFormat::Name, Some(name)) => name.clone(),
+            _ => self.id.to_string(),
+        };
+
+        let style = if self.yours {
+            colours.you()
+        } else if self.id == 0 {
+            colours.root()
+        } else {
+            colours.other()
+        };
+        TextCell::paint(style, user_name)
+    }
+}
+
 pub trait Colours {
     fn you(&self) -> Style;
     fn other(&self) -> Style;
@@ -48,10 +73,12 @@ pub trait Colours {
 }
 
 #[cfg(test)]
+#[cfg(unix)]
 #[allow(unused_results)]
 pub mod test {
     use super::{Colours, Render};
     use crate::fs::fields as f;
+    use crate::fs::manifest::Owner;
     use crate::output::cell::TextCell;
     use crate::output::table::UserFormat;
 
@@ -137,4 +164,34 @@ pub mod test {
             )
         );
     }
+
+    #[test]
+    fn remote() {
+        let owner = Owner {
+            id: 1000,
+            name: Some("enoch".into()),
+            yours: true,
+        };
+        assert_eq!(
+            TextCell::paint_str(Red.bold(), "enoch"),
+            owner.render_user(&TestColours, UserFormat::Name)
+        );
+        assert_eq!(
+            TextCell::paint_str(Red.bold(), "1000"),
+            owner.render_user(&TestColours, UserFormat::Numeric)
+        );
+    }
+
+    #[test]
+    fn remote_unnamed() {
+        let owner = Owner {
+            id: 1001,
+            name: None,
+            yours: false,
+        };
+        assert_eq!(
+            TextCell::paint_str(Blue.underline(), "1001"),
+            owner.render_user(&TestColours, UserFormat::Name)
+        );
+    }
 }
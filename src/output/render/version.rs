@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use nu_ansi_term::Style;
+
+use crate::fs::fields as f;
+use crate::output::cell::TextCell;
+
+impl f::FileVersion {
+    pub fn render(self, style: Style, punctuation: Style) -> TextCell {
+        let mut cell = match self.version {
+            Some(version) => TextCell::paint(style, version),
+            None if self.product.is_none() => return TextCell::blank(punctuation),
+            None => TextCell::default(),
+        };
+
+        if let Some(product) = self.product {
+            if *cell.width > 0 {
+                cell.add_spaces(1);
+            }
+            cell.append(TextCell::paint(punctuation, format!("({product})")));
+        }
+
+        cell
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::fs::fields as f;
+    use crate::output::cell::{DisplayWidth, TextCell};
+
+    use nu_ansi_term::Color::*;
+    use nu_ansi_term::Style;
+
+    #[test]
+    fn no_version() {
+        let version = f::FileVersion::default();
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(expected, version.render(Style::default(), Black.italic()));
+    }
+
+    #[test]
+    fn version_and_product() {
+        let version = f::FileVersion {
+            version: Some("10.0.1".into()),
+            product: Some("Windows".into()),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(16),
+            contents: vec![
+                Green.paint("10.0.1"),
+                Style::default().paint(" "),
+                Black.italic().paint("(Windows)"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, version.render(Green.normal(), Black.italic()));
+    }
+
+    #[test]
+    fn product_only() {
+        let version = f::FileVersion {
+            version: None,
+            product: Some("Tool".into()),
+        };
+
+        let expected = TextCell::paint_str(Black.italic(), "(Tool)");
+        assert_eq!(expected, version.render(Green.normal(), Black.italic()));
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Splitting long listings into alphabetical sections, each with its own
+//! heading, so that huge flat directories such as `/usr/bin` are easier to
+//! scan through.
+
+use std::fmt;
+
+use crate::fs::filter::{SortCase, SortField};
+
+/// The heading at the start of one section of a listing.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Section {
+    /// Names starting with this letter.
+    Letter(char),
+
+    /// Names starting with a digit.
+    Digits,
+
+    /// Names starting with anything else, such as punctuation.
+    Symbols,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Letter(c) => write!(f, "{c}"),
+            Self::Digits => write!(f, "0-9"),
+            Self::Symbols => write!(f, "#"),
+        }
+    }
+}
+
+/// Works out where each section starts in a listing that has already been
+/// sorted, by keeping track of which section the previous file was in.
+pub struct Sections {
+    case: SortCase,
+    strip_dot: bool,
+    current: Option<Section>,
+}
+
+impl Sections {
+    /// Returns a way to split up a listing of `count` files, or nothing if
+    /// the listing isn’t more than `threshold` files long. Listings that
+    /// aren’t sorted by name never get split, as the same letter would keep
+    /// coming back over and over.
+    pub fn new(threshold: Option<usize>, sort_field: SortField, count: usize) -> Option<Self> {
+        let (case, strip_dot) = match sort_field {
+            SortField::Name(case) => (case, false),
+            SortField::NameMixHidden(case) => (case, true),
+            _ => return None,
+        };
+
+        (count > threshold?).then_some(Self {
+            case,
+            strip_dot,
+            current: None,
+        })
+    }
+
+    /// Returns the section that starts with this file name, or nothing if
+    /// it goes in the same section as the name before it.
+    pub fn heading_for(&mut self, name: &str) -> Option<Section> {
+        let section = self.section(name);
+
+        if self.current == Some(section) {
+            None
+        } else {
+            self.current = Some(section);
+            Some(section)
+        }
+    }
+
+    fn section(&self, name: &str) -> Section {
+        let name = match name.strip_prefix('.') {
+            Some(stripped) if self.strip_dot => stripped,
+            _ => name,
+        };
+
+        match name.chars().next() {
+            Some(c) if c.is_ascii_digit() => Section::Digits,
+            Some(c) if c.is_alphabetic() => match self.case {
+                SortCase::AaBbCc => Section::Letter(c.to_uppercase().next().unwrap_or(c)),
+                SortCase::ABCabc => Section::Letter(c),
+            },
+            _ => Section::Symbols,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn headings(sort_field: SortField, names: &[&str]) -> Vec<Option<String>> {
+        let mut sections = Sections::new(Some(0), sort_field, names.len()).unwrap();
+        names
+            .iter()
+            .map(|name| sections.heading_for(name).map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn letters_ignoring_case() {
+        assert_eq!(
+            headings(
+                SortField::Name(SortCase::AaBbCc),
+                &["_build", "2to3", "7z", "apt", "Awk", "bash"]
+            ),
+            vec![
+                Some("#".into()),
+                Some("0-9".into()),
+                None,
+                Some("A".into()),
+                None,
+                Some("B".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn letters_with_case() {
+        assert_eq!(
+            headings(SortField::Name(SortCase::ABCabc), &["Awk", "apt"]),
+            vec![Some("A".into()), Some("a".into())]
+        );
+    }
+
+    #[test]
+    fn mixed_hidden_files() {
+        assert_eq!(
+            headings(
+                SortField::NameMixHidden(SortCase::AaBbCc),
+                &[".bashrc", "bin"]
+            ),
+            vec![Some("B".into()), None]
+        );
+    }
+
+    #[test]
+    fn short_listing() {
+        assert!(Sections::new(Some(10), SortField::Name(SortCase::AaBbCc), 10).is_none());
+        assert!(Sections::new(Some(10), SortField::Name(SortCase::AaBbCc), 11).is_some());
+    }
+
+    #[test]
+    fn not_sorted_by_name() {
+        assert!(Sections::new(Some(0), SortField::Size, 100).is_none());
+        assert!(Sections::new(None, SortField::Name(SortCase::AaBbCc), 100).is_none());
+    }
+}
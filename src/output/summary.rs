@@ -0,0 +1,166 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The footer printed after each directory’s listing by `--total`, giving
+//! how many entries of each kind it holds and how big they are together,
+//! and the note printed after listings that `--max-results` cuts short.
+
+use nu_ansi_term::{AnsiString as ANSIString, Style};
+
+use crate::fs::{fields as f, File};
+use crate::output::table::{SizeFormat, ENVIRONMENT};
+use crate::theme::Theme;
+
+/// The numbers that go in the footer.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Summary {
+    pub directories: usize,
+    pub files: usize,
+    pub links: usize,
+
+    /// Anything that isn’t one of the above, such as pipes or devices.
+    pub others: usize,
+
+    /// The combined size of every entry that has one.
+    pub size: u64,
+}
+
+impl Summary {
+    /// Adds up the entries in a listing. The `.` and `..` entries aren’t
+    /// counted, as every directory has those.
+    pub fn of(files: &[File<'_>]) -> Self {
+        let mut summary = Self::default();
+        for file in files {
+            summary.add(file);
+        }
+        summary
+    }
+
+    /// Counts one more entry, unless it’s `.` or `..`.
+    pub fn add(&mut self, file: &File<'_>) {
+        if file.is_all_all {
+            return;
+        }
+
+        if file.is_link() {
+            self.links += 1;
+        } else if file.is_directory() {
+            self.directories += 1;
+        } else if file.is_file() {
+            self.files += 1;
+        } else {
+            self.others += 1;
+        }
+
+        if let f::Size::Some(size) | f::Size::Lfs(size) = file.size() {
+            self.size += size;
+        }
+    }
+
+    pub fn entries(&self) -> usize {
+        self.directories + self.files + self.links + self.others
+    }
+
+    /// Returns the footer line, such as “12 entries: 3 directories, 8 files,
+    /// 1 link (45k in total)”, with the size in the theme’s colours. Empty
+    /// listings just get “0 entries”.
+    pub fn render(&self, theme: &Theme, size_format: SizeFormat) -> Vec<ANSIString<'static>> {
+        if self.entries() == 0 {
+            return vec![Style::default().paint(self.counts())];
+        }
+
+        let size = f::Size::Some(self.size).render(theme, size_format, &ENVIRONMENT.numeric, None);
+
+        let mut bits = vec![Style::default().paint(format!("{} (", self.counts()))];
+        bits.extend(size.contents.iter().cloned());
+        bits.push(Style::default().paint(" in total)"));
+        bits
+    }
+
+    /// Returns the number of entries, followed by a breakdown of them.
+    fn counts(&self) -> String {
+        let kinds = [
+            (self.directories, "directory", "directories"),
+            (self.files, "file", "files"),
+            (self.links, "link", "links"),
+            (self.others, "other", "others"),
+        ];
+
+        let breakdown = kinds
+            .into_iter()
+            .filter(|(count, _, _)| *count > 0)
+            .map(|(count, one, many)| plural(count, one, many))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let entries = plural(self.entries(), "entry", "entries");
+        if breakdown.is_empty() {
+            entries
+        } else {
+            format!("{entries}: {breakdown}")
+        }
+    }
+}
+
+/// Returns the note saying how many entries `--max-results` left out of a
+/// listing, such as “… and 3,482 more”.
+pub fn overflow(hidden: usize) -> String {
+    overflow_in(hidden, &ENVIRONMENT.numeric)
+}
+
+fn overflow_in(hidden: usize, numeric: &locale::Numeric) -> String {
+    format!("… and {} more", numeric.format_int(hidden))
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    if count == 1 {
+        format!("{count} {one}")
+    } else {
+        format!("{count} {many}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn breakdown() {
+        let summary = Summary {
+            directories: 3,
+            files: 8,
+            links: 1,
+            others: 0,
+            size: 0,
+        };
+        assert_eq!(
+            summary.counts(),
+            "12 entries: 3 directories, 8 files, 1 link"
+        );
+    }
+
+    #[test]
+    fn only_files() {
+        let summary = Summary {
+            files: 1,
+            ..Summary::default()
+        };
+        assert_eq!(summary.counts(), "1 entry: 1 file");
+    }
+
+    #[test]
+    fn nothing() {
+        assert_eq!(Summary::default().counts(), "0 entries");
+    }
+
+    #[test]
+    fn overflow_note() {
+        assert_eq!(
+            overflow_in(3482, &locale::Numeric::english()),
+            "… and 3,482 more"
+        );
+    }
+}
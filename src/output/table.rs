@@ -6,32 +6,39 @@
 // SPDX-License-Identifier: MIT
 use std::cmp::max;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(unix)]
 use std::sync::{Mutex, MutexGuard};
 
 use chrono::prelude::*;
 
 use log::*;
+use nu_ansi_term::Style;
 use once_cell::sync::Lazy;
 #[cfg(unix)]
 use uzers::UsersCache;
 
 use crate::fs::feature::git::GitCache;
-use crate::fs::{fields as f, File};
+use crate::fs::vcs::VcsCache;
+use crate::fs::{fields as f, preview, File};
 use crate::options::vars::EZA_WINDOWS_ATTRIBUTES;
 use crate::options::Vars;
-use crate::output::cell::TextCell;
+use crate::output::cell::{TextCell, TextCellContents};
 use crate::output::color_scale::ColorScaleInformation;
+use crate::output::escape;
+use crate::output::file_name::QuoteStyle;
+#[cfg(windows)]
+use crate::output::render::{GroupColours, UserColours};
 #[cfg(unix)]
 use crate::output::render::{GroupRender, OctalPermissionsRender, UserRender};
-use crate::output::render::{PermissionsPlusRender, TimeRender};
-use crate::output::time::TimeFormat;
+use crate::output::render::{MountRender, PermissionsPlusRender, TimeRender};
+use crate::output::time::{age_in_seconds, TimeFormat};
 use crate::theme::Theme;
 
 use super::color_scale::ColorScaleMode;
 
 /// Options for displaying a table.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Options {
     pub size_format: SizeFormat,
     pub time_format: TimeFormat,
@@ -39,139 +46,149 @@ pub struct Options {
     pub group_format: GroupFormat,
     pub flags_format: FlagsFormat,
     pub columns: Columns,
-}
+    pub header_names: HeaderNames,
 
-/// Extra columns to display in the table.
-#[allow(clippy::struct_excessive_bools)]
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
-pub struct Columns {
-    /// At least one of these timestamps will be shown.
-    pub time_types: TimeTypes,
-
-    // The rest are just on/off
-    pub inode: bool,
-    pub links: bool,
-    pub blocksize: bool,
-    pub group: bool,
-    pub git: bool,
-    pub subdir_git_repos: bool,
-    pub subdir_git_repos_no_stat: bool,
-    pub octal: bool,
-    pub security_context: bool,
-    pub file_flags: bool,
-
-    // Defaults to true:
-    pub permissions: bool,
-    pub filesize: bool,
-    pub user: bool,
+    /// The columns declared in the theme file, which `Column::Xattr`
+    /// columns refer to by their index.
+    pub xattr_columns: Vec<XattrColumn>,
 }
 
-impl Columns {
-    pub fn collect(&self, actually_enable_git: bool, git_repos: bool) -> Vec<Column> {
-        let mut columns = Vec::with_capacity(4);
-
-        if self.inode {
-            #[cfg(unix)]
-            columns.push(Column::Inode);
-        }
-
-        if self.octal {
-            #[cfg(unix)]
-            columns.push(Column::Octal);
-        }
-
-        if self.permissions {
-            columns.push(Column::Permissions);
-        }
-
-        if self.links {
-            #[cfg(unix)]
-            columns.push(Column::HardLinks);
-        }
-
-        if self.filesize {
-            columns.push(Column::FileSize);
-        }
+/// A column declared in the theme file, which shows the value of an
+/// extended attribute, so metadata kept in attributes can be listed
+/// without eza knowing about it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct XattrColumn {
+    /// The name of the attribute, such as `user.project`.
+    pub attribute: String,
 
-        if self.blocksize {
-            #[cfg(unix)]
-            columns.push(Column::Blocksize);
-        }
-
-        if self.user {
-            #[cfg(unix)]
-            columns.push(Column::User);
-        }
+    /// The column’s heading, which is the attribute’s name if there
+    /// isn’t one.
+    pub header: Option<String>,
 
-        if self.group {
-            #[cfg(unix)]
-            columns.push(Column::Group);
-        }
+    /// The style to show the attribute’s values in.
+    pub style: Option<Style>,
+}
 
-        if self.file_flags {
-            columns.push(Column::FileFlags);
-        }
+impl XattrColumn {
+    pub fn header(&self) -> &str {
+        self.header.as_deref().unwrap_or(&self.attribute)
+    }
+}
 
-        #[cfg(target_os = "linux")]
-        if self.security_context {
-            columns.push(Column::SecurityContext);
-        }
+/// The columns to display in the table, in the order they appear in. The
+/// file name isn’t one of them, as it always goes at the end of each row.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Columns(Vec<Column>);
 
-        if self.time_types.modified {
-            columns.push(Column::Timestamp(TimeType::Modified));
-        }
+impl Columns {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self(columns)
+    }
 
-        if self.time_types.changed {
-            columns.push(Column::Timestamp(TimeType::Changed));
-        }
+    /// Whether the given column is going to be shown, ignoring whether
+    /// there’s any Git information to fill it in with.
+    pub fn contains(&self, column: Column) -> bool {
+        self.0.contains(&column)
+    }
 
-        if self.time_types.created {
-            columns.push(Column::Timestamp(TimeType::Created));
-        }
+    /// These columns, with the given one taken out.
+    pub fn without(&self, column: Column) -> Self {
+        Self(self.0.iter().copied().filter(|c| *c != column).collect())
+    }
 
-        if self.time_types.accessed {
-            columns.push(Column::Timestamp(TimeType::Accessed));
-        }
+    pub fn collect(&self, actually_enable_git: bool, git_repos: bool) -> Vec<Column> {
+        self.0
+            .iter()
+            .copied()
+            .filter(|column| match column {
+                Column::GitStatus
+                | Column::GitCommit
+                | Column::GitSubject
+                | Column::GitAuthor
+                | Column::GitOwner
+                | Column::GitSinceTag
+                | Column::Timestamp(TimeType::Git) => actually_enable_git,
+                Column::SubdirGitRepo(_) => git_repos,
+                #[cfg(unix)]
+                Column::SecurityContext => cfg!(target_os = "linux"),
+                _ => true,
+            })
+            .collect()
+    }
+}
 
-        if self.git && actually_enable_git {
-            columns.push(Column::GitStatus);
-        }
+/// The text to use in the header row, in place of the usual heading, for
+/// any columns that have been given one.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct HeaderNames {
+    columns: Vec<(Column, String)>,
+    name: Option<String>,
+}
 
-        if self.subdir_git_repos && git_repos {
-            columns.push(Column::SubdirGitRepo(true));
-        }
+impl HeaderNames {
+    pub fn new(columns: Vec<(Column, String)>, name: Option<String>) -> Self {
+        Self { columns, name }
+    }
 
-        if self.subdir_git_repos_no_stat && git_repos {
-            columns.push(Column::SubdirGitRepo(false));
-        }
+    /// The heading for the given column.
+    pub fn column(&self, column: Column) -> &str {
+        self.columns
+            .iter()
+            .find(|(c, _)| *c == column)
+            .map_or(column.header(), |(_, name)| name)
+    }
 
-        columns
+    /// The heading for the file name, which isn’t one of the columns.
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("Name")
     }
 }
 
 /// A table contains these.
-#[derive(Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Column {
     Permissions,
     FileSize,
     Timestamp(TimeType),
     #[cfg(unix)]
     Blocksize,
-    #[cfg(unix)]
     User,
-    #[cfg(unix)]
     Group,
     #[cfg(unix)]
     HardLinks,
     #[cfg(unix)]
     Inode,
     GitStatus,
+    VcsStatus,
+    GitCommit,
+    GitSubject,
+    GitAuthor,
+    GitOwner,
+    GitSinceTag,
     SubdirGitRepo(bool),
     #[cfg(unix)]
     Octal,
     #[cfg(unix)]
     SecurityContext,
     FileFlags,
+    FileVersion,
+    Access,
+    Subvolume,
+    Compression,
+    Dataset,
+    Project,
+    Mount,
+    TargetPermissions,
+    TargetSize,
+    StorageClass,
+    Mime,
+    Hash,
+    Dimensions,
+    Binary,
+    TrashPath,
+    TrashDate,
+    Preview(usize),
+    Xattr(usize),
 }
 
 /// Each column can pick its own **Alignment**. Usually, numbers are
@@ -183,14 +200,73 @@ pub enum Alignment {
 }
 
 impl Column {
+    /// The column for one of the field names that `--fields`,
+    /// `--header-names`, and the theme file’s `columns` take.
+    pub fn from_field_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "perms" | "permissions" => Self::Permissions,
+            "size" => Self::FileSize,
+            #[cfg(unix)]
+            "blocksize" | "blocks" => Self::Blocksize,
+            "user" => Self::User,
+            "group" => Self::Group,
+            #[cfg(unix)]
+            "links" => Self::HardLinks,
+            #[cfg(unix)]
+            "inode" => Self::Inode,
+            #[cfg(unix)]
+            "octal" => Self::Octal,
+            #[cfg(unix)]
+            "context" => Self::SecurityContext,
+            "flags" => Self::FileFlags,
+            "version" => Self::FileVersion,
+            "access" => Self::Access,
+            "subvolume" | "subvol" => Self::Subvolume,
+            "compression" => Self::Compression,
+            "dataset" => Self::Dataset,
+            "project" | "project-id" => Self::Project,
+            "mount" | "mounts" => Self::Mount,
+            "target-perms" | "target-permissions" => Self::TargetPermissions,
+            "target-size" => Self::TargetSize,
+            "storage-class" | "class" => Self::StorageClass,
+            "mime" => Self::Mime,
+            "hash" | "sha256" => Self::Hash,
+            "dimensions" | "image-info" => Self::Dimensions,
+            "binary" | "binary-info" => Self::Binary,
+            "original-path" => Self::TrashPath,
+            "deleted" => Self::TrashDate,
+            "preview" => Self::Preview(preview::DEFAULT_LENGTH),
+            "mtime" | "modified" => Self::Timestamp(TimeType::Modified),
+            "ctime" | "changed" => Self::Timestamp(TimeType::Changed),
+            "atime" | "accessed" => Self::Timestamp(TimeType::Accessed),
+            "btime" | "created" => Self::Timestamp(TimeType::Created),
+            "git" => Self::GitStatus,
+            "vcs" | "vcs-status" => Self::VcsStatus,
+            "commit" | "git-commit" => Self::GitCommit,
+            "subject" | "git-subject" => Self::GitSubject,
+            "author" | "git-author" => Self::GitAuthor,
+            "git-owner" => Self::GitOwner,
+            "since-tag" | "git-since-tag" => Self::GitSinceTag,
+            "git-repos" => Self::SubdirGitRepo(true),
+            "git-repos-no-status" => Self::SubdirGitRepo(false),
+            _ => return None,
+        })
+    }
+
     /// Get the alignment this column should use.
     #[cfg(unix)]
     pub fn alignment(self) -> Alignment {
         #[allow(clippy::wildcard_in_or_patterns)]
         match self {
-            Self::FileSize | Self::HardLinks | Self::Inode | Self::Blocksize | Self::GitStatus => {
-                Alignment::Right
-            }
+            Self::FileSize
+            | Self::HardLinks
+            | Self::Inode
+            | Self::Blocksize
+            | Self::GitStatus
+            | Self::VcsStatus
+            | Self::Subvolume
+            | Self::Project
+            | Self::TargetSize => Alignment::Right,
             Self::Timestamp(_) | _ => Alignment::Left,
         }
     }
@@ -198,7 +274,9 @@ impl Column {
     #[cfg(windows)]
     pub fn alignment(self) -> Alignment {
         match self {
-            Self::FileSize | Self::GitStatus => Alignment::Right,
+            Self::FileSize | Self::GitStatus | Self::VcsStatus | Self::TargetSize => {
+                Alignment::Right
+            }
             _ => Alignment::Left,
         }
     }
@@ -215,21 +293,43 @@ impl Column {
             Self::Timestamp(t) => t.header(),
             #[cfg(unix)]
             Self::Blocksize => "Blocksize",
-            #[cfg(unix)]
             Self::User => "User",
-            #[cfg(unix)]
             Self::Group => "Group",
             #[cfg(unix)]
             Self::HardLinks => "Links",
             #[cfg(unix)]
             Self::Inode => "inode",
             Self::GitStatus => "Git",
+            Self::VcsStatus => "VCS",
+            Self::GitCommit => "Commit",
+            Self::GitSubject => "Subject",
+            Self::GitAuthor => "Author",
+            Self::GitOwner => "Owner",
+            Self::GitSinceTag => "Tag",
             Self::SubdirGitRepo(_) => "Repo",
             #[cfg(unix)]
             Self::Octal => "Octal",
             #[cfg(unix)]
             Self::SecurityContext => "Security Context",
             Self::FileFlags => "Flags",
+            Self::FileVersion => "Version",
+            Self::Access => "Access",
+            Self::Subvolume => "Subvol",
+            Self::Compression => "Compression",
+            Self::Dataset => "Dataset",
+            Self::Project => "Project",
+            Self::Mount => "Mount",
+            Self::TargetPermissions => "Target Permissions",
+            Self::TargetSize => "Target Size",
+            Self::StorageClass => "Class",
+            Self::Mime => "MIME",
+            Self::Hash => "SHA-256",
+            Self::Dimensions => "Dimensions",
+            Self::Binary => "Binary",
+            Self::TrashPath => "Original Path",
+            Self::TrashDate => "Deleted",
+            Self::Preview(_) => "Preview",
+            Self::Xattr(_) => "Attribute",
         }
     }
 }
@@ -249,6 +349,44 @@ pub enum SizeFormat {
 
     /// Do no formatting and just display the size as a number of bytes.
     JustBytes,
+
+    /// Display the size as a whole number of units of this many bytes,
+    /// such as 512-byte sectors or 4 KiB pages, rounding up.
+    Units(u64),
+}
+
+impl SizeFormat {
+    /// Returns the format for a unit given by name: `bytes`, `sectors`,
+    /// `pages`, or `blocks:N` for blocks of any number of bytes.
+    pub fn from_unit(unit: &str) -> Option<Self> {
+        match unit {
+            "bytes" => Some(Self::JustBytes),
+            "sectors" => Some(Self::Units(512)),
+            "pages" => Some(Self::Units(4096)),
+            _ => {
+                let size = unit.strip_prefix("blocks:")?.parse().ok()?;
+                (size > 0).then_some(Self::Units(size))
+            }
+        }
+    }
+
+    /// Returns the number to display for a size of this many bytes, when
+    /// the size isn’t being shown with a prefix.
+    pub fn count(self, size: u64) -> u64 {
+        match self {
+            Self::Units(unit) => size.div_ceil(unit),
+            _ => size,
+        }
+    }
+
+    /// Returns what goes after a number of units to say how big they are,
+    /// such as “×512” for sectors. Bytes and prefixed sizes have none.
+    pub fn unit_suffix(self) -> Option<String> {
+        match self {
+            Self::Units(unit) => Some(format!("×{unit}")),
+            _ => None,
+        }
+    }
 }
 
 /// Formatting options for user and group.
@@ -284,6 +422,10 @@ pub enum TimeType {
 
     /// The file’s creation time (`btime` or `birthtime`).
     Created,
+
+    /// When the last commit to change the file was made, in its Git
+    /// repository.
+    Git,
 }
 
 impl TimeType {
@@ -294,6 +436,7 @@ impl TimeType {
             Self::Changed => "Date Changed",
             Self::Accessed => "Date Accessed",
             Self::Created => "Date Created",
+            Self::Git => "Date Committed",
         }
     }
 
@@ -304,6 +447,9 @@ impl TimeType {
             TimeType::Changed => file.changed_time(),
             TimeType::Accessed => file.accessed_time(),
             TimeType::Created => file.created_time(),
+            // The commit time comes from the repository rather than the
+            // file, so only a table with a Git cache can look it up.
+            TimeType::Git => None,
         }
     }
 }
@@ -343,6 +489,7 @@ pub struct TimeTypes {
     pub changed:  bool,
     pub accessed: bool,
     pub created:  bool,
+    pub git:      bool,
 }
 
 impl Default for TimeTypes {
@@ -354,6 +501,7 @@ impl Default for TimeTypes {
             changed: false,
             accessed: false,
             created: false,
+            git: false,
         }
     }
 }
@@ -364,10 +512,10 @@ impl Default for TimeTypes {
 /// Any environment field should be able to be mocked up for test runs.
 pub struct Environment {
     /// The computer’s current time offset, determined from time zone.
-    time_offset: FixedOffset,
+    pub time_offset: FixedOffset,
 
     /// Localisation rules for formatting numbers.
-    numeric: locale::Numeric,
+    pub numeric: locale::Numeric,
 
     /// Mapping cache of user IDs to usernames.
     #[cfg(unix)]
@@ -381,10 +529,19 @@ impl Environment {
     }
 
     fn load_all() -> Self {
-        let time_offset = *Local::now().offset();
+        let fixed = FIXED_ENVIRONMENT.load(Ordering::Relaxed);
+
+        let time_offset = if fixed {
+            FixedOffset::east_opt(0).unwrap()
+        } else {
+            *Local::now().offset()
+        };
 
-        let numeric =
-            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english());
+        let numeric = if fixed {
+            locale::Numeric::english()
+        } else {
+            locale::Numeric::load_user_locale().unwrap_or_else(|_| locale::Numeric::english())
+        };
 
         #[cfg(unix)]
         let users = Mutex::new(UsersCache::new());
@@ -398,7 +555,16 @@ impl Environment {
     }
 }
 
-static ENVIRONMENT: Lazy<Environment> = Lazy::new(Environment::load_all);
+pub static ENVIRONMENT: Lazy<Environment> = Lazy::new(Environment::load_all);
+
+/// Whether the environment should use UTC and the English locale rather
+/// than the computer’s own, for `--deterministic`. This has to be set
+/// before anything gets rendered.
+pub static FIXED_ENVIRONMENT: AtomicBool = AtomicBool::new(false);
+
+/// The most characters of a commit’s subject to show in the `--git-log`
+/// columns, as long ones would push the file names far off to the right.
+const SUBJECT_LENGTH: usize = 50;
 
 pub struct Table<'a> {
     columns: Vec<Column>,
@@ -407,12 +573,15 @@ pub struct Table<'a> {
     widths: TableWidths,
     time_format: TimeFormat,
     size_format: SizeFormat,
-    #[cfg(unix)]
     user_format: UserFormat,
-    #[cfg(unix)]
     group_format: GroupFormat,
     flags_format: FlagsFormat,
     git: Option<&'a GitCache>,
+    vcs: Option<&'a VcsCache>,
+    header_names: &'a HeaderNames,
+    inline_errors: bool,
+    defer_hashes: bool,
+    xattr_columns: &'a [XattrColumn],
 }
 
 #[derive(Clone)]
@@ -420,10 +589,17 @@ pub struct Row {
     cells: Vec<TextCell>,
 }
 
+impl Row {
+    pub fn cells(&self) -> &[TextCell] {
+        &self.cells
+    }
+}
+
 impl<'a> Table<'a> {
     pub fn new(
         options: &'a Options,
         git: Option<&'a GitCache>,
+        vcs: Option<&'a VcsCache>,
         theme: &'a Theme,
         git_repos: bool,
     ) -> Table<'a> {
@@ -438,14 +614,55 @@ impl<'a> Table<'a> {
             widths,
             columns,
             git,
+            vcs,
             env,
             time_format: options.time_format.clone(),
             size_format: options.size_format,
-            #[cfg(unix)]
             user_format: options.user_format,
-            #[cfg(unix)]
             group_format: options.group_format,
             flags_format: options.flags_format,
+            header_names: &options.header_names,
+            inline_errors: false,
+            defer_hashes: false,
+            xattr_columns: &options.xattr_columns,
+        }
+    }
+
+    /// Adds the columns for where each file was deleted from, and when, for
+    /// listing a trash directory, unless they’re there already.
+    pub fn add_trash_columns(&mut self) {
+        for column in [Column::TrashPath, Column::TrashDate] {
+            if !self.columns.contains(&column) {
+                self.columns.push(column);
+                self.widths.0.push(0);
+            }
+        }
+    }
+
+    /// Fills the columns with `?` for files whose metadata couldn’t be
+    /// read, rather than with made-up values, for `--inline-errors`.
+    pub fn show_errors_inline(&mut self) {
+        self.inline_errors = true;
+    }
+
+    /// Leaves a placeholder in each row’s hash column rather than hashing
+    /// the file straight away, for the hash to be filled in later.
+    pub fn defer_hashes(&mut self) {
+        self.defer_hashes = self.columns.contains(&Column::Hash);
+    }
+
+    /// Whether rows get placeholders for their hashes.
+    pub fn defers_hashes(&self) -> bool {
+        self.defer_hashes
+    }
+
+    /// Fills in the hash that was left as a placeholder in a row.
+    pub fn fill_hash(&self, row: &mut Row, hash: f::Hash) {
+        if let Some(index) = self.columns.iter().position(|c| *c == Column::Hash) {
+            row.cells[index] = hash.render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            );
         }
     }
 
@@ -453,26 +670,86 @@ impl<'a> Table<'a> {
         &self.widths
     }
 
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Whether any of the files’ timestamps get shown, which get shaded by
+    /// age in place of their names.
+    pub fn shows_timestamps(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| matches!(column, Column::Timestamp(_)))
+    }
+
     pub fn header_row(&self) -> Row {
         let cells = self
             .columns
             .iter()
-            .map(|c| TextCell::paint_str(self.theme.ui.header.unwrap_or_default(), c.header()))
+            .map(|c| {
+                let heading = match *c {
+                    Column::Xattr(index) => self.xattr_columns[index].header(),
+                    column => self.header_names.column(column),
+                };
+                let style = self
+                    .theme
+                    .column_style(*c)
+                    .header_style
+                    .unwrap_or(self.theme.ui.header.unwrap_or_default());
+                TextCell::paint(style, heading.to_owned())
+            })
             .collect();
 
         Row { cells }
     }
 
+    /// The heading for the file names, which go after the table’s columns.
+    pub fn name_header(&self) -> &str {
+        self.header_names.name()
+    }
+
     pub fn row_for_file(
         &self,
         file: &File<'_>,
         xattrs: bool,
         color_scale_info: Option<ColorScaleInformation>,
     ) -> Row {
+        if self.inline_errors && !file.is_virtual() && file.metadata().is_err() {
+            return self.unknown_row();
+        }
+
+        // The file a symlink points to only gets looked up once, however
+        // many of its details get shown.
+        let target = self
+            .columns
+            .iter()
+            .any(|c| matches!(c, Column::TargetPermissions | Column::TargetSize))
+            .then(|| file.link_target_file())
+            .flatten();
+
+        let cells = self
+            .columns
+            .iter()
+            .map(|c| {
+                let cell = self.display(file, target.as_deref(), *c, xattrs, color_scale_info);
+                match self.theme.column_style(*c).style {
+                    Some(style) => cell.restyled(style),
+                    None => cell,
+                }
+            })
+            .collect();
+
+        Row { cells }
+    }
+
+    /// A row with a `?` in every column, for a file whose metadata
+    /// couldn’t be read.
+    fn unknown_row(&self) -> Row {
+        let style = self.theme.ui.punctuation.unwrap_or_default();
         let cells = self
             .columns
             .iter()
-            .map(|c| self.display(file, *c, xattrs, color_scale_info))
+            .map(|_| TextCell::paint_str(style, "?"))
             .collect();
 
         Row { cells }
@@ -483,27 +760,31 @@ impl<'a> Table<'a> {
     }
 
     #[cfg(unix)]
-    fn permissions_plus(&self, file: &File<'_>, xattrs: bool) -> Option<f::PermissionsPlus> {
+    pub(crate) fn permissions_plus(file: &File<'_>, xattrs: bool) -> Option<f::PermissionsPlus> {
         file.permissions().map(|p| f::PermissionsPlus {
             file_type: file.type_char(),
             permissions: p,
             xattrs,
+            acl: file.has_extended_acl(),
+            sparse: file.is_sparse(),
         })
     }
 
     #[allow(clippy::unnecessary_wraps)] // Needs to match Unix function
     #[cfg(windows)]
-    fn permissions_plus(&self, file: &File<'_>, xattrs: bool) -> Option<f::PermissionsPlus> {
+    pub(crate) fn permissions_plus(file: &File<'_>, xattrs: bool) -> Option<f::PermissionsPlus> {
         Some(f::PermissionsPlus {
             file_type: file.type_char(),
             #[cfg(windows)]
             attributes: file.attributes()?,
             xattrs,
+            acl: false,
+            sparse: false,
         })
     }
 
     #[cfg(unix)]
-    fn octal_permissions(&self, file: &File<'_>) -> Option<f::OctalPermissions> {
+    pub(crate) fn octal_permissions(file: &File<'_>) -> Option<f::OctalPermissions> {
         file.permissions()
             .map(|p| f::OctalPermissions { permissions: p })
     }
@@ -511,12 +792,13 @@ impl<'a> Table<'a> {
     fn display(
         &self,
         file: &File<'_>,
+        target: Option<&File<'_>>,
         column: Column,
         xattrs: bool,
         color_scale_info: Option<ColorScaleInformation>,
     ) -> TextCell {
         match column {
-            Column::Permissions => self.permissions_plus(file, xattrs).render(self.theme),
+            Column::Permissions => Self::permissions_plus(file, xattrs).render(self.theme),
             Column::FileSize => file.size().render(
                 self.theme,
                 self.size_format,
@@ -533,40 +815,132 @@ impl<'a> Table<'a> {
                     .render(self.theme, self.size_format, &self.env.numeric)
             }
             #[cfg(unix)]
-            Column::User => {
-                file.user()
-                    .render(self.theme, &*self.env.lock_users(), self.user_format)
-            }
+            Column::User => match file.remote_user() {
+                Some(owner) => owner.render_user(self.theme, self.user_format),
+                None => file
+                    .user()
+                    .render(self.theme, &*self.env.lock_users(), self.user_format),
+            },
             #[cfg(unix)]
-            Column::Group => file.group().render(
-                self.theme,
-                &*self.env.lock_users(),
-                self.user_format,
-                self.group_format,
-                file.user(),
-            ),
+            Column::Group => match file.remote_group() {
+                Some(owner) => owner.render_group(
+                    self.theme,
+                    self.user_format,
+                    self.group_format,
+                    file.remote_user(),
+                ),
+                None => file.group().render(
+                    self.theme,
+                    &*self.env.lock_users(),
+                    self.user_format,
+                    self.group_format,
+                    file.user(),
+                ),
+            },
+            #[cfg(windows)]
+            Column::User => match file.remote_user().or_else(|| file.security_user()) {
+                Some(owner) => owner.render_user(self.theme, self.user_format),
+                None => TextCell::blank(UserColours::no_user(self.theme)),
+            },
+            #[cfg(windows)]
+            Column::Group => match file.remote_group().or_else(|| file.security_group()) {
+                Some(owner) => owner.render_group(
+                    self.theme,
+                    self.user_format,
+                    self.group_format,
+                    file.remote_user().or_else(|| file.security_user()),
+                ),
+                None => TextCell::blank(GroupColours::no_group(self.theme)),
+            },
             #[cfg(unix)]
             Column::SecurityContext => file.security_context().render(self.theme),
-            Column::FileFlags => file
-                .flags()
-                .render(self.theme.ui.flags.unwrap_or_default(), self.flags_format),
+            Column::FileFlags => file.flags().render(self.theme, self.flags_format),
+            Column::FileVersion => file.file_version().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Access => file.access_rights().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Subvolume => file.subvolume().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Compression => file.compression().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Dataset => file.dataset().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Project => file.project_id().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::TargetPermissions | Column::TargetSize => self.link_target(target, column),
+            Column::Mount => file.mount_point_info().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::StorageClass => file.storage_class().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Mime => file.mime_type().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Dimensions => file.image_dimensions().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Binary => file.binary_info().render(
+                Style::default(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::TrashPath | Column::TrashDate => self.trash_info(file, column),
+            Column::Preview(length) => file.preview(length).render(
+                Style::default(),
+                self.theme.ui.control_char(),
+                self.theme.ui.punctuation.unwrap_or_default(),
+            ),
+            Column::Xattr(index) => self.xattr_value(file, &self.xattr_columns[index]),
+            Column::Hash => {
+                let hash = if self.defer_hashes && file.is_hashable() {
+                    f::Hash::Pending
+                } else {
+                    file.hash()
+                };
+                hash.render(
+                    Style::default(),
+                    self.theme.ui.punctuation.unwrap_or_default(),
+                )
+            }
             Column::GitStatus => self.git_status(file).render(self.theme),
+            Column::VcsStatus => self.vcs_status(file).render(self.theme),
+            Column::GitCommit | Column::GitSubject | Column::GitAuthor => {
+                self.git_commit(file, column)
+            }
+            Column::GitOwner => self.git_owner(file),
+            Column::GitSinceTag => self.git_since_tag(file),
             Column::SubdirGitRepo(status) => self.subdir_git_repo(file, status).render(self.theme),
             #[cfg(unix)]
-            Column::Octal => self
-                .octal_permissions(file)
-                .render(self.theme.ui.octal.unwrap_or_default()),
+            Column::Octal => {
+                Self::octal_permissions(file).render(self.theme.ui.octal.unwrap_or_default())
+            }
 
-            Column::Timestamp(time_type) => time_type.get_corresponding_time(file).render(
+            Column::Timestamp(time_type) => self.timestamp(file, time_type).render(
                 if color_scale_info.is_some_and(|csi| csi.options.mode == ColorScaleMode::Gradient)
                 {
                     color_scale_info.unwrap().apply_time_gradient(
-                        self.theme.ui.date.unwrap_or_default(),
+                        self.date_style(file, time_type),
                         file,
                         time_type,
                     )
                 } else {
-                    self.theme.ui.date.unwrap_or_default()
+                    self.date_style(file, time_type)
                 },
                 self.env.time_offset,
                 self.time_format.clone(),
@@ -574,6 +948,96 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Renders where a file in a trash directory was deleted from, or when.
+    fn trash_info(&self, file: &File<'_>, column: Column) -> TextCell {
+        let info = file.trash_info();
+        if column == Column::TrashDate {
+            return info.and_then(|info| info.deleted).render(
+                self.theme.ui.date.unwrap_or_default(),
+                self.env.time_offset,
+                self.time_format.clone(),
+            );
+        }
+
+        match info {
+            Some(info) => self.escaped(info.original_path.display().to_string()),
+            None => TextCell::blank(self.theme.ui.punctuation.unwrap_or_default()),
+        }
+    }
+
+    /// Renders one of the details of the file a symlink points to, dimmed
+    /// to set it apart from the symlink’s own details when there are
+    /// colours to dim.
+    fn link_target(&self, target: Option<&File<'_>>, column: Column) -> TextCell {
+        let cell = match (target, column) {
+            (Some(target), Column::TargetPermissions) => {
+                Self::permissions_plus(target, false).render(self.theme)
+            }
+            (Some(target), _) => {
+                target
+                    .size()
+                    .render(self.theme, self.size_format, &self.env.numeric, None)
+            }
+            (None, _) => TextCell::blank(self.theme.ui.punctuation.unwrap_or_default()),
+        };
+
+        if self.theme.ui.colourful == Some(false) {
+            cell
+        } else {
+            cell.dimmed()
+        }
+    }
+
+    /// Renders the value of the extended attribute that a column from the
+    /// theme file shows, in the column’s style when there are colours.
+    fn xattr_value(&self, file: &File<'_>, column: &XattrColumn) -> TextCell {
+        let attribute = file
+            .extended_attributes()
+            .iter()
+            .find(|a| a.name == column.attribute);
+
+        let Some(attribute) = attribute else {
+            return TextCell::blank(self.theme.ui.punctuation.unwrap_or_default());
+        };
+
+        let style = if self.theme.ui.colourful == Some(false) {
+            Style::default()
+        } else {
+            column.style.unwrap_or_default()
+        };
+        TextCell::paint(style, attribute.display_value())
+    }
+
+    /// Returns the style for a timestamp, which is the theme’s style for
+    /// recent or old dates if it has one and the timestamp is younger or
+    /// older than the theme’s thresholds.
+    fn date_style(&self, file: &File<'_>, time_type: TimeType) -> Style {
+        let ui = &self.theme.ui;
+        let date = ui.date.unwrap_or_default();
+        let Some(time) = self.timestamp(file, time_type) else {
+            return date;
+        };
+
+        let [recent, old] = ui.date_thresholds();
+        let age = age_in_seconds(time);
+        match (ui.date_recent, ui.date_old) {
+            (Some(style), _) if age < recent => style,
+            (_, Some(style)) if age >= old => style,
+            _ => date,
+        }
+    }
+
+    /// One of a file’s timestamps, with the commit time looked up in its
+    /// Git repository.
+    fn timestamp(&self, file: &File<'_>, time_type: TimeType) -> Option<NaiveDateTime> {
+        if time_type != TimeType::Git {
+            return time_type.get_corresponding_time(file);
+        }
+
+        let commit = self.git.and_then(|g| g.last_commit(&file.path))?;
+        DateTime::from_timestamp(commit.time, 0).map(|dt| dt.naive_local())
+    }
+
     fn git_status(&self, file: &File<'_>) -> f::Git {
         debug!("Getting Git status for file {:?}", file.path);
 
@@ -582,6 +1046,80 @@ impl<'a> Table<'a> {
             .unwrap_or_default()
     }
 
+    fn vcs_status(&self, file: &File<'_>) -> f::Git {
+        debug!("Getting VCS status for file {:?}", file.path);
+
+        self.vcs
+            .map(|v| v.get(&file.path, file.is_directory()))
+            .unwrap_or_default()
+    }
+
+    /// Renders the hash, subject, or author of the last commit to change a
+    /// file, cutting long subjects short.
+    fn git_commit(&self, file: &File<'_>, column: Column) -> TextCell {
+        let Some(commit) = self.git.and_then(|g| g.last_commit(&file.path)) else {
+            return TextCell::blank(self.theme.ui.punctuation.unwrap_or_default());
+        };
+
+        match column {
+            Column::GitCommit => return TextCell::paint(Style::default(), commit.hash),
+            Column::GitAuthor => return self.escaped(commit.author),
+            _ => {}
+        }
+
+        let subject = if commit.subject.chars().count() > SUBJECT_LENGTH {
+            let mut subject = commit
+                .subject
+                .chars()
+                .take(SUBJECT_LENGTH - 1)
+                .collect::<String>();
+            subject.push('…');
+            subject
+        } else {
+            commit.subject
+        };
+        self.escaped(subject)
+    }
+
+    /// Paints text that comes from somewhere anyone could have written it,
+    /// such as a commit or a trash info file, with any control characters in it escaped so they
+    /// can’t mess up the terminal.
+    fn escaped(&self, text: String) -> TextCell {
+        let mut bits = Vec::new();
+        escape::escape(
+            text,
+            &mut bits,
+            Style::default(),
+            self.theme.ui.control_char(),
+            QuoteStyle::NoQuotes,
+        );
+        TextCellContents::from(bits).promote()
+    }
+
+    /// Renders who wrote the most lines of a file. Directories are left
+    /// blank, as there’s no one line count to go by for them.
+    fn git_owner(&self, file: &File<'_>) -> TextCell {
+        let owner = if file.is_directory() {
+            None
+        } else {
+            self.git.and_then(|g| g.owner(&file.path))
+        };
+
+        match owner {
+            Some(owner) => self.escaped(owner),
+            None => TextCell::blank(self.theme.ui.punctuation.unwrap_or_default()),
+        }
+    }
+
+    /// Renders the first tag to include the last change to a file, which
+    /// is left blank for files that have changed since the latest tag.
+    fn git_since_tag(&self, file: &File<'_>) -> TextCell {
+        match self.git.and_then(|g| g.since_tag(&file.path)) {
+            Some(tag) => TextCell::paint(Style::default(), tag),
+            None => TextCell::blank(self.theme.ui.punctuation.unwrap_or_default()),
+        }
+    }
+
     fn subdir_git_repo(&self, file: &File<'_>, status: bool) -> f::SubdirGitRepo {
         debug!("Getting subdir repo status for path {:?}", file.path);
 
@@ -0,0 +1,409 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The template view renders each file on its own line, using a
+//! user-supplied format string such as `{name}\t{size:bytes}`.
+
+use std::io::{self, Write};
+
+use nu_ansi_term::{AnsiString as ANSIString, AnsiStrings as ANSIStrings, Style};
+
+use crate::fs::filter::FileFilter;
+use crate::fs::File;
+use crate::output::cell::TextCellContents;
+use crate::output::escape;
+use crate::output::file_name::{Options as FileStyle, QuoteStyle};
+#[cfg(unix)]
+use crate::output::render::{GroupRender, OctalPermissionsRender, UserRender};
+use crate::output::render::{PermissionsPlusRender, TimeRender};
+#[cfg(unix)]
+use crate::output::table::GroupFormat;
+use crate::output::table::{SizeFormat, Table, TimeType, UserFormat, ENVIRONMENT};
+use crate::output::time::TimeFormat;
+use crate::theme::Theme;
+
+/// A parsed format template: a list of literal text runs and the fields
+/// that get substituted in between them.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum Part {
+    Text(String),
+    Field(Field),
+}
+
+/// A field that can appear between braces in a template, along with any
+/// formatting specification given after its colon.
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum Field {
+    Name,
+    Path,
+    Extension,
+    Type,
+    Permissions,
+    Size(Option<SizeFormat>),
+    #[cfg(unix)]
+    Blocksize(Option<SizeFormat>),
+    #[cfg(unix)]
+    Inode,
+    #[cfg(unix)]
+    Links,
+    #[cfg(unix)]
+    User(Option<UserFormat>),
+    #[cfg(unix)]
+    Group(Option<UserFormat>),
+    #[cfg(unix)]
+    Octal,
+    Time(TimeType, Option<TimeFormat>),
+}
+
+impl Template {
+    /// Parse a template string. Fields are written as `{field}` or
+    /// `{field:spec}`, literal braces as `{{` and `}}`, and the escapes
+    /// `\t`, `\n`, and `\\` are expanded so they can be typed in a shell.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => text.push('\t'),
+                    Some('n') => text.push('\n'),
+                    Some('\\') => text.push('\\'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => text.push('\\'),
+                },
+                '}' => {
+                    if chars.next() != Some('}') {
+                        return Err("unmatched '}' (use '}}' for a literal brace)".into());
+                    }
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    if let Some(stripped) = rest.strip_prefix('{') {
+                        text.push('{');
+                        chars = stripped.chars();
+                        continue;
+                    }
+
+                    let Some(end) = rest.find('}') else {
+                        return Err("unterminated '{' in template".into());
+                    };
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(Field::parse(&rest[..end])?));
+                    chars = rest[end + 1..].chars();
+                }
+                _ => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+}
+
+impl Field {
+    fn parse(field: &str) -> Result<Self, String> {
+        let (name, spec) = match field.split_once(':') {
+            Some((name, spec)) => (name, Some(spec)),
+            None => (field, None),
+        };
+
+        let field = match name {
+            "name" => Self::Name,
+            "path" => Self::Path,
+            "ext" | "extension" => Self::Extension,
+            "type" => Self::Type,
+            "permissions" | "perms" => Self::Permissions,
+            "size" => Self::Size(spec.map(size_format).transpose()?),
+            #[cfg(unix)]
+            "blocksize" | "blocks" => Self::Blocksize(spec.map(size_format).transpose()?),
+            #[cfg(unix)]
+            "inode" => Self::Inode,
+            #[cfg(unix)]
+            "links" => Self::Links,
+            #[cfg(unix)]
+            "user" => Self::User(spec.map(user_format).transpose()?),
+            #[cfg(unix)]
+            "group" => Self::Group(spec.map(user_format).transpose()?),
+            #[cfg(unix)]
+            "octal" => Self::Octal,
+            "mtime" | "modified" => Self::Time(TimeType::Modified, time_format(spec)?),
+            "ctime" | "changed" => Self::Time(TimeType::Changed, time_format(spec)?),
+            "atime" | "accessed" => Self::Time(TimeType::Accessed, time_format(spec)?),
+            "btime" | "created" => Self::Time(TimeType::Created, time_format(spec)?),
+            _ => return Err(format!("unknown field {name:?}")),
+        };
+
+        let takes_spec = matches!(field, Self::Size(_) | Self::Time(..));
+        #[cfg(unix)]
+        let takes_spec =
+            takes_spec || matches!(field, Self::Blocksize(_) | Self::User(_) | Self::Group(_));
+
+        match spec {
+            Some(spec) if !takes_spec => {
+                Err(format!("field {name:?} takes no format, got {spec:?}"))
+            }
+            Some("") => Err(format!("empty format for field {name:?}")),
+            _ => Ok(field),
+        }
+    }
+}
+
+fn size_format(spec: &str) -> Result<SizeFormat, String> {
+    match spec {
+        "binary" => Ok(SizeFormat::BinaryBytes),
+        "decimal" => Ok(SizeFormat::DecimalBytes),
+        _ => SizeFormat::from_unit(spec).ok_or_else(|| format!("unknown size format {spec:?}")),
+    }
+}
+
+/// Empty specs are left for the caller to complain about, along with the
+/// name of the field.
+fn time_format(spec: Option<&str>) -> Result<Option<TimeFormat>, String> {
+    match spec {
+        Some(spec) if !spec.is_empty() => TimeFormat::custom(spec).map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+fn user_format(spec: &str) -> Result<UserFormat, String> {
+    match spec {
+        "name" => Ok(UserFormat::Name),
+        "numeric" => Ok(UserFormat::Numeric),
+        _ => Err(format!("unknown user format {spec:?}")),
+    }
+}
+
+/// Options for the template view, which fill in for any field that
+/// doesn’t specify its own format.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Options {
+    pub template: Template,
+    pub size_format: SizeFormat,
+    pub time_format: TimeFormat,
+    pub user_format: UserFormat,
+}
+
+pub struct Render<'a> {
+    pub files: Vec<File<'a>>,
+    pub theme: &'a Theme,
+    pub file_style: &'a FileStyle,
+    pub opts: &'a Options,
+    pub filter: &'a FileFilter,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        self.filter.sort_files(&mut self.files);
+        for file in &self.files {
+            let mut line: Vec<ANSIString<'static>> = Vec::new();
+
+            for part in &self.opts.template.parts {
+                match part {
+                    Part::Text(text) => line.push(ANSIString::from(text.clone())),
+                    Part::Field(field) => {
+                        line.extend(self.render_field(file, field).iter().cloned());
+                    }
+                }
+            }
+
+            writeln!(w, "{}", ANSIStrings(&line))?;
+        }
+
+        Ok(())
+    }
+
+    fn render_field(&self, file: &File<'_>, field: &Field) -> TextCellContents {
+        let env = &*ENVIRONMENT;
+
+        let cell = match field {
+            Field::Name => return self.file_style.for_file(file, self.theme).paint(),
+            Field::Path => return self.escaped(file.path.to_string_lossy().into_owned()),
+            Field::Extension => return self.escaped(file.ext.clone().unwrap_or_default()),
+            Field::Type => return vec![file.type_char().render(self.theme)].into(),
+            Field::Permissions => Table::permissions_plus(file, false).render(self.theme),
+            Field::Size(format) => file.size().render(
+                self.theme,
+                format.unwrap_or(self.opts.size_format),
+                &env.numeric,
+                None,
+            ),
+            #[cfg(unix)]
+            Field::Blocksize(format) => file.blocksize().render(
+                self.theme,
+                format.unwrap_or(self.opts.size_format),
+                &env.numeric,
+            ),
+            #[cfg(unix)]
+            Field::Inode => file.inode().render(self.theme.ui.inode.unwrap_or_default()),
+            #[cfg(unix)]
+            Field::Links => file.links().render(self.theme, &env.numeric),
+            #[cfg(unix)]
+            Field::User(format) => {
+                let format = format.unwrap_or(self.opts.user_format);
+                match file.remote_user() {
+                    Some(owner) => owner.render_user(self.theme, format),
+                    None => file.user().render(self.theme, &*env.lock_users(), format),
+                }
+            }
+            #[cfg(unix)]
+            Field::Group(format) => {
+                let format = format.unwrap_or(self.opts.user_format);
+                match file.remote_group() {
+                    Some(owner) => owner.render_group(
+                        self.theme,
+                        format,
+                        GroupFormat::Regular,
+                        file.remote_user(),
+                    ),
+                    None => file.group().render(
+                        self.theme,
+                        &*env.lock_users(),
+                        format,
+                        GroupFormat::Regular,
+                        file.user(),
+                    ),
+                }
+            }
+            #[cfg(unix)]
+            Field::Octal => {
+                Table::octal_permissions(file).render(self.theme.ui.octal.unwrap_or_default())
+            }
+            Field::Time(time_type, spec) => {
+                let time_format = spec
+                    .clone()
+                    .unwrap_or_else(|| self.opts.time_format.clone());
+
+                time_type.get_corresponding_time(file).render(
+                    self.theme.ui.date.unwrap_or_default(),
+                    env.time_offset,
+                    time_format,
+                )
+            }
+        };
+
+        cell.contents
+    }
+
+    /// Paints part of a file’s path with any control characters in it
+    /// escaped, the same as the name field does.
+    fn escaped(&self, text: String) -> TextCellContents {
+        let mut bits = Vec::new();
+        escape::escape(
+            text,
+            &mut bits,
+            Style::default(),
+            self.theme.ui.control_char(),
+            QuoteStyle::NoQuotes,
+        );
+        bits.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        let template = Template::parse("hello").unwrap();
+        assert_eq!(template.parts, vec![Part::Text("hello".into())]);
+    }
+
+    #[test]
+    fn fields_and_escapes() {
+        let template = Template::parse(r"{name}\t{size:bytes}\n").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![
+                Part::Field(Field::Name),
+                Part::Text("\t".into()),
+                Part::Field(Field::Size(Some(SizeFormat::JustBytes))),
+                Part::Text("\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn time_format() {
+        let template = Template::parse("{mtime:%Y-%m-%d}").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![Part::Field(Field::Time(
+                TimeType::Modified,
+                Some(TimeFormat::Custom {
+                    non_recent: "%Y-%m-%d".into(),
+                    recent: None,
+                })
+            ))]
+        );
+    }
+
+    #[test]
+    fn bad_time_spec() {
+        assert!(Template::parse("{mtime:%Q}").is_err());
+    }
+
+    #[test]
+    fn empty_time_spec() {
+        assert!(Template::parse("{mtime:}").is_err());
+    }
+
+    #[test]
+    fn literal_braces() {
+        let template = Template::parse("{{{ext}}}").unwrap();
+        assert_eq!(
+            template.parts,
+            vec![
+                Part::Text("{".into()),
+                Part::Field(Field::Extension),
+                Part::Text("}".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_field() {
+        assert!(Template::parse("{colour}").is_err());
+    }
+
+    #[test]
+    fn unterminated_field() {
+        assert!(Template::parse("{name").is_err());
+    }
+
+    #[test]
+    fn stray_brace() {
+        assert!(Template::parse("name}").is_err());
+    }
+
+    #[test]
+    fn unexpected_spec() {
+        assert!(Template::parse("{name:bytes}").is_err());
+    }
+
+    #[test]
+    fn bad_size_spec() {
+        assert!(Template::parse("{size:furlongs}").is_err());
+    }
+}
@@ -6,6 +6,7 @@
 // SPDX-License-Identifier: MIT
 //! Timestamp formatting.
 
+use chrono::format::{Item, StrftimeItems};
 use chrono::prelude::*;
 use core::cmp::max;
 use once_cell::sync::Lazy;
@@ -49,7 +50,8 @@ pub enum TimeFormat {
     /// only numbers so doesn’t require any special consideration.
     FullISO,
 
-    /// Use a relative but fixed width representation.
+    /// Use a relative but fixed width representation, giving how long ago
+    /// the timestamp was in the largest unit that fits, such as “3m” or “2y”.
     Relative,
 
     /// Use custom formats, optionally a different custom format can be
@@ -62,6 +64,36 @@ pub enum TimeFormat {
 }
 
 impl TimeFormat {
+    /// Parses a custom format, without its leading `+`. Like GNU `ls`, it
+    /// can have a second line, which gets used for recent timestamps instead
+    /// of the first. Returns why the format is invalid if it is.
+    pub fn custom(formats: &str) -> Result<Self, String> {
+        let mut lines = formats.lines();
+        let non_recent = lines.next().unwrap_or_default();
+        let recent = lines.next();
+
+        if non_recent.is_empty() {
+            return Err(String::from(
+                "it is empty; please supply a chrono format string after the plus sign",
+            ));
+        }
+        check_format(non_recent)?;
+
+        if let Some(recent) = recent {
+            if recent.is_empty() {
+                return Err(String::from(
+                    "the second line is empty; please supply a chrono format string for recent timestamps",
+                ));
+            }
+            check_format(recent)?;
+        }
+
+        Ok(Self::Custom {
+            non_recent: non_recent.to_owned(),
+            recent: recent.map(str::to_owned),
+        })
+    }
+
     pub fn format(self, time: &DateTime<FixedOffset>) -> String {
         #[rustfmt::skip]
         return match self {
@@ -111,23 +143,52 @@ fn long(time: &DateTime<FixedOffset>) -> String {
     time.format("%Y-%m-%d %H:%M").to_string()
 }
 
-// #[allow(trivial_numeric_casts)]
 fn relative(time: &DateTime<FixedOffset>) -> String {
-    timeago::Formatter::new()
-        .ago("")
-        .convert(Duration::from_secs(
-            max(0, Local::now().timestamp() - time.timestamp())
-                // this .unwrap is safe since the call above can never result in a
-                // value < 0
-                .try_into()
-                .unwrap(),
-        ))
+    // Timestamps from the future count as just now.
+    let seconds = max(0, Local::now().timestamp() - time.timestamp()).unsigned_abs();
+    format!("{:>4}", compact_duration(Duration::from_secs(seconds)))
+}
+
+/// Formats a duration using only its largest whole unit, so that every
+/// value is at most four characters wide.
+fn compact_duration(duration: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const YEAR: u64 = 365 * DAY;
+
+    #[rustfmt::skip]
+    return match duration.as_secs() {
+        s if s < MINUTE => format!("{s}s"),
+        s if s < HOUR   => format!("{}m", s / MINUTE),
+        s if s < DAY    => format!("{}h", s / HOUR),
+        s if s < YEAR   => format!("{}d", s / DAY),
+        s               => format!("{}y", s / YEAR),
+    };
+}
+
+/// Returns how many seconds old a timestamp is, which the theme can use to
+/// pick out recent and old timestamps in different colours. Timestamps from
+/// the future count as just now.
+pub fn age_in_seconds(time: NaiveDateTime) -> u64 {
+    let age = Utc::now().naive_utc() - time;
+    max(0, age.num_seconds()).unsigned_abs()
 }
 
 fn full(time: &DateTime<FixedOffset>) -> String {
     time.format("%Y-%m-%d %H:%M:%S.%f %z").to_string()
 }
 
+/// Makes sure that chrono can understand every specifier in a format,
+/// because it panics when asked to use one it can’t.
+fn check_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        Err(format!("{format:?} is not a valid chrono format string"))
+    } else {
+        Ok(())
+    }
+}
+
 fn custom(time: &DateTime<FixedOffset>, non_recent_fmt: &str, recent_fmt: Option<&str>) -> String {
     if let Some(recent_fmt) = recent_fmt {
         if time.year() == *CURRENT_YEAR {
@@ -159,6 +220,54 @@ static MAX_MONTH_WIDTH: Lazy<usize> = Lazy::new(|| {
 mod test {
     use super::*;
 
+    #[test]
+    fn custom_with_recent_line() {
+        assert_eq!(
+            TimeFormat::custom("%Y-%m-%d\n%m-%d %H:%M"),
+            Ok(TimeFormat::Custom {
+                non_recent: "%Y-%m-%d".into(),
+                recent: Some("%m-%d %H:%M".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn custom_with_offset() {
+        let time = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 9, 14, 5, 0)
+            .unwrap();
+        let format = TimeFormat::custom("%Y-%m-%dT%H:%M:%S%z").unwrap();
+        assert_eq!(format.format(&time), "2024-03-09T14:05:00+0100");
+    }
+
+    #[test]
+    fn custom_invalid() {
+        assert!(TimeFormat::custom("").is_err());
+        assert!(TimeFormat::custom("%Y\n\n%H").is_err());
+        assert!(TimeFormat::custom("%Y-%Q").is_err());
+    }
+
+    #[test]
+    fn compact_durations() {
+        assert_eq!(compact_duration(Duration::from_secs(0)), "0s");
+        assert_eq!(compact_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(compact_duration(Duration::from_secs(3 * 60 + 20)), "3m");
+        assert_eq!(compact_duration(Duration::from_secs(2 * 3600)), "2h");
+        assert_eq!(compact_duration(Duration::from_secs(5 * 86400 + 1)), "5d");
+        assert_eq!(compact_duration(Duration::from_secs(364 * 86400)), "364d");
+        assert_eq!(compact_duration(Duration::from_secs(800 * 86400)), "2y");
+    }
+
+    #[test]
+    fn ages() {
+        let now = Utc::now().naive_utc();
+        let age = age_in_seconds(now - chrono::Duration::hours(3));
+        assert!((3 * 3600..3 * 3600 + 60).contains(&age));
+        assert!(age_in_seconds(now - chrono::Duration::days(2)) >= 2 * 86400);
+        assert_eq!(age_in_seconds(now + chrono::Duration::hours(1)), 0);
+    }
+
     #[test]
     fn short_month_width_japanese() {
         let max_month_width = 4;
@@ -59,16 +59,35 @@ pub enum TreePart {
     Blank,
 }
 
+/// The set of characters to draw a tree with.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum TreeStyle {
+    /// Unicode box drawing characters, such as `├──` and `└──`.
+    #[default]
+    Unicode,
+
+    /// The same box drawing characters, but with a rounded corner: `╰──`.
+    Rounded,
+
+    /// Plain ASCII characters, such as `|--` and `` `-- ``, for terminals
+    /// and log files that mangle box drawing characters.
+    Ascii,
+}
+
 impl TreePart {
     /// Turn this tree part into ASCII-licious box drawing characters!
-    /// (Warning: not actually ASCII)
-    pub fn ascii_art(self) -> &'static str {
+    /// (Warning: only actually ASCII in the ASCII style)
+    pub fn ascii_art(self, style: TreeStyle) -> &'static str {
         #[rustfmt::skip]
-        return match self {
-            Self::Edge    => "├── ",
-            Self::Line    => "│   ",
-            Self::Corner  => "└── ",
-            Self::Blank   => "    ",
+        return match (self, style) {
+            (Self::Edge,   TreeStyle::Ascii)    => "|-- ",
+            (Self::Edge,   _)                   => "├── ",
+            (Self::Line,   TreeStyle::Ascii)    => "|   ",
+            (Self::Line,   _)                   => "│   ",
+            (Self::Corner, TreeStyle::Unicode)  => "└── ",
+            (Self::Corner, TreeStyle::Rounded)  => "╰── ",
+            (Self::Corner, TreeStyle::Ascii)    => "`-- ",
+            (Self::Blank,  _)                   => "    ",
         };
     }
 }
@@ -149,6 +168,12 @@ impl TreeParams {
     pub fn new(depth: TreeDepth, last: bool) -> Self {
         Self { depth, last }
     }
+
+    /// Whether this row sits beneath another one, such as a file inside a
+    /// directory or an attribute of a file.
+    pub fn is_nested(&self) -> bool {
+        self.depth.0 > 0
+    }
 }
 
 impl TreeDepth {
@@ -285,3 +310,26 @@ mod iter_test {
         assert!(iter.next().is_none());
     }
 }
+
+#[cfg(test)]
+mod style_test {
+    use super::*;
+
+    #[test]
+    fn ascii_is_ascii() {
+        for part in [
+            TreePart::Edge,
+            TreePart::Line,
+            TreePart::Corner,
+            TreePart::Blank,
+        ] {
+            assert!(part.ascii_art(TreeStyle::Ascii).is_ascii());
+        }
+    }
+
+    #[test]
+    fn rounded_corner() {
+        assert_eq!(TreePart::Corner.ascii_art(TreeStyle::Rounded), "╰── ");
+        assert_eq!(TreePart::Edge.ascii_art(TreeStyle::Rounded), "├── ");
+    }
+}
@@ -0,0 +1,197 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Working out whether the terminal has a light or a dark background, for
+//! `--theme=auto`.
+//!
+//! Some terminals, such as rxvt and Konsole, say so in the `COLORFGBG`
+//! environment variable, as the numbers of their foreground and background
+//! colours. Most others can be asked for their background colour with an
+//! OSC 11 escape sequence, which they answer as if it had been typed. Not
+//! every terminal answers, so the question gets followed by a request for
+//! the device attributes, which every terminal answers, so there’s no need
+//! to wait any longer once that answer has arrived.
+
+use super::Background;
+
+/// Reads the background out of `COLORFGBG`, which is the foreground and
+/// background colour numbers separated by semicolons, sometimes with
+/// another number in the middle. Dark grey is the only dark colour among
+/// the bright ones.
+pub fn from_colorfgbg(value: &str) -> Option<Background> {
+    let background = value.rsplit(';').next()?.parse::<u8>().ok()?;
+    match background {
+        0..=6 | 8 => Some(Background::Dark),
+        7 | 9..=15 => Some(Background::Light),
+        _ => None,
+    }
+}
+
+/// Reads the background out of a terminal’s answer to OSC 11, which has
+/// the colour in the middle, like `rgb:ffff/ffff/dddd`, with between one and
+/// four hex digits for each component.
+fn from_osc11_answer(answer: &[u8]) -> Option<Background> {
+    let answer = std::str::from_utf8(answer).ok()?;
+    let (_, colour) = answer.split_once("\x1b]11;")?;
+    let colour = colour.split(['\x1b', '\x07']).next()?;
+    let components = colour
+        .strip_prefix("rgb:")
+        .or_else(|| colour.strip_prefix("rgba:"))?;
+
+    let mut channels = components.split('/').map(|hex| {
+        let max = 16_u32.checked_pow(u32::try_from(hex.len()).ok()?)? - 1;
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        Some(f64::from(value) / f64::from(max))
+    });
+    let (red, green, blue) = (channels.next()??, channels.next()??, channels.next()??);
+
+    let luminance = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+    if luminance > 0.5 {
+        Some(Background::Light)
+    } else {
+        Some(Background::Dark)
+    }
+}
+
+/// Whether an answer to the device attributes request, which looks like
+/// `ESC [ ? 6 2 ; 2 2 c`, has arrived yet.
+fn has_device_attributes(answer: &[u8]) -> bool {
+    answer
+        .windows(3)
+        .position(|w| w == b"\x1b[?")
+        .is_some_and(|start| answer[start..].contains(&b'c'))
+}
+
+/// How long to wait for the terminal to answer.
+#[cfg(unix)]
+const TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Asks the terminal for its background colour, if standard input and
+/// output are both a terminal and eza is in the foreground, as a job in
+/// the background would get stopped for changing the terminal’s settings.
+#[cfg(unix)]
+pub fn query_terminal() -> Option<Background> {
+    use std::io::IsTerminal;
+    use std::os::unix::io::AsRawFd;
+
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let fd = tty.as_raw_fd();
+    if unsafe { libc::tcgetpgrp(fd) != libc::getpgrp() } {
+        return None;
+    }
+
+    // The answer shouldn’t wait for a newline, or be shown on the screen.
+    let mut original = unsafe { std::mem::zeroed::<libc::termios>() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+
+    let answer = ask_terminal(&tty);
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    from_osc11_answer(&answer?)
+}
+
+#[cfg(not(unix))]
+pub fn query_terminal() -> Option<Background> {
+    None
+}
+
+/// Sends the questions to the terminal, and reads whatever it answers until
+/// the device attributes arrive or it takes too long.
+#[cfg(unix)]
+fn ask_terminal(mut tty: &std::fs::File) -> Option<Vec<u8>> {
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut answer = Vec::new();
+    while !has_device_attributes(&answer) {
+        let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+            break;
+        };
+
+        let mut poll = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = libc::c_int::try_from(left.as_millis()).unwrap_or(libc::c_int::MAX);
+        if unsafe { libc::poll(&mut poll, 1, millis) } <= 0 {
+            break;
+        }
+
+        let mut buf = [0; 64];
+        match tty.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(count) => answer.extend_from_slice(&buf[..count]),
+        }
+    }
+
+    Some(answer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn colorfgbg() {
+        assert_eq!(from_colorfgbg("15;0"), Some(Background::Dark));
+        assert_eq!(from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(from_colorfgbg("0;default;15"), Some(Background::Light));
+        assert_eq!(from_colorfgbg("15;8"), Some(Background::Dark));
+        assert_eq!(from_colorfgbg("default;default"), None);
+        assert_eq!(from_colorfgbg(""), None);
+    }
+
+    #[test]
+    fn osc11_dark() {
+        let answer = b"\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\\x1b[?62;22c";
+        assert_eq!(from_osc11_answer(answer), Some(Background::Dark));
+    }
+
+    #[test]
+    fn osc11_light() {
+        let answer = b"\x1b]11;rgb:ffff/ffff/ffff\x07";
+        assert_eq!(from_osc11_answer(answer), Some(Background::Light));
+    }
+
+    #[test]
+    fn osc11_short_components() {
+        let answer = b"\x1b]11;rgba:f/e/d/0\x07";
+        assert_eq!(from_osc11_answer(answer), Some(Background::Light));
+    }
+
+    #[test]
+    fn no_osc11_answer() {
+        assert_eq!(from_osc11_answer(b"\x1b[?62;22c"), None);
+        assert_eq!(from_osc11_answer(b""), None);
+    }
+
+    #[test]
+    fn device_attributes() {
+        assert!(has_device_attributes(b"\x1b]11;rgb:0/0/0\x07\x1b[?62;22c"));
+        assert!(!has_device_attributes(b"\x1b]11;rgb:0/0/0\x07\x1b[?62;2"));
+        assert!(!has_device_attributes(b""));
+    }
+}
@@ -0,0 +1,229 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Turning the 24-bit colours that themes can ask for into colours that the
+//! terminal can actually show.
+//!
+//! Terminals that understand 24-bit colours say so by setting `COLORTERM`
+//! to `truecolor` or `24bit`. Others get each 24-bit colour replaced by the
+//! nearest one in xterm’s palette of 256 colours, or, on terminals with
+//! only 16, by the nearest of the basic colours.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use nu_ansi_term::Color as Colour;
+
+/// How many colours the terminal can show.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ColourDepth {
+    /// Any 24-bit colour, so nothing needs changing.
+    TrueColour,
+
+    /// The 256 colours of xterm’s palette.
+    Colours256,
+
+    /// The 8 basic colours and their bright versions.
+    Colours16,
+}
+
+/// The depth that colours from the theme definitions get reduced to. This
+/// gets set when the theme gets built, before any of it gets parsed.
+static DEPTH: AtomicU8 = AtomicU8::new(ColourDepth::TrueColour as u8);
+
+impl ColourDepth {
+    pub(super) fn set(self) {
+        DEPTH.store(self as u8, Ordering::Relaxed);
+    }
+
+    fn current() -> Self {
+        match DEPTH.load(Ordering::Relaxed) {
+            1 => Self::Colours256,
+            2 => Self::Colours16,
+            _ => Self::TrueColour,
+        }
+    }
+
+    /// The colour that gets shown instead of the given one at this depth.
+    pub fn reduce(self, colour: Colour) -> Colour {
+        match (self, colour) {
+            (Self::Colours256, Colour::Rgb(r, g, b)) => Colour::Fixed(nearest_fixed(r, g, b)),
+            (Self::Colours16, Colour::Rgb(r, g, b)) => nearest_basic(r, g, b),
+            (Self::Colours16, Colour::Fixed(n)) if n >= 16 => {
+                let (r, g, b) = fixed_to_rgb(n);
+                nearest_basic(r, g, b)
+            }
+            _ => colour,
+        }
+    }
+}
+
+/// Reduces a colour from a theme definition to the terminal’s depth.
+pub fn reduce_colour(colour: Colour) -> Colour {
+    ColourDepth::current().reduce(colour)
+}
+
+/// The levels of red, green, and blue in xterm’s 6×6×6 colour cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The basic colours, in palette order, with xterm’s default values.
+const BASIC_COLOURS: [(Colour, (u8, u8, u8)); 16] = [
+    (Colour::Black, (0, 0, 0)),
+    (Colour::Red, (205, 0, 0)),
+    (Colour::Green, (0, 205, 0)),
+    (Colour::Yellow, (205, 205, 0)),
+    (Colour::Blue, (0, 0, 238)),
+    (Colour::Purple, (205, 0, 205)),
+    (Colour::Cyan, (0, 205, 205)),
+    (Colour::White, (229, 229, 229)),
+    (Colour::DarkGray, (127, 127, 127)),
+    (Colour::LightRed, (255, 0, 0)),
+    (Colour::LightGreen, (0, 255, 0)),
+    (Colour::LightYellow, (255, 255, 0)),
+    (Colour::LightBlue, (92, 92, 255)),
+    (Colour::LightPurple, (255, 0, 255)),
+    (Colour::LightCyan, (0, 255, 255)),
+    (Colour::LightGray, (255, 255, 255)),
+];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let diff = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    diff(r1, r2) + diff(g1, g2) + diff(b1, b2)
+}
+
+/// The number of the closest colour in the cube or the grey ramp. The 16
+/// basic colours get left out, as terminals often change what they look like.
+fn nearest_fixed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (
+        CUBE_LEVELS[usize::from(ri)],
+        CUBE_LEVELS[usize::from(gi)],
+        CUBE_LEVELS[usize::from(bi)],
+    );
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let grey_index = if average > 238 {
+        23
+    } else {
+        u8::try_from(average.saturating_sub(3) / 10).unwrap_or(23)
+    };
+    let grey = 8 + grey_index * 10;
+
+    if distance((grey, grey, grey), (r, g, b)) < distance(cube, (r, g, b)) {
+        232 + grey_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+/// The colour a number in xterm’s palette has by default.
+pub fn fixed_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => BASIC_COLOURS[usize::from(n)].1,
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[usize::from(n / 36)],
+                CUBE_LEVELS[usize::from(n / 6 % 6)],
+                CUBE_LEVELS[usize::from(n % 6)],
+            )
+        }
+        _ => {
+            let grey = 8 + (n - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+fn nearest_basic(r: u8, g: u8, b: u8) -> Colour {
+    BASIC_COLOURS
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map_or(Colour::Default, |(colour, _)| *colour)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn true_colour_is_kept() {
+        let colour = Colour::Rgb(18, 52, 86);
+        assert_eq!(ColourDepth::TrueColour.reduce(colour), colour);
+    }
+
+    #[test]
+    fn rgb_to_cube() {
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Rgb(255, 0, 0)),
+            Colour::Fixed(196)
+        );
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Rgb(0, 0, 0)),
+            Colour::Fixed(16)
+        );
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Rgb(255, 135, 0)),
+            Colour::Fixed(208)
+        );
+    }
+
+    #[test]
+    fn rgb_to_grey() {
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Rgb(128, 128, 128)),
+            Colour::Fixed(244)
+        );
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Rgb(30, 30, 32)),
+            Colour::Fixed(234)
+        );
+    }
+
+    #[test]
+    fn fixed_kept_at_256() {
+        assert_eq!(
+            ColourDepth::Colours256.reduce(Colour::Fixed(125)),
+            Colour::Fixed(125)
+        );
+    }
+
+    #[test]
+    fn rgb_to_basic() {
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Rgb(250, 10, 10)),
+            Colour::LightRed
+        );
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Rgb(0, 150, 140)),
+            Colour::Cyan
+        );
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Rgb(10, 10, 10)),
+            Colour::Black
+        );
+    }
+
+    #[test]
+    fn fixed_to_basic() {
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Fixed(196)),
+            Colour::LightRed
+        );
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Fixed(244)),
+            Colour::DarkGray
+        );
+        assert_eq!(
+            ColourDepth::Colours16.reduce(Colour::Fixed(3)),
+            Colour::Fixed(3)
+        );
+    }
+}
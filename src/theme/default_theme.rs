@@ -5,7 +5,7 @@
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
 use nu_ansi_term::Color::*;
-use nu_ansi_term::Style;
+use nu_ansi_term::{Color, Style};
 use std::default::Default;
 
 use crate::output::color_scale::{ColorScaleMode, ColorScaleOptions};
@@ -19,6 +19,63 @@ impl UiStyles {
     }
 }
 
+/// The colours used in place of yellow, cyan, and white on light
+/// backgrounds, where those are too faint to read.
+const DARK_YELLOW: Color = Fixed(136);
+const DARK_CYAN: Color = Fixed(30);
+
+impl UiStyles {
+    /// Swaps the default colours that are hard to read on a light
+    /// background for darker ones.
+    #[rustfmt::skip]
+    pub fn for_light_background(&mut self) {
+        let filekinds = self.filekinds();
+        filekinds.symlink      = Some(DARK_CYAN.normal());
+        filekinds.pipe         = Some(DARK_YELLOW.normal());
+        filekinds.block_device = Some(DARK_YELLOW.bold());
+        filekinds.char_device  = Some(DARK_YELLOW.bold());
+        filekinds.special      = Some(DARK_YELLOW.normal());
+        filekinds.offline      = Some(DARK_CYAN.normal());
+
+        let perms = self.perms();
+        perms.user_read  = Some(DARK_YELLOW.bold());
+        perms.group_read = Some(DARK_YELLOW.normal());
+        perms.other_read = Some(DARK_YELLOW.normal());
+
+        let size = self.size();
+        if size.number_mega == Some(Yellow.normal()) {
+            size.number_mega = Some(DARK_YELLOW.normal());
+            size.unit_mega   = Some(DARK_YELLOW.normal());
+        }
+
+        let users = self.users();
+        users.user_you    = Some(DARK_YELLOW.bold());
+        users.group_yours = Some(DARK_YELLOW.bold());
+
+        self.git().renamed = Some(DARK_YELLOW.normal());
+
+        let git_repo = self.git_repo();
+        git_repo.branch_other = Some(DARK_YELLOW.normal());
+        git_repo.git_dirty    = Some(DARK_YELLOW.bold());
+
+        if let Some(selinux) = &mut self.security_context().selinux {
+            selinux.typ   = Some(DARK_YELLOW.normal());
+            selinux.range = Some(DARK_CYAN.normal());
+        }
+
+        let file_type = self.file_type();
+        file_type.music    = Some(DARK_CYAN.normal());
+        file_type.lossless = Some(DARK_CYAN.bold());
+        file_type.temp     = Some(DarkGray.normal());
+        file_type.compiled = Some(DARK_YELLOW.normal());
+        file_type.build    = Some(DARK_YELLOW.bold().underline());
+        file_type.source   = Some(DARK_YELLOW.bold());
+
+        self.blocks       = Some(DARK_CYAN.normal());
+        self.symlink_path = Some(DARK_CYAN.normal());
+    }
+}
+
 impl Default for UiStyles {
     fn default() -> Self {
         Self {
@@ -36,6 +93,9 @@ impl Default for UiStyles {
             special: Some(Yellow.normal()),
             executable: Some(Green.bold()),
             mount_point: Some(Blue.bold().underline()),
+            encrypted: Some(Purple.normal()),
+            offline: Some(Cyan.normal()),
+            ..FileKinds::default()
             }),
 
             #[rustfmt::skip]
@@ -124,19 +184,31 @@ impl Default for UiStyles {
 
             punctuation: Some(DarkGray.bold()),
             date: Some(Blue.normal()),
+            date_recent: None,
+            date_old: None,
             inode: Some(Purple.normal()),
             blocks: Some(Cyan.normal()),
             octal: Some(Purple.normal()),
             flags: Some(Style::default()),
+            file_flags: None,
+            date_threshold_recent: None,
+            date_threshold_old: None,
             header: Some(Style::default().underline()),
 
             symlink_path: Some(Cyan.normal()),
             control_char: Some(Red.normal()),
             broken_symlink: Some(Red.normal()),
             broken_path_overlay: Some(Style::default().underline()),
+            tree_branch: None,
+            link_arrow: None,
+            permission_dash: None,
+            grid_separator: None,
+            error_note: None,
 
             filenames: None,
             extensions: None,
+            globs: None,
+            columns: None,
         }
     }
 }
@@ -166,6 +238,8 @@ impl Size {
             unit_mega: Some(Green.normal()),
             unit_giga: Some(Green.normal()),
             unit_huge: Some(Green.normal()),
+
+            ..Self::default()
         }
     }
 
@@ -185,6 +259,8 @@ impl Size {
             unit_mega: Some(Yellow.normal()),
             unit_giga: Some(Red.normal()),
             unit_huge: Some(Purple.normal()),
+
+            ..Self::default()
         }
     }
 }
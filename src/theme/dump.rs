@@ -0,0 +1,246 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Listing every style in the theme along with where it came from, for
+//! `--dump-theme`.
+//!
+//! The theme gets built up in layers: the defaults, then the theme file,
+//! then `LS_COLORS`, then `EZA_COLORS`. Each layer gets built on its own
+//! and flattened into a list of styles named by their path in the theme
+//! file, so a style comes from the last layer that changed it.
+
+use std::fmt::Write;
+
+use nu_ansi_term::Style;
+use serde_norway::Value;
+
+use super::lsc::style_codes;
+use super::{Definitions, Options, UiStyles};
+
+/// Where a style in the theme came from.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Source {
+    Default,
+    ThemeFile,
+    LsColors,
+    EzaColors,
+}
+
+impl Source {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::ThemeFile => "theme file",
+            Self::LsColors => "LS_COLORS",
+            Self::EzaColors => "EZA_COLORS",
+        }
+    }
+}
+
+/// The fields of `UiStyles` that are maps, which get sorted by their keys
+/// so the output is the same each time.
+const MAP_FIELDS: &[&str] = &["file_flags", "filenames", "extensions", "globs", "columns"];
+
+impl Options {
+    /// Lists every style in the theme, one per line, with the name it has
+    /// in the theme file, where it came from, and its `LS_COLORS` codes,
+    /// followed by the styles for file name patterns from `LS_COLORS` and
+    /// `EZA_COLORS`, in increasing order of precedence. The styles are
+    /// the ones that would get used if colours were on.
+    pub fn dump_theme(&self, isatty: bool) -> String {
+        self.colour_depth.set();
+        let light = self.background.is_light();
+
+        let themed = self
+            .theme_config
+            .as_ref()
+            .and_then(|theme| theme.to_theme(light));
+        let mut defaults = if themed.is_some() {
+            UiStyles::default()
+        } else {
+            UiStyles::default_theme(self.colour_scale)
+        };
+        if light {
+            defaults.for_light_background();
+        }
+
+        let ls = Definitions {
+            ls: self.definitions.ls.clone(),
+            exa: None,
+        };
+        let mut with_ls = themed.clone().unwrap_or_else(|| defaults.clone());
+        let (ls_patterns, _) = ls.parse_color_vars(&mut with_ls);
+
+        let eza = Definitions {
+            ls: None,
+            exa: self.definitions.exa.clone(),
+        };
+        let mut with_eza = with_ls.clone();
+        let (eza_patterns, _) = eza.parse_color_vars(&mut with_eza);
+
+        let mut layers = vec![(Source::Default, flatten(&defaults))];
+        if let Some(themed) = &themed {
+            layers.push((Source::ThemeFile, flatten(themed)));
+        }
+        layers.push((Source::LsColors, flatten(&with_ls)));
+        layers.push((Source::EzaColors, flatten(&with_eza)));
+
+        let mut lines = Vec::new();
+        let (_, resolved) = layers.last().expect("there's always a layer");
+        for (name, style) in resolved {
+            let source = layers
+                .windows(2)
+                .rev()
+                .find(|pair| lookup(&pair[0].1, name) != lookup(&pair[1].1, name))
+                .map_or(Source::Default, |pair| pair[1].0);
+            lines.push((name.clone(), source, *style));
+        }
+
+        for (source, patterns) in [
+            (Source::LsColors, ls_patterns),
+            (Source::EzaColors, eza_patterns),
+        ] {
+            for (pattern, style) in patterns.mappings {
+                lines.push((pattern.as_str().to_owned(), source, style));
+            }
+        }
+
+        let painted = self.use_colours.resolve(isatty);
+        let width = lines.iter().map(|(name, ..)| name.len()).max().unwrap_or(0);
+        let mut out = String::new();
+        for (name, source, style) in lines {
+            let codes = style_codes(style);
+            let codes = if painted {
+                style.paint(codes).to_string()
+            } else {
+                codes
+            };
+            let _ = writeln!(out, "{name:width$}  {:10}  {codes}", source.name());
+        }
+        out
+    }
+}
+
+fn lookup(styles: &[(String, Style)], name: &str) -> Option<Style> {
+    styles
+        .iter()
+        .find(|(other, _)| other == name)
+        .map(|(_, style)| *style)
+}
+
+/// Every style that’s set in the given UI styles, named by its path.
+fn flatten(ui: &UiStyles) -> Vec<(String, Style)> {
+    let mut styles = Vec::new();
+    if let Ok(value) = serde_norway::to_value(ui) {
+        flatten_value("", &value, false, &mut styles);
+    }
+    styles
+}
+
+fn flatten_value(path: &str, value: &Value, sorted: bool, styles: &mut Vec<(String, Style)>) {
+    let Value::Mapping(mapping) = value else {
+        return;
+    };
+
+    // A style is the only thing with an `is_bold` field.
+    if mapping.contains_key("is_bold") {
+        if let Ok(style) = serde_norway::from_value::<Style>(value.clone()) {
+            styles.push((path.to_owned(), style));
+        }
+        return;
+    }
+
+    let mut fields = mapping
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str()?, value)))
+        .collect::<Vec<_>>();
+    if sorted {
+        fields.sort_by_key(|(key, _)| *key);
+    }
+
+    for (key, value) in fields {
+        let child = if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{path}.{key}")
+        };
+        let sorted = path.is_empty() && MAP_FIELDS.contains(&key);
+        flatten_value(&child, value, sorted, styles);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::output::color_scale::ColorScaleOptions;
+    use crate::output::icons::IconOverrides;
+    use crate::theme::{Background, ColourDepth, UseColours};
+
+    fn dump(ls: &str, exa: &str) -> String {
+        let options = Options {
+            use_colours: UseColours::Never,
+            colour_scale: ColorScaleOptions::default(),
+            definitions: Definitions {
+                ls: Some(ls.into()),
+                exa: Some(exa.into()),
+            },
+            background: Background::Dark,
+            colour_depth: ColourDepth::TrueColour,
+            theme_config: None,
+            icons: IconOverrides::default(),
+        };
+
+        options.dump_theme(false)
+    }
+
+    fn line<'a>(dump: &'a str, name: &str) -> Vec<&'a str> {
+        dump.lines()
+            .map(|line| {
+                line.split("  ")
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .find(|fields| fields[0].trim() == name)
+            .unwrap_or_else(|| panic!("no line for {name} in {dump}"))
+            .into_iter()
+            .map(str::trim)
+            .collect()
+    }
+
+    #[test]
+    fn defaults() {
+        let dump = dump("", "");
+        assert_eq!(
+            line(&dump, "filekinds.directory"),
+            ["filekinds.directory", "default", "1;34"]
+        );
+        assert_eq!(
+            line(&dump, "punctuation"),
+            ["punctuation", "default", "1;90"]
+        );
+    }
+
+    #[test]
+    fn sources() {
+        let dump = dump("di=31:ex=32", "ex=33:da=35");
+        assert_eq!(
+            line(&dump, "filekinds.directory"),
+            ["filekinds.directory", "LS_COLORS", "31"]
+        );
+        assert_eq!(
+            line(&dump, "filekinds.executable"),
+            ["filekinds.executable", "EZA_COLORS", "33"]
+        );
+        assert_eq!(line(&dump, "date"), ["date", "EZA_COLORS", "35"]);
+    }
+
+    #[test]
+    fn patterns() {
+        let dump = dump("*.txt=31", "*.md=32");
+        assert_eq!(line(&dump, "*.txt"), ["*.txt", "LS_COLORS", "31"]);
+        assert_eq!(line(&dump, "*.md"), ["*.md", "EZA_COLORS", "32"]);
+    }
+}
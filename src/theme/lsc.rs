@@ -10,6 +10,8 @@ use std::ops::FnMut;
 use nu_ansi_term::Color::*;
 use nu_ansi_term::{Color as Colour, Style};
 
+use super::reduce_colour;
+
 // Parsing the LS_COLORS environment variable into a map of names to Style values.
 //
 // This is sitting around undocumented at the moment because it’s a feature
@@ -86,12 +88,37 @@ where
             }
         }
 
+        Some(&hex) if hex.starts_with('#') => {
+            let _ = iter.next();
+            return parse_hex_colour(hex);
+        }
+
         _ => {}
     }
 
     None
 }
 
+/// Parses a 24-bit colour written as `#rrggbb`, or as `#rgb` for short.
+fn parse_hex_colour(hex: &str) -> Option<Colour> {
+    let digits = hex.strip_prefix('#')?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize, len: usize| {
+        let value = u8::from_str_radix(digits.get(i * len..(i + 1) * len)?, 16).ok()?;
+        Some(if len == 1 { value * 17 } else { value })
+    };
+    let len = match digits.len() {
+        3 => 1,
+        6 => 2,
+        _ => return None,
+    };
+
+    Some(Rgb(channel(0, len)?, channel(1, len)?, channel(2, len)?))
+}
+
 pub struct Pair<'var> {
     pub key: &'var str,
     pub value: &'var str,
@@ -135,7 +162,12 @@ impl<'var> Pair<'var> {
                 "97" => style = style.fg(LightGray),
                 "38" => {
                     if let Some(c) = parse_into_high_colour(&mut iter) {
-                        style = style.fg(c);
+                        style = style.fg(reduce_colour(c));
+                    }
+                }
+                hex if hex.starts_with('#') => {
+                    if let Some(c) = parse_hex_colour(hex) {
+                        style = style.fg(reduce_colour(c));
                     }
                 }
 
@@ -159,7 +191,7 @@ impl<'var> Pair<'var> {
                 "107" => style = style.on(LightGray),
                 "48" => {
                     if let Some(c) = parse_into_high_colour(&mut iter) {
-                        style = style.on(c);
+                        style = style.on(reduce_colour(c));
                     }
                 }
                 _ => { /* ignore the error and do nothing */ }
@@ -170,6 +202,28 @@ impl<'var> Pair<'var> {
     }
 }
 
+impl Pair<'_> {
+    /// The style for one of the keys that only get used when they’re set,
+    /// such as `su` or `mh`, where `0` means not to use them, as it does
+    /// for ls, rather than to use the plain style.
+    pub fn to_optional_style(&self) -> Option<Style> {
+        Some(self.to_style()).filter(|style| *style != Style::default())
+    }
+}
+
+/// Turns a style back into the codes that `LS_COLORS` uses for it, the
+/// reverse of `Pair::to_style`. A style with nothing set becomes `0`.
+pub fn style_codes(style: Style) -> String {
+    let prefix = style.prefix().to_string();
+    let codes = prefix.trim_start_matches("\x1b[").trim_end_matches('m');
+
+    if codes.is_empty() {
+        String::from("0")
+    } else {
+        String::from(codes)
+    }
+}
+
 #[cfg(test)]
 mod ansi_test {
     use super::*;
@@ -219,12 +273,38 @@ mod ansi_test {
 
     test!(rgb:   "38;2;255;100;0"     => Style::default().fg(Rgb(255, 100, 0)));
     test!(rgbi:  "38;2;255;100;0;3"   => Style::default().fg(Rgb(255, 100, 0)).italic());
+
+    // Hex colours
+    test!(hex:      "#ff6400"           => Style::default().fg(Rgb(255, 100, 0)));
+    test!(hexshort: "#f60"              => Style::default().fg(Rgb(255, 102, 0)));
+    test!(hexbold:  "1;#ff6400"         => Style::default().fg(Rgb(255, 100, 0)).bold());
+    test!(hexfg:    "38;#ff6400"        => Style::default().fg(Rgb(255, 100, 0)));
+    test!(hexbg:    "48;#ff6400;#000"   => Rgb(0, 0, 0).on(Rgb(255, 100, 0)));
+    test!(badhex:   "#ff640"            => Style::default());
+    test!(nothex:   "#gg6400"           => Style::default());
     test!(rgbbg: "48;2;255;100;0"     => Style::default().on(Rgb(255, 100, 0)));
     test!(rgbbi: "48;2;255;100;0;3"   => Style::default().on(Rgb(255, 100, 0)).italic());
 
     test!(fgbg:  "38;5;121;48;5;212"  => Fixed(121).on(Fixed(212)));
     test!(bgfg:  "48;5;121;38;5;212"  => Fixed(212).on(Fixed(121)));
     test!(toohi: "48;5;999"           => Style::default());
+
+    #[test]
+    fn codes_round_trip() {
+        for codes in ["1;34", "4;38;5;149", "48;5;1;38;2;255;100;0", "100;97"] {
+            let style = Pair {
+                key: "",
+                value: codes,
+            }
+            .to_style();
+            assert_eq!(style_codes(style), codes);
+        }
+    }
+
+    #[test]
+    fn plain_codes() {
+        assert_eq!(style_codes(Style::default()), "0");
+    }
 }
 
 #[cfg(test)]
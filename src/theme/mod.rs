@@ -11,7 +11,9 @@ use crate::info::filetype::FileType;
 use crate::options::config::ThemeConfig;
 use crate::output::color_scale::ColorScaleOptions;
 use crate::output::file_name::Colours as FileNameColours;
+use crate::output::icons::IconOverrides;
 use crate::output::render;
+use crate::output::table::Column;
 
 mod ui_styles;
 pub(crate) use self::ui_styles::FileType as ThemeFileType;
@@ -19,10 +21,20 @@ pub use self::ui_styles::UiStyles;
 pub(crate) use self::ui_styles::*;
 
 mod lsc;
+use self::lsc::style_codes;
 pub use self::lsc::LSColors;
 
+mod background;
+pub use self::background::from_colorfgbg;
+
+mod colour_depth;
+pub use self::colour_depth::ColourDepth;
+pub(crate) use self::colour_depth::{fixed_to_rgb, reduce_colour};
+
 mod default_theme;
 
+mod dump;
+
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
     pub use_colours: UseColours,
@@ -31,7 +43,14 @@ pub struct Options {
 
     pub definitions: Definitions,
 
+    pub background: Background,
+
+    pub colour_depth: ColourDepth,
+
     pub theme_config: Option<ThemeConfig>,
+
+    /// The icons the user picked, which get used with or without colours.
+    pub icons: IconOverrides,
 }
 
 /// Under what circumstances we should display coloured, rather than plain,
@@ -53,6 +72,32 @@ pub enum UseColours {
     Never,
 }
 
+/// Whether the terminal’s background is light or dark, which decides which
+/// default colours are readable on it.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Background {
+    /// Use the colours for light backgrounds.
+    Light,
+
+    /// Use the colours for dark backgrounds, which is what’s used when
+    /// nothing says otherwise.
+    Dark,
+
+    /// Ask the terminal, when there are colours to pick.
+    Automatic,
+}
+
+impl Background {
+    /// Whether the background is light, asking the terminal if it has to.
+    fn is_light(self) -> bool {
+        match self {
+            Self::Light => true,
+            Self::Dark => false,
+            Self::Automatic => background::query_terminal() == Some(Self::Light),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Default)]
 pub struct Definitions {
     pub ls: Option<String>,
@@ -62,16 +107,148 @@ pub struct Definitions {
 pub struct Theme {
     pub ui: UiStyles,
     pub exts: Box<dyn FileStyle>,
+    pub icons: IconOverrides,
+
+    /// The glob patterns from the theme file’s `globs`, compiled once
+    /// rather than for every file, with the longest first as it wins.
+    globs: Vec<(glob::Pattern, FileNameStyle)>,
+
+    /// The styles from the theme file’s `columns`, with their names
+    /// turned into the columns they’re for.
+    columns: Vec<(Column, ColumnStyle)>,
+}
+
+impl Theme {
+    fn new(ui: UiStyles, exts: Box<dyn FileStyle>, icons: IconOverrides) -> Self {
+        let mut globs = Vec::new();
+        for (pattern, style) in ui.globs.iter().flatten() {
+            match glob::Pattern::new(pattern) {
+                Ok(pat) => globs.push((pat, *style)),
+                Err(e) => log::warn!("Couldn't parse glob pattern {pattern:?}: {e}"),
+            }
+        }
+        globs.sort_by(|a, b| {
+            let (a, b) = (a.0.as_str(), b.0.as_str());
+            b.len().cmp(&a.len()).then_with(|| a.cmp(b))
+        });
+
+        let mut columns = Vec::new();
+        for (name, style) in ui.columns.iter().flatten() {
+            if let Some(column) = Column::from_field_name(name) {
+                columns.push((column, *style));
+            } else {
+                log::warn!("Unknown column name {name:?} in the theme file");
+            }
+        }
+
+        Self {
+            ui,
+            exts,
+            icons,
+            globs,
+            columns,
+        }
+    }
+
+    /// The styles the theme file gives the given column, if any.
+    pub fn column_style(&self, column: Column) -> ColumnStyle {
+        self.columns
+            .iter()
+            .find(|(c, _)| *c == column)
+            .map(|(_, style)| *style)
+            .unwrap_or_default()
+    }
+
+    /// The style for the branches of the tree view.
+    pub fn tree_branch(&self) -> Style {
+        self.ui.tree_branch.unwrap_or_else(|| self.ui.punctuation())
+    }
+
+    /// The style for the gutters between the columns of the grid view.
+    pub fn grid_separator(&self) -> Style {
+        self.ui.grid_separator.unwrap_or_default()
+    }
+
+    /// Converts this theme back into an `LS_COLORS` string, so that other
+    /// programs can colour files the same way. Later entries take precedence
+    /// over earlier ones, as they do when eza reads the variable.
+    pub fn to_ls_colors(&self) -> String {
+        let kinds = self.ui.filekinds.unwrap_or_default();
+
+        #[rustfmt::skip]
+        let mut pairs = vec![
+            (String::from("fi"), kinds.normal()),
+            (String::from("di"), kinds.directory()),
+            (String::from("ln"), kinds.symlink()),
+            (String::from("pi"), kinds.pipe()),
+            (String::from("bd"), kinds.block_device()),
+            (String::from("cd"), kinds.char_device()),
+            (String::from("so"), kinds.socket()),
+            (String::from("ex"), kinds.executable()),
+            (String::from("or"), self.ui.broken_symlink()),
+        ];
+
+        #[rustfmt::skip]
+        let special_kinds = [
+            ("mh", kinds.multi_hard_link),
+            ("do", kinds.door),
+            ("su", kinds.setuid),
+            ("sg", kinds.setgid),
+            ("ca", kinds.capability),
+            ("tw", kinds.sticky_other_writable),
+            ("ow", kinds.other_writable),
+            ("st", kinds.sticky),
+        ];
+        for (key, style) in special_kinds {
+            if let Some(style) = style {
+                pairs.push((String::from(key), style));
+            }
+        }
+
+        self.exts.ls_colors(self, &mut pairs);
+
+        // Styles from the theme file override everything else, with names
+        // overriding extensions, and extensions overriding globs.
+        for (pattern, style) in self.globs.iter().rev() {
+            if let Some(filename) = style.filename {
+                pairs.push((pattern.as_str().to_owned(), filename));
+            }
+        }
+
+        if let Some(ref ext_overrides) = self.ui.extensions {
+            let mut overrides: Vec<_> = ext_overrides.iter().collect();
+            overrides.sort_by_key(|(ext, _)| *ext);
+            for (ext, style) in overrides {
+                if let Some(filename) = style.filename {
+                    pairs.push((format!("*.{ext}"), filename));
+                }
+            }
+        }
+
+        if let Some(ref name_overrides) = self.ui.filenames {
+            let mut overrides: Vec<_> = name_overrides.iter().collect();
+            overrides.sort_by_key(|(name, _)| *name);
+            for (name, style) in overrides {
+                if let Some(filename) = style.filename {
+                    pairs.push((format!("*{name}"), filename));
+                }
+            }
+        }
+
+        pairs
+            .into_iter()
+            .map(|(pattern, style)| format!("{pattern}={}", style_codes(style)))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
 }
 
 impl Options {
     pub fn to_theme(&self, isatty: bool) -> Theme {
-        if self.use_colours == UseColours::Never
-            || (self.use_colours == UseColours::Automatic && !isatty)
-        {
+        if !self.use_colours.resolve(isatty) {
             let ui = UiStyles::plain();
             let exts = Box::new(NoFileStyle);
-            return Theme { ui, exts };
+            return Theme::new(ui, exts, self.icons.clone());
         };
 
         #[cfg(windows)]
@@ -83,12 +260,14 @@ impl Options {
             }
             let ui = UiStyles::plain();
             let exts = Box::new(NoFileStyle);
-            return Theme { ui, exts };
+            return Theme::new(ui, exts, self.icons.clone());
         }
 
+        self.colour_depth.set();
+        let light = self.background.is_light();
         match self.theme_config {
             Some(ref theme) => {
-                if let Some(mut ui) = theme.to_theme() {
+                if let Some(mut ui) = theme.to_theme(light) {
                     let (exts, use_default_filetypes) = self.definitions.parse_color_vars(&mut ui);
                     let exts: Box<dyn FileStyle> =
                         match (exts.is_non_empty(), use_default_filetypes) {
@@ -97,16 +276,19 @@ impl Options {
                             (true, false) => Box::new(exts),
                             (true, true) => Box::new((exts, FileTypes)),
                         };
-                    return Theme { ui, exts };
+                    return Theme::new(ui, exts, self.icons.clone());
                 }
-                self.default_theme()
+                self.default_theme(light)
             }
-            None => self.default_theme(),
+            None => self.default_theme(light),
         }
     }
 
-    fn default_theme(&self) -> Theme {
+    fn default_theme(&self, light: bool) -> Theme {
         let mut ui = UiStyles::default_theme(self.colour_scale);
+        if light {
+            ui.for_light_background();
+        }
         let (exts, use_default_filetypes) = self.definitions.parse_color_vars(&mut ui);
         let exts: Box<dyn FileStyle> = match (exts.is_non_empty(), use_default_filetypes) {
             (false, false) => Box::new(NoFileStyle),
@@ -114,7 +296,7 @@ impl Options {
             (true, false) => Box::new(exts),
             (true, true) => Box::new((exts, FileTypes)),
         };
-        Theme { ui, exts }
+        Theme::new(ui, exts, self.icons.clone())
     }
 }
 
@@ -154,7 +336,7 @@ impl Definitions {
             }
 
             LSColors(exa).each_pair(|pair| {
-                if !colours.set_ls(&pair) && !colours.set_exa(&pair) {
+                if !colours.set_exa(&pair) && !colours.set_ls(&pair) {
                     match glob::Pattern::new(pair.key) {
                         Ok(pat) => {
                             exts.add(pat, pair.to_style());
@@ -176,6 +358,10 @@ pub trait FileStyle: Sync {
     /// Return the style to paint the filename text for `file` from the given
     /// `theme`.
     fn get_style(&self, file: &File<'_>, theme: &Theme) -> Option<Style>;
+
+    /// Adds the `LS_COLORS` patterns this uses to pick styles, along with
+    /// their styles, in increasing order of precedence.
+    fn ls_colors(&self, theme: &Theme, pairs: &mut Vec<(String, Style)>);
 }
 
 #[derive(PartialEq, Debug)]
@@ -185,6 +371,8 @@ impl FileStyle for NoFileStyle {
     fn get_style(&self, _file: &File<'_>, _theme: &Theme) -> Option<Style> {
         None
     }
+
+    fn ls_colors(&self, _theme: &Theme, _pairs: &mut Vec<(String, Style)>) {}
 }
 
 // When getting the colour of a file from a *pair* of colourisers, try the
@@ -201,6 +389,11 @@ where
             .get_style(file, theme)
             .or_else(|| self.1.get_style(file, theme))
     }
+
+    fn ls_colors(&self, theme: &Theme, pairs: &mut Vec<(String, Style)>) {
+        self.1.ls_colors(theme, pairs);
+        self.0.ls_colors(theme, pairs);
+    }
 }
 
 #[derive(PartialEq, Debug, Default)]
@@ -229,28 +422,47 @@ impl FileStyle for ExtensionMappings {
             .find(|t| t.0.matches(&file.name))
             .map(|t| t.1)
     }
+
+    fn ls_colors(&self, _theme: &Theme, pairs: &mut Vec<(String, Style)>) {
+        for (pattern, style) in &self.mappings {
+            pairs.push((pattern.as_str().to_owned(), *style));
+        }
+    }
 }
 
 #[derive(Debug)]
 struct FileTypes;
 
+impl FileTypes {
+    fn style_for(file_type: &FileType, theme: &Theme) -> Option<Style> {
+        #[rustfmt::skip]
+        return match file_type {
+            FileType::Image      => theme.ui.file_type.unwrap_or_default().image,
+            FileType::Video      => theme.ui.file_type.unwrap_or_default().video,
+            FileType::Music      => theme.ui.file_type.unwrap_or_default().music,
+            FileType::Lossless   => theme.ui.file_type.unwrap_or_default().lossless,
+            FileType::Crypto     => theme.ui.file_type.unwrap_or_default().crypto,
+            FileType::Document   => theme.ui.file_type.unwrap_or_default().document,
+            FileType::Compressed => theme.ui.file_type.unwrap_or_default().compressed,
+            FileType::Temp       => theme.ui.file_type.unwrap_or_default().temp,
+            FileType::Compiled   => theme.ui.file_type.unwrap_or_default().compiled,
+            FileType::Build      => theme.ui.file_type.unwrap_or_default().build,
+            FileType::Source     => theme.ui.file_type.unwrap_or_default().source,
+        };
+    }
+}
+
 impl FileStyle for FileTypes {
     fn get_style(&self, file: &File<'_>, theme: &Theme) -> Option<Style> {
-        #[rustfmt::skip]
-        return match FileType::get_file_type(file) {
-            Some(FileType::Image)      => theme.ui.file_type.unwrap_or_default().image,
-            Some(FileType::Video)      => theme.ui.file_type.unwrap_or_default().video,
-            Some(FileType::Music)      => theme.ui.file_type.unwrap_or_default().music,
-            Some(FileType::Lossless)   => theme.ui.file_type.unwrap_or_default().lossless,
-            Some(FileType::Crypto)     => theme.ui.file_type.unwrap_or_default().crypto,
-            Some(FileType::Document)   => theme.ui.file_type.unwrap_or_default().document,
-            Some(FileType::Compressed) => theme.ui.file_type.unwrap_or_default().compressed,
-            Some(FileType::Temp)       => theme.ui.file_type.unwrap_or_default().temp,
-            Some(FileType::Compiled)   => theme.ui.file_type.unwrap_or_default().compiled,
-            Some(FileType::Build)      => theme.ui.file_type.unwrap_or_default().build,
-            Some(FileType::Source)     => theme.ui.file_type.unwrap_or_default().source,
-            None                       => None
-    };
+        FileType::get_file_type(file).and_then(|file_type| Self::style_for(&file_type, theme))
+    }
+
+    fn ls_colors(&self, theme: &Theme, pairs: &mut Vec<(String, Style)>) {
+        for (pattern, file_type) in FileType::patterns() {
+            if let Some(style) = Self::style_for(file_type, theme) {
+                pairs.push((pattern, style));
+            }
+        }
     }
 }
 
@@ -324,7 +536,6 @@ impl render::GitRepoColours for Theme {
 }
 
 #[rustfmt::skip]
-#[cfg(unix)]
 impl render::GroupColours for Theme {
     fn yours(&self)      -> Style { self.ui.users.unwrap_or_default().group_yours() }
     fn not_yours(&self)  -> Style { self.ui.users.unwrap_or_default().group_other() }
@@ -332,6 +543,21 @@ impl render::GroupColours for Theme {
     fn no_group(&self)   -> Style { self.ui.punctuation() }
 }
 
+impl render::FlagsColours for Theme {
+    fn flags(&self) -> Style {
+        self.ui.flags()
+    }
+
+    fn flag(&self, name: &str) -> Style {
+        self.ui
+            .file_flags
+            .as_ref()
+            .and_then(|styles| styles.get(name))
+            .copied()
+            .unwrap_or_else(|| self.ui.flags())
+    }
+}
+
 #[rustfmt::skip]
 impl render::LinksColours for Theme {
     fn normal(&self)           -> Style { self.ui.links.unwrap_or_default().normal() }
@@ -340,7 +566,7 @@ impl render::LinksColours for Theme {
 
 #[rustfmt::skip]
 impl render::PermissionsColours for Theme {
-    fn dash(&self)               -> Style { self.ui.punctuation() }
+    fn dash(&self)               -> Style { self.ui.permission_dash.unwrap_or_else(|| self.ui.punctuation()) }
     fn user_read(&self)          -> Style { self.ui.perms.unwrap_or_default().user_read() }
     fn user_write(&self)         -> Style { self.ui.perms.unwrap_or_default().user_write() }
     fn user_execute_file(&self)  -> Style { self.ui.perms.unwrap_or_default().user_execute_file() }
@@ -383,6 +609,21 @@ impl render::SizeColours for Theme {
         };
     }
 
+    fn band(&self, size: u64) -> Option<number_prefix::Prefix> {
+        use number_prefix::Prefix::*;
+
+        let [kilo, mega, giga, huge] = self.ui.size.unwrap_or_default().thresholds();
+
+        #[rustfmt::skip]
+        return match size {
+            s if s >= huge => Some(Tebi),
+            s if s >= giga => Some(Gibi),
+            s if s >= mega => Some(Mebi),
+            s if s >= kilo => Some(Kibi),
+            _              => None,
+        };
+    }
+
     #[rustfmt::skip]
     fn no_size(&self) -> Style { self.ui.punctuation() }
     #[rustfmt::skip]
@@ -394,7 +635,6 @@ impl render::SizeColours for Theme {
 }
 
 #[rustfmt::skip]
-#[cfg(unix)]
 impl render::UserColours for Theme {
     fn you(&self)           -> Style { self.ui.users.unwrap_or_default().user_you() }
     fn other(&self)         -> Style { self.ui.users.unwrap_or_default().user_other() }
@@ -405,13 +645,27 @@ impl render::UserColours for Theme {
 #[rustfmt::skip]
 impl FileNameColours for Theme {
     fn symlink_path(&self)        -> Style { self.ui.symlink_path() }
-    fn normal_arrow(&self)        -> Style { self.ui.punctuation() }
+    fn normal_arrow(&self)        -> Style { self.ui.link_arrow.unwrap_or_else(|| self.ui.punctuation()) }
     fn broken_symlink(&self)      -> Style { self.ui.broken_symlink() }
     fn broken_filename(&self)     -> Style { apply_overlay(self.ui.broken_symlink(), self.ui.broken_path_overlay()) }
     fn control_char(&self)        -> Style { self.ui.control_char() }
     fn broken_control_char(&self) -> Style { apply_overlay(self.ui.control_char(),   self.ui.broken_path_overlay()) }
+    fn normalized_name(&self)     -> Style { self.ui.punctuation() }
+    fn error_note(&self)          -> Style { self.ui.error_note.unwrap_or_else(|| self.ui.broken_symlink()) }
     fn executable_file(&self)     -> Style { self.ui.filekinds.unwrap_or_default().executable() }
     fn mount_point(&self)         -> Style { self.ui.filekinds.unwrap_or_default().mount_point() }
+    fn encrypted(&self)           -> Style { self.ui.filekinds.unwrap_or_default().encrypted() }
+    fn offline(&self)             -> Style { self.ui.filekinds.unwrap_or_default().offline() }
+    fn date(&self)                -> Style { self.ui.date() }
+
+    fn multi_hard_link(&self)       -> Option<Style> { self.ui.filekinds.unwrap_or_default().multi_hard_link }
+    fn door(&self)                  -> Option<Style> { self.ui.filekinds.unwrap_or_default().door }
+    fn setuid(&self)                -> Option<Style> { self.ui.filekinds.unwrap_or_default().setuid }
+    fn setgid(&self)                -> Option<Style> { self.ui.filekinds.unwrap_or_default().setgid }
+    fn capability(&self)            -> Option<Style> { self.ui.filekinds.unwrap_or_default().capability }
+    fn sticky_other_writable(&self) -> Option<Style> { self.ui.filekinds.unwrap_or_default().sticky_other_writable }
+    fn other_writable(&self)        -> Option<Style> { self.ui.filekinds.unwrap_or_default().other_writable }
+    fn sticky(&self)                -> Option<Style> { self.ui.filekinds.unwrap_or_default().sticky }
 
     fn colour_file(&self, file: &File<'_>) -> Style {
         self.exts
@@ -434,7 +688,14 @@ impl FileNameColours for Theme {
             }
         }
 
-        None
+        self.globs
+            .iter()
+            .find(|(pattern, _)| pattern.matches(&file.name))
+            .map(|(_, style)| *style)
+    }
+
+    fn icon_override(&self, file: &File<'_>) -> Option<char> {
+        self.icons.get(file)
     }
 }
 
@@ -553,6 +814,17 @@ mod customs_test {
     test!(ls_cd:   ls "cd=35", exa ""  =>  colours c -> { c.filekinds().char_device  = Some(Purple.normal()); });
     test!(ls_ln:   ls "ln=34", exa ""  =>  colours c -> { c.filekinds().symlink      = Some(Blue.normal());   });
     test!(ls_or:   ls "or=33", exa ""  =>  colours c -> { c.broken_symlink         = Some(Yellow.normal()); });
+    test!(ls_mh:   ls "mh=31", exa ""  =>  colours c -> { c.filekinds().multi_hard_link       = Some(Red.normal());    });
+    test!(ls_do:   ls "do=32", exa ""  =>  colours c -> { c.filekinds().door                  = Some(Green.normal());  });
+    test!(ls_su:   ls "su=33", exa ""  =>  colours c -> { c.filekinds().setuid                = Some(Yellow.normal()); });
+    test!(ls_sg:   ls "sg=34", exa ""  =>  colours c -> { c.filekinds().setgid                = Some(Blue.normal());   });
+    test!(ls_ca:   ls "ca=35", exa ""  =>  colours c -> { c.filekinds().capability            = Some(Purple.normal()); });
+    test!(ls_tw:   ls "tw=36", exa ""  =>  colours c -> { c.filekinds().sticky_other_writable = Some(Cyan.normal());   });
+    test!(ls_ow:   ls "ow=35", exa ""  =>  colours c -> { c.filekinds().other_writable        = Some(Purple.normal()); });
+    test!(ls_st:   ls "st=34", exa ""  =>  colours c -> { c.filekinds().sticky                = Some(Blue.normal());   });
+
+    // As in ls, setting those to 0 turns them off, rather than making them plain:
+    test!(ls_mh_0: ls "mh=31:mh=00", exa ""  =>  colours c -> { c.filekinds().multi_hard_link = None; });
 
     // EZA_COLORS can affect all those colours too:
     test!(exa_di:  ls "", exa "di=32"  =>  colours c -> { c.filekinds().directory    = Some(Green.normal());  });
@@ -564,6 +836,7 @@ mod customs_test {
     test!(exa_cd:  ls "", exa "cd=34"  =>  colours c -> { c.filekinds().char_device  = Some(Blue.normal());   });
     test!(exa_ln:  ls "", exa "ln=33"  =>  colours c -> { c.filekinds().symlink      = Some(Yellow.normal()); });
     test!(exa_or:  ls "", exa "or=32"  =>  colours c -> { c.broken_symlink         = Some(Green.normal());  });
+    test!(exa_ca:  ls "", exa "ca=31"  =>  colours c -> { c.filekinds().capability   = Some(Red.normal());    });
 
     // EZA_COLORS will even override options from LS_COLORS:
     test!(ls_exa_di: ls "di=31", exa "di=32"  =>  colours c -> { c.filekinds().directory  = Some(Green.normal());  });
@@ -633,6 +906,7 @@ mod customs_test {
 
     test!(exa_xx:  ls "", exa "xx=38;5;128"  =>  colours c -> { c.punctuation                           = Some(Fixed(128).normal()); });
     test!(exa_da:  ls "", exa "da=38;5;129"  =>  colours c -> { c.date                                  = Some(Fixed(129).normal()); });
+    test!(exa_dold: ls "", exa "dO=90"        =>  colours c -> { c.date_old                              = Some(DarkGray.normal()); });
     test!(exa_in:  ls "", exa "in=38;5;130"  =>  colours c -> { c.inode                                 = Some(Fixed(130).normal()); });
     test!(exa_bl:  ls "", exa "bl=38;5;131"  =>  colours c -> { c.blocks                                = Some(Fixed(131).normal()); });
     test!(exa_hd:  ls "", exa "hd=38;5;132"  =>  colours c -> { c.header                                = Some(Fixed(132).normal()); });
@@ -641,8 +915,15 @@ mod customs_test {
     test!(exa_oc:  ls "", exa "oc=38;5;135"  =>  colours c -> { c.octal                                 = Some(Fixed(135).normal()); });
     test!(exa_ff:  ls "", exa "ff=38;5;136"  =>  colours c -> { c.flags                                 = Some(Fixed(136).normal()); });
     test!(exa_bo:  ls "", exa "bO=4"         =>  colours c -> { c.broken_path_overlay                   = Some(Style::default().underline()); });
+    test!(exa_tb:  ls "", exa "tb=2;37"      =>  colours c -> { c.tree_branch                           = Some(White.dimmed()); });
+    test!(exa_la:  ls "", exa "la=38;5;137"  =>  colours c -> { c.link_arrow                            = Some(Fixed(137).normal()); });
+    test!(exa_pd:  ls "", exa "pd=38;5;138"  =>  colours c -> { c.permission_dash                       = Some(Fixed(138).normal()); });
+    test!(exa_gs:  ls "", exa "gs=44"        =>  colours c -> { c.grid_separator                        = Some(Style::default().on(Blue)); });
+    test!(exa_en:  ls "", exa "en=38;5;139"  =>  colours c -> { c.error_note                            = Some(Fixed(139).normal()); });
 
     test!(exa_mp:  ls "", exa "mp=1;34;4"    =>  colours c -> { c.filekinds().mount_point                 = Some(Blue.bold().underline()); });
+    test!(exa_ec:  ls "", exa "ec=35"        =>  colours c -> { c.filekinds().encrypted                   = Some(Purple.normal()); });
+    test!(exa_of:  ls "", exa "of=36"        =>  colours c -> { c.filekinds().offline                     = Some(Cyan.normal()); });
     test!(exa_sp:  ls "", exa "sp=1;35;4"    =>  colours c -> { c.filekinds().special                     = Some(Purple.bold().underline()); });
 
     test!(exa_im:  ls "", exa "im=38;5;128"  =>  colours c -> { c.file_type().image                       = Some(Fixed(128).normal()); });
@@ -700,3 +981,104 @@ mod customs_test {
     test!(ls_txt_exa_fi:  ls "*.txt=31", exa "fi=33"  => colours c -> { c.filekinds().normal = Some(Yellow.normal()); }, exts [ ("*.txt", Red.normal()) ]);
     test!(eza_fi_exa_txt: ls "", exa "fi=33:*.txt=31" => colours c -> { c.filekinds().normal = Some(Yellow.normal()); }, exts [ ("*.txt", Red.normal()) ]);
 }
+
+#[cfg(test)]
+mod export_test {
+    use super::*;
+
+    fn export(ls: &str, exa: &str) -> String {
+        let options = Options {
+            use_colours: UseColours::Always,
+            colour_scale: ColorScaleOptions::default(),
+            definitions: Definitions {
+                ls: Some(ls.into()),
+                exa: Some(exa.into()),
+            },
+            background: Background::Dark,
+            colour_depth: ColourDepth::TrueColour,
+            theme_config: None,
+            icons: IconOverrides::default(),
+        };
+
+        options.to_theme(true).to_ls_colors()
+    }
+
+    #[test]
+    fn file_kinds() {
+        let exported = export("di=31", "ln=1;36");
+        assert!(exported.contains(":di=31:ln=1;36:"));
+    }
+
+    #[test]
+    fn globs_come_last() {
+        assert!(export("*.txt=32", "").ends_with(":*.txt=32"));
+    }
+
+    #[test]
+    fn default_file_types() {
+        assert!(export("", "").contains(":*.rs="));
+        assert!(!export("", "reset").contains(":*.rs="));
+    }
+
+    #[test]
+    fn no_colours() {
+        let options = Options {
+            use_colours: UseColours::Never,
+            colour_scale: ColorScaleOptions::default(),
+            definitions: Definitions::default(),
+            background: Background::Dark,
+            colour_depth: ColourDepth::TrueColour,
+            theme_config: None,
+            icons: IconOverrides::default(),
+        };
+
+        assert_eq!(
+            options.to_theme(true).to_ls_colors(),
+            "fi=0:di=0:ln=0:pi=0:bd=0:cd=0:so=0:ex=0:or=0"
+        );
+    }
+}
+
+#[cfg(test)]
+mod column_style_test {
+    use super::*;
+    use crate::output::table::TimeType;
+    use nu_ansi_term::Color::*;
+
+    fn theme(columns: &[(&str, ColumnStyle)]) -> Theme {
+        let ui = UiStyles {
+            columns: Some(
+                columns
+                    .iter()
+                    .map(|(name, style)| ((*name).to_owned(), *style))
+                    .collect(),
+            ),
+            ..UiStyles::default()
+        };
+        Theme::new(ui, Box::new(NoFileStyle), IconOverrides::default())
+    }
+
+    #[test]
+    fn by_field_name() {
+        let style = ColumnStyle {
+            header_style: Some(Red.bold()),
+            style: Some(Cyan.normal()),
+        };
+        let theme = theme(&[("size", style), ("mtime", ColumnStyle::default())]);
+        assert_eq!(theme.column_style(Column::FileSize), style);
+        assert_eq!(
+            theme.column_style(Column::Timestamp(TimeType::Modified)),
+            ColumnStyle::default()
+        );
+    }
+
+    #[test]
+    fn unstyled_and_unknown() {
+        let style = ColumnStyle {
+            header_style: None,
+            style: Some(Cyan.normal()),
+        };
+        let theme = theme(&[("nonsense", style)]);
+        assert_eq!(theme.column_style(Column::User), ColumnStyle::default());
+    }
+}
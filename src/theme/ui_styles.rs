@@ -22,6 +22,17 @@ pub struct FileNameStyle {
     pub filename: Option<Style>,
 }
 
+/// The styles for one of the long view’s columns, which go over the
+/// styles for whatever the column shows.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct ColumnStyle {
+    /// The style for the column’s heading in the header row.
+    pub header_style: Option<Style>,
+
+    /// The style for everything in the column.
+    pub style: Option<Style>,
+}
+
 #[rustfmt::skip]
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct UiStyles {
@@ -39,19 +50,50 @@ pub struct UiStyles {
 
     pub punctuation:  Option<Style>,          // xx
     pub date:         Option<Style>,          // da
+    pub date_recent:  Option<Style>,          // dr
+    pub date_old:     Option<Style>,          // dO
     pub inode:        Option<Style>,          // in
     pub blocks:       Option<Style>,          // bl
     pub header:       Option<Style>,          // hd
     pub octal:        Option<Style>,          // oc
     pub flags:        Option<Style>,          // ff
 
+    /// Styles for individual file flags, such as `uchg`, which otherwise
+    /// get the `flags` style.
+    pub file_flags:   Option<HashMap<String, Style>>,
+
+    /// How many seconds old a timestamp can be and still get the
+    /// `date_recent` style, and has to be to get the `date_old` style.
+    pub date_threshold_recent: Option<u64>,
+    pub date_threshold_old:    Option<u64>,
+
     pub symlink_path:         Option<Style>,  // lp
     pub control_char:         Option<Style>,  // cc
     pub broken_symlink:       Option<Style>,  // or
     pub broken_path_overlay:  Option<Style>,  // bO
 
+    /// Styles for pieces of the UI that get the `punctuation` style when
+    /// they don’t have one of their own. The gutter between the columns of
+    /// the grid is left unstyled instead.
+    pub tree_branch:          Option<Style>,  // tb
+    pub link_arrow:           Option<Style>,  // la
+    pub permission_dash:      Option<Style>,  // pd
+    pub grid_separator:       Option<Style>,  // gs
+
+    /// The style for the note after a name whose metadata couldn’t be
+    /// read, for `--inline-errors`, which gets the `broken_symlink` style
+    /// when it’s not set.
+    pub error_note:           Option<Style>,  // en
+
     pub filenames: Option<HashMap<String, FileNameStyle>>,
     pub extensions: Option<HashMap<String, FileNameStyle>>,
+
+    /// Styles for files matching glob patterns, which get used when
+    /// neither a file’s name nor its extension has one.
+    pub globs: Option<HashMap<String, FileNameStyle>>,
+
+    /// Styles for the long view’s columns, by the names `--fields` takes.
+    pub columns: Option<HashMap<String, ColumnStyle>>,
 }
 // Macro to generate .unwrap_or_default getters for each field to cut down boilerplate
 macro_rules! field_accessors {
@@ -101,6 +143,8 @@ field_accessors!(
     UiStyles,
     punctuation: Option<Style>,
     date: Option<Style>,
+    date_recent: Option<Style>,
+    date_old: Option<Style>,
     inode: Option<Style>,
     blocks: Option<Style>,
     header: Option<Style>,
@@ -125,6 +169,18 @@ pub struct FileKinds {
     pub special: Option<Style>,       // sp
     pub executable: Option<Style>,    // ex
     pub mount_point: Option<Style>,   // mp
+    pub encrypted: Option<Style>,     // ec
+    pub offline: Option<Style>,       // of
+
+    // These only get used when they’re set, in place of the styles above.
+    pub multi_hard_link: Option<Style>,       // mh
+    pub door: Option<Style>,                  // do
+    pub setuid: Option<Style>,                // su
+    pub setgid: Option<Style>,                // sg
+    pub capability: Option<Style>,            // ca
+    pub sticky_other_writable: Option<Style>, // tw
+    pub other_writable: Option<Style>,        // ow
+    pub sticky: Option<Style>,                // st
 }
 
 impl Default for FileKinds {
@@ -140,6 +196,16 @@ impl Default for FileKinds {
             special: Some(Yellow.normal()),
             executable: Some(Green.bold()),
             mount_point: Some(Blue.bold().underline()),
+            encrypted: Some(Purple.normal()),
+            offline: Some(Cyan.normal()),
+            multi_hard_link: None,
+            door: None,
+            setuid: None,
+            setgid: None,
+            capability: None,
+            sticky_other_writable: None,
+            other_writable: None,
+            sticky: None,
         }
     }
 }
@@ -154,7 +220,9 @@ field_accessors!(
     socket: Option<Style>,
     special: Option<Style>,
     executable: Option<Style>,
-    mount_point: Option<Style>
+    mount_point: Option<Style>,
+    encrypted: Option<Style>,
+    offline: Option<Style>
 );
 
 #[rustfmt::skip]
@@ -212,6 +280,11 @@ pub struct Size {
     pub unit_mega: Option<Style>,    // sb um
     pub unit_giga: Option<Style>,    // sb ug
     pub unit_huge: Option<Style>,    // sb ut
+
+    pub threshold_kilo: Option<u64>,
+    pub threshold_mega: Option<u64>,
+    pub threshold_giga: Option<u64>,
+    pub threshold_huge: Option<u64>,
 }
 field_accessors!(
     Size,
@@ -229,6 +302,20 @@ field_accessors!(
     unit_huge: Option<Style>
 );
 
+impl Size {
+    /// Returns the smallest sizes, in bytes, that get coloured as kilo,
+    /// mega, giga, and huge sizes when the size column is colour-scaled.
+    /// These default to the binary prefixes.
+    pub fn thresholds(&self) -> [u64; 4] {
+        [
+            self.threshold_kilo.unwrap_or(1 << 10),
+            self.threshold_mega.unwrap_or(1 << 20),
+            self.threshold_giga.unwrap_or(1 << 30),
+            self.threshold_huge.unwrap_or(1 << 40),
+        ]
+    }
+}
+
 #[rustfmt::skip]
 #[derive(Clone, Copy, Debug,Eq, Default, PartialEq, Serialize, Deserialize)]
 pub struct Users {
@@ -382,6 +469,16 @@ pub struct FileType {
 }
 
 impl UiStyles {
+    /// Returns the ages, in seconds, below which timestamps get coloured as
+    /// recent and from which they get coloured as old. These default to a
+    /// day and a year.
+    pub fn date_thresholds(&self) -> [u64; 2] {
+        [
+            self.date_threshold_recent.unwrap_or(24 * 60 * 60),
+            self.date_threshold_old.unwrap_or(365 * 24 * 60 * 60),
+        ]
+    }
+
     pub fn plain() -> Self {
         Self {
             colourful: Some(false),
@@ -398,6 +495,9 @@ impl UiStyles {
             special: Some(Style::default()),
             executable: Some(Style::default()),
             mount_point: Some(Style::default()),
+            encrypted: Some(Style::default()),
+            offline: Some(Style::default()),
+            ..FileKinds::default()
             }),
 
             #[rustfmt::skip]
@@ -486,19 +586,31 @@ impl UiStyles {
 
             punctuation: Some(Style::default()),
             date: Some(Style::default()),
+            date_recent: None,
+            date_old: None,
             inode: Some(Style::default()),
             blocks: Some(Style::default()),
             octal: Some(Style::default()),
             flags: Some(Style::default()),
+            file_flags: None,
+            date_threshold_recent: None,
+            date_threshold_old: None,
             header: Some(Style::default()),
 
             symlink_path: Some(Style::default()),
             control_char: Some(Style::default()),
             broken_symlink: Some(Style::default()),
             broken_path_overlay: Some(Style::default()),
+            tree_branch: None,
+            link_arrow: None,
+            permission_dash: None,
+            grid_separator: None,
+            error_note: None,
 
             filenames: None,
             extensions: None,
+            globs: None,
+            columns: None,
         }
     }
 }
@@ -519,10 +631,18 @@ impl UiStyles {
             "cd" => self.filekinds().char_device  = Some(pair.to_style()),  // CHR
             "ln" => self.filekinds().symlink      = Some(pair.to_style()),  // LINK
             "or" => self.broken_symlink         = Some(pair.to_style()),  // ORPHAN
+            "mh" => self.filekinds().multi_hard_link       = pair.to_optional_style(),  // MULTIHARDLINK
+            "do" => self.filekinds().door                  = pair.to_optional_style(),  // DOOR
+            "su" => self.filekinds().setuid                = pair.to_optional_style(),  // SETUID
+            "sg" => self.filekinds().setgid                = pair.to_optional_style(),  // SETGID
+            "ca" => self.filekinds().capability            = pair.to_optional_style(),  // CAPABILITY
+            "tw" => self.filekinds().sticky_other_writable = pair.to_optional_style(),  // STICKY_OTHER_WRITABLE
+            "ow" => self.filekinds().other_writable        = pair.to_optional_style(),  // OTHER_WRITABLE
+            "st" => self.filekinds().sticky                = pair.to_optional_style(),  // STICKY
              _   => return false,
              // Codes we don’t do anything with:
-             // MULTIHARDLINK, DOOR, SETUID, SETGID, CAPABILITY,
-             // STICKY_OTHER_WRITABLE, OTHER_WRITABLE, STICKY, MISSING
+             // MISSING, and the LEFT, RIGHT, END, RESET, and CLEAR_LINE
+             // codes that ls writes around each name
         };
         true
     }
@@ -530,7 +650,8 @@ impl UiStyles {
     /// Sets a value on this set of colours using one of the keys understood
     /// by the `EZA_COLORS` environment variable. Invalid keys set nothing,
     /// but return false. This doesn’t take the `LS_COLORS` keys into account,
-    /// so `set_ls` should have been run first.
+    /// so `set_ls` should be run for the keys this doesn’t recognise. A few
+    /// keys, such as `do` and `tw`, mean something different to each.
     pub fn set_exa(&mut self, pair: &Pair<'_>) -> bool {
         #[rustfmt::skip]
         match pair.key {
@@ -587,6 +708,8 @@ impl UiStyles {
             "Gd" => self.git_repo().git_dirty             = Some(pair.to_style()),
             "xx" => self.punctuation                     = Some(pair.to_style()),
             "da" => self.date                            = Some(pair.to_style()),
+            "dr" => self.date_recent                     = Some(pair.to_style()),
+            "dO" => self.date_old                        = Some(pair.to_style()),
             "in" => self.inode                           = Some(pair.to_style()),
             "bl" => self.blocks                          = Some(pair.to_style()),
             "hd" => self.header                          = Some(pair.to_style()),
@@ -595,8 +718,15 @@ impl UiStyles {
             "lp" => self.symlink_path                    = Some(pair.to_style()),
             "cc" => self.control_char                    = Some(pair.to_style()),
             "bO" => self.broken_path_overlay             = Some(pair.to_style()),
+            "tb" => self.tree_branch                     = Some(pair.to_style()),
+            "la" => self.link_arrow                      = Some(pair.to_style()),
+            "pd" => self.permission_dash                 = Some(pair.to_style()),
+            "gs" => self.grid_separator                  = Some(pair.to_style()),
+            "en" => self.error_note                      = Some(pair.to_style()),
 
             "mp" => self.filekinds().mount_point          = Some(pair.to_style()),
+            "ec" => self.filekinds().encrypted            = Some(pair.to_style()),
+            "of" => self.filekinds().offline              = Some(pair.to_style()),
             "sp" => self.filekinds().special              = Some(pair.to_style()),  // Catch-all for unrecognized file kind
 
             "im" => self.file_type().image                = Some(pair.to_style()),